@@ -0,0 +1,76 @@
+//! Benchmarks of pairing and ranking for fields from a local game store to a large open.
+//!
+//! ```text
+//! $ cargo bench --bench pairing
+//! ```
+//!
+//! Every benchmark starts from a tournament of synthetic players with three rounds played, so
+//! that pairing has to avoid rematches and the tiebreakers have opponents to look at.
+
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use swyss::{simulate, Tournament};
+
+const FIELD_SIZES: [u32; 4] = [16, 128, 1024, 4096];
+
+/// Rounds played before measuring
+const ROUNDS_PLAYED: u32 = 3;
+
+/// Returns a tournament of `players` synthetic players with `ROUNDS_PLAYED` rounds played, saved
+/// as JSON so that every iteration can start from the same state
+fn played(players: u32) -> String {
+    let mut rng = StdRng::seed_from_u64(u64::from(players));
+    let (players, ratings) = simulate::synthetic_players(players, 200.0, &mut rng);
+    let mut tourn = Tournament::with_players(players).unwrap();
+    let rounds = tourn.rounds().max(ROUNDS_PLAYED + 1);
+    tourn.set_rounds(rounds).unwrap();
+
+    for _ in 0..ROUNDS_PLAYED {
+        tourn.next_round_with_seed(rng.gen()).unwrap();
+        simulate::play_round(&mut tourn, Some(&ratings), &mut rng);
+    }
+
+    tourn.to_json()
+}
+
+fn next_round(c: &mut Criterion) {
+    let mut group = c.benchmark_group("next_round");
+    group.sample_size(10);
+
+    for &players in &FIELD_SIZES {
+        let json = played(players);
+        group.bench_with_input(BenchmarkId::from_parameter(players), &json, |b, json| {
+            b.iter_batched(
+                || Tournament::from_json(json).unwrap(),
+                |mut tourn| tourn.next_round_with_seed(42).unwrap(),
+                BatchSize::LargeInput,
+            )
+        });
+
+        let mut tourn = Tournament::from_json(&json).unwrap();
+        tourn.next_round_with_seed(42).unwrap();
+        if let Some(stats) = tourn.last_pairing_stats() {
+            eprintln!("{} players: {:?}", players, stats);
+        }
+    }
+
+    group.finish();
+}
+
+fn ranking(c: &mut Criterion) {
+    let mut group = c.benchmark_group("ranking");
+    group.sample_size(10);
+
+    for &players in &FIELD_SIZES {
+        let mut tourn = Tournament::from_json(&played(players)).unwrap();
+        group.bench_function(BenchmarkId::from_parameter(players), |b| {
+            b.iter(|| tourn.ranking().len())
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, next_round, ranking);
+criterion_main!(benches);
@@ -0,0 +1,18 @@
+//! Prints the JSON Schema of all serialized swyss formats.
+//!
+//! ```text
+//! $ cargo run --example schema --features schemars
+//! ```
+
+use swyss::schema;
+
+fn main() {
+    println!("{}", schema::to_json(&schema::tournament()));
+    println!("{}", schema::to_json(&schema::standings()));
+    println!("{}", schema::to_json(&schema::season()));
+    println!("{}", schema::to_json(&schema::ratings()));
+    println!("{}", schema::to_json(&schema::audit_log()));
+    println!("{}", schema::to_json(&schema::journal_entry()));
+    println!("{}", schema::to_json(&schema::webhook_payload()));
+    println!("{}", schema::to_json(&schema::overlay()));
+}
@@ -0,0 +1,85 @@
+//! Feeds random sequences of results, drops, and late additions into the tournament API.
+//!
+//! The first byte selects the number of players and the second one the number of rounds. Every
+//! following byte selects an operation, some of which consume further bytes as arguments.
+
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+use swyss::{Player, Tournament};
+
+fuzz_target!(|data: &[u8]| {
+    let mut bytes = data.iter().copied();
+
+    let num_players = match bytes.next() {
+        Some(n) => n % 17,
+        None => return,
+    };
+
+    let players = (0..num_players)
+        .map(|i| Player::new(&format!("Player {}", i)))
+        .collect();
+
+    let mut tourn = Tournament::with_players(players).unwrap();
+
+    if let Some(rounds) = bytes.next() {
+        tourn.set_rounds(u32::from(rounds % 9)).unwrap();
+    }
+
+    let mut pairings = Vec::new();
+    let mut added = 0;
+
+    while let Some(op) = bytes.next() {
+        match op % 5 {
+            // Advance to the next round
+            0 => match tourn.next_round() {
+                Ok(Some(p)) => pairings = p,
+                Ok(None) => break,
+                // Results of the current round are still missing
+                Err(_) => {}
+            },
+            // Report a result, which may be invalid
+            1 => {
+                let (i, score) = match (bytes.next(), bytes.next()) {
+                    (Some(i), Some(score)) => (usize::from(i), score),
+                    _ => break,
+                };
+
+                if pairings.is_empty() {
+                    continue;
+                }
+
+                let (uuid, _, _) = pairings[i % pairings.len()];
+                let _ = tourn.end_match(uuid, score % 4, (score / 4) % 4, (score / 16) % 4);
+            }
+            // Drop a player
+            2 => {
+                let i = match bytes.next() {
+                    Some(i) => usize::from(i),
+                    None => break,
+                };
+
+                if tourn.player_arena().is_empty() {
+                    continue;
+                }
+
+                let uuid = tourn.player_arena()[i % tourn.player_arena().len()].uuid;
+                assert!(tourn.drop_player(uuid).unwrap().is_some());
+            }
+            // Add a player late
+            3 => {
+                added += 1;
+                let p = Player::new(&format!("Late Player {}", added));
+                tourn.add_player(p).unwrap();
+            }
+            // Rank, export, and reload
+            _ => {
+                tourn.ranking();
+                tourn.standings_to_json();
+
+                let loaded = Tournament::from_json(&tourn.to_json()).unwrap();
+                assert_eq!(loaded.player_arena().len(), tourn.player_arena().len());
+                assert_eq!(loaded.pairings().len(), tourn.pairings().len());
+            }
+        }
+    }
+});
@@ -0,0 +1,298 @@
+//! Highlights of an event to announce at its end: the longest win streak, the biggest upset, the
+//! fastest match, and who played the most games. Everything is taken from the round history, so
+//! byes count for none of them.
+
+use crate::history::minutes;
+use crate::ratings::Ratings;
+use crate::Tournament;
+use std::collections::HashMap;
+use std::fmt;
+use std::time::Duration;
+
+/// The most consecutive matches a player won
+#[derive(Clone, Debug, PartialEq)]
+pub struct WinStreak {
+    pub player: uuid::Uuid,
+    pub name: String,
+    pub wins: u32,
+}
+
+/// A match won by the lower rated player
+#[derive(Clone, Debug, PartialEq)]
+pub struct Upset {
+    pub round: u32,
+    pub winner: String,
+    pub winner_rating: f64,
+    pub loser: String,
+    pub loser_rating: f64,
+}
+
+/// The match that took the least time from pairing to result
+#[derive(Clone, Debug, PartialEq)]
+pub struct FastestMatch {
+    pub round: u32,
+    pub pairing: uuid::Uuid,
+    pub home: String,
+    pub away: String,
+    pub duration: Duration,
+}
+
+/// The player who played the most games
+#[derive(Clone, Debug, PartialEq)]
+pub struct MostGames {
+    pub player: uuid::Uuid,
+    pub name: String,
+    pub games: u32,
+}
+
+/// Highlights of the event, as returned by `Tournament::achievements()`. Each is `None` if no
+/// match qualifies. Ties go to whoever achieved it first, or to the lower player number.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Achievements {
+    pub longest_win_streak: Option<WinStreak>,
+    /// Only known if ratings were given
+    pub biggest_upset: Option<Upset>,
+    /// Conceded matches and matches without timestamps don't count
+    pub fastest_match: Option<FastestMatch>,
+    pub most_games: Option<MostGames>,
+}
+
+impl Tournament {
+    /// Collects the highlights of all results entered so far. The biggest upset is the match won
+    /// by the player rated furthest below their opponent, with players looked up by name in the
+    /// given ratings as they were before the event.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use swyss::{Player, Tournament};
+    ///
+    /// let players = (1..5)
+    ///     .map(|i| Player::new(&format!("Player {}", i)))
+    ///     .collect();
+    /// let mut tourn = Tournament::with_players(players).unwrap();
+    /// while let Some(pairings) = tourn.next_round().unwrap() {
+    ///     for (uuid, _, _) in pairings {
+    ///         tourn.end_match(uuid, 2, 1, 0).unwrap();
+    ///     }
+    /// }
+    ///
+    /// let achievements = tourn.achievements(None);
+    /// assert_eq!(achievements.longest_win_streak.unwrap().wins, 2);
+    /// assert_eq!(achievements.most_games.unwrap().games, 6);
+    /// assert!(achievements.biggest_upset.is_none());
+    /// ```
+    pub fn achievements(&self, ratings: Option<&Ratings>) -> Achievements {
+        let name = |uuid: uuid::Uuid| self.player(uuid).map(|p| p.name).unwrap_or_default();
+        let number = |uuid: uuid::Uuid| self.player_number(uuid).unwrap_or(u32::MAX);
+
+        let mut streaks: HashMap<uuid::Uuid, u32> = HashMap::new();
+        let mut longest: Option<(uuid::Uuid, u32)> = None;
+        let mut games: HashMap<uuid::Uuid, u32> = HashMap::new();
+        let mut upset: Option<Upset> = None;
+        let mut fastest: Option<FastestMatch> = None;
+
+        for round in self.round_history() {
+            // Players are visited in player number order, so ties go to the lower number
+            let mut pairings: Vec<_> = round
+                .pairings
+                .iter()
+                .filter_map(|pair| pair.result.map(|result| (pair, result)))
+                .collect();
+            pairings.sort_by_key(|(pair, _)| number(pair.home).min(number(pair.away)));
+
+            let mut results = Vec::new();
+            for (pair, result) in &pairings {
+                let played = u32::from(result.home_score + result.away_score + result.drawn);
+                *games.entry(pair.home).or_default() += played;
+                *games.entry(pair.away).or_default() += played;
+
+                let (winner, loser) = match result.home_score.cmp(&result.away_score) {
+                    std::cmp::Ordering::Greater => (pair.home, pair.away),
+                    std::cmp::Ordering::Less => (pair.away, pair.home),
+                    std::cmp::Ordering::Equal => {
+                        results.push((pair.home, false));
+                        results.push((pair.away, false));
+                        continue;
+                    }
+                };
+                results.push((winner, true));
+                results.push((loser, false));
+
+                if let Some(ratings) = ratings {
+                    let (winner, loser) = (name(winner), name(loser));
+                    let (winner_rating, loser_rating) =
+                        (ratings.get(&winner).rating, ratings.get(&loser).rating);
+                    let gap = loser_rating - winner_rating;
+                    let biggest = upset
+                        .as_ref()
+                        .map_or(0.0, |u| u.loser_rating - u.winner_rating);
+                    if gap > biggest {
+                        upset = Some(Upset {
+                            round: round.number,
+                            winner,
+                            winner_rating,
+                            loser,
+                            loser_rating,
+                        });
+                    }
+                }
+
+                if let Some(duration) = pair.duration().filter(|_| result.conceded.is_none()) {
+                    if fastest.as_ref().is_none_or(|f| duration < f.duration) {
+                        fastest = Some(FastestMatch {
+                            round: round.number,
+                            pairing: pair.uuid,
+                            home: name(pair.home),
+                            away: name(pair.away),
+                            duration,
+                        });
+                    }
+                }
+            }
+
+            results.sort_by_key(|(player, _)| number(*player));
+            for (player, won) in results {
+                let streak = streaks.entry(player).or_default();
+                *streak = if won { *streak + 1 } else { 0 };
+                if *streak > longest.map_or(0, |(_, wins)| wins) {
+                    longest = Some((player, *streak));
+                }
+            }
+        }
+
+        let mut most_games: Vec<_> = games.into_iter().collect();
+        most_games.sort_by_key(|&(player, games)| (std::cmp::Reverse(games), number(player)));
+
+        Achievements {
+            longest_win_streak: longest.map(|(player, wins)| WinStreak {
+                player,
+                name: name(player),
+                wins,
+            }),
+            biggest_upset: upset,
+            fastest_match: fastest,
+            most_games: most_games.first().map(|&(player, games)| MostGames {
+                player,
+                name: name(player),
+                games,
+            }),
+        }
+    }
+}
+
+impl fmt::Display for Achievements {
+    /// Formats the achievements one per line, leaving out those that nobody achieved
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(streak) = &self.longest_win_streak {
+            writeln!(
+                f,
+                "Longest win streak: {} ({} wins)",
+                streak.name, streak.wins
+            )?;
+        }
+        if let Some(upset) = &self.biggest_upset {
+            writeln!(
+                f,
+                "Biggest upset: {} ({:.0}) beat {} ({:.0}) in round {}",
+                upset.winner, upset.winner_rating, upset.loser, upset.loser_rating, upset.round
+            )?;
+        }
+        if let Some(fastest) = &self.fastest_match {
+            writeln!(
+                f,
+                "Fastest match: {} vs. {} in round {} ({})",
+                fastest.home,
+                fastest.away,
+                fastest.round,
+                minutes(fastest.duration)
+            )?;
+        }
+        if let Some(most) = &self.most_games {
+            writeln!(f, "Most games played: {} ({} games)", most.name, most.games)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ratings::{Rating, RatingSystem};
+    use crate::{Player, PlayerSide};
+
+    #[test]
+    fn highlights() {
+        let players = ["Alice", "Bob", "Carol", "Dave"]
+            .iter()
+            .map(|name| Player::new(name))
+            .collect();
+        let mut tourn = Tournament::with_players(players).unwrap();
+        tourn.rounds = 3;
+        let mut ratings = Ratings::new(RatingSystem::default());
+        for (name, rating) in &[("Alice", 1400.0), ("Bob", 1500.0), ("Carol", 1800.0)] {
+            ratings.ratings.insert(
+                String::from(*name),
+                Rating {
+                    rating: *rating,
+                    ..Rating::default()
+                },
+            );
+        }
+
+        // Alice wins every match; the away player wins every other match, 2-1
+        let mut minutes = 30;
+        while let Some(pairings) = tourn.next_round().unwrap() {
+            for (uuid, home, away) in pairings {
+                if home == "Alice" {
+                    tourn.end_match(uuid, 2, 0, 0).unwrap();
+                } else if away == "Alice" {
+                    tourn.end_match(uuid, 0, 2, 0).unwrap();
+                } else {
+                    tourn.end_match(uuid, 1, 2, 0).unwrap();
+                }
+                let pair = tourn.pairings.get_mut(&uuid).unwrap();
+                pair.created = 1000;
+                pair.finished.set(Some(1000 + minutes * 60));
+                minutes -= 1;
+            }
+        }
+
+        let achievements = tourn.achievements(Some(&ratings));
+        let streak = achievements.longest_win_streak.unwrap();
+        assert_eq!(streak.name, "Alice");
+        assert_eq!(streak.wins, 3);
+
+        // Alice beating Carol is the biggest gap a win can have here
+        let upset = achievements.biggest_upset.unwrap();
+        assert_eq!(upset.winner, "Alice");
+        assert_eq!(upset.loser, "Carol");
+        assert_eq!(upset.loser_rating - upset.winner_rating, 400.0);
+
+        let fastest = achievements.fastest_match.unwrap();
+        assert_eq!(fastest.round, 3);
+        assert_eq!(fastest.duration, Duration::from_secs(25 * 60));
+
+        // Everyone but Alice played three games in every round but the one against her
+        let most = achievements.most_games.unwrap();
+        assert_ne!(most.name, "Alice");
+        assert_eq!(most.games, 8);
+
+        assert!(tourn.achievements(None).biggest_upset.is_none());
+    }
+
+    #[test]
+    fn conceded_matches_are_not_fastest() {
+        let players = (1..3)
+            .map(|i| Player::new(format!("Player {}", i).as_str()))
+            .collect();
+        let mut tourn = Tournament::with_players(players).unwrap();
+        let (uuid, _, _) = tourn.next_round().unwrap().unwrap()[0];
+        tourn.concede(uuid, PlayerSide::Home).unwrap();
+
+        let achievements = tourn.achievements(None);
+        assert!(achievements.fastest_match.is_none());
+        assert_eq!(achievements.longest_win_streak.as_ref().unwrap().wins, 1);
+        assert_eq!(achievements.to_string().lines().count(), 2);
+    }
+}
@@ -0,0 +1,375 @@
+//! Reading archives of finished tournaments. An archive is a directory of tournament files as
+//! written by `Tournament::save()`. Players are identified across events by their name, since
+//! every tournament assigns its own UUIDs.
+
+use crate::persist::LoadError;
+use crate::Tournament;
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// A single finished tournament read from the archive
+pub struct ArchivedEvent {
+    pub name: String,
+    pub tournament: Tournament,
+}
+
+impl ArchivedEvent {
+    /// Returns the name of the event winner, if the event had any players
+    pub fn winner(&self) -> Option<String> {
+        self.tournament
+            .players
+            .iter()
+            .next()
+            .map(|p| p.name.clone())
+    }
+}
+
+/// A player's accumulated results over all events in the archive
+#[derive(Clone, Debug, PartialEq)]
+pub struct LifetimeRecord {
+    pub name: String,
+    pub events: u32,
+    pub titles: u32,
+    pub matches_played: u32,
+    pub matches_won: u32,
+    pub matches_drawn: u32,
+}
+
+impl LifetimeRecord {
+    fn new(name: &str) -> LifetimeRecord {
+        LifetimeRecord {
+            name: String::from(name),
+            events: 0,
+            titles: 0,
+            matches_played: 0,
+            matches_won: 0,
+            matches_drawn: 0,
+        }
+    }
+
+    /// Number of matches lost, derived from the other counters. Counters that don't add up, e.g.
+    /// in a file edited by hand, count as no losses rather than overflowing.
+    pub fn matches_lost(&self) -> u32 {
+        self.matches_played
+            .saturating_sub(self.matches_won)
+            .saturating_sub(self.matches_drawn)
+    }
+
+    /// Share of all matches played that were won. Returns 0 if no matches were played.
+    pub fn win_rate(&self) -> f64 {
+        if self.matches_played == 0 {
+            0.0
+        } else {
+            self.matches_won as f64 / self.matches_played as f64
+        }
+    }
+}
+
+/// Reading an archive fails if the directory can't be listed or if one of its tournament files
+/// can't be loaded
+#[derive(Debug)]
+pub enum ArchiveError {
+    Io(io::Error),
+    Load(PathBuf, LoadError),
+}
+
+impl std::error::Error for ArchiveError {}
+
+impl fmt::Display for ArchiveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ArchiveError::Io(e) => write!(f, "Could not read archive: {}", e),
+            ArchiveError::Load(path, e) => write!(f, "{}: {}", path.display(), e),
+        }
+    }
+}
+
+impl From<io::Error> for ArchiveError {
+    fn from(e: io::Error) -> ArchiveError {
+        ArchiveError::Io(e)
+    }
+}
+
+/// A collection of finished tournaments that can be queried for lifetime statistics
+pub struct Archive {
+    pub events: Vec<ArchivedEvent>,
+}
+
+impl Archive {
+    /// Loads all `.json` tournament files in the given directory, ordered by file name. Files of
+    /// tournaments that haven't finished yet are skipped. The final standings of each event are
+    /// restored from the saved player order, so ties keep the order they had when the event
+    /// ended.
+    pub fn load<P: AsRef<Path>>(dir: P) -> Result<Archive, ArchiveError> {
+        let mut paths = fs::read_dir(dir)?
+            .map(|res| res.map(|e| e.path()))
+            .collect::<Result<Vec<_>, io::Error>>()?;
+
+        paths.retain(|p| p.extension().map(|ext| ext == "json").unwrap_or(false));
+        paths.sort();
+
+        let mut events = Vec::with_capacity(paths.len());
+
+        for path in paths {
+            let mut tournament = match Tournament::load_readonly(&path) {
+                Ok(t) => t,
+                Err(LoadError::Unfinished) => continue,
+                Err(e) => return Err(ArchiveError::Load(path, e)),
+            };
+
+            tournament.order_by_final_standings();
+
+            let name = path
+                .file_stem()
+                .map(|s| s.to_string_lossy().into_owned())
+                .unwrap_or_default();
+
+            events.push(ArchivedEvent { name, tournament });
+        }
+
+        Ok(Archive { events })
+    }
+
+    /// Accumulates the results of every player over all events, ordered by titles and then by
+    /// matches won
+    pub fn records(&self) -> Vec<LifetimeRecord> {
+        let mut records: HashMap<String, LifetimeRecord> = HashMap::new();
+
+        for event in &self.events {
+            for (rank, p) in event.tournament.players.iter().enumerate() {
+                let record = records
+                    .entry(p.name.clone())
+                    .or_insert_with(|| LifetimeRecord::new(&p.name));
+
+                record.events += 1;
+                record.matches_played += p.matches_played;
+                record.matches_won += p.matches_won;
+                record.matches_drawn += p.matches_drawn;
+
+                if rank == 0 {
+                    record.titles += 1;
+                }
+            }
+        }
+
+        let mut records: Vec<LifetimeRecord> = records.into_values().collect();
+        records.sort_by(|a, b| {
+            b.titles
+                .cmp(&a.titles)
+                .then(b.matches_won.cmp(&a.matches_won))
+                .then(a.name.cmp(&b.name))
+        });
+
+        records
+    }
+
+    /// Returns all players ordered by the number of matches won over all events
+    pub fn most_wins(&self) -> Vec<LifetimeRecord> {
+        let mut records = self.records();
+        records.sort_by(|a, b| b.matches_won.cmp(&a.matches_won).then(a.name.cmp(&b.name)));
+        records
+    }
+
+    /// Returns all players with at least `min_matches` matches played, ordered by their lifetime
+    /// win rate
+    pub fn best_win_rate(&self, min_matches: u32) -> Vec<LifetimeRecord> {
+        let mut records = self.records();
+        records.retain(|r| r.matches_played >= min_matches);
+        records.sort_by(|a, b| {
+            b.win_rate()
+                .partial_cmp(&a.win_rate())
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then(a.name.cmp(&b.name))
+        });
+        records
+    }
+
+    /// Returns all players who won at least one event, ordered by the number of events won
+    pub fn titles(&self) -> Vec<LifetimeRecord> {
+        let mut records = self.records();
+        records.retain(|r| r.titles > 0);
+        records
+    }
+
+    /// Renders a season summary as a Markdown document
+    pub fn to_markdown(&self) -> String {
+        let mut out = String::from("# Season Summary\n\n");
+
+        out.push_str("## Events\n\n");
+        out.push_str("| Event | Players | Winner |\n");
+        out.push_str("|---|---|---|\n");
+        for event in &self.events {
+            out.push_str(&format!(
+                "| {} | {} | {} |\n",
+                event.name,
+                event.tournament.players.len(),
+                event.winner().unwrap_or_default()
+            ));
+        }
+
+        out.push_str("\n## Players\n\n");
+        out.push_str("| Player | Events | Titles | W-L-D | Win rate |\n");
+        out.push_str("|---|---|---|---|---|\n");
+        for r in self.records() {
+            out.push_str(&format!(
+                "| {} | {} | {} | {}-{}-{} | {:.2} |\n",
+                r.name,
+                r.events,
+                r.titles,
+                r.matches_won,
+                r.matches_lost(),
+                r.matches_drawn,
+                r.win_rate()
+            ));
+        }
+
+        out
+    }
+
+    /// Renders a season summary as a standalone HTML page, including the wall chart of every event
+    pub fn to_html(&self) -> String {
+        let mut out = String::from(
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n\
+             <title>Season Summary</title>\n</head>\n<body>\n<h1>Season Summary</h1>\n",
+        );
+
+        out.push_str("<h2>Events</h2>\n<table>\n");
+        out.push_str("<tr><th>Event</th><th>Players</th><th>Winner</th></tr>\n");
+        for event in &self.events {
+            out.push_str(&format!(
+                "<tr><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+                escape_html(&event.name),
+                event.tournament.players.len(),
+                escape_html(&event.winner().unwrap_or_default())
+            ));
+        }
+        out.push_str("</table>\n");
+
+        out.push_str("<h2>Players</h2>\n<table>\n");
+        out.push_str(
+            "<tr><th>Player</th><th>Events</th><th>Titles</th><th>W-L-D</th>\
+             <th>Win rate</th></tr>\n",
+        );
+        for r in self.records() {
+            out.push_str(&format!(
+                "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}-{}-{}</td><td>{:.2}</td></tr>\n",
+                escape_html(&r.name),
+                r.events,
+                r.titles,
+                r.matches_won,
+                r.matches_lost(),
+                r.matches_drawn,
+                r.win_rate()
+            ));
+        }
+        out.push_str("</table>\n");
+
+        out.push_str("<h2>Wall Charts</h2>\n");
+        for event in &self.events {
+            out.push_str(&format!("<h3>{}</h3>\n", escape_html(&event.name)));
+            out.push_str(&event.tournament.wall_chart_html());
+        }
+        out.push_str("</body>\n</html>\n");
+
+        out
+    }
+}
+
+/// Escapes the characters that have a special meaning in HTML
+pub(crate) fn escape_html(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Player;
+
+    /// Plays a two-player tournament in which `winner` beats `loser` 2-0
+    fn play(winner: &str, loser: &str) -> Tournament {
+        let players = vec![Player::new(winner), Player::new(loser)];
+
+        let mut tourn = Tournament::with_players(players).unwrap();
+        while let Some(pairings) = tourn.next_round().unwrap() {
+            for (uuid, home, _) in &pairings {
+                if home == winner {
+                    assert!(tourn.end_match(*uuid, 2, 0, 0).is_ok());
+                } else {
+                    assert!(tourn.end_match(*uuid, 0, 2, 0).is_ok());
+                }
+            }
+        }
+
+        tourn.ranking();
+        tourn
+    }
+
+    #[test]
+    fn season() {
+        let dir = std::env::temp_dir().join(format!("swyss-archive-{}", uuid::Uuid::new_v4()));
+        fs::create_dir(&dir).unwrap();
+
+        play("Alice", "Bob").save(dir.join("week1.json")).unwrap();
+        play("Alice", "Carol").save(dir.join("week2.json")).unwrap();
+        play("Bob", "Carol").save(dir.join("week3.json")).unwrap();
+
+        // Unfinished events are skipped
+        let players = vec![Player::new("Alice"), Player::new("Dave")];
+        Tournament::with_players(players)
+            .unwrap()
+            .save(dir.join("week4.json"))
+            .unwrap();
+
+        let archive = Archive::load(&dir).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(archive.events.len(), 3);
+        assert_eq!(archive.events[0].name, "week1");
+        assert_eq!(archive.events[2].winner().unwrap(), "Bob");
+
+        let titles = archive.titles();
+        assert_eq!(titles.len(), 2);
+        assert_eq!(titles[0].name, "Alice");
+        assert_eq!(titles[0].titles, 2);
+        assert_eq!(titles[1].name, "Bob");
+
+        let wins = archive.most_wins();
+        assert_eq!(wins[0].name, "Alice");
+        assert_eq!(wins[0].matches_won, 2);
+
+        let rates = archive.best_win_rate(2);
+        assert_eq!(rates.len(), 3);
+        assert_eq!(rates[0].name, "Alice");
+        assert_eq!(rates[1].name, "Bob");
+        assert_eq!(rates[1].win_rate(), 0.5);
+        assert_eq!(rates[2].name, "Carol");
+        assert_eq!(rates[2].matches_lost(), 2);
+        let inconsistent = LifetimeRecord {
+            matches_played: 1,
+            matches_won: 1,
+            matches_drawn: 1,
+            ..LifetimeRecord::new("Dave")
+        };
+        assert_eq!(inconsistent.matches_lost(), 0);
+
+        let md = archive.to_markdown();
+        assert!(md.contains("| week3 | 2 | Bob |"));
+        assert!(md.contains("| Alice | 2 | 2 | 2-0-0 | 1.00 |"));
+
+        let html = archive.to_html();
+        assert!(html.contains("<tr><td>week1</td><td>2</td><td>Alice</td></tr>"));
+        assert!(html.contains("<h3>week1</h3>\n<table>"));
+        assert!(html.contains("<tr><td>1</td><td>Alice</td><td>W2</td><td>3</td></tr>"));
+    }
+
+    #[test]
+    fn escape() {
+        assert_eq!(escape_html("<A & B>"), "&lt;A &amp; B&gt;");
+    }
+}
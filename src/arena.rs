@@ -0,0 +1,302 @@
+//! Storage for the players of a tournament. Players are kept by value and refer to each other by
+//! UUID, e.g. in their list of opponents, so that nothing about them is shared: changing one
+//! player only ever needs a mutable borrow of the arena, which the borrow checker verifies, and
+//! references into it can't be held while the tournament records results.
+
+use crate::Player;
+use core::cell::RefCell;
+use std::collections::HashMap;
+use std::iter::FromIterator;
+use std::ops::{Index, IndexMut};
+use std::rc::Rc;
+use std::slice;
+use uuid::Uuid;
+
+/// The players of a tournament, in their current order, e.g. the order of the standings after
+/// `Tournament::ranking()`. Players are found by position like in a `Vec`, or by UUID.
+///
+/// # Example
+///
+/// ```
+/// use swyss::arena::PlayerArena;
+/// use swyss::Player;
+///
+/// let mut players: PlayerArena = vec![Player::new("Alice"), Player::new("Bob")].into();
+/// let bob = players[1].uuid;
+/// players.get_mut(bob).unwrap().win_match();
+///
+/// assert_eq!(players.get(bob).unwrap().match_points, 3);
+/// assert_eq!(players.iter().map(|p| p.match_points).sum::<u32>(), 3);
+/// ```
+#[derive(Clone, Default)]
+pub struct PlayerArena {
+    players: Vec<Player>,
+    /// Position of each player by UUID. Positions are checked before use, as players can be
+    /// changed through `iter_mut()` or by index.
+    index: HashMap<Uuid, usize>,
+    /// Players who are no longer part of the tournament but still count as opponents, e.g. those
+    /// cut after a stage whose standings were carried into the next one
+    former: Vec<Player>,
+}
+
+impl PlayerArena {
+    /// Returns an empty arena
+    pub fn new() -> PlayerArena {
+        PlayerArena::default()
+    }
+
+    /// Number of players
+    pub fn len(&self) -> usize {
+        self.players.len()
+    }
+
+    /// Checks whether there are no players
+    pub fn is_empty(&self) -> bool {
+        self.players.is_empty()
+    }
+
+    /// Iterates over the players in their current order
+    pub fn iter(&self) -> slice::Iter<'_, Player> {
+        self.players.iter()
+    }
+
+    /// Iterates mutably over the players in their current order
+    pub fn iter_mut(&mut self) -> slice::IterMut<'_, Player> {
+        self.players.iter_mut()
+    }
+
+    /// Returns the position of a player, if they are in the arena
+    pub fn position(&self, uuid: Uuid) -> Option<usize> {
+        match self.index.get(&uuid) {
+            Some(&i) if self.players.get(i).map(|p| p.uuid) == Some(uuid) => Some(i),
+            _ => self.players.iter().position(|p| p.uuid == uuid),
+        }
+    }
+
+    /// Checks whether a player is in the arena
+    pub fn contains(&self, uuid: Uuid) -> bool {
+        self.position(uuid).is_some()
+    }
+
+    /// Returns a player, if they are in the arena
+    pub fn get(&self, uuid: Uuid) -> Option<&Player> {
+        self.position(uuid).map(|i| &self.players[i])
+    }
+
+    /// Returns a player for changing them, if they are in the arena
+    pub fn get_mut(&mut self, uuid: Uuid) -> Option<&mut Player> {
+        self.position(uuid).map(move |i| &mut self.players[i])
+    }
+
+    /// Returns two different players for changing both at once, e.g. the players of a pairing.
+    /// `None` if either of them isn't in the arena or both are the same player.
+    pub fn pair_mut(&mut self, a: Uuid, b: Uuid) -> Option<(&mut Player, &mut Player)> {
+        let (i, j) = (self.position(a)?, self.position(b)?);
+        if i < j {
+            let (left, right) = self.players.split_at_mut(j);
+            Some((&mut left[i], &mut right[0]))
+        } else if j < i {
+            let (left, right) = self.players.split_at_mut(i);
+            Some((&mut right[0], &mut left[j]))
+        } else {
+            None
+        }
+    }
+
+    /// Looks up an opponent of one of the players: a player in the arena, or one who is no longer
+    /// part of the tournament but whose results still count towards their opponents' tiebreakers
+    pub fn opponent(&self, uuid: Uuid) -> Option<&Player> {
+        self.get(uuid)
+            .or_else(|| self.former.iter().find(|p| p.uuid == uuid))
+    }
+
+    /// Adds a player after all others
+    pub fn push(&mut self, player: Player) {
+        self.index.insert(player.uuid, self.players.len());
+        self.players.push(player);
+    }
+
+    /// Removes a player and returns them, if they are in the arena. The other players keep their
+    /// order.
+    pub fn remove(&mut self, uuid: Uuid) -> Option<Player> {
+        let i = self.position(uuid)?;
+        let player = self.players.remove(i);
+        self.reindex();
+        Some(player)
+    }
+
+    /// Keeps only the players the predicate holds for, in their order
+    pub fn retain<F: FnMut(&Player) -> bool>(&mut self, f: F) {
+        self.players.retain(f);
+        self.reindex();
+    }
+
+    /// Players who are no longer part of the tournament but still count as opponents
+    pub fn former(&self) -> &[Player] {
+        &self.former
+    }
+
+    /// Keeps players who leave the tournament as opponents of those who stay
+    pub(crate) fn retire(&mut self, players: impl IntoIterator<Item = Player>) {
+        self.former.extend(players);
+    }
+
+    /// Puts the players in the given order. Players missing from it follow in their current
+    /// order.
+    pub(crate) fn reorder(&mut self, order: &[Uuid]) {
+        let position: HashMap<Uuid, usize> =
+            order.iter().enumerate().map(|(i, &u)| (u, i)).collect();
+        self.players
+            .sort_by_key(|p| position.get(&p.uuid).copied().unwrap_or(order.len()));
+        self.reindex();
+    }
+
+    fn reindex(&mut self) {
+        self.index = self
+            .players
+            .iter()
+            .enumerate()
+            .map(|(i, p)| (p.uuid, i))
+            .collect();
+    }
+}
+
+impl Index<usize> for PlayerArena {
+    type Output = Player;
+
+    fn index(&self, i: usize) -> &Player {
+        &self.players[i]
+    }
+}
+
+impl IndexMut<usize> for PlayerArena {
+    fn index_mut(&mut self, i: usize) -> &mut Player {
+        &mut self.players[i]
+    }
+}
+
+impl<'a> IntoIterator for &'a PlayerArena {
+    type Item = &'a Player;
+    type IntoIter = slice::Iter<'a, Player>;
+
+    fn into_iter(self) -> slice::Iter<'a, Player> {
+        self.players.iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a mut PlayerArena {
+    type Item = &'a mut Player;
+    type IntoIter = slice::IterMut<'a, Player>;
+
+    fn into_iter(self) -> slice::IterMut<'a, Player> {
+        self.players.iter_mut()
+    }
+}
+
+impl IntoIterator for PlayerArena {
+    type Item = Player;
+    type IntoIter = std::vec::IntoIter<Player>;
+
+    fn into_iter(self) -> std::vec::IntoIter<Player> {
+        self.players.into_iter()
+    }
+}
+
+impl FromIterator<Player> for PlayerArena {
+    fn from_iter<I: IntoIterator<Item = Player>>(iter: I) -> PlayerArena {
+        let mut arena = PlayerArena::new();
+        arena.extend(iter);
+        arena
+    }
+}
+
+impl Extend<Player> for PlayerArena {
+    fn extend<I: IntoIterator<Item = Player>>(&mut self, iter: I) {
+        for player in iter {
+            self.push(player);
+        }
+    }
+}
+
+impl From<Vec<Player>> for PlayerArena {
+    fn from(players: Vec<Player>) -> PlayerArena {
+        players.into_iter().collect()
+    }
+}
+
+/// Takes a player out of the `Rc<RefCell<_>>` that constructors accepted before players were
+/// stored in an arena. A player who is still shared is copied, so the other copies no longer
+/// follow the tournament.
+pub(crate) fn unwrap_player(player: Rc<RefCell<Player>>) -> Player {
+    Rc::try_unwrap(player)
+        .map(RefCell::into_inner)
+        .unwrap_or_else(|shared| shared.borrow().clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn arena(n: usize) -> PlayerArena {
+        (0..n)
+            .map(|i| Player::new(&format!("Player {}", i)))
+            .collect()
+    }
+
+    #[test]
+    fn lookup_survives_removal_and_reordering() {
+        let mut players = arena(4);
+        let uuids: Vec<Uuid> = players.iter().map(|p| p.uuid).collect();
+
+        assert_eq!(players.remove(uuids[1]).unwrap().uuid, uuids[1]);
+        assert!(players.get(uuids[1]).is_none());
+        assert_eq!(players.get(uuids[3]).unwrap().name, "Player 3");
+
+        players.reorder(&[uuids[3], uuids[0]]);
+        let order: Vec<Uuid> = players.iter().map(|p| p.uuid).collect();
+        assert_eq!(order, [uuids[3], uuids[0], uuids[2]]);
+        assert_eq!(players.position(uuids[2]), Some(2));
+
+        // Changing a UUID by index doesn't leave a stale position behind
+        let renamed = Uuid::new_v4();
+        players[0].uuid = renamed;
+        assert!(players.get(uuids[3]).is_none());
+        assert_eq!(players.get(renamed).unwrap().name, "Player 3");
+    }
+
+    #[test]
+    fn pair_mut_returns_both_players_in_order() {
+        let mut players = arena(3);
+        let (a, c) = (players[0].uuid, players[2].uuid);
+
+        let (first, second) = players.pair_mut(c, a).unwrap();
+        assert_eq!((first.uuid, second.uuid), (c, a));
+        first.win_match();
+        assert_eq!(players.get(c).unwrap().match_points, 3);
+
+        assert!(players.pair_mut(a, a).is_none());
+        assert!(players.pair_mut(a, Uuid::new_v4()).is_none());
+    }
+
+    #[test]
+    fn former_players_are_only_opponents() {
+        let mut players = arena(2);
+        let cut = Player::new("Cut");
+        let uuid = cut.uuid;
+        players.retire(vec![cut]);
+
+        assert_eq!(players.len(), 2);
+        assert!(players.get(uuid).is_none());
+        assert_eq!(players.opponent(uuid).unwrap().name, "Cut");
+    }
+
+    #[test]
+    fn shared_players_are_copied() {
+        let shared = Rc::new(RefCell::new(Player::new("Shared")));
+        let player = unwrap_player(Rc::clone(&shared));
+        assert_eq!(player.uuid, shared.borrow().uuid);
+
+        let unique = Rc::new(RefCell::new(Player::new("Unique")));
+        assert_eq!(unwrap_player(unique).name, "Unique");
+    }
+}
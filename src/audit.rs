@@ -0,0 +1,83 @@
+//! Audit log of changes made to a tournament, so that organizers can later show what was changed
+//! and when.
+
+use crate::history::MatchResult;
+use crate::Tournament;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Something that happened to the tournament and should be kept on record
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub enum AuditEvent {
+    /// A setting was changed, e.g. by reloading the configuration file
+    ConfigChanged {
+        setting: String,
+        old: String,
+        new: String,
+    },
+    /// The seed all random decisions of a round were derived from
+    RoundSeed { seed: u64 },
+    /// The seed of the random draw between players tied on all tiebreakers
+    TiebreakSeed { seed: u64 },
+    /// The result of a pairing was corrected
+    ResultAmended {
+        pairing: uuid::Uuid,
+        old: MatchResult,
+        new: MatchResult,
+    },
+}
+
+impl fmt::Display for AuditEvent {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AuditEvent::ConfigChanged { setting, old, new } => {
+                write!(f, "Changed {} from {} to {}", setting, old, new)
+            }
+            AuditEvent::RoundSeed { seed } => write!(f, "Round seed: {}", seed),
+            AuditEvent::TiebreakSeed { seed } => write!(f, "Final tiebreak draw seed: {}", seed),
+            AuditEvent::ResultAmended { pairing, old, new } => write!(
+                f,
+                "Amended result of pairing {} from {}-{}-{} to {}-{}-{}",
+                pairing,
+                old.home_score,
+                old.away_score,
+                old.drawn,
+                new.home_score,
+                new.away_score,
+                new.drawn
+            ),
+        }
+    }
+}
+
+/// A single entry of the audit log
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct AuditEntry {
+    /// The round the tournament was in when the event happened
+    pub round: u32,
+    /// Seconds since the Unix epoch
+    pub timestamp: u64,
+    pub event: AuditEvent,
+}
+
+impl Tournament {
+    /// Appends an event to the audit log, stamped with the current round and time
+    pub(crate) fn log(&mut self, event: AuditEvent) {
+        self.audit_log.push(AuditEntry {
+            round: self.current_round,
+            timestamp: unix_time(),
+            event,
+        });
+    }
+}
+
+/// Current time in seconds since the Unix epoch
+pub fn unix_time() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
@@ -0,0 +1,238 @@
+//! Participant lists and results in the CSV layouts that online bracket sites like Challonge and
+//! start.gg accept for bulk upload, so that an event run partly online and partly offline doesn't
+//! need its players or results entered twice.
+//!
+//! Participant lists have a header line naming their columns, of which the name (`Name`,
+//! `Participant`, or `Display Name`), the gamer tag (`Gamer Tag`, `GamerTag`, or `Tag`), and the
+//! seed (`Seed`) are read and any others skipped:
+//!
+//! ```text
+//! Seed,Name,Gamer Tag,Email
+//! 2,Bob,,bob@example.com
+//! 1,Alice Smith,Ally,
+//! ```
+//!
+//! A list without a header line has the name in the first column and the seed in the second. The
+//! gamer tag becomes the player's alias, or their name if there is no name column. Players are
+//! returned in seeding order, with players without a seed last in the order they are listed.
+
+use crate::roster::{self, RosterError};
+use crate::{Player, Tournament};
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+
+/// Reads a participant list from a CSV file
+pub fn load_participants<P: AsRef<Path>>(path: P) -> Result<Vec<Player>, RosterError> {
+    parse_participants(&fs::read_to_string(path)?)
+}
+
+/// Parses a participant list in CSV form
+///
+/// # Example
+///
+/// ```
+/// use swyss::bracket;
+///
+/// let players =
+///     bracket::parse_participants("Seed,Name,Gamer Tag\n2,Bob,\n1,Alice Smith,Ally\n").unwrap();
+/// assert_eq!(players[0].name, "Alice Smith");
+/// assert_eq!(players[0].display_name(), "Ally");
+/// assert_eq!(players[1].alias, None);
+/// ```
+pub fn parse_participants(text: &str) -> Result<Vec<Player>, RosterError> {
+    let mut players: Vec<(Option<u32>, Player)> = Vec::new();
+    // Columns of the name, the gamer tag, and the seed
+    let mut columns = None;
+
+    for (i, line) in text.lines().enumerate() {
+        let error = |message: String| RosterError::Line(i + 1, message);
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let fields = roster::fields(line).map_err(error)?;
+        let (name, tag, seed) = match columns {
+            Some(columns) => columns,
+            None => {
+                let column = |names: &[&str]| {
+                    fields
+                        .iter()
+                        .position(|field| names.iter().any(|name| field.eq_ignore_ascii_case(name)))
+                };
+                let name = column(&["name", "participant", "display name"]);
+                let tag = column(&["gamer tag", "gamertag", "tag"]);
+                let seed = column(&["seed"]);
+                if name.is_some() || tag.is_some() {
+                    columns = Some((name, tag, seed));
+                    continue;
+                }
+                *columns.insert((Some(0), None, Some(1)))
+            }
+        };
+
+        let field = |column: Option<usize>| {
+            column
+                .and_then(|column| fields.get(column))
+                .filter(|field| !field.is_empty())
+        };
+        let tag = field(tag);
+        let mut player = match field(name).or(tag) {
+            Some(name) => Player::new(name),
+            None => return Err(error(String::from("The name is missing"))),
+        };
+        if field(name).is_some() {
+            player.alias = tag.cloned();
+        }
+        if players.iter().any(|(_, p)| p.name == player.name) {
+            return Err(error(format!("{} is listed twice", player.name)));
+        }
+
+        let seed = match field(seed) {
+            None => None,
+            Some(seed) => match seed.parse::<u32>() {
+                Ok(seed) => Some(seed),
+                Err(_) => return Err(error(format!("The seed \"{}\" is not a number", seed))),
+            },
+        };
+        players.push((seed, player));
+    }
+
+    // The sort is stable, so players without a seed keep the order they are listed in
+    players.sort_by_key(|(seed, _)| (seed.is_none(), *seed));
+
+    Ok(players.into_iter().map(|(_, player)| player).collect())
+}
+
+impl Tournament {
+    /// Returns the results of all rounds as CSV for bulk upload to a bracket site: one line per
+    /// match with the round, the match number within the round, both players by the name they
+    /// compete under, the score as games won by each (`2-1`), and the winner, which is empty for
+    /// a draw. Matches without a result and byes are left out.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use swyss::{Player, Tournament};
+    ///
+    /// let players = vec![
+    ///     Player::new("Alice"),
+    ///     Player::new("Bob"),
+    /// ];
+    /// let mut tourn = Tournament::with_players(players).unwrap();
+    /// let pairings = tourn.next_round().unwrap().unwrap();
+    /// tourn.end_match(pairings[0].0, 2, 1, 0).unwrap();
+    ///
+    /// let csv = tourn.results_csv();
+    /// let winner = &pairings[0].1;
+    /// assert!(csv.starts_with("Round,Match,Player 1,Player 2,Score,Winner\n"));
+    /// assert!(csv.ends_with(&format!("2-1,{}\n", winner)));
+    /// ```
+    pub fn results_csv(&self) -> String {
+        let name = |uuid: uuid::Uuid| {
+            self.player(uuid)
+                .map(|p| String::from(p.display_name()))
+                .unwrap_or_default()
+        };
+
+        let mut csv = String::from("Round,Match,Player 1,Player 2,Score,Winner\n");
+        for round in self.round_history() {
+            for (i, pair) in round.pairings.iter().enumerate() {
+                let result = match pair.result {
+                    Some(result) => result,
+                    None => continue,
+                };
+                let winner = if result.double_loss {
+                    String::new()
+                } else if result.home_score > result.away_score {
+                    name(pair.home)
+                } else if result.away_score > result.home_score {
+                    name(pair.away)
+                } else {
+                    String::new()
+                };
+                writeln!(
+                    csv,
+                    "{},{},{},{},{}-{},{}",
+                    round.number,
+                    i + 1,
+                    csv_field(&name(pair.home)),
+                    csv_field(&name(pair.away)),
+                    result.home_score,
+                    result.away_score,
+                    csv_field(&winner)
+                )
+                .expect("writing to a string succeeds");
+            }
+        }
+
+        csv
+    }
+}
+
+/// Quotes a field if it contains commas, quotes, or line breaks, doubling the quotes inside
+fn csv_field(field: &str) -> String {
+    if field.contains(&[',', '"', '\n', '\r'][..]) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        String::from(field)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn participants_and_results() {
+        let players = parse_participants(
+            "Participant,Seed,Email\n\
+             \"Doe, John\",,john@example.com\n\
+             Carol,2,\n\
+             \n\
+             Bob,1,\n",
+        )
+        .unwrap();
+        let names: Vec<&str> = players.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, ["Bob", "Carol", "Doe, John"]);
+
+        // Without a header, the name comes first and the seed second
+        let players = parse_participants("Alice,2\nBob,1\nCarol\n").unwrap();
+        let names: Vec<&str> = players.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, ["Bob", "Alice", "Carol"]);
+
+        // A gamer tag alone is the name
+        let players = parse_participants("Tag,Seed\nAlly,1\n").unwrap();
+        assert_eq!(
+            (players[0].name.as_str(), players[0].alias.as_ref()),
+            ("Ally", None)
+        );
+
+        let error = |text: &str| parse_participants(text).err().unwrap().to_string();
+        assert_eq!(
+            error("Name,Seed\nAlice,first\n"),
+            "Line 2: The seed \"first\" is not a number"
+        );
+        assert_eq!(
+            error("Name\nAlice\nAlice\n"),
+            "Line 3: Alice is listed twice"
+        );
+
+        let mut tourn = Tournament::with_players(
+            ["Doe, John", "Carol", "Bob"]
+                .iter()
+                .map(|name| Player::new(name))
+                .collect(),
+        )
+        .unwrap();
+        let pairings = tourn.next_round().unwrap().unwrap();
+        tourn.end_match(pairings[0].0, 1, 1, 1).unwrap();
+        let csv = tourn.results_csv();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[1].starts_with("1,1,"));
+        assert!(lines[1].ends_with(",1-1,"));
+        assert_eq!(csv_field("Doe, John"), "\"Doe, John\"");
+        assert_eq!(csv_field("Bob \"B\""), "\"Bob \"\"B\"\"\"");
+    }
+}
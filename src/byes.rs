@@ -0,0 +1,339 @@
+//! Byes requested in advance, e.g. by a player who can't make one round of a weekend event. A
+//! player with a requested bye isn't paired in that round and receives the score of the bye
+//! instead, typically half a point or nothing. Unlike the bye granted for an uneven number of
+//! players, a requested bye doesn't keep the player from being granted that one later.
+
+use crate::{ByeScore, LockError, Player, Tournament};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// A bye requested by a player for a single round
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct RequestedBye {
+    pub player: uuid::Uuid,
+    pub round: u32,
+    pub score: ByeScore,
+}
+
+/// A bye that broke `Tournament::max_byes`, or was withheld because of it. Byes are never granted
+/// beyond the limit silently: if everyone available has reached it, a player sits the round out
+/// instead, and if a round robin schedule or a published preview grants one anyway, it is
+/// recorded.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub enum ByeWarning {
+    /// The player was granted a bye in the round, and now has more byes than the limit
+    OverLimit {
+        player: uuid::Uuid,
+        round: u32,
+        byes: u32,
+    },
+    /// The player could neither be paired nor granted a bye in the round, because everyone
+    /// available had reached the limit
+    Withheld { player: uuid::Uuid, round: u32 },
+}
+
+impl ByeWarning {
+    /// The round the warning is about
+    pub fn round(&self) -> u32 {
+        match self {
+            ByeWarning::OverLimit { round, .. } | ByeWarning::Withheld { round, .. } => *round,
+        }
+    }
+}
+
+impl fmt::Display for ByeWarning {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ByeWarning::OverLimit {
+                player,
+                round,
+                byes,
+            } => write!(
+                f,
+                "Player {} has {} byes after round {}!",
+                player, byes, round
+            ),
+            ByeWarning::Withheld { player, round } => write!(
+                f,
+                "Player {} sits out round {} because everyone has had the maximum number of byes!",
+                player, round
+            ),
+        }
+    }
+}
+
+/// Reasons why a bye can't be requested
+#[derive(Debug, PartialEq)]
+pub enum ByeRequestError {
+    /// There is no such player
+    UnknownPlayer(uuid::Uuid),
+    /// The round was already paired
+    RoundPaired(u32),
+    /// The tournament doesn't have that many rounds
+    NoSuchRound(u32),
+    /// The tournament is complete, so no more byes can be requested or withdrawn
+    Finished,
+    /// The tournament was opened read-only, so byes can't be requested or withdrawn
+    ReadOnly,
+}
+
+impl std::error::Error for ByeRequestError {}
+
+impl fmt::Display for ByeRequestError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ByeRequestError::UnknownPlayer(uuid) => write!(f, "Unknown player {}!", uuid),
+            ByeRequestError::RoundPaired(round) => {
+                write!(f, "Round {} has already been paired!", round)
+            }
+            ByeRequestError::NoSuchRound(round) => write!(f, "There is no round {}!", round),
+            ByeRequestError::Finished => write!(f, "The tournament is already finished!"),
+            ByeRequestError::ReadOnly => write!(f, "The tournament was opened read-only!"),
+        }
+    }
+}
+
+impl From<LockError> for ByeRequestError {
+    fn from(e: LockError) -> ByeRequestError {
+        match e {
+            LockError::Finished => ByeRequestError::Finished,
+            LockError::ReadOnly => ByeRequestError::ReadOnly,
+        }
+    }
+}
+
+impl Tournament {
+    /// Requests a bye for a player in a future round, worth the given score. A second request
+    /// for the same round replaces the first.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use swyss::{ByeScore, Player, Tournament};
+    ///
+    /// let players = (1..5)
+    ///     .map(|i| Player::new(&format!("Player {}", i)))
+    ///     .collect();
+    /// let mut tourn = Tournament::with_players(players).unwrap();
+    /// let away = tourn.player_arena()[0].uuid;
+    /// tourn.request_bye(away, 1, ByeScore::Draw).unwrap();
+    ///
+    /// // The other three players are paired, so one of them gets the regular bye
+    /// assert_eq!(tourn.next_round().unwrap().unwrap().len(), 1);
+    /// assert_eq!(tourn.player(away).unwrap().match_points, 1);
+    /// assert!(!tourn.player(away).unwrap().has_bye);
+    /// assert_eq!(tourn.round(1).unwrap().requested_byes[0].player, away);
+    /// ```
+    pub fn request_bye(
+        &mut self,
+        player: uuid::Uuid,
+        round: u32,
+        score: ByeScore,
+    ) -> Result<(), ByeRequestError> {
+        self.check_unlocked()?;
+        if !self.players.iter().any(|p| p.uuid == player) {
+            return Err(ByeRequestError::UnknownPlayer(player));
+        }
+        if round <= self.current_round {
+            return Err(ByeRequestError::RoundPaired(round));
+        }
+        if round > self.rounds {
+            return Err(ByeRequestError::NoSuchRound(round));
+        }
+
+        self.cancel_bye(player, round)?;
+        self.requested_byes.push(RequestedBye {
+            player,
+            round,
+            score,
+        });
+
+        Ok(())
+    }
+
+    /// Withdraws a bye requested for a round that hasn't been paired yet. Returns whether there
+    /// was such a request.
+    pub fn cancel_bye(&mut self, player: uuid::Uuid, round: u32) -> Result<bool, ByeRequestError> {
+        self.check_unlocked()?;
+        if round <= self.current_round {
+            return Ok(false);
+        }
+
+        let requests = self.requested_byes.len();
+        self.requested_byes
+            .retain(|bye| bye.player != player || bye.round != round);
+        Ok(self.requested_byes.len() < requests)
+    }
+
+    /// Returns the byes that broke `max_byes` and the byes withheld because of it, in the order of
+    /// the rounds
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use swyss::byes::ByeWarning;
+    /// use swyss::{Player, Tournament};
+    ///
+    /// let players = (1..4)
+    ///     .map(|i| Player::new(&format!("Player {}", i)))
+    ///     .collect();
+    /// let mut tourn = Tournament::with_players(players).unwrap();
+    /// tourn.set_rounds(4).unwrap();
+    /// while let Some(pairings) = tourn.next_round().unwrap() {
+    ///     tourn.end_match(pairings[0].0, 2, 0, 0).unwrap();
+    /// }
+    ///
+    /// // Everyone had a bye in the first three rounds, so someone sits out the fourth
+    /// assert!(matches!(
+    ///     tourn.bye_warnings(),
+    ///     [ByeWarning::Withheld { round: 4, .. }]
+    /// ));
+    /// ```
+    pub fn bye_warnings(&self) -> &[ByeWarning] {
+        &self.bye_warnings
+    }
+
+    /// Returns all byes requested by a player, including those of rounds already played
+    pub fn requested_byes_of(&self, player: uuid::Uuid) -> Vec<RequestedBye> {
+        self.requested_byes
+            .iter()
+            .filter(|bye| bye.player == player)
+            .copied()
+            .collect()
+    }
+
+    /// Returns the requested byes of the current round of those of the given players who are
+    /// available in it, which are awarded once the round is paired
+    pub(crate) fn requested_byes_in_round(&self, players: &[&Player]) -> Vec<RequestedBye> {
+        let round = self.current_round;
+        self.requested_byes
+            .iter()
+            .filter(|bye| bye.round == round)
+            .filter(|bye| {
+                players
+                    .iter()
+                    .any(|p| p.uuid == bye.player && p.availability.is_available(round))
+            })
+            .copied()
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn players(n: usize) -> Vec<Player> {
+        (1..=n)
+            .map(|i| Player::new(format!("Player {}", i).as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn requested_byes() {
+        let players = players(4);
+        let uuids: Vec<_> = players.iter().map(|p| p.uuid).collect();
+        let mut tourn = Tournament::with_players(players).unwrap();
+        tourn.rounds = 3;
+
+        assert_eq!(
+            tourn.request_bye(uuids[0], 4, ByeScore::Draw),
+            Err(ByeRequestError::NoSuchRound(4))
+        );
+        tourn.request_bye(uuids[0], 2, ByeScore::Draw).unwrap();
+        tourn.request_bye(uuids[1], 2, ByeScore::Draw).unwrap();
+        tourn.request_bye(uuids[1], 2, ByeScore::Loss).unwrap();
+        tourn.request_bye(uuids[2], 3, ByeScore::Draw).unwrap();
+        assert!(tourn.cancel_bye(uuids[2], 3).unwrap());
+        assert!(!tourn.cancel_bye(uuids[2], 3).unwrap());
+        assert_eq!(tourn.requested_byes_of(uuids[1]).len(), 1);
+
+        for (uuid, _, _) in tourn.next_round().unwrap().unwrap() {
+            tourn.end_match(uuid, 2, 0, 0).unwrap();
+        }
+        let before: Vec<_> = uuids
+            .iter()
+            .map(|uuid| tourn.player(*uuid).unwrap().match_points)
+            .collect();
+
+        // Only the two players without a request are paired in the second round
+        let pairings = tourn.next_round().unwrap().unwrap();
+        assert_eq!(pairings.len(), 1);
+        assert_eq!(tourn.round(2).unwrap().bye, None);
+        assert_eq!(
+            tourn.request_bye(uuids[2], 2, ByeScore::Draw),
+            Err(ByeRequestError::RoundPaired(2))
+        );
+        assert!(!tourn.cancel_bye(uuids[0], 2).unwrap());
+
+        let p = tourn.player(uuids[0]).unwrap();
+        assert_eq!(p.match_points, before[0] + 1);
+        assert_eq!(p.matches_played, 2);
+        let p = tourn.player(uuids[1]).unwrap();
+        assert_eq!(p.match_points, before[1]);
+        assert_eq!(p.matches_played, 2);
+        assert!(!p.has_bye);
+
+        let round = tourn.round(2).unwrap();
+        assert_eq!(round.requested_byes.len(), 2);
+        assert!(round.pairing_of(uuids[0]).is_none());
+
+        tourn.end_match(pairings[0].0, 2, 1, 0).unwrap();
+        assert_eq!(tourn.check_invariants(), Ok(()));
+
+        let loaded = Tournament::from_json(&tourn.to_json()).unwrap();
+        assert_eq!(
+            loaded.requested_byes_of(uuids[0]),
+            tourn.requested_byes_of(uuids[0])
+        );
+        assert_eq!(loaded.round_history(), tourn.round_history());
+    }
+
+    fn play_round(tourn: &mut Tournament) {
+        for (uuid, _, _) in tourn.next_round().unwrap().unwrap() {
+            tourn.end_match(uuid, 2, 0, 0).unwrap();
+        }
+    }
+
+    #[test]
+    fn bye_limit() {
+        let players = players(3);
+        let uuids: Vec<_> = players.iter().map(|p| p.uuid).collect();
+        let mut tourn = Tournament::with_players(players).unwrap();
+        tourn.rounds = 8;
+        tourn.max_byes = 2;
+
+        for _ in 0..6 {
+            play_round(&mut tourn);
+        }
+        assert!(tourn.bye_warnings().is_empty());
+        assert!(tourn.players().all(|p| p.byes == 2));
+
+        // A player who drops and comes back keeps their byes
+        tourn.drop_player(uuids[0]).unwrap();
+        assert_eq!(tourn.readd_player(uuids[0]).unwrap().unwrap().byes, 2);
+
+        // Everyone reached the limit, so the preview leaves someone out, but the organizer may
+        // still give them a third bye
+        let mut preview = tourn.preview_round().unwrap().unwrap();
+        assert_eq!(preview.bye, None);
+        let (home, away) = preview.pairings[0];
+        let left_out = *uuids.iter().find(|&&u| u != home && u != away).unwrap();
+        preview.bye = Some(left_out);
+        tourn.publish_round(&preview).unwrap();
+        assert_eq!(
+            tourn.bye_warnings(),
+            [ByeWarning::OverLimit {
+                player: left_out,
+                round: 7,
+                byes: 3
+            }]
+        );
+
+        let loaded = Tournament::from_json(&tourn.to_json()).unwrap();
+        assert_eq!(loaded.bye_warnings(), tourn.bye_warnings());
+        assert_eq!(loaded.player(left_out).unwrap().byes, 3);
+    }
+}
@@ -0,0 +1,237 @@
+//! Analysis of who can still make the top cut going into the final round. Every combination of
+//! final round results is evaluated, so this is only feasible for small brackets.
+
+use crate::Tournament;
+use std::fmt;
+
+/// Largest number of final round pairings that can be analysed. Each pairing has three possible
+/// outcomes, so the number of combinations grows as 3^n.
+pub const MAX_PAIRINGS: usize = 10;
+
+/// A player's position with respect to the cut. Only match points are considered certain; a
+/// player who ends up tied on points at the edge of the cut depends on tiebreakers and is never
+/// considered safe.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ClinchStatus {
+    /// Makes the cut regardless of any result
+    Locked,
+    /// Makes the cut with a draw, so the player can intentionally draw in
+    DrawIn,
+    /// Makes the cut with a win, but a draw isn't enough
+    MustWin,
+    /// Can still make the cut, but even a win doesn't guarantee it
+    NeedsHelp,
+    /// Can't make the cut anymore
+    Eliminated,
+}
+
+/// Result of the clinch analysis for a single player
+#[derive(Clone, Debug, PartialEq)]
+pub struct Clinch {
+    pub uuid: uuid::Uuid,
+    pub name: String,
+    pub status: ClinchStatus,
+}
+
+/// The analysis is only possible once the final round has been paired, and only if the bracket
+/// is small enough
+#[derive(Debug, PartialEq)]
+pub enum ClinchError {
+    /// The tournament isn't in its final round
+    NotFinalRound,
+    /// There are more than `MAX_PAIRINGS` pairings in the final round
+    TooManyPairings(usize),
+}
+
+impl std::error::Error for ClinchError {}
+
+impl fmt::Display for ClinchError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ClinchError::NotFinalRound => write!(f, "The final round hasn't been paired yet!"),
+            ClinchError::TooManyPairings(n) => write!(
+                f,
+                "Cannot analyse {} pairings, at most {} are supported!",
+                n, MAX_PAIRINGS
+            ),
+        }
+    }
+}
+
+/// Which outcomes of a player's own match were seen to guarantee a spot or to allow one
+#[derive(Clone, Copy)]
+struct Outcomes {
+    /// Indexed by the player's own result: win, draw, loss
+    always_in: [bool; 3],
+    possibly_in: [bool; 3],
+    seen: [bool; 3],
+}
+
+impl Tournament {
+    /// Determines for every player whether they are locked for a top cut of `cut_size` players,
+    /// can draw in, must win, need help, or are eliminated. Call this after the final round has
+    /// been paired and before its results are entered.
+    pub fn clinch_analysis(&self, cut_size: usize) -> Result<Vec<Clinch>, ClinchError> {
+        if self.current_round != self.rounds || self.rounds == 0 {
+            return Err(ClinchError::NotFinalRound);
+        }
+
+        let pairings: Vec<(usize, usize)> = self
+            .pairings
+            .values()
+            .filter_map(|pair| {
+                let home = self.players.position(pair.home)?;
+                let away = self.players.position(pair.away)?;
+                Some((home, away))
+            })
+            .collect();
+
+        if pairings.len() > MAX_PAIRINGS {
+            return Err(ClinchError::TooManyPairings(pairings.len()));
+        }
+
+        let base: Vec<u32> = self.players.iter().map(|p| p.match_points).collect();
+
+        let mut outcomes = vec![
+            Outcomes {
+                always_in: [true; 3],
+                possibly_in: [false; 3],
+                seen: [false; 3],
+            };
+            base.len()
+        ];
+
+        // Players who don't play in the final round have their result fixed as a "win"
+        let mut own_result = vec![0; base.len()];
+
+        for combination in 0..3usize.pow(pairings.len() as u32) {
+            let mut points = base.clone();
+            let mut c = combination;
+
+            for &(home, away) in &pairings {
+                match c % 3 {
+                    0 => {
+                        points[home] += 3;
+                        own_result[home] = 0;
+                        own_result[away] = 2;
+                    }
+                    1 => {
+                        points[home] += 1;
+                        points[away] += 1;
+                        own_result[home] = 1;
+                        own_result[away] = 1;
+                    }
+                    _ => {
+                        points[away] += 3;
+                        own_result[home] = 2;
+                        own_result[away] = 0;
+                    }
+                }
+                c /= 3;
+            }
+
+            for (i, &mp) in points.iter().enumerate() {
+                let above = points.iter().filter(|&&other| other > mp).count();
+                let tied = points.iter().filter(|&&other| other == mp).count() - 1;
+
+                let o = &mut outcomes[i];
+                let r = own_result[i];
+                o.seen[r] = true;
+                o.always_in[r] &= above + tied < cut_size;
+                o.possibly_in[r] |= above < cut_size;
+            }
+        }
+
+        let analysis = self
+            .players
+            .iter()
+            .zip(&outcomes)
+            .map(|(p, o)| {
+                let results: Vec<usize> = (0..3).filter(|&r| o.seen[r]).collect();
+
+                let status = if results.iter().all(|&r| o.always_in[r]) {
+                    ClinchStatus::Locked
+                } else if o.seen[1] && o.always_in[1] {
+                    ClinchStatus::DrawIn
+                } else if o.seen[0] && o.always_in[0] && o.seen[1] {
+                    ClinchStatus::MustWin
+                } else if results.iter().any(|&r| o.possibly_in[r]) {
+                    ClinchStatus::NeedsHelp
+                } else {
+                    ClinchStatus::Eliminated
+                };
+
+                Clinch {
+                    uuid: p.uuid,
+                    name: p.name.clone(),
+                    status,
+                }
+            })
+            .collect();
+
+        Ok(analysis)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Player;
+
+    /// Plays the first of two rounds between four players, with the home player winning, and
+    /// pairs the final round. Returns the tournament and the names of the two winners.
+    fn final_round() -> (Tournament, Vec<String>) {
+        let players = (1..5)
+            .map(|i| Player::new(format!("Player {}", i).as_str()))
+            .collect();
+
+        let mut tourn = Tournament::with_players(players).unwrap();
+        let mut winners = Vec::new();
+
+        for (uuid, home, _) in &tourn.next_round().unwrap().unwrap() {
+            winners.push(home.clone());
+            assert!(tourn.end_match(*uuid, 2, 0, 0).is_ok());
+        }
+
+        assert!(tourn.next_round().unwrap().is_some());
+        (tourn, winners)
+    }
+
+    fn status(analysis: &[Clinch], name: &str) -> ClinchStatus {
+        analysis.iter().find(|c| c.name == name).unwrap().status
+    }
+
+    #[test]
+    fn statuses() {
+        let (tourn, winners) = final_round();
+        let losers: Vec<String> = tourn
+            .players
+            .iter()
+            .map(|p| p.name.clone())
+            .filter(|name| !winners.contains(name))
+            .collect();
+
+        // Only the winner of the final between the undefeated players takes first place
+        let analysis = tourn.clinch_analysis(1).unwrap();
+        assert_eq!(status(&analysis, &winners[0]), ClinchStatus::MustWin);
+        assert_eq!(status(&analysis, &losers[0]), ClinchStatus::Eliminated);
+
+        // A draw puts both undefeated players ahead of everyone else
+        let analysis = tourn.clinch_analysis(2).unwrap();
+        assert_eq!(status(&analysis, &winners[1]), ClinchStatus::DrawIn);
+        assert_eq!(status(&analysis, &losers[1]), ClinchStatus::NeedsHelp);
+
+        // Even with a loss, an undefeated player is at worst tied for second, and a win gets one
+        // of the others in
+        let analysis = tourn.clinch_analysis(3).unwrap();
+        assert_eq!(status(&analysis, &winners[0]), ClinchStatus::Locked);
+        assert_eq!(status(&analysis, &losers[0]), ClinchStatus::MustWin);
+    }
+
+    #[test]
+    fn not_final_round() {
+        let (mut tourn, _) = final_round();
+        tourn.rounds = 3;
+        assert_eq!(tourn.clinch_analysis(2), Err(ClinchError::NotFinalRound));
+    }
+}
@@ -0,0 +1,93 @@
+//! ANSI colors for terminal output, so that results and byes stand out in a noisy venue. Colors
+//! are only used on terminals, and never if the user opted out with `--no-color` or by setting
+//! the `NO_COLOR` environment variable (see <https://no-color.org>).
+
+use std::env;
+use std::io::IsTerminal;
+
+/// How a piece of text is highlighted
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Style {
+    /// Headers
+    Bold,
+    /// Winners
+    Green,
+    /// Byes
+    Yellow,
+    /// Errors
+    Red,
+}
+
+impl Style {
+    /// The SGR parameter of the style
+    fn code(self) -> &'static str {
+        match self {
+            Style::Bold => "1",
+            Style::Green => "32",
+            Style::Yellow => "33",
+            Style::Red => "31",
+        }
+    }
+}
+
+/// Whether text written to a stream is colored
+///
+/// # Example
+///
+/// ```
+/// use swyss::color::{Colors, Style};
+///
+/// assert_eq!(Colors::new(true).paint(Style::Red, "Error"), "\x1b[31mError\x1b[0m");
+/// assert_eq!(Colors::new(false).paint(Style::Red, "Error"), "Error");
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Colors {
+    enabled: bool,
+}
+
+impl Colors {
+    pub fn new(enabled: bool) -> Colors {
+        Colors { enabled }
+    }
+
+    /// Colors text written to `stream` if it is a terminal, unless `no_color` is set or the
+    /// `NO_COLOR` environment variable is set to anything but an empty string
+    pub fn detect<S: IsTerminal>(no_color: bool, stream: &S) -> Colors {
+        let opted_out = env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty());
+        Colors::new(!no_color && !opted_out && stream.is_terminal())
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Returns the text in the given style, or unchanged if colors are off
+    pub fn paint(&self, style: Style, text: &str) -> String {
+        if self.enabled && !text.is_empty() {
+            format!("\x1b[{}m{}\x1b[0m", style.code(), text)
+        } else {
+            String::from(text)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn paint() {
+        let colors = Colors::new(true);
+        assert_eq!(colors.paint(Style::Bold, "R1"), "\x1b[1mR1\x1b[0m");
+        assert_eq!(colors.paint(Style::Green, "Alice"), "\x1b[32mAlice\x1b[0m");
+        assert_eq!(colors.paint(Style::Yellow, ""), "");
+
+        let plain = Colors::default();
+        assert!(!plain.is_enabled());
+        assert_eq!(plain.paint(Style::Green, "Alice"), "Alice");
+
+        // A file is never a terminal
+        let file = std::fs::File::open(file!()).unwrap();
+        assert!(!Colors::detect(false, &file).is_enabled());
+    }
+}
@@ -0,0 +1,590 @@
+//! Tournament configuration files. The configuration can be re-read between rounds; settings that
+//! may not legally change mid-event are rejected, and every accepted change is recorded in the
+//! tournament's audit log.
+//!
+//! A configuration file is written in TOML, and every setting is optional:
+//!
+//! ```toml
+//! rounds = 5
+//! round_length = 50
+//! deadline = { grace = 5, outcome = "DoubleLoss" }
+//! featured_table = 1
+//! locale = "German"
+//! first_player = "Random"
+//! bye_selection = "LowestTiebreakers"
+//! bye_score = "Draw"
+//! max_byes = 1
+//! percentage_floor = { Fraction = { numerator = 1, denominator = 4 } }
+//! tiebreakers = ["Wins", "GameDifferential"]
+//! tiebreak_decimals = 3
+//! final_tiebreak = "RegistrationOrder"
+//! prizes = { Split = { pool = 500, percentages = [50, 30, 20] } }
+//! viewer = "XdgOpen"
+//!
+//! [event]
+//! name = "Friday Night"
+//! date = "2024-05-18"
+//! location = "Game Haven"
+//! ```
+
+use crate::audit::AuditEvent;
+use crate::deadline::DeadlinePolicy;
+use crate::event::EventInfo;
+use crate::explain::Criterion;
+use crate::locale::Locale;
+use crate::prizes::PayoutStructure;
+use crate::viewer::ViewerKind;
+use crate::{
+    ByeScore, ByeSelection, FinalTiebreak, FirstPlayer, LockError, PercentageFloor, Tournament,
+    MAX_TIEBREAK_DECIMALS,
+};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// Tournament settings read from a configuration file. Settings that are `None` are left as they
+/// are.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    /// Total number of rounds
+    pub rounds: Option<u32>,
+    /// Length of a round in minutes
+    pub round_length: Option<u32>,
+    /// Minutes after the end of a round until results are due, and what results that aren't
+    /// reported by then are recorded as, e.g. `deadline = { grace = 5, outcome = "Draw" }`
+    pub deadline: Option<DeadlinePolicy>,
+    /// Table number the featured pairing of each round is moved to
+    pub featured_table: Option<u32>,
+    /// Language of the texts shown to players and organizers
+    pub locale: Option<Locale>,
+    /// Who goes first in each pairing
+    pub first_player: Option<FirstPlayer>,
+    /// Who receives the bye. Volunteers are given by UUID, e.g.
+    /// `bye_selection = { Volunteers = ["..."] }`.
+    pub bye_selection: Option<ByeSelection>,
+    /// What the bye is worth. Only applies to byes granted after the change.
+    pub bye_score: Option<ByeScore>,
+    /// How many byes a player may be granted for an uneven number of players
+    pub max_byes: Option<u32>,
+    /// Lowest value win percentages count as, e.g. `percentage_floor = "None"` for chess
+    pub percentage_floor: Option<PercentageFloor>,
+    /// Tiebreakers applied after match points, in order, e.g. simpler ones for casual events
+    pub tiebreakers: Option<Vec<Criterion>>,
+    /// Decimal places tiebreaker values are shown with, e.g. 3 to match official Magic software.
+    /// Values above `MAX_TIEBREAK_DECIMALS` are capped to it.
+    pub tiebreak_decimals: Option<u32>,
+    /// How players tied on all tiebreakers are ordered. Seeds are given by UUID, e.g.
+    /// `final_tiebreak = { Seeds = ["..."] }`.
+    pub final_tiebreak: Option<FinalTiebreak>,
+    /// Prizes paid out over the final standings, e.g. `prizes = { Places = [12, 8, 6, 4] }` for
+    /// booster packs
+    pub prizes: Option<PayoutStructure>,
+    /// Details of the event. Only the details given are changed.
+    pub event: Option<EventInfo>,
+    /// Image viewer of the CLI's image mode. This isn't a tournament setting, so it is ignored
+    /// by `Tournament::apply_config()`.
+    pub viewer: Option<ViewerKind>,
+}
+
+/// Reading or applying a configuration fails if the file can't be read or parsed, or if it tries
+/// to change a setting in a way that isn't allowed at this point of the tournament
+#[derive(Debug)]
+pub enum ConfigError {
+    Io(io::Error),
+    Parse(toml::de::Error),
+    /// The number of rounds can't be reduced below the current round
+    RoundsTooLow {
+        requested: u32,
+        current: u32,
+    },
+    /// The tournament is complete, so its settings are final
+    Finished,
+    /// The tournament was opened read-only, so its settings can't be changed
+    ReadOnly,
+}
+
+impl std::error::Error for ConfigError {}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ConfigError::Io(e) => write!(f, "Could not read configuration: {}", e),
+            ConfigError::Parse(e) => write!(f, "Could not parse configuration: {}", e),
+            ConfigError::RoundsTooLow { requested, current } => write!(
+                f,
+                "Cannot reduce the number of rounds to {} during round {}!",
+                requested, current
+            ),
+            ConfigError::Finished => write!(f, "The tournament is already finished!"),
+            ConfigError::ReadOnly => write!(f, "The tournament was opened read-only!"),
+        }
+    }
+}
+
+impl From<io::Error> for ConfigError {
+    fn from(e: io::Error) -> ConfigError {
+        ConfigError::Io(e)
+    }
+}
+
+impl From<toml::de::Error> for ConfigError {
+    fn from(e: toml::de::Error) -> ConfigError {
+        ConfigError::Parse(e)
+    }
+}
+
+impl From<LockError> for ConfigError {
+    fn from(e: LockError) -> ConfigError {
+        match e {
+            LockError::Finished => ConfigError::Finished,
+            LockError::ReadOnly => ConfigError::ReadOnly,
+        }
+    }
+}
+
+impl Config {
+    /// Parses a configuration from TOML
+    pub fn from_toml(toml: &str) -> Result<Config, ConfigError> {
+        Ok(toml::from_str(toml)?)
+    }
+
+    /// Reads a configuration file
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Config, ConfigError> {
+        let toml = fs::read_to_string(path)?;
+        Config::from_toml(&toml)
+    }
+}
+
+impl Tournament {
+    /// Applies a configuration to the tournament. Meant to be called before the tournament
+    /// starts or between rounds. The whole configuration is validated first, so if any setting
+    /// can't be changed, nothing is changed at all. Returns the changes that were made, which are
+    /// also recorded in the audit log.
+    pub fn apply_config(&mut self, config: &Config) -> Result<Vec<AuditEvent>, ConfigError> {
+        self.check_unlocked()?;
+        if let Some(rounds) = config.rounds {
+            if rounds < self.current_round {
+                return Err(ConfigError::RoundsTooLow {
+                    requested: rounds,
+                    current: self.current_round,
+                });
+            }
+        }
+
+        let mut changes = Vec::new();
+
+        if let Some(rounds) = config.rounds {
+            if rounds != self.rounds {
+                changes.push(AuditEvent::ConfigChanged {
+                    setting: String::from("rounds"),
+                    old: self.rounds.to_string(),
+                    new: rounds.to_string(),
+                });
+                self.rounds = rounds;
+            }
+        }
+
+        if let Some(length) = config.round_length {
+            if Some(length) != self.round_length {
+                let old = match self.round_length {
+                    Some(old) => old.to_string(),
+                    None => String::from("none"),
+                };
+                changes.push(AuditEvent::ConfigChanged {
+                    setting: String::from("round_length"),
+                    old,
+                    new: length.to_string(),
+                });
+                self.round_length = Some(length);
+            }
+        }
+
+        if let Some(deadline) = config.deadline {
+            if Some(deadline) != self.deadline {
+                let old = match self.deadline {
+                    Some(old) => format!("{:?}", old),
+                    None => String::from("none"),
+                };
+                changes.push(AuditEvent::ConfigChanged {
+                    setting: String::from("deadline"),
+                    old,
+                    new: format!("{:?}", deadline),
+                });
+                self.deadline = Some(deadline);
+            }
+        }
+
+        if let Some(table) = config.featured_table {
+            if table != self.featured_table {
+                changes.push(AuditEvent::ConfigChanged {
+                    setting: String::from("featured_table"),
+                    old: self.featured_table.to_string(),
+                    new: table.to_string(),
+                });
+                self.featured_table = table;
+            }
+        }
+
+        if let Some(locale) = config.locale {
+            if locale != self.locale {
+                changes.push(AuditEvent::ConfigChanged {
+                    setting: String::from("locale"),
+                    old: format!("{:?}", self.locale),
+                    new: format!("{:?}", locale),
+                });
+                self.locale = locale;
+            }
+        }
+
+        if let Some(first_player) = config.first_player {
+            if first_player != self.first_player {
+                changes.push(AuditEvent::ConfigChanged {
+                    setting: String::from("first_player"),
+                    old: format!("{:?}", self.first_player),
+                    new: format!("{:?}", first_player),
+                });
+                self.first_player = first_player;
+            }
+        }
+
+        if let Some(bye_selection) = &config.bye_selection {
+            if *bye_selection != self.bye_selection {
+                changes.push(AuditEvent::ConfigChanged {
+                    setting: String::from("bye_selection"),
+                    old: format!("{:?}", self.bye_selection),
+                    new: format!("{:?}", bye_selection),
+                });
+                self.bye_selection = bye_selection.clone();
+            }
+        }
+
+        if let Some(bye_score) = config.bye_score {
+            if bye_score != self.bye_score {
+                changes.push(AuditEvent::ConfigChanged {
+                    setting: String::from("bye_score"),
+                    old: format!("{:?}", self.bye_score),
+                    new: format!("{:?}", bye_score),
+                });
+                self.bye_score = bye_score;
+            }
+        }
+
+        if let Some(max_byes) = config.max_byes {
+            if max_byes != self.max_byes {
+                changes.push(AuditEvent::ConfigChanged {
+                    setting: String::from("max_byes"),
+                    old: self.max_byes.to_string(),
+                    new: max_byes.to_string(),
+                });
+                self.max_byes = max_byes;
+            }
+        }
+
+        if let Some(floor) = config.percentage_floor {
+            if floor != self.percentage_floor() {
+                changes.push(AuditEvent::ConfigChanged {
+                    setting: String::from("percentage_floor"),
+                    old: self.percentage_floor().to_string(),
+                    new: floor.to_string(),
+                });
+                self.apply_percentage_floor(floor);
+            }
+        }
+
+        if let Some(tiebreakers) = &config.tiebreakers {
+            if *tiebreakers != self.tiebreakers {
+                changes.push(AuditEvent::ConfigChanged {
+                    setting: String::from("tiebreakers"),
+                    old: format!("{:?}", self.tiebreakers),
+                    new: format!("{:?}", tiebreakers),
+                });
+                self.tiebreakers = tiebreakers.clone();
+            }
+        }
+
+        if let Some(decimals) = config.tiebreak_decimals {
+            let decimals = decimals.min(MAX_TIEBREAK_DECIMALS);
+            if decimals != self.tiebreak_decimals {
+                changes.push(AuditEvent::ConfigChanged {
+                    setting: String::from("tiebreak_decimals"),
+                    old: self.tiebreak_decimals.to_string(),
+                    new: decimals.to_string(),
+                });
+                self.tiebreak_decimals = decimals;
+            }
+        }
+
+        if let Some(final_tiebreak) = &config.final_tiebreak {
+            if *final_tiebreak != self.final_tiebreak {
+                changes.push(AuditEvent::ConfigChanged {
+                    setting: String::from("final_tiebreak"),
+                    old: format!("{:?}", self.final_tiebreak),
+                    new: format!("{:?}", final_tiebreak),
+                });
+                self.final_tiebreak = final_tiebreak.clone();
+            }
+        }
+
+        if let Some(prizes) = &config.prizes {
+            if Some(prizes) != self.prizes.as_ref() {
+                let old = match &self.prizes {
+                    Some(old) => format!("{:?}", old),
+                    None => String::from("none"),
+                };
+                changes.push(AuditEvent::ConfigChanged {
+                    setting: String::from("prizes"),
+                    old,
+                    new: format!("{:?}", prizes),
+                });
+                self.prizes = Some(prizes.clone());
+            }
+        }
+
+        if let Some(event) = &config.event {
+            for (detail, old, new) in self.event.update(event) {
+                changes.push(AuditEvent::ConfigChanged {
+                    setting: format!("event.{}", detail),
+                    old,
+                    new,
+                });
+            }
+        }
+
+        for change in &changes {
+            self.log(change.clone());
+        }
+
+        Ok(changes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Player;
+
+    fn tournament() -> Tournament {
+        let players = (1..=4)
+            .map(|i| Player::new(format!("Player {}", i).as_str()))
+            .collect();
+        Tournament::with_players(players).unwrap()
+    }
+
+    #[test]
+    fn parse() {
+        let config = Config::from_toml("rounds = 5\nfirst_player = \"HigherSeed\"\n").unwrap();
+        assert_eq!(config.rounds, Some(5));
+        assert_eq!(config.round_length, None);
+        assert_eq!(config.first_player, Some(FirstPlayer::HigherSeed));
+        assert_eq!(config.viewer, None);
+        assert_eq!(
+            Config::from_toml("viewer = \"Preview\"").unwrap().viewer,
+            Some(ViewerKind::Preview)
+        );
+
+        assert_eq!(
+            Config::from_toml("prizes = { Places = [12, 8, 4.5] }")
+                .unwrap()
+                .prizes,
+            Some(PayoutStructure::Places(vec![12.0, 8.0, 4.5]))
+        );
+
+        assert_eq!(
+            Config::from_toml("deadline = { grace = 5, outcome = \"DoubleLoss\" }")
+                .unwrap()
+                .deadline,
+            Some(DeadlinePolicy {
+                grace: 5,
+                outcome: crate::deadline::DeadlineOutcome::DoubleLoss,
+            })
+        );
+
+        assert!(Config::from_toml("tiebreakers = 3").is_err());
+        assert!(Config::from_toml("tie_breakers = []").is_err());
+    }
+
+    #[test]
+    fn apply_between_rounds() {
+        let mut tourn = tournament();
+        tourn.next_round().unwrap();
+        let logged = tourn.audit_log.len();
+
+        let config = Config {
+            rounds: Some(4),
+            round_length: Some(50),
+            deadline: None,
+            featured_table: None,
+            locale: None,
+            first_player: None,
+            bye_selection: None,
+            bye_score: None,
+            max_byes: None,
+            percentage_floor: None,
+            tiebreakers: None,
+            tiebreak_decimals: None,
+            final_tiebreak: None,
+            prizes: None,
+            event: None,
+            viewer: None,
+        };
+
+        let changes = tourn.apply_config(&config).unwrap();
+        assert_eq!(changes.len(), 2);
+        assert_eq!(tourn.rounds, 4);
+        assert_eq!(tourn.round_length, Some(50));
+
+        assert_eq!(tourn.audit_log.len(), logged + 2);
+        assert_eq!(tourn.audit_log[logged].round, 1);
+        assert_eq!(
+            tourn.audit_log[logged].event,
+            AuditEvent::ConfigChanged {
+                setting: String::from("rounds"),
+                old: String::from("2"),
+                new: String::from("4"),
+            }
+        );
+
+        // Reapplying the same configuration changes nothing
+        assert!(tourn.apply_config(&config).unwrap().is_empty());
+        assert_eq!(tourn.audit_log.len(), logged + 2);
+    }
+
+    #[test]
+    fn event_details() {
+        let mut tourn = tournament();
+        tourn.event.name = Some(String::from("Friday Night"));
+
+        let config = Config::from_toml("[event]\ndate = \"2024-05-18\"\n").unwrap();
+        let changes = tourn.apply_config(&config).unwrap();
+        assert_eq!(
+            changes,
+            [AuditEvent::ConfigChanged {
+                setting: String::from("event.date"),
+                old: String::from("none"),
+                new: String::from("2024-05-18"),
+            }]
+        );
+        assert_eq!(tourn.event.title(), "Friday Night");
+        assert_eq!(tourn.event.details(), ["Date: 2024-05-18"]);
+
+        let loaded = Tournament::from_json(&tourn.to_json()).unwrap();
+        assert_eq!(loaded.event, tourn.event);
+    }
+
+    #[test]
+    fn percentage_floor() {
+        let mut tourn = tournament();
+        for (uuid, _, _) in tourn.next_round().unwrap().unwrap() {
+            tourn.end_match(uuid, 2, 0, 0).unwrap();
+        }
+        assert_eq!(tourn.standings()[0].game_win_percentage, 1.0);
+        assert_eq!(tourn.standings()[3].game_win_percentage, 1.0 / 3.0);
+
+        let config = Config::from_toml("percentage_floor = \"None\"").unwrap();
+        let changes = tourn.apply_config(&config).unwrap();
+        assert_eq!(
+            changes,
+            [AuditEvent::ConfigChanged {
+                setting: String::from("percentage_floor"),
+                old: String::from("1/3"),
+                new: String::from("none"),
+            }]
+        );
+        assert_eq!(tourn.standings()[3].game_win_percentage, 0.0);
+        assert_eq!(tourn.standings()[0].opponents_match_win_percentage, 0.0);
+
+        let config = Config::from_toml(
+            "percentage_floor = { Fraction = { numerator = 1, denominator = 4 } }",
+        )
+        .unwrap();
+        tourn.apply_config(&config).unwrap();
+        assert_eq!(tourn.standings()[3].game_win_percentage, 0.25);
+
+        // Players added later and loaded tournaments use the same floor
+        tourn.add_player(Player::new("Latecomer")).unwrap();
+        let loaded = Tournament::from_json(&tourn.to_json()).unwrap();
+        assert_eq!(
+            loaded.percentage_floor(),
+            PercentageFloor::Fraction {
+                numerator: 1,
+                denominator: 4,
+            }
+        );
+        for p in &loaded.players {
+            assert_eq!(
+                p.percentage_floor,
+                PercentageFloor::Fraction {
+                    numerator: 1,
+                    denominator: 4,
+                }
+            );
+        }
+    }
+
+    #[test]
+    fn tiebreakers() {
+        let mut tourn = tournament();
+        let config = Config::from_toml("tiebreakers = [\"Wins\", \"GameDifferential\"]").unwrap();
+        let changes = tourn.apply_config(&config).unwrap();
+        assert_eq!(
+            changes,
+            [AuditEvent::ConfigChanged {
+                setting: String::from("tiebreakers"),
+                old: format!("{:?}", Criterion::DEFAULT_TIEBREAKERS),
+                new: String::from("[Wins, GameDifferential]"),
+            }]
+        );
+        assert!(tourn.apply_config(&config).unwrap().is_empty());
+
+        let loaded = Tournament::from_json(&tourn.to_json()).unwrap();
+        assert_eq!(
+            loaded.tiebreakers,
+            [Criterion::Wins, Criterion::GameDifferential]
+        );
+    }
+
+    #[test]
+    fn reject_illegal_change() {
+        let mut tourn = tournament();
+        for (uuid, _, _) in tourn.next_round().unwrap().unwrap() {
+            tourn.end_match(uuid, 2, 0, 0).unwrap();
+        }
+        tourn.next_round().unwrap();
+        let logged = tourn.audit_log.len();
+
+        let config = Config {
+            rounds: Some(1),
+            round_length: Some(30),
+            deadline: None,
+            featured_table: None,
+            locale: None,
+            first_player: None,
+            bye_selection: None,
+            bye_score: None,
+            max_byes: None,
+            percentage_floor: None,
+            tiebreakers: None,
+            tiebreak_decimals: None,
+            final_tiebreak: None,
+            prizes: None,
+            event: None,
+            viewer: None,
+        };
+
+        match tourn.apply_config(&config) {
+            Err(ConfigError::RoundsTooLow { requested, current }) => {
+                assert_eq!(requested, 1);
+                assert_eq!(current, 2);
+            }
+            _ => panic!("Expected rounds to be rejected"),
+        }
+
+        // Nothing is applied if any setting is rejected
+        assert_eq!(tourn.rounds, 2);
+        assert_eq!(tourn.round_length, None);
+        assert_eq!(tourn.audit_log.len(), logged);
+    }
+}
@@ -0,0 +1,319 @@
+//! Constraints on who is paired against whom, registered by the organizer. Two players can be kept
+//! apart for the whole event, e.g. family members or players with a grudge, which pairing treats
+//! as a hard constraint that goes before avoiding rematches. Two players can also be paired in the
+//! first round if possible, e.g. friends who came together, which is a soft constraint that gives
+//! way to everything else.
+
+use crate::oracle::PlayerPair;
+use crate::{LockError, Player, Tournament};
+use std::fmt;
+
+/// Reasons why a constraint can't be registered
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConstraintError {
+    UnknownPlayer(uuid::Uuid),
+    /// Both players are the same
+    SamePlayer(uuid::Uuid),
+    /// Keeping the two players apart would leave the players available in the next round
+    /// without any pairing in which everyone avoids who they must
+    Unsatisfiable(uuid::Uuid, uuid::Uuid),
+    /// The two players must be kept apart, so they can't be paired in the first round
+    Avoided(uuid::Uuid, uuid::Uuid),
+    /// The player is already to be paired against someone else in the first round
+    AlreadyPreferred(uuid::Uuid),
+    /// The first round was already paired
+    AlreadyPaired,
+    /// The tournament is complete, so its players are final
+    Finished,
+    /// The tournament was opened read-only, so no players can be kept apart or paired together
+    ReadOnly,
+}
+
+impl std::error::Error for ConstraintError {}
+
+impl fmt::Display for ConstraintError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ConstraintError::UnknownPlayer(uuid) => write!(f, "Unknown player {}", uuid),
+            ConstraintError::SamePlayer(uuid) => {
+                write!(f, "Player {} can't be paired against themselves", uuid)
+            }
+            ConstraintError::Unsatisfiable(a, b) => write!(
+                f,
+                "Keeping players {} and {} apart would leave no possible pairing",
+                a, b
+            ),
+            ConstraintError::Avoided(a, b) => {
+                write!(f, "Players {} and {} must not be paired", a, b)
+            }
+            ConstraintError::AlreadyPreferred(uuid) => write!(
+                f,
+                "Player {} is already to be paired against someone else in the first round",
+                uuid
+            ),
+            ConstraintError::AlreadyPaired => write!(f, "The first round was already paired"),
+            ConstraintError::Finished => write!(f, "The tournament is already finished!"),
+            ConstraintError::ReadOnly => write!(f, "The tournament was opened read-only!"),
+        }
+    }
+}
+
+impl From<LockError> for ConstraintError {
+    fn from(e: LockError) -> ConstraintError {
+        match e {
+            LockError::Finished => ConstraintError::Finished,
+            LockError::ReadOnly => ConstraintError::ReadOnly,
+        }
+    }
+}
+
+impl Tournament {
+    /// Keeps two players from ever being paired against each other. Pairing only breaks this if
+    /// players drop out and there is no other way to pair the rest. Fails if the players available
+    /// in the next round couldn't be paired anymore, in which case nothing is changed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use swyss::constraints::ConstraintError;
+    /// use swyss::{Player, Tournament};
+    ///
+    /// let players: Vec<_> = (1..5)
+    ///     .map(|i| Player::new(&format!("Player {}", i)))
+    ///     .collect();
+    /// let uuids: Vec<_> = players.iter().map(|p| p.uuid).collect();
+    /// let mut tourn = Tournament::with_players(players).unwrap();
+    ///
+    /// tourn.avoid_pairing(uuids[0], uuids[1]).unwrap();
+    /// tourn.avoid_pairing(uuids[0], uuids[2]).unwrap();
+    /// // Player 1 would have nobody left to play
+    /// assert_eq!(
+    ///     tourn.avoid_pairing(uuids[0], uuids[3]),
+    ///     Err(ConstraintError::Unsatisfiable(uuids[0], uuids[3]))
+    /// );
+    /// ```
+    pub fn avoid_pairing(&mut self, a: uuid::Uuid, b: uuid::Uuid) -> Result<(), ConstraintError> {
+        self.check_unlocked()?;
+        let (first, second) = self.constrained_players(a, b)?;
+        if first.avoids(second) {
+            return Ok(());
+        }
+
+        if let Some(first) = self.players.get_mut(a) {
+            first.avoid.push(b);
+        }
+        if !self.constraints_satisfiable() {
+            if let Some(first) = self.players.get_mut(a) {
+                first.avoid.retain(|&uuid| uuid != b);
+            }
+            return Err(ConstraintError::Unsatisfiable(a, b));
+        }
+        self.preferred_pairs
+            .retain(|&(x, y)| (x, y) != (a, b) && (x, y) != (b, a));
+
+        Ok(())
+    }
+
+    /// Allows two players to be paired against each other again. Returns whether they were kept
+    /// apart before.
+    pub fn allow_pairing(&mut self, a: uuid::Uuid, b: uuid::Uuid) -> Result<bool, ConstraintError> {
+        self.check_unlocked()?;
+        let mut allowed = false;
+        for p in &mut self.players {
+            let other = match p.uuid {
+                uuid if uuid == a => b,
+                uuid if uuid == b => a,
+                _ => continue,
+            };
+            let before = p.avoid.len();
+            p.avoid.retain(|&uuid| uuid != other);
+            allowed |= p.avoid.len() < before;
+        }
+        Ok(allowed)
+    }
+
+    /// Pairs two players against each other in the first round if it is possible, i.e. if both
+    /// are available and neither gets the bye
+    pub fn prefer_pairing(&mut self, a: uuid::Uuid, b: uuid::Uuid) -> Result<(), ConstraintError> {
+        self.check_unlocked()?;
+        if self.current_round > 0 {
+            return Err(ConstraintError::AlreadyPaired);
+        }
+        let (first, second) = self.constrained_players(a, b)?;
+        if first.avoids(second) {
+            return Err(ConstraintError::Avoided(a, b));
+        }
+        for &player in &[a, b] {
+            let preferred = self
+                .preferred_pairs
+                .iter()
+                .find(|&&(x, y)| x == player || y == player);
+            match preferred {
+                Some(&(x, y)) if (x, y) == (a, b) || (x, y) == (b, a) => return Ok(()),
+                Some(_) => return Err(ConstraintError::AlreadyPreferred(player)),
+                None => {}
+            }
+        }
+
+        self.preferred_pairs.push((a, b));
+        Ok(())
+    }
+
+    /// Returns the pairs of players who are to be paired against each other in the first round
+    pub fn preferred_pairs(&self) -> &[(uuid::Uuid, uuid::Uuid)] {
+        &self.preferred_pairs
+    }
+
+    /// Checks whether the players available in the next round can be paired so that nobody plays
+    /// a player they must avoid. With an uneven number of players, one of them may be left over.
+    pub fn constraints_satisfiable(&self) -> bool {
+        let round = self.current_round + 1;
+        let mut players: Vec<&Player> = self
+            .players
+            .iter()
+            .filter(|p| p.availability.is_available(round))
+            .collect();
+        // The players with the most constraints first, so that dead ends show up early
+        players.sort_by_key(|p| std::cmp::Reverse(p.avoid.len()));
+        let may_skip = players.len() % 2 == 1;
+
+        pairable(&players, may_skip)
+    }
+
+    /// Looks up the two players of a constraint
+    fn constrained_players(
+        &self,
+        a: uuid::Uuid,
+        b: uuid::Uuid,
+    ) -> Result<PlayerPair<'_>, ConstraintError> {
+        if a == b {
+            return Err(ConstraintError::SamePlayer(a));
+        }
+        let find = |uuid: uuid::Uuid| {
+            self.players
+                .get(uuid)
+                .ok_or(ConstraintError::UnknownPlayer(uuid))
+        };
+        Ok((find(a)?, find(b)?))
+    }
+
+    /// Takes the preferred pairs out of the active players if this is the first round, returning
+    /// them along with the players who still need to be paired
+    pub(crate) fn take_preferred_pairs<'a>(
+        &self,
+        active: &[&'a Player],
+    ) -> (Vec<PlayerPair<'a>>, Vec<&'a Player>) {
+        let mut rest = active.to_vec();
+        if self.current_round != 1 {
+            return (Vec::new(), rest);
+        }
+
+        let mut pairs = Vec::new();
+        for &(a, b) in &self.preferred_pairs {
+            let position = |uuid| rest.iter().position(|p| p.uuid == uuid);
+            if let (Some(i), Some(j)) = (position(a), position(b)) {
+                // Removing the later player first keeps the index of the earlier one
+                let away = rest.remove(i.max(j));
+                let home = rest.remove(i.min(j));
+                if i < j {
+                    pairs.push((home, away));
+                } else {
+                    pairs.push((away, home));
+                }
+            }
+        }
+        debug_event!(preferred = pairs.len(), "paired preferred pairs");
+
+        (pairs, rest)
+    }
+}
+
+/// Checks whether the players can be paired without anyone playing a player they must avoid,
+/// leaving one of them over if `may_skip` is set
+fn pairable(players: &[&Player], may_skip: bool) -> bool {
+    let (first, rest) = match players.split_first() {
+        Some(split) => split,
+        None => return true,
+    };
+
+    for (i, other) in rest.iter().enumerate() {
+        if first.avoids(other) {
+            continue;
+        }
+        let mut others = rest.to_vec();
+        others.remove(i);
+        if pairable(&others, may_skip) {
+            return true;
+        }
+    }
+
+    may_skip && pairable(rest, false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn avoided_and_preferred_pairs() {
+        let players: Vec<_> = (1..7)
+            .map(|i| Player::new(format!("Player {}", i).as_str()))
+            .collect();
+        let uuids: Vec<uuid::Uuid> = players.iter().map(|p| p.uuid).collect();
+        let mut tourn = Tournament::with_players(players).unwrap();
+        tourn.rounds = 5;
+
+        for &other in &uuids[1..5] {
+            tourn.avoid_pairing(uuids[0], other).unwrap();
+        }
+        assert_eq!(
+            tourn.avoid_pairing(uuids[0], uuids[5]),
+            Err(ConstraintError::Unsatisfiable(uuids[0], uuids[5]))
+        );
+        assert!(tourn.allow_pairing(uuids[0], uuids[4]).unwrap());
+        assert!(!tourn.allow_pairing(uuids[0], uuids[4]).unwrap());
+        assert_eq!(
+            tourn.avoid_pairing(uuids[2], uuids[2]),
+            Err(ConstraintError::SamePlayer(uuids[2]))
+        );
+
+        tourn.prefer_pairing(uuids[2], uuids[3]).unwrap();
+        assert_eq!(
+            tourn.prefer_pairing(uuids[0], uuids[1]),
+            Err(ConstraintError::Avoided(uuids[0], uuids[1]))
+        );
+        assert_eq!(
+            tourn.prefer_pairing(uuids[3], uuids[5]),
+            Err(ConstraintError::AlreadyPreferred(uuids[3]))
+        );
+
+        // Player 1 never meets the players they must avoid
+        let met = |tourn: &Tournament, round: u32, a: usize, b: usize| {
+            tourn.round(round).unwrap().pairings.iter().any(|pair| {
+                (pair.home, pair.away) == (uuids[a], uuids[b])
+                    || (pair.home, pair.away) == (uuids[b], uuids[a])
+            })
+        };
+        for round in 1..3 {
+            let pairings = tourn
+                .next_round_with_seed(u64::from(round))
+                .unwrap()
+                .unwrap();
+            for (uuid, _, _) in pairings {
+                tourn.end_match(uuid, 2, 1, 0).unwrap();
+            }
+            assert!((1..4).all(|other| !met(&tourn, round, 0, other)));
+        }
+        assert!(met(&tourn, 1, 2, 3));
+        assert_eq!(
+            tourn.prefer_pairing(uuids[1], uuids[5]),
+            Err(ConstraintError::AlreadyPaired)
+        );
+
+        let loaded = Tournament::from_json(&tourn.to_json()).unwrap();
+        assert_eq!(loaded.preferred_pairs(), &[(uuids[2], uuids[3])]);
+        assert!(loaded.constraints_satisfiable());
+        let avoids = |p: &Player| p.avoid.contains(&uuids[1]);
+        assert!(loaded.players.iter().any(avoids));
+    }
+}
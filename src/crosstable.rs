@@ -0,0 +1,666 @@
+//! Queries over the results of the whole event: the record of a single player, and a cross-table
+//! of who beat whom, as needed for chess wall charts and for settling disputes.
+
+use crate::archive::escape_html;
+use crate::history::{MatchResult, Round};
+use crate::texttable::{Align, TextTable, MAX_NAME_WIDTH};
+use crate::{MatchFormat, Player, Tournament};
+use serde::{Deserialize, Serialize};
+
+/// Outcome of a round from one player's point of view
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub enum Outcome {
+    Win,
+    Loss,
+    Draw,
+    /// The player had a bye, by default a win without games lost, e.g. 2-0, or a bye they
+    /// requested
+    Bye,
+    /// The match has no result yet
+    Pending,
+}
+
+impl Outcome {
+    /// Returns the letter the outcome is shown as in wall charts and form guides, e.g. `W` for a
+    /// win
+    pub fn letter(self) -> char {
+        match self {
+            Outcome::Win => 'W',
+            Outcome::Loss => 'L',
+            Outcome::Draw => 'D',
+            Outcome::Bye => 'B',
+            Outcome::Pending => '?',
+        }
+    }
+}
+
+/// A single round of a player's record
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct MatchRecord {
+    pub round: u32,
+    /// `None` for a bye
+    pub opponent: Option<uuid::Uuid>,
+    pub outcome: Outcome,
+    pub games_won: u8,
+    pub games_lost: u8,
+    pub games_drawn: u8,
+}
+
+/// Everything a player played over the event, round by round
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct PlayerRecord {
+    pub uuid: uuid::Uuid,
+    pub name: String,
+    pub wins: u32,
+    pub losses: u32,
+    pub draws: u32,
+    pub byes: u32,
+    /// Rounds the player sat out without a bye are left out
+    pub matches: Vec<MatchRecord>,
+}
+
+/// A row of the cross-table
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct CrossTableRow {
+    pub uuid: uuid::Uuid,
+    pub name: String,
+    /// The outcomes of all matches against the player of the same column, in the order they were
+    /// played. Empty if the two players never met.
+    pub results: Vec<Vec<Outcome>>,
+}
+
+/// Matrix of the outcomes between all pairs of players. Rows and columns are in the same order,
+/// which is the current ranking.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct CrossTable {
+    pub rows: Vec<CrossTableRow>,
+}
+
+/// How strong a player's opponents were, as a row of `Tournament::strength_of_schedule()`
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct ScheduleStrength {
+    pub uuid: uuid::Uuid,
+    pub name: String,
+    pub match_points: u32,
+    /// Number of matches against opponents, counting rematches twice like the tiebreakers do
+    pub opponents: u32,
+    /// Combined match record of all opponents over the whole event
+    pub opponent_wins: u32,
+    pub opponent_losses: u32,
+    pub opponent_draws: u32,
+    /// Average match points of the opponents, or 0 without any opponents
+    pub average_opponent_match_points: f64,
+    /// The opponents' match-win percentage, the first tiebreaker
+    pub opponents_match_win_percentage: f64,
+}
+
+impl CrossTable {
+    /// Returns the outcomes of the player in row `row` against the player in column `column`
+    pub fn outcomes(&self, row: usize, column: usize) -> &[Outcome] {
+        &self.rows[row].results[column]
+    }
+}
+
+/// Looks at a match from one side, returning the outcome and the games won, lost and drawn
+fn from_side(result: Option<MatchResult>, home: bool) -> (Outcome, u8, u8, u8) {
+    let result = match result {
+        Some(result) => result,
+        None => return (Outcome::Pending, 0, 0, 0),
+    };
+
+    let (won, lost) = if home {
+        (result.home_score, result.away_score)
+    } else {
+        (result.away_score, result.home_score)
+    };
+
+    let outcome = match won.cmp(&lost) {
+        _ if result.double_loss => Outcome::Loss,
+        std::cmp::Ordering::Greater => Outcome::Win,
+        std::cmp::Ordering::Less => Outcome::Loss,
+        std::cmp::Ordering::Equal => Outcome::Draw,
+    };
+
+    (outcome, won, lost, result.drawn)
+}
+
+/// Returns the player's record in a single round, if they played or had a bye
+fn match_record(round: &Round, player: uuid::Uuid, format: MatchFormat) -> Option<MatchRecord> {
+    let requested = round.requested_byes.iter().find(|bye| bye.player == player);
+    let bye_score = match requested {
+        Some(bye) => Some(bye.score),
+        None if round.bye == Some(player) => Some(round.bye_score),
+        None => None,
+    };
+
+    if let Some(bye_score) = bye_score {
+        let (games_won, games_drawn) = bye_score.games(format);
+        return Some(MatchRecord {
+            round: round.number,
+            opponent: None,
+            outcome: Outcome::Bye,
+            games_won,
+            games_lost: 0,
+            games_drawn,
+        });
+    }
+
+    let pair = round.pairing_of(player)?;
+    let home = pair.home == player;
+    let (outcome, games_won, games_lost, games_drawn) = from_side(pair.result, home);
+
+    Some(MatchRecord {
+        round: round.number,
+        opponent: Some(if home { pair.away } else { pair.home }),
+        outcome,
+        games_won,
+        games_lost,
+        games_drawn,
+    })
+}
+
+/// A row of the wall chart, with the cells already rendered
+struct WallChartRow {
+    name: String,
+    cells: Vec<String>,
+    match_points: u32,
+}
+
+/// Renders a round of a player's record as the outcome followed by the opponent's number, e.g.
+/// `W3` for a win against player 3
+fn wall_chart_cell(record: &MatchRecord, numbers: &[uuid::Uuid]) -> String {
+    if record.outcome == Outcome::Bye {
+        return String::from("bye");
+    }
+    let outcome = record.outcome.letter();
+
+    let opponent = record
+        .opponent
+        .and_then(|opp| numbers.iter().position(|&uuid| uuid == opp))
+        .map(|i| (i + 1).to_string())
+        .unwrap_or_else(|| String::from("?"));
+
+    format!("{}{}", outcome, opponent)
+}
+
+impl Tournament {
+    /// Returns the round-by-round record of a player, or `None` if there is no such player
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use swyss::crosstable::Outcome;
+    /// use swyss::{Player, Tournament};
+    ///
+    /// let players = (1..3)
+    ///     .map(|i| Player::new(&format!("Player {}", i)))
+    ///     .collect();
+    /// let mut tourn = Tournament::with_players(players).unwrap();
+    /// let (uuid, home, _) = tourn.next_round().unwrap().unwrap().remove(0);
+    /// tourn.end_match(uuid, 2, 1, 0).unwrap();
+    ///
+    /// let winner = tourn.player_arena().iter().find(|p| p.name == home).unwrap();
+    /// let record = tourn.player_record(winner.uuid).unwrap();
+    /// assert_eq!(record.wins, 1);
+    /// assert_eq!(record.matches[0].outcome, Outcome::Win);
+    /// assert_eq!(record.matches[0].games_lost, 1);
+    /// ```
+    pub fn player_record(&self, player: uuid::Uuid) -> Option<PlayerRecord> {
+        let name = self.players.iter().find(|p| p.uuid == player)?.name.clone();
+
+        let matches: Vec<MatchRecord> = self
+            .round_history()
+            .iter()
+            .filter_map(|round| match_record(round, player, self.match_format))
+            .collect();
+        let count = |outcome| matches.iter().filter(|m| m.outcome == outcome).count() as u32;
+
+        Some(PlayerRecord {
+            uuid: player,
+            name,
+            wins: count(Outcome::Win),
+            losses: count(Outcome::Loss),
+            draws: count(Outcome::Draw),
+            byes: count(Outcome::Bye),
+            matches,
+        })
+    }
+
+    /// Returns every match between two players so far, from the first player's point of view,
+    /// e.g. to check whether they already played before fixing a pairing by hand
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use swyss::crosstable::Outcome;
+    /// use swyss::{Player, Tournament};
+    ///
+    /// let players: Vec<_> = (1..3)
+    ///     .map(|i| Player::new(&format!("Player {}", i)))
+    ///     .collect();
+    /// let (a, b) = (players[0].uuid, players[1].uuid);
+    /// let mut tourn = Tournament::with_players(players).unwrap();
+    /// assert!(tourn.head_to_head(a, b).is_empty());
+    ///
+    /// let (uuid, _, _) = tourn.next_round().unwrap().unwrap()[0];
+    /// tourn.end_match(uuid, 1, 1, 1).unwrap();
+    ///
+    /// let matches = tourn.head_to_head(a, b);
+    /// assert_eq!(matches.len(), 1);
+    /// assert_eq!(matches[0].outcome, Outcome::Draw);
+    /// ```
+    pub fn head_to_head(&self, player: uuid::Uuid, opponent: uuid::Uuid) -> Vec<MatchRecord> {
+        self.round_history()
+            .iter()
+            .filter_map(|round| match_record(round, player, self.match_format))
+            .filter(|record| record.opponent == Some(opponent))
+            .collect()
+    }
+
+    /// Returns the cross-table of all players, ordered by the current standings. Unlike
+    /// `ranking()`, this doesn't reorder the tournament's players.
+    pub fn cross_table(&self) -> CrossTable {
+        let players = self.sorted_players();
+
+        let uuids: Vec<uuid::Uuid> = players.iter().map(|p| p.uuid).collect();
+        let mut rows: Vec<CrossTableRow> = players
+            .iter()
+            .map(|p| CrossTableRow {
+                uuid: p.uuid,
+                name: p.name.clone(),
+                results: vec![Vec::new(); uuids.len()],
+            })
+            .collect();
+
+        for round in self.round_history() {
+            for pair in &round.pairings {
+                let home = uuids.iter().position(|&uuid| uuid == pair.home);
+                let away = uuids.iter().position(|&uuid| uuid == pair.away);
+
+                if let (Some(home), Some(away)) = (home, away) {
+                    rows[home].results[away].push(from_side(pair.result, true).0);
+                    rows[away].results[home].push(from_side(pair.result, false).0);
+                }
+            }
+        }
+
+        CrossTable { rows }
+    }
+
+    /// Builds the rows of the wall chart in ranking order. Players are numbered by their row.
+    fn wall_chart_rows(&self) -> (u32, Vec<WallChartRow>) {
+        let players = self.sorted_players();
+
+        let numbers: Vec<uuid::Uuid> = players.iter().map(|p| p.uuid).collect();
+        let history = self.round_history();
+        let rounds = history.last().map(|round| round.number).unwrap_or(0);
+
+        let rows = players
+            .iter()
+            .map(|p| {
+                let cells = (1..=rounds)
+                    .map(|number| {
+                        history
+                            .iter()
+                            .find(|round| round.number == number)
+                            .and_then(|round| match_record(round, p.uuid, self.match_format))
+                            .map(|record| wall_chart_cell(&record, &numbers))
+                            .unwrap_or_else(|| String::from("-"))
+                    })
+                    .collect();
+
+                WallChartRow {
+                    name: p.name.clone(),
+                    cells,
+                    match_points: p.match_points,
+                }
+            })
+            .collect();
+
+        (rounds, rows)
+    }
+
+    /// Renders a classic Swiss wall chart as plain text: one row per player in ranking order and
+    /// one column per round, showing the outcome and the number of the opponent. Rounds a player
+    /// sat out are shown as `-`, results that are still missing as `?`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use swyss::{Player, Tournament};
+    ///
+    /// let players = ["Alice", "Bob"]
+    ///     .iter()
+    ///     .map(|name| Player::new(name))
+    ///     .collect();
+    /// let mut tourn = Tournament::with_players(players).unwrap();
+    /// let (uuid, home, _) = tourn.next_round().unwrap().unwrap().remove(0);
+    /// tourn.end_match(uuid, 2, 0, 0).unwrap();
+    ///
+    /// let chart = tourn.wall_chart();
+    /// let lines: Vec<&str> = chart.lines().collect();
+    /// assert!(lines[1].contains(&home));
+    /// assert!(lines[1].contains("W2"));
+    /// assert!(lines[2].contains("L1"));
+    /// ```
+    pub fn wall_chart(&self) -> String {
+        let (rounds, rows) = self.wall_chart_rows();
+        let headers: Vec<String> = (1..=rounds).map(|round| format!("R{}", round)).collect();
+        let mut columns = vec![("No", Align::Right), ("Name", Align::Left)];
+        columns.extend(headers.iter().map(|header| (header.as_str(), Align::Left)));
+        columns.push(("MP", Align::Right));
+
+        let mut table = TextTable::new(&columns);
+        table.set_max_width(1, MAX_NAME_WIDTH);
+        for (i, row) in rows.iter().enumerate() {
+            let mut cells = vec![(i + 1).to_string(), row.name.clone()];
+            cells.extend(row.cells.iter().cloned());
+            cells.push(row.match_points.to_string());
+            table.push(cells);
+        }
+
+        table.to_string()
+    }
+
+    /// Renders the wall chart as an HTML table, e.g. for embedding in a report
+    pub fn wall_chart_html(&self) -> String {
+        let (rounds, rows) = self.wall_chart_rows();
+
+        let mut out = String::from("<table>\n<tr><th>No</th><th>Name</th>");
+        for round in 1..=rounds {
+            out.push_str(&format!("<th>R{}</th>", round));
+        }
+        out.push_str("<th>MP</th></tr>\n");
+
+        for (i, row) in rows.iter().enumerate() {
+            out.push_str(&format!(
+                "<tr><td>{}</td><td>{}</td>",
+                i + 1,
+                escape_html(&row.name)
+            ));
+            for cell in &row.cells {
+                out.push_str(&format!("<td>{}</td>", cell));
+            }
+            out.push_str(&format!("<td>{}</td></tr>\n", row.match_points));
+        }
+        out.push_str("</table>\n");
+
+        out
+    }
+
+    /// Returns the combined record and average match points of every player's opponents, in the
+    /// order of the current standings. This shows why players on the same match points are
+    /// ranked the way they are.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use swyss::{Player, Tournament};
+    ///
+    /// let players = (1..5)
+    ///     .map(|i| Player::new(&format!("Player {}", i)))
+    ///     .collect();
+    /// let mut tourn = Tournament::with_players(players).unwrap();
+    /// for (uuid, _, _) in tourn.next_round().unwrap().unwrap() {
+    ///     tourn.end_match(uuid, 2, 0, 0).unwrap();
+    /// }
+    ///
+    /// let strength = tourn.strength_of_schedule();
+    /// // The winners each played a player who lost their only match
+    /// assert_eq!(strength[0].match_points, 3);
+    /// assert_eq!(strength[0].opponent_losses, 1);
+    /// assert_eq!(strength[0].average_opponent_match_points, 0.0);
+    /// ```
+    pub fn strength_of_schedule(&self) -> Vec<ScheduleStrength> {
+        let players = self.sorted_players();
+
+        players
+            .iter()
+            .map(|p| {
+                let opponents: Vec<_> = p
+                    .opponents
+                    .iter()
+                    .filter_map(|&o| self.players.opponent(o))
+                    .collect();
+                let sum = |f: fn(&Player) -> u32| opponents.iter().map(|o| f(o)).sum::<u32>();
+                let match_points = sum(|o| o.match_points);
+                let count = opponents.len() as u32;
+
+                ScheduleStrength {
+                    uuid: p.uuid,
+                    name: p.name.clone(),
+                    match_points: p.match_points,
+                    opponents: count,
+                    opponent_wins: sum(|o| o.matches_won),
+                    opponent_losses: sum(|o| o.matches_played - o.matches_won - o.matches_drawn),
+                    opponent_draws: sum(|o| o.matches_drawn),
+                    average_opponent_match_points: if count > 0 {
+                        f64::from(match_points) / f64::from(count)
+                    } else {
+                        0.0
+                    },
+                    opponents_match_win_percentage: p.opponents_match_win_percentage(&self.players),
+                }
+            })
+            .collect()
+    }
+
+    /// Renders the strength of schedule of every player as a text table
+    pub fn strength_of_schedule_report(&self) -> String {
+        let mut table = TextTable::new(&[
+            ("Rank", Align::Right),
+            ("Name", Align::Left),
+            ("MP", Align::Right),
+            ("Opponents", Align::Right),
+            ("Opp. W-L-D", Align::Right),
+            ("Opp. MP", Align::Right),
+            ("OMWP", Align::Right),
+        ]);
+        table.set_max_width(1, MAX_NAME_WIDTH);
+        for (i, row) in self.strength_of_schedule().iter().enumerate() {
+            table.push(vec![
+                (i + 1).to_string(),
+                row.name.clone(),
+                row.match_points.to_string(),
+                row.opponents.to_string(),
+                row.opponent_record(),
+                format!("{:.2}", row.average_opponent_match_points),
+                format!("{:.2}", row.opponents_match_win_percentage),
+            ]);
+        }
+
+        table.to_string()
+    }
+
+    /// Renders the strength of schedule of every player as an HTML table
+    pub fn strength_of_schedule_html(&self) -> String {
+        let mut out = String::from(
+            "<table>\n<tr><th>Rank</th><th>Name</th><th>MP</th><th>Opponents</th>\
+             <th>Opp. W-L-D</th><th>Opp. MP</th><th>OMWP</th></tr>\n",
+        );
+        for (i, row) in self.strength_of_schedule().iter().enumerate() {
+            out.push_str(&format!(
+                "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{:.2}</td>\
+                 <td>{:.2}</td></tr>\n",
+                i + 1,
+                escape_html(&row.name),
+                row.match_points,
+                row.opponents,
+                row.opponent_record(),
+                row.average_opponent_match_points,
+                row.opponents_match_win_percentage
+            ));
+        }
+        out.push_str("</table>\n");
+
+        out
+    }
+}
+
+impl ScheduleStrength {
+    /// The opponents' combined record as "W-L-D"
+    pub fn opponent_record(&self) -> String {
+        format!(
+            "{}-{}-{}",
+            self.opponent_wins, self.opponent_losses, self.opponent_draws
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn who_beat_whom() {
+        let players = (1..6)
+            .map(|i| Player::new(format!("Player {}", i).as_str()))
+            .collect();
+        let mut tourn = Tournament::with_players(players).unwrap();
+
+        // Home always wins, except for one draw in the last round
+        for round in 1..4 {
+            let pairings = tourn.next_round().unwrap().unwrap();
+            for (i, (uuid, _, _)) in pairings.iter().enumerate() {
+                if round == 3 && i == 0 {
+                    tourn.end_match(*uuid, 1, 1, 0).unwrap();
+                } else {
+                    tourn.end_match(*uuid, 2, 0, 0).unwrap();
+                }
+            }
+        }
+
+        let table = tourn.cross_table();
+        assert_eq!(table.rows.len(), 5);
+
+        let mut wins = 0;
+        let mut draws = 0;
+        for i in 0..5 {
+            assert!(table.outcomes(i, i).is_empty());
+            for j in 0..5 {
+                // Every outcome is mirrored from the opponent's point of view
+                let mirrored: Vec<Outcome> = table
+                    .outcomes(j, i)
+                    .iter()
+                    .map(|o| match o {
+                        Outcome::Win => Outcome::Loss,
+                        Outcome::Loss => Outcome::Win,
+                        other => *other,
+                    })
+                    .collect();
+                assert_eq!(table.outcomes(i, j), &mirrored[..]);
+
+                wins += table
+                    .outcomes(i, j)
+                    .iter()
+                    .filter(|&&o| o == Outcome::Win)
+                    .count();
+                draws += table
+                    .outcomes(i, j)
+                    .iter()
+                    .filter(|&&o| o == Outcome::Draw)
+                    .count();
+            }
+        }
+        assert_eq!(wins, 5);
+        assert_eq!(draws, 2);
+
+        // The records agree with the players' counters
+        for p in &tourn.players {
+            let record = tourn.player_record(p.uuid).unwrap();
+            assert_eq!(record.matches.len(), 3);
+            assert_eq!(record.wins + record.byes, p.matches_won);
+            assert_eq!(record.draws, p.matches_drawn);
+            assert_eq!(record.wins + record.losses + record.draws + record.byes, 3);
+        }
+        assert!(tourn.player_record(uuid::Uuid::new_v4()).is_none());
+    }
+
+    #[test]
+    fn wall_chart() {
+        let players = ["Alice", "Bob", "<Carol>"]
+            .iter()
+            .map(|name| Player::new(name))
+            .collect();
+        let mut tourn = Tournament::with_players(players).unwrap();
+        assert_eq!(tourn.wall_chart().lines().count(), 4);
+
+        let (uuid, _, _) = tourn.next_round().unwrap().unwrap().remove(0);
+        tourn.end_match(uuid, 1, 1, 1).unwrap();
+        tourn.next_round().unwrap().unwrap();
+
+        let chart = tourn.wall_chart();
+        let lines: Vec<&str> = chart.lines().collect();
+        assert!(lines[0].starts_with("No  Name"));
+        assert!(lines[0].ends_with("R1   R2   MP"));
+
+        // Two players drew in the first round, and the second round has one bye and one match
+        // without a result yet
+        assert_eq!(chart.matches("bye").count(), 2);
+        assert_eq!(chart.matches(" D").count(), 2);
+        assert_eq!(chart.matches('?').count(), 2);
+        assert!(lines[1].ends_with("  4"));
+
+        let html = tourn.wall_chart_html();
+        assert!(html.starts_with("<table>\n<tr><th>No</th><th>Name</th><th>R1</th><th>R2</th>"));
+        assert!(html.contains("&lt;Carol&gt;"));
+        assert_eq!(html.matches("<tr>").count(), 4);
+    }
+
+    #[test]
+    fn strength_of_schedule() {
+        let players = (1..6)
+            .map(|i| Player::new(format!("Player {}", i).as_str()))
+            .collect();
+        let mut tourn = Tournament::with_players(players).unwrap();
+        for _ in 0..2 {
+            for (uuid, _, _) in tourn.next_round().unwrap().unwrap() {
+                tourn.end_match(uuid, 2, 1, 0).unwrap();
+            }
+        }
+
+        let rows = tourn.strength_of_schedule();
+        let standings = tourn.standings();
+        assert_eq!(rows.len(), 5);
+        for (row, standing) in rows.iter().zip(&standings) {
+            assert_eq!(row.uuid, standing.uuid);
+            assert_eq!(
+                row.opponents_match_win_percentage,
+                standing.opponents_match_win_percentage
+            );
+
+            let p = tourn.players.iter().find(|p| p.uuid == row.uuid).unwrap();
+            let opponents: Vec<&Player> = p
+                .opponents
+                .iter()
+                .map(|&o| tourn.players.opponent(o).unwrap())
+                .collect();
+            assert_eq!(row.opponents as usize, opponents.len());
+            let points: u32 = opponents.iter().map(|o| o.match_points).sum();
+            if row.opponents > 0 {
+                let average = f64::from(points) / f64::from(row.opponents);
+                assert!((row.average_opponent_match_points - average).abs() < 1e-9);
+            }
+            let played: u32 = opponents.iter().map(|o| o.matches_played).sum();
+            assert_eq!(
+                row.opponent_wins + row.opponent_losses + row.opponent_draws,
+                played
+            );
+        }
+
+        let report = tourn.strength_of_schedule_report();
+        let lines: Vec<&str> = report.lines().collect();
+        assert_eq!(lines.len(), 6);
+        assert!(lines[0].starts_with("Rank  Name"));
+        assert!(lines[1].starts_with("   1  "));
+
+        let html = tourn.strength_of_schedule_html();
+        assert_eq!(html.matches("<tr>").count(), 6);
+    }
+}
@@ -0,0 +1,211 @@
+//! Result deadlines, for events that don't chase missing results at the end of a round: once the
+//! round timer and a grace period have run out, the organizer can have every pairing that still
+//! has no result recorded with a fixed outcome, either a loss for both players or a draw. Nothing
+//! is recorded without the organizer confirming the pairings it applies to.
+//!
+//! The deadline is set in the configuration file together with the round length:
+//!
+//! ```toml
+//! round_length = 50
+//! deadline = { grace = 5, outcome = "DoubleLoss" }
+//! ```
+
+use crate::{PairingResultError, Tournament};
+use serde::{Deserialize, Serialize};
+
+/// What unreported pairings are recorded as once the deadline has passed
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub enum DeadlineOutcome {
+    /// Both players lose the match, without any games
+    DoubleLoss,
+    /// The match is drawn with a single drawn game
+    Draw,
+}
+
+/// When results are due and what happens to the ones that aren't reported in time
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct DeadlinePolicy {
+    /// Minutes after the end of the round until results are due
+    pub grace: u32,
+    pub outcome: DeadlineOutcome,
+}
+
+impl Tournament {
+    /// Returns when results of the current round are due, in seconds since the Unix epoch: the
+    /// round length plus the grace period after the round's first pairing was made. Returns `None`
+    /// if the tournament has no deadline or no round length, or no round is being played.
+    pub fn result_deadline(&self) -> Option<u64> {
+        let policy = self.deadline?;
+        let length = self.round_length?;
+        let start = self.pairings.values().map(|pair| pair.created).min()?;
+        Some(start + 60 * u64::from(length + policy.grace))
+    }
+
+    /// Returns the pairings of the current round that have neither a result nor a pending report
+    /// from a player at the time `now`, in seconds since the Unix epoch, if the deadline has
+    /// passed by then
+    pub fn overdue_pairings(&self, now: u64) -> Vec<uuid::Uuid> {
+        match self.result_deadline() {
+            Some(deadline) if now >= deadline => {}
+            _ => return Vec::new(),
+        }
+
+        // In table order, as the organizer sees them
+        let pairings = self
+            .round(self.current_round)
+            .map(|round| round.pairings)
+            .unwrap_or_default();
+        pairings
+            .iter()
+            .filter(|pair| match self.pairings.get(&pair.uuid) {
+                Some(pair) => !pair.is_completed() && pair.report.is_none(),
+                None => false,
+            })
+            .map(|pair| pair.uuid)
+            .collect()
+    }
+
+    /// Records the outcome of the deadline policy for all pairings that are overdue at the time
+    /// `now`. `confirm` is called with the overdue pairings and the outcome first, and nothing is
+    /// recorded unless it returns `true`. Returns the pairings that were recorded.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use swyss::deadline::{DeadlineOutcome, DeadlinePolicy};
+    /// use swyss::{Player, Tournament};
+    ///
+    /// let players = vec![
+    ///     Player::new("Alice"),
+    ///     Player::new("Bob"),
+    /// ];
+    /// let mut tourn = Tournament::with_players(players).unwrap();
+    /// tourn.set_round_length(Some(50)).unwrap();
+    /// tourn
+    ///     .set_deadline(Some(DeadlinePolicy {
+    ///         grace: 5,
+    ///         outcome: DeadlineOutcome::DoubleLoss,
+    ///     }))
+    ///     .unwrap();
+    /// let (uuid, _, _) = tourn.next_round().unwrap().unwrap()[0];
+    ///
+    /// let due = tourn.result_deadline().unwrap();
+    /// assert!(tourn.overdue_pairings(due - 1).is_empty());
+    /// assert_eq!(tourn.apply_deadline(due, |_, _| false).unwrap(), vec![]);
+    /// assert_eq!(tourn.apply_deadline(due, |_, _| true).unwrap(), vec![uuid]);
+    ///
+    /// let result = tourn.round(1).unwrap().pairings[0].result.unwrap();
+    /// assert!(result.double_loss);
+    /// assert!(tourn.players().all(|p| p.match_points == 0));
+    /// ```
+    pub fn apply_deadline<F>(
+        &mut self,
+        now: u64,
+        confirm: F,
+    ) -> Result<Vec<uuid::Uuid>, PairingResultError>
+    where
+        F: FnOnce(&[uuid::Uuid], DeadlineOutcome) -> bool,
+    {
+        let overdue = self.overdue_pairings(now);
+        let outcome = match self.deadline {
+            Some(policy) if !overdue.is_empty() => policy.outcome,
+            _ => return Ok(Vec::new()),
+        };
+        if !confirm(&overdue, outcome) {
+            return Ok(Vec::new());
+        }
+
+        for &uuid in &overdue {
+            match outcome {
+                DeadlineOutcome::DoubleLoss => self.double_loss(uuid)?,
+                DeadlineOutcome::Draw => self.end_match(uuid, 0, 0, 1)?,
+            }
+        }
+
+        Ok(overdue)
+    }
+
+    /// Records a loss for both players of a pairing, as described for `Pairing::double_loss()`
+    pub fn double_loss(&mut self, uuid: uuid::Uuid) -> Result<(), PairingResultError> {
+        self.open_pairing(uuid)?;
+        let pair = &self.pairings[&uuid];
+        if pair.is_completed() {
+            return Err(PairingResultError::AlreadyReported(uuid));
+        }
+
+        pair.double_loss(&mut self.players);
+        self.notify(|o| o.on_result(self, uuid, 0, 0, 0));
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crosstable::Outcome;
+    use crate::Player;
+
+    #[test]
+    fn overdue_results() {
+        let players = (1..5)
+            .map(|i| Player::new(format!("Player {}", i).as_str()))
+            .collect();
+        let mut tourn = Tournament::with_players(players).unwrap();
+        tourn.rounds = 2;
+        tourn.round_length = Some(30);
+        tourn.deadline = Some(DeadlinePolicy {
+            grace: 10,
+            outcome: DeadlineOutcome::Draw,
+        });
+
+        tourn.next_round().unwrap().unwrap();
+        let due = tourn.result_deadline().unwrap();
+        let first = tourn.round(1).unwrap().pairings;
+        tourn.end_match(first[0].uuid, 2, 0, 0).unwrap();
+
+        // Only the pairing without a result is overdue, and the hook sees it first
+        let mut seen = Vec::new();
+        let recorded = tourn
+            .apply_deadline(due, |overdue, outcome| {
+                seen = overdue.to_vec();
+                outcome == DeadlineOutcome::Draw
+            })
+            .unwrap();
+        assert_eq!(seen, vec![first[1].uuid]);
+        assert_eq!(recorded, seen);
+        assert_eq!(tourn.player(first[1].home).unwrap().match_points, 1);
+        assert!(tourn.overdue_pairings(due).is_empty());
+
+        // A double loss can be amended like any other result
+        tourn.deadline = Some(DeadlinePolicy {
+            grace: 0,
+            outcome: DeadlineOutcome::DoubleLoss,
+        });
+        tourn.next_round().unwrap().unwrap();
+        let due = tourn.result_deadline().unwrap();
+        assert_eq!(tourn.apply_deadline(due, |_, _| true).unwrap().len(), 2);
+        assert!(tourn.check_invariants().is_ok());
+        let second = tourn.round(2).unwrap().pairings;
+        let table = tourn.cross_table();
+        let losses = table
+            .rows
+            .iter()
+            .flat_map(|row| row.results.iter().flatten())
+            .filter(|&&outcome| outcome == Outcome::Loss)
+            .count();
+        assert_eq!(losses, 1 + 4);
+
+        tourn.amend_match(second[0].uuid, 2, 1, 0).unwrap();
+        assert!(tourn.check_invariants().is_ok());
+        let points: u32 = tourn.players().map(|p| p.match_points).sum();
+        assert_eq!(points, 3 + 2 + 3);
+
+        let loaded = Tournament::from_json(&tourn.to_json()).unwrap();
+        assert_eq!(loaded.deadline, tourn.deadline);
+        let reloaded = loaded.round(2).unwrap().pairings;
+        assert!(reloaded[1].result.unwrap().double_loss);
+    }
+}
@@ -0,0 +1,260 @@
+//! Results as organizers type them at the score prompt: a score like "2-1" or "2:0 (1 draw)",
+//! "id" for an intentional draw, "bye 1" when a player's opponent didn't show up, "c 2" when a
+//! player concedes, or "s" to look at the standings. Scores are checked against the match format
+//! before they are recorded, so that a rejected score comes with the reason it was rejected.
+
+use crate::locale::{Locale, Message};
+use crate::{MatchFormat, PlayerSide};
+use regex::Regex;
+use std::fmt;
+use std::str::FromStr;
+
+/// What was entered at the score prompt of a pairing
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ResultInput {
+    /// Games won by the home and away player and drawn games. Equal scores like "1-1" count as a
+    /// draw with one drawn game unless the drawn games are given.
+    Score(u8, u8, u8),
+    /// Both players agreed to a draw without playing, recorded as a match of drawn games only,
+    /// e.g. 0-0-3 in best-of-three
+    IntentionalDraw,
+    /// The given player wins because the opponent didn't show up, recorded as a concession of the
+    /// opponent
+    Bye(PlayerSide),
+    /// The given player concedes the match
+    Concede(PlayerSide),
+    /// "s" asks for the current standings
+    Standings,
+}
+
+impl ResultInput {
+    /// Returns the home, away, and drawn games to record, or `None` if the input isn't a score
+    pub fn scores(self, format: MatchFormat) -> Option<(u8, u8, u8)> {
+        match self {
+            ResultInput::Score(home, away, drawn) => Some((home, away, drawn)),
+            ResultInput::IntentionalDraw => Some((0, 0, format.games())),
+            _ => None,
+        }
+    }
+
+    /// Parses an input in which the players of the pairing may be named instead of numbered, as
+    /// in "c Alice" or "bye Bob", ignoring case
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use swyss::entry::ResultInput;
+    /// use swyss::PlayerSide;
+    ///
+    /// let input = ResultInput::parse_with_names("c bob", "Alice", "Bob");
+    /// assert_eq!(input, Ok(ResultInput::Concede(PlayerSide::Away)));
+    /// ```
+    pub fn parse_with_names(
+        input: &str,
+        home: &str,
+        away: &str,
+    ) -> Result<ResultInput, ScoreError> {
+        let trimmed = input.trim();
+        for command in &["bye ", "c "] {
+            let name = match trimmed.get(..command.len()) {
+                Some(prefix) if prefix.eq_ignore_ascii_case(command) => {
+                    trimmed[command.len()..].trim()
+                }
+                _ => continue,
+            };
+            for (num, player) in &[(1, home), (2, away)] {
+                if name.to_lowercase() == player.to_lowercase() {
+                    return format!("{}{}", command, num).parse();
+                }
+            }
+        }
+
+        input.parse()
+    }
+}
+
+impl FromStr for ResultInput {
+    type Err = ScoreError;
+
+    /// Parses an input, ignoring case and spaces around the numbers
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use swyss::entry::ResultInput;
+    /// use swyss::PlayerSide;
+    ///
+    /// assert_eq!("2-1".parse(), Ok(ResultInput::Score(2, 1, 0)));
+    /// assert_eq!("2:0 (1 draw)".parse(), Ok(ResultInput::Score(2, 0, 1)));
+    /// assert_eq!("1-1".parse(), Ok(ResultInput::Score(1, 1, 1)));
+    /// assert_eq!("ID".parse(), Ok(ResultInput::IntentionalDraw));
+    /// assert_eq!("bye 2".parse(), Ok(ResultInput::Bye(PlayerSide::Away)));
+    /// assert!("two to one".parse::<ResultInput>().is_err());
+    /// ```
+    fn from_str(s: &str) -> Result<ResultInput, ScoreError> {
+        let input = s.trim().to_lowercase();
+        let side = |n: &str| match n.trim() {
+            "1" => Some(PlayerSide::Home),
+            "2" => Some(PlayerSide::Away),
+            _ => None,
+        };
+
+        match input.as_str() {
+            "s" => return Ok(ResultInput::Standings),
+            "id" => return Ok(ResultInput::IntentionalDraw),
+            _ => {}
+        }
+        if let Some(side) = input.strip_prefix("bye").and_then(side) {
+            return Ok(ResultInput::Bye(side));
+        }
+        if let Some(side) = input.strip_prefix('c').and_then(side) {
+            return Ok(ResultInput::Concede(side));
+        }
+
+        let re =
+            Regex::new(r"^(\d+)\s*[-:]\s*(\d+)(?:\s*[-:]\s*(\d+)|\s*\(\s*(\d+)\s*draws?\s*\))?$")
+                .expect("the score pattern is valid");
+        let unreadable = || ScoreError::Unreadable(String::from(s.trim()));
+        let caps = re.captures(&input).ok_or_else(unreadable)?;
+        let number = |i: usize| -> Result<Option<u8>, ScoreError> {
+            caps.get(i)
+                .map(|m| m.as_str().parse().map_err(|_| unreadable()))
+                .transpose()
+        };
+
+        let home = number(1)?.expect("the home score is required");
+        let away = number(2)?.expect("the away score is required");
+        let drawn = match (number(3)?, number(4)?) {
+            (Some(drawn), _) | (_, Some(drawn)) => drawn,
+            _ if home == away => 1,
+            _ => 0,
+        };
+
+        Ok(ResultInput::Score(home, away, drawn))
+    }
+}
+
+/// Why an input isn't a valid result
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ScoreError {
+    /// The input is neither a score nor one of the commands
+    Unreadable(String),
+    /// A player won more games than it takes to win the match
+    TooManyWins { wins: u8, needed: u8 },
+    /// More games were played than the match has
+    TooManyGames { games: u8, max: u8 },
+    /// Not a single game was played
+    NoGames,
+}
+
+impl ScoreError {
+    /// Describes the error in the given locale
+    pub fn text(&self, locale: Locale) -> String {
+        match self {
+            ScoreError::Unreadable(input) => locale.format(Message::CouldNotParseScore, &[input]),
+            ScoreError::TooManyWins { wins, needed } => {
+                locale.format(Message::TooManyWins, &[wins, needed])
+            }
+            ScoreError::TooManyGames { games, max } => {
+                locale.format(Message::TooManyGames, &[games, max])
+            }
+            ScoreError::NoGames => String::from(locale.text(Message::NoGames)),
+        }
+    }
+}
+
+impl fmt::Display for ScoreError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.text(Locale::English))
+    }
+}
+
+/// Checks that game scores are a valid result of a match of the given format, like
+/// `Tournament::end_match()` does, but tells why they aren't
+///
+/// # Example
+///
+/// ```
+/// use swyss::entry::{validate, ScoreError};
+/// use swyss::MatchFormat;
+///
+/// let format = MatchFormat::BestOf(3);
+/// assert_eq!(validate(format, 2, 1, 0), Ok(()));
+/// assert_eq!(
+///     validate(format, 3, 0, 0),
+///     Err(ScoreError::TooManyWins { wins: 3, needed: 2 })
+/// );
+/// ```
+pub fn validate(format: MatchFormat, home: u8, away: u8, drawn: u8) -> Result<(), ScoreError> {
+    let needed = format.wins_needed();
+    if let Some(&wins) = [home, away].iter().find(|&&wins| wins > needed) {
+        return Err(ScoreError::TooManyWins { wins, needed });
+    }
+
+    let games = home.saturating_add(away).saturating_add(drawn);
+    if games == 0 {
+        return Err(ScoreError::NoGames);
+    }
+    if games > format.games() {
+        return Err(ScoreError::TooManyGames {
+            games,
+            max: format.games(),
+        });
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::check_scores;
+
+    #[test]
+    fn parse_and_validate() {
+        assert_eq!("2 - 1".parse(), Ok(ResultInput::Score(2, 1, 0)));
+        assert_eq!("1:1:1".parse(), Ok(ResultInput::Score(1, 1, 1)));
+        assert_eq!("0-0 (3 draws)".parse(), Ok(ResultInput::Score(0, 0, 3)));
+        assert_eq!("C1".parse(), Ok(ResultInput::Concede(PlayerSide::Home)));
+        assert_eq!("s".parse(), Ok(ResultInput::Standings));
+        assert_eq!(
+            "2-1-1 (1 draw)".parse::<ResultInput>(),
+            Err(ScoreError::Unreadable(String::from("2-1-1 (1 draw)")))
+        );
+        assert!("bye".parse::<ResultInput>().is_err());
+        assert!("c 3".parse::<ResultInput>().is_err());
+        assert!("300-0".parse::<ResultInput>().is_err());
+        assert_eq!(
+            ResultInput::parse_with_names("Bye  carol ann", "Carol Ann", "Dave"),
+            Ok(ResultInput::Bye(PlayerSide::Home))
+        );
+        assert!(ResultInput::parse_with_names("c Eve", "Carol Ann", "Dave").is_err());
+
+        let format = MatchFormat::BestOf(3);
+        assert_eq!(ResultInput::IntentionalDraw.scores(format), Some((0, 0, 3)));
+        assert_eq!(ResultInput::Standings.scores(format), None);
+        assert_eq!(validate(format, 0, 0, 0), Err(ScoreError::NoGames));
+        assert_eq!(
+            validate(format, 2, 2, 0),
+            Err(ScoreError::TooManyGames { games: 4, max: 3 })
+        );
+        assert_eq!(
+            ScoreError::TooManyWins { wins: 3, needed: 2 }.text(Locale::German),
+            "3 gewonnene Spiele, aber das Match endet nach 2 Siegen"
+        );
+
+        // Exactly the scores the tournament accepts are valid
+        for format in [MatchFormat::BestOf(1), format, MatchFormat::BestOf(5)] {
+            for home in 0..5 {
+                for away in 0..5 {
+                    for drawn in 0..5 {
+                        assert_eq!(
+                            validate(format, home, away, drawn).is_ok(),
+                            check_scores(format, home, away, drawn).is_ok()
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,77 @@
+//! Details that identify an event, such as its name and date, so that exported files and printed
+//! reports can be told apart once several events have been run.
+
+use serde::{Deserialize, Serialize};
+
+/// Name, date and other details of an event. Every detail is free text and optional; the date is
+/// printed as given, e.g. "2024-05-18".
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct EventInfo {
+    pub name: Option<String>,
+    pub date: Option<String>,
+    /// What is played, e.g. the game or its format, as opposed to the `MatchFormat`
+    pub format: Option<String>,
+    pub organizer: Option<String>,
+    pub location: Option<String>,
+}
+
+impl EventInfo {
+    /// Returns the name of the event, or a generic title if it has none
+    pub fn title(&self) -> &str {
+        self.name.as_deref().unwrap_or("Tournament")
+    }
+
+    /// Returns the details other than the name as labeled lines like "Date: 2024-05-18", in a
+    /// fixed order and leaving out those that aren't set
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use swyss::event::EventInfo;
+    ///
+    /// let event = EventInfo {
+    ///     name: Some(String::from("Friday Night")),
+    ///     location: Some(String::from("Game Haven")),
+    ///     ..EventInfo::default()
+    /// };
+    /// assert_eq!(event.title(), "Friday Night");
+    /// assert_eq!(event.details(), ["Location: Game Haven"]);
+    /// ```
+    pub fn details(&self) -> Vec<String> {
+        [
+            ("Date", &self.date),
+            ("Format", &self.format),
+            ("Organizer", &self.organizer),
+            ("Location", &self.location),
+        ]
+        .iter()
+        .filter_map(|(label, value)| value.as_ref().map(|v| format!("{}: {}", label, v)))
+        .collect()
+    }
+
+    /// Sets every detail that is set in `other`, and returns the names of the details that
+    /// changed along with their old and new values
+    pub(crate) fn update(&mut self, other: &EventInfo) -> Vec<(&'static str, String, String)> {
+        let mut changes = Vec::new();
+
+        for (name, field, new) in [
+            ("name", &mut self.name, &other.name),
+            ("date", &mut self.date, &other.date),
+            ("format", &mut self.format, &other.format),
+            ("organizer", &mut self.organizer, &other.organizer),
+            ("location", &mut self.location, &other.location),
+        ] {
+            if let Some(new) = new {
+                if field.as_ref() != Some(new) {
+                    let old = field
+                        .replace(new.clone())
+                        .unwrap_or_else(|| String::from("none"));
+                    changes.push((name, old, new.clone()));
+                }
+            }
+        }
+
+        changes
+    }
+}
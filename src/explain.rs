@@ -0,0 +1,303 @@
+//! Explanations of the standings, so that judges can show a player exactly why they are ranked
+//! where they are: which tiebreaker separated them from the players directly above and below
+//! them, and with which numbers.
+
+use crate::arena::PlayerArena;
+use crate::{criterion_value, Player, Tournament};
+use num_traits::ToPrimitive;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::fmt;
+
+/// A criterion players are ranked by. Match points always come first; the others are the
+/// tiebreakers a tournament can choose from.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub enum Criterion {
+    MatchPoints,
+    OpponentsMatchWinPercentage,
+    GameWinPercentage,
+    OpponentsGameWinPercentage,
+    OpponentsOpponentsMatchWinPercentage,
+    /// Games won minus games lost
+    GameDifferential,
+    /// Sum of the match points after each round, as in chess
+    CumulativeScore,
+    /// Matches won
+    Wins,
+}
+
+impl Criterion {
+    /// All criteria
+    pub const ALL: [Criterion; 8] = [
+        Criterion::MatchPoints,
+        Criterion::OpponentsMatchWinPercentage,
+        Criterion::GameWinPercentage,
+        Criterion::OpponentsGameWinPercentage,
+        Criterion::OpponentsOpponentsMatchWinPercentage,
+        Criterion::GameDifferential,
+        Criterion::CumulativeScore,
+        Criterion::Wins,
+    ];
+
+    /// The tiebreakers a new tournament applies after match points, in order
+    pub const DEFAULT_TIEBREAKERS: [Criterion; 4] = [
+        Criterion::OpponentsMatchWinPercentage,
+        Criterion::GameWinPercentage,
+        Criterion::OpponentsGameWinPercentage,
+        Criterion::OpponentsOpponentsMatchWinPercentage,
+    ];
+
+    /// Returns the player's value of the criterion. Match points and the other counts are
+    /// returned as floats, so that all criteria can be listed alike. The opponents are looked up
+    /// in `players`.
+    pub fn value(self, player: &Player, players: &PlayerArena) -> f64 {
+        criterion_value(player, players, self)
+            .to_f64()
+            .expect("criteria are finite")
+    }
+
+    /// Compares two players by this criterion alone, like the standings do. The player who is
+    /// ranked higher compares as `Ordering::Less`.
+    fn compare(self, a: &Player, b: &Player, players: &PlayerArena) -> Ordering {
+        criterion_value(b, players, self).cmp(&criterion_value(a, players, self))
+    }
+}
+
+impl fmt::Display for Criterion {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Criterion::MatchPoints => write!(f, "match points"),
+            Criterion::OpponentsMatchWinPercentage => {
+                write!(f, "opponents' match-win percentage")
+            }
+            Criterion::GameWinPercentage => write!(f, "game-win percentage"),
+            Criterion::OpponentsGameWinPercentage => write!(f, "opponents' game-win percentage"),
+            Criterion::OpponentsOpponentsMatchWinPercentage => {
+                write!(f, "opponents' opponents' match-win percentage")
+            }
+            Criterion::GameDifferential => write!(f, "game differential"),
+            Criterion::CumulativeScore => write!(f, "cumulative score"),
+            Criterion::Wins => write!(f, "wins"),
+        }
+    }
+}
+
+/// How a player compares to a neighbor in the standings
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct Comparison {
+    pub uuid: uuid::Uuid,
+    pub name: String,
+    pub rank: u32,
+    /// The first criterion the two players differ in, or `None` if they are tied on all of them
+    /// and only keep their previous order
+    pub decided_by: Option<Criterion>,
+    /// The neighbor's match points and tiebreakers, in the order they are applied
+    pub values: Vec<(Criterion, f64)>,
+}
+
+/// Why a player is ranked where they are, as returned by `Tournament::explain_rank()`
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct RankExplanation {
+    pub uuid: uuid::Uuid,
+    pub name: String,
+    pub rank: u32,
+    /// The player's match points and tiebreakers, in the order they are applied
+    pub values: Vec<(Criterion, f64)>,
+    /// The player ranked directly above, if any
+    pub above: Option<Comparison>,
+    /// The player ranked directly below, if any
+    pub below: Option<Comparison>,
+}
+
+impl Tournament {
+    /// Explains the rank of a player in the current standings by comparing them to the players
+    /// directly above and below them. Returns `None` if there is no such player.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use swyss::explain::Criterion;
+    /// use swyss::{Player, Tournament};
+    ///
+    /// let players = (1..3)
+    ///     .map(|i| Player::new(&format!("Player {}", i)))
+    ///     .collect();
+    /// let mut tourn = Tournament::with_players(players).unwrap();
+    /// let (uuid, _, _) = tourn.next_round().unwrap().unwrap()[0];
+    /// tourn.end_match(uuid, 2, 1, 0).unwrap();
+    ///
+    /// let winner = tourn.standings()[0].uuid;
+    /// let explanation = tourn.explain_rank(winner).unwrap();
+    /// assert_eq!(explanation.rank, 1);
+    /// assert!(explanation.above.is_none());
+    /// let below = explanation.below.unwrap();
+    /// assert_eq!(below.decided_by, Some(Criterion::MatchPoints));
+    /// ```
+    pub fn explain_rank(&self, player: uuid::Uuid) -> Option<RankExplanation> {
+        let players = self.sorted_players();
+        let index = players.iter().position(|p| p.uuid == player)?;
+
+        let criteria: Vec<Criterion> = std::iter::once(Criterion::MatchPoints)
+            .chain(self.tiebreakers.iter().copied())
+            .collect();
+        let values = |p: &Player| {
+            criteria
+                .iter()
+                .map(|c| (*c, c.value(p, &self.players)))
+                .collect::<Vec<_>>()
+        };
+        let p = players[index];
+        let compare = |i: usize| {
+            let other = players[i];
+            Comparison {
+                uuid: other.uuid,
+                name: other.name.clone(),
+                rank: i as u32 + 1,
+                decided_by: criteria
+                    .iter()
+                    .copied()
+                    .find(|c| c.compare(p, other, &self.players) != Ordering::Equal),
+                values: values(other),
+            }
+        };
+
+        Some(RankExplanation {
+            uuid: p.uuid,
+            name: p.name.clone(),
+            rank: index as u32 + 1,
+            values: values(p),
+            above: index.checked_sub(1).map(compare),
+            below: Some(index + 1).filter(|i| *i < players.len()).map(compare),
+        })
+    }
+}
+
+impl fmt::Display for RankExplanation {
+    /// Formats the explanation as sentences a judge can read out, e.g. "Ranked above Bob (#3) on
+    /// opponents' match-win percentage: 0.5556 to 0.4815"
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "{} is ranked #{}", self.name, self.rank)?;
+        for (criterion, value) in &self.values {
+            writeln!(f, "  {}: {}", criterion, format_value(*criterion, *value))?;
+        }
+
+        for (relation, comparison) in [("below", &self.above), ("above", &self.below)] {
+            let other = match comparison {
+                Some(other) => other,
+                None => continue,
+            };
+            match other.decided_by {
+                Some(criterion) => {
+                    let value = |values: &[(Criterion, f64)]| {
+                        let value = values.iter().find(|(c, _)| *c == criterion);
+                        format_value(criterion, value.map_or(0.0, |(_, v)| *v))
+                    };
+                    writeln!(
+                        f,
+                        "Ranked {} {} (#{}) on {}: {} to {}",
+                        relation,
+                        other.name,
+                        other.rank,
+                        criterion,
+                        value(&self.values),
+                        value(&other.values)
+                    )?;
+                }
+                None => writeln!(
+                    f,
+                    "Ranked {} {} (#{}), tied on all tiebreakers",
+                    relation, other.name, other.rank
+                )?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Formats counts as whole numbers and percentages with four decimals, so that close
+/// tiebreakers can be told apart
+fn format_value(criterion: Criterion, value: f64) -> String {
+    match criterion {
+        Criterion::MatchPoints
+        | Criterion::GameDifferential
+        | Criterion::CumulativeScore
+        | Criterion::Wins => format!("{}", value),
+        _ => format!("{:.4}", value),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn neighbors() {
+        let players = (1..=6)
+            .map(|i| Player::new(format!("Player {}", i).as_str()))
+            .collect();
+        let mut tourn = Tournament::with_players(players).unwrap();
+        for _ in 0..2 {
+            for (uuid, _, _) in tourn.next_round().unwrap().unwrap() {
+                tourn.end_match(uuid, 2, 1, 0).unwrap();
+            }
+        }
+
+        let standings = tourn.standings();
+        for (i, row) in standings.iter().enumerate() {
+            let explanation = tourn.explain_rank(row.uuid).unwrap();
+            assert_eq!(explanation.rank, row.rank);
+            assert_eq!(
+                explanation.values[1],
+                (
+                    Criterion::OpponentsMatchWinPercentage,
+                    row.opponents_match_win_percentage
+                )
+            );
+            assert_eq!(
+                explanation.above.as_ref().map(|c| c.uuid),
+                i.checked_sub(1).map(|i| standings[i].uuid)
+            );
+            assert_eq!(
+                explanation.below.as_ref().map(|c| c.uuid),
+                standings.get(i + 1).map(|r| r.uuid)
+            );
+
+            // The deciding criterion is the first one that differs, and favors the higher rank
+            if let Some(below) = &explanation.below {
+                if let Some(criterion) = below.decided_by {
+                    let k = explanation
+                        .values
+                        .iter()
+                        .position(|(c, _)| *c == criterion)
+                        .unwrap();
+                    assert!(explanation.values[..k] == below.values[..k]);
+                    assert!(explanation.values[k].1 > below.values[k].1);
+                } else {
+                    assert_eq!(explanation.values, below.values);
+                }
+            }
+        }
+
+        // Players with more match points are separated from the rest by match points
+        let i = (0..standings.len() - 1)
+            .find(|&i| standings[i].match_points > standings[i + 1].match_points)
+            .unwrap();
+        let explanation = tourn.explain_rank(standings[i].uuid).unwrap();
+        assert_eq!(
+            explanation.below.unwrap().decided_by,
+            Some(Criterion::MatchPoints)
+        );
+
+        let text = tourn.explain_rank(standings[0].uuid).unwrap().to_string();
+        assert!(text.starts_with(&format!("{} is ranked #1\n", standings[0].name)));
+        assert!(text.contains("  match points: 6\n"));
+        assert!(text.contains("Ranked above "));
+        assert!(!text.contains("Ranked below "));
+
+        assert!(tourn.explain_rank(uuid::Uuid::new_v4()).is_none());
+    }
+}
@@ -0,0 +1,437 @@
+//! Flights, where the field of a large event is split into groups (flights A, B, ...) that are
+//! paired independently but play their rounds side by side. Unlike pods, all flights are ranked
+//! together at the end, and an optional combined cut sends the best players of all flights to a
+//! top cut. Each flight and the top cut are run as their own `Tournament`.
+
+use crate::arena::PlayerArena;
+use crate::explain::Criterion;
+use crate::history::RoundError;
+use crate::ratings::Ratings;
+use crate::stage::{fresh_player, Cut};
+use crate::{sort_by_tiebreakers, PairingResultError, PairingSystem, Pairings, Player, Tournament};
+use rand::seq::SliceRandom;
+use rand::thread_rng;
+use std::fmt;
+
+/// How players are assigned to flights
+#[derive(Clone, Debug, PartialEq)]
+pub enum FlightAssignment {
+    /// Players are shuffled and dealt out to the flights in turn
+    Random,
+    /// Players are dealt out by rating in snake order (A, B, B, A, ...), so that every flight is
+    /// about equally strong. Unrated players count with the default rating.
+    ByRating(Ratings),
+    /// Players fill the flights in the order they registered, i.e. the order they are given in:
+    /// the first registrations make up flight A, the next flight B, and so on
+    ByRegistration,
+}
+
+/// Configuration of a flighted event
+#[derive(Clone, Debug, PartialEq)]
+pub struct FlightConfig {
+    /// Number of flights
+    pub flights: usize,
+    /// How players are assigned to flights
+    pub assignment: FlightAssignment,
+    /// Number of rounds every flight plays, or `None` to derive it from the size of the largest
+    /// flight
+    pub rounds: Option<u32>,
+    /// Combined cut over all flights once their rounds are over, or `None` to just merge the
+    /// standings
+    pub cut: Option<Cut>,
+    /// Number of Swiss rounds of the top cut, or `None` to derive it from the number of players
+    pub cut_rounds: Option<u32>,
+}
+
+impl FlightConfig {
+    /// Returns a configuration for the given number of randomly assigned flights without a cut
+    pub fn new(flights: usize) -> FlightConfig {
+        FlightConfig {
+            flights,
+            assignment: FlightAssignment::Random,
+            rounds: None,
+            cut: None,
+            cut_rounds: None,
+        }
+    }
+}
+
+/// Reasons why a flighted event can't be set up
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FlightError {
+    /// The configuration asks for no flights
+    NoFlights,
+}
+
+impl std::error::Error for FlightError {}
+
+impl fmt::Display for FlightError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FlightError::NoFlights => write!(f, "An event needs at least one flight"),
+        }
+    }
+}
+
+/// Runs the flights side by side, followed by the top cut if there is one
+pub struct FlightEvent {
+    config: FlightConfig,
+    flights: Vec<Tournament>,
+    top_cut: Option<Tournament>,
+    /// Players who didn't make the top cut, with those who did as their former opponents
+    eliminated: PlayerArena,
+}
+
+impl FlightEvent {
+    /// Assigns the players to the configured number of flights, which differ in size by at most
+    /// one player. Fails with `FlightError::NoFlights` if the configuration asks for none.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use swyss::flights::{FlightAssignment, FlightConfig, FlightEvent};
+    /// use swyss::stage::Cut;
+    /// use swyss::Player;
+    ///
+    /// let players = (1..=16)
+    ///     .map(|i| Player::new(&format!("Player {}", i)))
+    ///     .collect();
+    /// let config = FlightConfig {
+    ///     assignment: FlightAssignment::ByRegistration,
+    ///     cut: Some(Cut::Top(4)),
+    ///     ..FlightConfig::new(2)
+    /// };
+    /// let mut event = FlightEvent::new(players, config).unwrap();
+    ///
+    /// while let Some(pairings) = event.next_round().unwrap() {
+    ///     for (uuid, _, _) in pairings {
+    ///         event.end_match(uuid, 2, 1, 0).unwrap();
+    ///     }
+    /// }
+    ///
+    /// assert_eq!(event.top_cut().unwrap().player_arena().len(), 4);
+    /// assert_eq!(event.standings().len(), 16);
+    /// ```
+    pub fn new(players: Vec<Player>, config: FlightConfig) -> Result<FlightEvent, FlightError> {
+        if config.flights == 0 {
+            return Err(FlightError::NoFlights);
+        }
+
+        let num_flights = config.flights;
+        let mut members = vec![Vec::new(); num_flights];
+        match &config.assignment {
+            FlightAssignment::Random => {
+                let mut players = players;
+                players.shuffle(&mut thread_rng());
+                for (i, p) in players.into_iter().enumerate() {
+                    members[i % num_flights].push(p);
+                }
+            }
+            FlightAssignment::ByRating(ratings) => {
+                let mut players = players;
+                players.sort_by(|a, b| {
+                    let a = ratings.get(&a.name).rating;
+                    let b = ratings.get(&b.name).rating;
+                    b.total_cmp(&a)
+                });
+                for (i, p) in players.into_iter().enumerate() {
+                    let (pass, j) = (i / num_flights, i % num_flights);
+                    let flight = if pass % 2 == 1 {
+                        num_flights - 1 - j
+                    } else {
+                        j
+                    };
+                    members[flight].push(p);
+                }
+            }
+            FlightAssignment::ByRegistration => {
+                let (size, larger) = (players.len() / num_flights, players.len() % num_flights);
+                let mut players = players.into_iter();
+                for (i, flight) in members.iter_mut().enumerate() {
+                    let n = if i < larger { size + 1 } else { size };
+                    flight.extend(players.by_ref().take(n));
+                }
+            }
+        }
+
+        let largest = members.iter().map(Vec::len).max().unwrap_or(0);
+        let rounds = config
+            .rounds
+            .unwrap_or_else(|| PairingSystem::Swiss.rounds(largest));
+        let flights = members
+            .into_iter()
+            .map(|players| {
+                let mut flight = Tournament::from_arena(players.into());
+                flight.rounds = rounds;
+                flight
+            })
+            .collect();
+
+        Ok(FlightEvent {
+            config,
+            flights,
+            top_cut: None,
+            eliminated: PlayerArena::new(),
+        })
+    }
+
+    /// The tournaments of the flights, in order (A, B, ...)
+    pub fn flights(&mut self) -> &mut [Tournament] {
+        &mut self.flights
+    }
+
+    /// Returns the index of the flight a player was assigned to, starting at 0 for flight A
+    pub fn flight_of(&self, player: uuid::Uuid) -> Option<usize> {
+        self.flights
+            .iter()
+            .position(|flight| flight.players.iter().any(|p| p.uuid == player))
+    }
+
+    /// The tournament of the top cut, once all flights are finished
+    pub fn top_cut(&mut self) -> Option<&mut Tournament> {
+        self.top_cut.as_mut()
+    }
+
+    /// Advances the event by one round. All flights are paired in the same round, and once they
+    /// are finished, the combined cut is applied and the top cut is paired. Returns `None` once
+    /// the event is complete, and an error if results of any flight's current round are
+    /// outstanding.
+    pub fn next_round(&mut self) -> Result<Option<Pairings>, RoundError> {
+        if let Some(top_cut) = &mut self.top_cut {
+            return top_cut.next_round();
+        }
+
+        // Check all flights before pairing any, so that no flight gets ahead of the others
+        let outstanding: Vec<_> = self
+            .flights
+            .iter()
+            .flat_map(|flight| flight.round_status().outstanding)
+            .collect();
+        if !outstanding.is_empty() {
+            return Err(RoundError::ResultsOutstanding(outstanding));
+        }
+
+        let mut pairings = Vec::new();
+        for flight in self
+            .flights
+            .iter_mut()
+            .filter(|flight| flight.current_round <= flight.rounds)
+        {
+            if let Some(flight_pairings) = flight.next_round()? {
+                pairings.extend(flight_pairings);
+            }
+        }
+
+        if !pairings.is_empty() || self.config.cut.is_none() {
+            return Ok(Some(pairings).filter(|p| !p.is_empty()));
+        }
+
+        self.start_top_cut();
+        self.next_round()
+    }
+
+    /// Ranks all flights together and moves the players who make the cut into a fresh tournament,
+    /// seeded by their combined rank
+    fn start_top_cut(&mut self) {
+        let mut standings = self.merged_standings();
+        let advancing = match self.config.cut {
+            Some(Cut::MinPoints(points)) => standings
+                .iter()
+                .take_while(|p| p.match_points >= points)
+                .count(),
+            Some(Cut::Top(n)) => n.min(standings.len()),
+            None => standings.len(),
+        };
+        let eliminated = standings.split_off(advancing);
+
+        let mut top_cut = Tournament::from_arena(standings.iter().map(fresh_player).collect());
+        if let Some(rounds) = self.config.cut_rounds {
+            top_cut.rounds = rounds;
+        }
+        if let Some(flight) = self.flights.first() {
+            top_cut.first_player = flight.first_player;
+            top_cut.match_format = flight.match_format;
+        }
+
+        self.top_cut = Some(top_cut);
+        self.eliminated = eliminated.into_iter().collect();
+        self.eliminated.retire(standings);
+    }
+
+    /// Records the result of a pairing of any flight or the top cut
+    pub fn end_match(
+        &mut self,
+        uuid: uuid::Uuid,
+        home_score: u8,
+        away_score: u8,
+        drawn: u8,
+    ) -> Result<(), PairingResultError> {
+        self.top_cut
+            .iter_mut()
+            .chain(&mut self.flights)
+            .find(|t| t.pairings.contains_key(&uuid))
+            .ok_or(PairingResultError::NotFound(uuid))?
+            .end_match(uuid, home_score, away_score, drawn)
+    }
+
+    /// Combined standings of the event. Once the top cut has started, its players are ranked
+    /// above everyone else; the remaining players are ranked by their results across all flights,
+    /// using the default tiebreakers.
+    pub fn standings(&mut self) -> Vec<Player> {
+        match &mut self.top_cut {
+            Some(top_cut) => {
+                let mut standings: Vec<Player> = top_cut.ranking().into_iter().cloned().collect();
+                let mut eliminated: Vec<&Player> = self.eliminated.iter().collect();
+                sort_by_tiebreakers(
+                    &mut eliminated,
+                    &self.eliminated,
+                    &Criterion::DEFAULT_TIEBREAKERS,
+                );
+                standings.extend(eliminated.into_iter().cloned());
+                standings
+            }
+            None => self.merged_standings(),
+        }
+    }
+
+    /// All players of all flights, ranked together by their flight results
+    fn merged_standings(&self) -> Vec<Player> {
+        let players: PlayerArena = self
+            .flights
+            .iter()
+            .flat_map(|flight| flight.players.iter().cloned())
+            .collect();
+        let mut standings: Vec<&Player> = players.iter().collect();
+        sort_by_tiebreakers(&mut standings, &players, &Criterion::DEFAULT_TIEBREAKERS);
+        standings.into_iter().cloned().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ratings::{Rating, RatingSystem};
+
+    fn players(n: u32) -> Vec<Player> {
+        (1..=n)
+            .map(|i| Player::new(format!("Player {}", i).as_str()))
+            .collect()
+    }
+
+    fn names(flight: &Tournament) -> Vec<String> {
+        flight.players.iter().map(|p| p.name.clone()).collect()
+    }
+
+    #[test]
+    fn no_flights() {
+        assert_eq!(
+            FlightEvent::new(players(4), FlightConfig::new(0)).err(),
+            Some(FlightError::NoFlights)
+        );
+    }
+
+    #[test]
+    fn assignment() {
+        let config = FlightConfig {
+            assignment: FlightAssignment::ByRegistration,
+            ..FlightConfig::new(2)
+        };
+        let mut event = FlightEvent::new(players(5), config).unwrap();
+        assert_eq!(
+            names(&event.flights()[0]),
+            ["Player 1", "Player 2", "Player 3"]
+        );
+        assert_eq!(names(&event.flights()[1]), ["Player 4", "Player 5"]);
+
+        let mut ratings = Ratings::new(RatingSystem::default());
+        for i in 1..=6 {
+            let rating = Rating {
+                rating: 1500.0 + 100.0 * f64::from(i),
+                ..Rating::default()
+            };
+            ratings.ratings.insert(format!("Player {}", i), rating);
+        }
+        let config = FlightConfig {
+            assignment: FlightAssignment::ByRating(ratings),
+            ..FlightConfig::new(2)
+        };
+        let mut event = FlightEvent::new(players(6), config).unwrap();
+        assert_eq!(
+            names(&event.flights()[0]),
+            ["Player 6", "Player 3", "Player 2"]
+        );
+        assert_eq!(
+            names(&event.flights()[1]),
+            ["Player 5", "Player 4", "Player 1"]
+        );
+
+        let event = FlightEvent::new(players(7), FlightConfig::new(3)).unwrap();
+        let mut sizes: Vec<_> = event.flights.iter().map(|f| f.players.len()).collect();
+        sizes.sort_unstable();
+        assert_eq!(sizes, [2, 2, 3]);
+    }
+
+    #[test]
+    fn flights_pair_independently() {
+        let players = players(12);
+        let uuids: Vec<_> = players.iter().map(|p| p.uuid).collect();
+        let mut event = FlightEvent::new(players, FlightConfig::new(2)).unwrap();
+        assert_eq!(event.flights[0].rounds, event.flights[1].rounds);
+
+        let pairings = event.next_round().unwrap().unwrap();
+        assert_eq!(pairings.len(), 6);
+        assert!(event.next_round().is_err());
+        for (uuid, _, _) in &pairings {
+            event.end_match(*uuid, 2, 0, 0).unwrap();
+        }
+        while let Some(pairings) = event.next_round().unwrap() {
+            for (uuid, _, _) in pairings {
+                event.end_match(uuid, 2, 1, 0).unwrap();
+            }
+        }
+        assert!(event.top_cut().is_none());
+
+        // Nobody met a player of the other flight
+        for flight in event.flights.iter() {
+            for p in &flight.players {
+                let own = event.flight_of(p.uuid);
+                assert!(p.opponents.iter().all(|&o| event.flight_of(o) == own));
+            }
+        }
+
+        let standings = event.standings();
+        assert_eq!(standings.len(), 12);
+        let points: Vec<u32> = standings.iter().map(|p| p.match_points).collect();
+        assert!(points.windows(2).all(|w| w[0] >= w[1]));
+        assert!(uuids.iter().all(|uuid| event.flight_of(*uuid).is_some()));
+    }
+
+    #[test]
+    fn combined_cut() {
+        let config = FlightConfig {
+            cut: Some(Cut::Top(4)),
+            cut_rounds: Some(1),
+            ..FlightConfig::new(2)
+        };
+        let mut event = FlightEvent::new(players(16), config).unwrap();
+
+        let mut rounds = 0;
+        while let Some(pairings) = event.next_round().unwrap() {
+            rounds += 1;
+            for (uuid, _, _) in pairings {
+                event.end_match(uuid, 2, 1, 0).unwrap();
+            }
+        }
+        assert_eq!(rounds, event.flights()[0].rounds + 1);
+
+        let top_cut = event.top_cut().unwrap();
+        assert_eq!(top_cut.players.len(), 4);
+        let finalists: Vec<_> = top_cut.players.iter().map(|p| p.uuid).collect();
+
+        let standings = event.standings();
+        assert_eq!(standings.len(), 16);
+        for p in &standings[..4] {
+            assert!(finalists.contains(&p.uuid));
+        }
+    }
+}
@@ -0,0 +1,186 @@
+//! The pairing network of a tournament as a graph: every player is a node, and every match an
+//! edge between its two players, annotated with its round and result. Exported as DOT for
+//! Graphviz or as GraphML for tools like Gephi, to visualize how the Swiss bracket evolved.
+
+use crate::archive::escape_html;
+use crate::history::MatchResult;
+use crate::Tournament;
+
+/// A match of the pairing network, from the home player to the away player
+struct Edge {
+    round: u32,
+    home: String,
+    away: String,
+    result: Option<MatchResult>,
+}
+
+/// Formats a result from the home player's point of view, e.g. "2-1", with drawn games appended
+/// if there were any
+fn result_label(result: &MatchResult) -> String {
+    if result.drawn > 0 {
+        format!(
+            "{}-{}-{}",
+            result.home_score, result.away_score, result.drawn
+        )
+    } else {
+        format!("{}-{}", result.home_score, result.away_score)
+    }
+}
+
+/// Escapes a string for a quoted DOT ID
+fn escape_dot(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+impl Tournament {
+    /// Node IDs by player number, e.g. "p3"
+    fn node_id(&self, uuid: uuid::Uuid) -> String {
+        match self.player_number(uuid) {
+            Some(number) => format!("p{}", number),
+            None => uuid.to_string(),
+        }
+    }
+
+    /// All matches of all rounds in the order they were paired. Byes aren't matches and are left
+    /// out.
+    fn graph_edges(&self) -> Vec<Edge> {
+        self.round_history()
+            .iter()
+            .flat_map(|round| {
+                round.pairings.iter().map(move |pair| Edge {
+                    round: round.number,
+                    home: self.node_id(pair.home),
+                    away: self.node_id(pair.away),
+                    result: pair.result,
+                })
+            })
+            .collect()
+    }
+
+    /// Renders the pairing network in the DOT language of Graphviz. Players are labeled with
+    /// their number and name, and every match with its round and result, e.g. "R2: 2-1".
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use swyss::{Player, Tournament};
+    ///
+    /// let players = vec![
+    ///     Player::new("Alice"),
+    ///     Player::new("Bob"),
+    /// ];
+    /// let mut tourn = Tournament::with_players(players).unwrap();
+    /// let (uuid, _, _) = tourn.next_round().unwrap().unwrap()[0];
+    /// tourn.end_match(uuid, 2, 1, 0).unwrap();
+    ///
+    /// let dot = tourn.pairing_graph_dot();
+    /// assert!(dot.starts_with("graph pairings {\n"));
+    /// assert!(dot.contains("p1 [label=\"1. Alice\"];"));
+    /// assert!(dot.contains("[label=\"R1: 2-1\", round=1];"));
+    /// ```
+    pub fn pairing_graph_dot(&self) -> String {
+        let mut out = String::from("graph pairings {\n");
+
+        for p in self.players() {
+            let number = self.player_number(p.uuid).unwrap_or_default();
+            out.push_str(&format!(
+                "  {} [label=\"{}. {}\"];\n",
+                self.node_id(p.uuid),
+                number,
+                escape_dot(&p.name)
+            ));
+        }
+
+        for edge in self.graph_edges() {
+            let label = match &edge.result {
+                Some(result) => format!("R{}: {}", edge.round, result_label(result)),
+                None => format!("R{}", edge.round),
+            };
+            out.push_str(&format!(
+                "  {} -- {} [label=\"{}\", round={}];\n",
+                edge.home, edge.away, label, edge.round
+            ));
+        }
+
+        out.push_str("}\n");
+        out
+    }
+
+    /// Renders the pairing network as GraphML. Players carry their name, number, and match
+    /// points, and every match its round and, once reported, its result from the home player's
+    /// point of view. The home player is the source of each edge.
+    pub fn pairing_graph_graphml(&self) -> String {
+        let mut out = String::from(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+             <graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">\n  \
+             <key id=\"name\" for=\"node\" attr.name=\"name\" attr.type=\"string\"/>\n  \
+             <key id=\"number\" for=\"node\" attr.name=\"number\" attr.type=\"int\"/>\n  \
+             <key id=\"match_points\" for=\"node\" attr.name=\"match_points\" attr.type=\"int\"/>\n  \
+             <key id=\"round\" for=\"edge\" attr.name=\"round\" attr.type=\"int\"/>\n  \
+             <key id=\"result\" for=\"edge\" attr.name=\"result\" attr.type=\"string\"/>\n  \
+             <graph id=\"pairings\" edgedefault=\"undirected\">\n",
+        );
+
+        for p in self.players() {
+            out.push_str(&format!(
+                "    <node id=\"{}\"><data key=\"name\">{}</data><data key=\"number\">{}</data>\
+                 <data key=\"match_points\">{}</data></node>\n",
+                self.node_id(p.uuid),
+                escape_html(&p.name),
+                self.player_number(p.uuid).unwrap_or_default(),
+                p.match_points
+            ));
+        }
+
+        for (i, edge) in self.graph_edges().iter().enumerate() {
+            out.push_str(&format!(
+                "    <edge id=\"e{}\" source=\"{}\" target=\"{}\"><data key=\"round\">{}</data>",
+                i + 1,
+                edge.home,
+                edge.away,
+                edge.round
+            ));
+            if let Some(result) = &edge.result {
+                out.push_str(&format!(
+                    "<data key=\"result\">{}</data>",
+                    result_label(result)
+                ));
+            }
+            out.push_str("</edge>\n");
+        }
+
+        out.push_str("  </graph>\n</graphml>\n");
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Player, Tournament};
+
+    #[test]
+    fn every_match_is_an_edge() {
+        let players = ["Alice", "Bob \"the Builder\"", "Carol & Dave", "Eve"]
+            .iter()
+            .map(|name| Player::new(name))
+            .collect();
+        let mut tourn = Tournament::with_players(players).unwrap();
+        for (uuid, _, _) in tourn.next_round().unwrap().unwrap() {
+            tourn.end_match(uuid, 1, 1, 1).unwrap();
+        }
+        tourn.next_round().unwrap();
+
+        let dot = tourn.pairing_graph_dot();
+        assert_eq!(dot.matches(" -- ").count(), 4);
+        assert_eq!(dot.matches("R1: 1-1-1").count(), 2);
+        assert_eq!(dot.matches("[label=\"R2\", round=2]").count(), 2);
+        assert!(dot.contains("p2 [label=\"2. Bob \\\"the Builder\\\"\"];"));
+
+        let graphml = tourn.pairing_graph_graphml();
+        assert_eq!(graphml.matches("<node ").count(), 4);
+        assert_eq!(graphml.matches("<edge ").count(), 4);
+        assert_eq!(graphml.matches("<data key=\"result\">").count(), 2);
+        assert!(graphml.contains("<data key=\"name\">Carol &amp; Dave</data>"));
+        assert!(graphml.ends_with("</graphml>\n"));
+    }
+}
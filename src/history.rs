@@ -0,0 +1,775 @@
+//! Record of all rounds of a tournament. The pairings of the current round are replaced whenever
+//! the next round is paired, so every round is kept along with its results once it is over.
+//! Previous rounds can then still be displayed and exported.
+
+use crate::byes::RequestedBye;
+use crate::persist::{standings_rows, StandingsRow};
+use crate::rationale::PairingRationale;
+use crate::texttable::{Align, TextTable, MAX_NAME_WIDTH};
+use crate::{ByeScore, Pairing, PlayerSide, Tournament};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fmt;
+use std::time::Duration;
+
+/// The recorded result of a match, including games awarded by penalties
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct MatchResult {
+    pub home_score: u8,
+    pub away_score: u8,
+    /// Drawn games, including a game that was unfinished when time was called
+    pub drawn: u8,
+    /// Time was called before the match was decided
+    #[serde(default)]
+    pub time_called: bool,
+    /// The player who conceded the match, if it wasn't played out
+    #[serde(default)]
+    pub conceded: Option<crate::PlayerSide>,
+    /// Both players were given a loss, e.g. because no result was reported by the deadline
+    #[serde(default)]
+    pub double_loss: bool,
+}
+
+/// A pairing of a past or current round
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct RoundPairing {
+    pub uuid: uuid::Uuid,
+    pub home: uuid::Uuid,
+    pub away: uuid::Uuid,
+    /// `None` if no result was entered
+    pub result: Option<MatchResult>,
+    /// When the pairing was created, in seconds since the Unix epoch
+    #[serde(default)]
+    pub started: Option<u64>,
+    /// When the result was entered, in seconds since the Unix epoch
+    #[serde(default)]
+    pub finished: Option<u64>,
+    /// Who went first, if the tournament decided it
+    #[serde(default)]
+    pub first: Option<PlayerSide>,
+}
+
+impl RoundPairing {
+    /// Returns how long the match took, if it was timed and has a result
+    pub fn duration(&self) -> Option<Duration> {
+        let started = self.started?;
+        let finished = self.finished?;
+        Some(Duration::from_secs(finished.saturating_sub(started)))
+    }
+}
+
+impl From<&Pairing> for RoundPairing {
+    fn from(pair: &Pairing) -> RoundPairing {
+        RoundPairing {
+            uuid: pair.uuid,
+            home: pair.home,
+            away: pair.away,
+            result: pair.result.get(),
+            started: Some(pair.created),
+            finished: pair.finished.get(),
+            first: pair.first,
+        }
+    }
+}
+
+/// All pairings of a round and the player who got the bye, if any
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct Round {
+    pub number: u32,
+    pub pairings: Vec<RoundPairing>,
+    pub bye: Option<uuid::Uuid>,
+    /// What the bye was worth
+    #[serde(default)]
+    pub bye_score: ByeScore,
+    /// Byes that players requested for this round
+    #[serde(default)]
+    pub requested_byes: Vec<RequestedBye>,
+    /// Standings at the end of the round, recorded once the next round is paired or the
+    /// tournament ends
+    #[serde(default)]
+    pub standings: Vec<StandingsRow>,
+    /// The pairing shown on stream, if the organizer featured one
+    #[serde(default)]
+    pub featured: Option<uuid::Uuid>,
+    /// Why the round was paired the way it was; `None` for rounds paired before it was recorded
+    #[serde(default)]
+    pub(crate) rationale: Option<PairingRationale>,
+}
+
+impl Round {
+    /// Returns why the round was paired the way it was: the score groups, who floated, which
+    /// rematches couldn't be avoided, and who got the bye and why
+    pub fn rationale(&self) -> Option<&PairingRationale> {
+        self.rationale.as_ref()
+    }
+
+    /// Returns the pairing a player was part of, if they were paired in this round
+    pub fn pairing_of(&self, player: uuid::Uuid) -> Option<&RoundPairing> {
+        self.pairings
+            .iter()
+            .find(|pair| pair.home == player || pair.away == player)
+    }
+
+    /// Checks whether every pairing of the round has a result
+    pub fn is_complete(&self) -> bool {
+        self.pairings.iter().all(|pair| pair.result.is_some())
+    }
+
+    /// Average duration of the timed matches of the round that have a result
+    pub fn average_duration(&self) -> Option<Duration> {
+        let durations: Vec<Duration> = self.pairings.iter().filter_map(|p| p.duration()).collect();
+        if durations.is_empty() {
+            return None;
+        }
+
+        Some(durations.iter().sum::<Duration>() / durations.len() as u32)
+    }
+
+    /// Duration of the longest timed match of the round that has a result
+    pub fn longest_duration(&self) -> Option<Duration> {
+        self.pairings.iter().filter_map(|p| p.duration()).max()
+    }
+}
+
+/// How the players who went first fared, over all matches where the tournament decided who goes
+/// first. In best-of-one, this is the win rate on the play.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct PlayDrawStats {
+    /// Matches with a result and a decided first player
+    pub matches: u32,
+    /// Matches won by the player who went first
+    pub first_wins: u32,
+    /// Matches won by the player who went second
+    pub second_wins: u32,
+    pub draws: u32,
+}
+
+impl PlayDrawStats {
+    /// Share of matches won by the player who went first, or `None` without any matches
+    pub fn first_win_rate(&self) -> Option<f64> {
+        if self.matches == 0 {
+            None
+        } else {
+            Some(f64::from(self.first_wins) / f64::from(self.matches))
+        }
+    }
+}
+
+/// Formats a duration as minutes and seconds, e.g. "12:05"
+pub(crate) fn minutes(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    format!("{}:{:02}", secs / 60, secs % 60)
+}
+
+/// Which pairings of the current round have a result and which are still outstanding. Pairings
+/// are in the order of `Tournament::round()`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RoundStatus {
+    pub round: u32,
+    pub reported: Vec<uuid::Uuid>,
+    pub outstanding: Vec<uuid::Uuid>,
+}
+
+impl RoundStatus {
+    /// Checks whether every pairing of the round has a result
+    pub fn is_complete(&self) -> bool {
+        self.outstanding.is_empty()
+    }
+}
+
+/// Where a tournament stands, as returned by `Tournament::state()`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TournamentState {
+    /// Players are still registering and checking in
+    Registration,
+    /// The given round is being played and results are still outstanding
+    InRound(u32),
+    /// Every result of the current round is in, or the first round hasn't been paired yet
+    BetweenRounds,
+    /// All rounds have been played, or the tournament was ended early with `finish()`
+    Complete,
+}
+
+/// Reasons why the next round can't be paired
+#[derive(Debug, PartialEq)]
+pub enum RoundError {
+    /// These pairings of the current round don't have a result yet
+    ResultsOutstanding(Vec<uuid::Uuid>),
+    /// A round preview was made for a different round than the one to be paired next
+    StalePreview { previewed: u32, next: u32 },
+    /// A round preview pairs this player twice, pairs them although they aren't available, or
+    /// leaves them out although they need a pairing
+    InvalidPreview(uuid::Uuid),
+    /// The tournament was opened read-only, so no more rounds can be paired
+    ReadOnly,
+    /// The tournament couldn't be copied to pair the round on the copy first, e.g. because two
+    /// players share a UUID. Holds the reason the copy failed.
+    Inconsistent(String),
+}
+
+impl std::error::Error for RoundError {}
+
+impl fmt::Display for RoundError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RoundError::ResultsOutstanding(pairings) => {
+                write!(
+                    f,
+                    "{} result(s) of the current round missing!",
+                    pairings.len()
+                )
+            }
+            RoundError::StalePreview { previewed, next } => write!(
+                f,
+                "The preview is of round {}, but round {} is next!",
+                previewed, next
+            ),
+            RoundError::InvalidPreview(player) => {
+                write!(f, "The preview can't be used to pair player {}!", player)
+            }
+            RoundError::ReadOnly => write!(f, "The tournament was opened read-only!"),
+            RoundError::Inconsistent(reason) => {
+                write!(f, "The tournament can't be copied: {}", reason)
+            }
+        }
+    }
+}
+
+impl Tournament {
+    /// Returns all rounds paired so far, in order. The current round includes all results that
+    /// have been entered up to now.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use swyss::{Player, Tournament};
+    ///
+    /// let players = (1..5)
+    ///     .map(|i| Player::new(&format!("Player {}", i)))
+    ///     .collect();
+    /// let mut tourn = Tournament::with_players(players).unwrap();
+    /// for (uuid, _, _) in tourn.next_round().unwrap().unwrap() {
+    ///     tourn.end_match(uuid, 2, 1, 0).unwrap();
+    /// }
+    /// tourn.next_round().unwrap().unwrap();
+    ///
+    /// let history = tourn.round_history();
+    /// assert_eq!(history.len(), 2);
+    /// assert!(history[0].is_complete());
+    /// assert!(!history[1].is_complete());
+    /// ```
+    pub fn round_history(&self) -> Vec<Round> {
+        let mut history = self.history.clone();
+        self.update_current_round(&mut history);
+        history
+    }
+
+    /// Returns a single round by its number, starting at 1
+    pub fn round(&self, number: u32) -> Option<Round> {
+        self.round_history()
+            .into_iter()
+            .find(|round| round.number == number)
+    }
+
+    /// Returns the pairings of a round between players who already met in an earlier round
+    pub fn rematches(&self, number: u32) -> Vec<uuid::Uuid> {
+        let history = self.round_history();
+        let met: HashSet<(uuid::Uuid, uuid::Uuid)> = history
+            .iter()
+            .filter(|round| round.number < number)
+            .flat_map(|round| &round.pairings)
+            .flat_map(|pair| vec![(pair.home, pair.away), (pair.away, pair.home)])
+            .collect();
+
+        history
+            .iter()
+            .filter(|round| round.number == number)
+            .flat_map(|round| &round.pairings)
+            .filter(|pair| met.contains(&(pair.home, pair.away)))
+            .map(|pair| pair.uuid)
+            .collect()
+    }
+
+    /// Reports which pairings of the current round still lack a result. The next round can only
+    /// be paired once the status is complete. Before the first round, there is nothing to report.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use swyss::history::RoundError;
+    /// use swyss::{Player, Tournament};
+    ///
+    /// let players = (1..5)
+    ///     .map(|i| Player::new(&format!("Player {}", i)))
+    ///     .collect();
+    /// let mut tourn = Tournament::with_players(players).unwrap();
+    /// let pairings = tourn.next_round().unwrap().unwrap();
+    /// tourn.end_match(pairings[0].0, 2, 0, 0).unwrap();
+    ///
+    /// let status = tourn.round_status();
+    /// assert_eq!(status.reported, vec![pairings[0].0]);
+    /// assert_eq!(status.outstanding, vec![pairings[1].0]);
+    /// assert_eq!(
+    ///     tourn.next_round(),
+    ///     Err(RoundError::ResultsOutstanding(vec![pairings[1].0]))
+    /// );
+    /// ```
+    pub fn round_status(&self) -> RoundStatus {
+        let pairings = self
+            .round(self.current_round)
+            .map(|round| round.pairings)
+            .unwrap_or_default();
+        let (reported, outstanding): (Vec<_>, Vec<_>) =
+            pairings.iter().partition(|pair| pair.result.is_some());
+
+        RoundStatus {
+            round: self.current_round,
+            reported: reported.iter().map(|pair| pair.uuid).collect(),
+            outstanding: outstanding.iter().map(|pair| pair.uuid).collect(),
+        }
+    }
+
+    /// Returns where the tournament stands: in registration, in a round with results outstanding,
+    /// between rounds, or complete
+    pub fn state(&self) -> TournamentState {
+        if self.is_finished() {
+            TournamentState::Complete
+        } else if self.is_registering() {
+            TournamentState::Registration
+        } else if !self.round_status().is_complete() {
+            TournamentState::InRound(self.current_round)
+        } else {
+            TournamentState::BetweenRounds
+        }
+    }
+
+    /// Ends the tournament after the current round, e.g. when time runs out before all planned
+    /// rounds are played. The standings of the current round become the final standings and
+    /// observers are notified of the completion. Afterwards, no more rounds are paired and
+    /// results can no longer be entered or amended. Finishing a complete tournament does nothing.
+    ///
+    /// Fails if results of the current round are still outstanding.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use swyss::history::TournamentState;
+    /// use swyss::{PairingResultError, Player, Tournament};
+    ///
+    /// let players = (1..5)
+    ///     .map(|i| Player::new(&format!("Player {}", i)))
+    ///     .collect();
+    /// let mut tourn = Tournament::with_players(players).unwrap();
+    /// let pairings = tourn.next_round().unwrap().unwrap();
+    /// assert_eq!(tourn.state(), TournamentState::InRound(1));
+    /// for (uuid, _, _) in &pairings {
+    ///     tourn.end_match(*uuid, 2, 0, 0).unwrap();
+    /// }
+    ///
+    /// tourn.finish().unwrap();
+    /// assert_eq!(tourn.state(), TournamentState::Complete);
+    /// assert_eq!(tourn.next_round(), Ok(None));
+    /// assert!(matches!(
+    ///     tourn.amend_match(pairings[0].0, 0, 2, 0),
+    ///     Err(PairingResultError::Finished)
+    /// ));
+    /// ```
+    pub fn finish(&mut self) -> Result<(), RoundError> {
+        if self.read_only {
+            return Err(RoundError::ReadOnly);
+        }
+        if self.is_finished() {
+            return Ok(());
+        }
+        let status = self.round_status();
+        if !status.is_complete() {
+            return Err(RoundError::ResultsOutstanding(status.outstanding));
+        }
+
+        self.record_standings();
+        self.rounds = self.current_round;
+        self.current_round += 1;
+        self.notify(|o| o.on_complete(self));
+
+        Ok(())
+    }
+
+    /// Returns the standings as they were at the end of the given round, starting at 1. For the
+    /// current round, the standings are only available once all its results are in. Returns
+    /// `None` for rounds that haven't been played to the end.
+    pub fn standings_after(&self, round: u32) -> Option<Vec<StandingsRow>> {
+        let record = self.round(round)?;
+        if !record.standings.is_empty() {
+            Some(record.standings)
+        } else if round == self.current_round && record.is_complete() {
+            Some(self.standings())
+        } else {
+            None
+        }
+    }
+
+    /// Returns a player's rank at the end of every round played so far, as pairs of round number
+    /// and rank
+    pub fn rank_progression(&self, player: uuid::Uuid) -> Vec<(u32, u32)> {
+        (1..=self.current_round.min(self.rounds))
+            .filter_map(|round| {
+                let standings = self.standings_after(round)?;
+                let row = standings.iter().find(|row| row.uuid == player)?;
+                Some((round, row.rank))
+            })
+            .collect()
+    }
+
+    /// Renders every player's rank after each round as a text table, with players in the order of
+    /// the latest standings
+    pub fn progression_chart(&self) -> String {
+        let rounds: Vec<_> = (1..=self.current_round.min(self.rounds))
+            .filter_map(|round| self.standings_after(round))
+            .collect();
+        let latest = match rounds.last() {
+            Some(latest) => latest,
+            None => return String::new(),
+        };
+
+        let headers: Vec<String> = (1..=rounds.len())
+            .map(|round| format!("R{}", round))
+            .collect();
+        let mut columns = vec![("Name", Align::Left)];
+        columns.extend(headers.iter().map(|header| (header.as_str(), Align::Right)));
+
+        let mut table = TextTable::new(&columns);
+        table.set_max_width(0, MAX_NAME_WIDTH);
+        for row in latest {
+            let mut cells = vec![row.name.clone()];
+            for standings in &rounds {
+                match standings.iter().find(|r| r.uuid == row.uuid) {
+                    Some(r) => cells.push(r.rank.to_string()),
+                    None => cells.push(String::from("-")),
+                }
+            }
+            table.push(cells);
+        }
+
+        table.to_string()
+    }
+
+    /// Returns the standings as of now, including the results entered so far in the current round.
+    /// Unlike `ranking()`, this leaves the tournament untouched: it never makes the random draw
+    /// between players who are tied on everything, who keep their current order until it's made.
+    pub fn standings(&self) -> Vec<StandingsRow> {
+        let players = self.sorted_players();
+        standings_rows(self, &players)
+    }
+
+    /// Records the standings at the end of the last round, once all of its results are in. Called
+    /// before the next round is paired, so byes of the next round aren't counted yet.
+    pub(crate) fn record_standings(&mut self) {
+        let complete = match self.round(self.current_round) {
+            Some(round) => round.standings.is_empty() && round.is_complete(),
+            None => false,
+        };
+
+        if complete {
+            let standings = self.standings();
+            if let Some(round) = self.history.last_mut() {
+                round.standings = standings;
+            }
+        }
+    }
+
+    /// Checks whether a pairing belongs to a round before the current one
+    pub(crate) fn in_closed_round(&self, pairing: uuid::Uuid) -> bool {
+        self.history
+            .iter()
+            .filter(|round| round.number < self.current_round)
+            .any(|round| round.pairings.iter().any(|pair| pair.uuid == pairing))
+    }
+
+    /// Lists the average and longest match time of every round, one line per round, to help
+    /// plan the schedule of future events. Rounds without timed results are left out.
+    pub fn match_times_report(&self) -> String {
+        let mut report = String::new();
+
+        for round in self.round_history() {
+            if let (Some(average), Some(longest)) =
+                (round.average_duration(), round.longest_duration())
+            {
+                report.push_str(&format!(
+                    "Round {}: average {}, longest {}\n",
+                    round.number,
+                    minutes(average),
+                    minutes(longest)
+                ));
+            }
+        }
+
+        report
+    }
+
+    /// Counts how often the player who went first won, over all rounds played so far
+    pub fn play_draw_stats(&self) -> PlayDrawStats {
+        let mut stats = PlayDrawStats::default();
+
+        for round in self.round_history() {
+            for pair in &round.pairings {
+                let (first, result) = match (pair.first, pair.result) {
+                    (Some(first), Some(result)) if !result.double_loss => (first, result),
+                    _ => continue,
+                };
+
+                stats.matches += 1;
+                let (first_score, second_score) = match first {
+                    PlayerSide::Home => (result.home_score, result.away_score),
+                    PlayerSide::Away => (result.away_score, result.home_score),
+                };
+                match first_score.cmp(&second_score) {
+                    std::cmp::Ordering::Greater => stats.first_wins += 1,
+                    std::cmp::Ordering::Less => stats.second_wins += 1,
+                    std::cmp::Ordering::Equal => stats.draws += 1,
+                }
+            }
+        }
+
+        stats
+    }
+
+    /// Copies the results of the current round into its record. Called before the pairings of
+    /// the current round are replaced.
+    pub(crate) fn close_round(&mut self) {
+        let mut history = std::mem::take(&mut self.history);
+        self.update_current_round(&mut history);
+        self.history = history;
+    }
+
+    /// Records a newly paired round
+    pub(crate) fn open_round(
+        &mut self,
+        bye: Option<uuid::Uuid>,
+        requested_byes: Vec<RequestedBye>,
+    ) {
+        let mut pairings: Vec<RoundPairing> =
+            self.pairings.values().map(RoundPairing::from).collect();
+        pairings.sort_by_key(|pair| pair.uuid);
+
+        self.history.push(Round {
+            number: self.current_round,
+            pairings,
+            bye,
+            bye_score: self.bye_score,
+            requested_byes,
+            standings: Vec::new(),
+            featured: None,
+            rationale: None,
+        });
+    }
+
+    /// The live pairings always belong to the round that was paired last
+    fn update_current_round(&self, history: &mut [Round]) {
+        if let Some(round) = history.last_mut() {
+            for pair in &mut round.pairings {
+                if let Some(live) = self.pairings.get(&pair.uuid) {
+                    *pair = RoundPairing::from(live);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{FirstPlayer, MatchFormat, Player};
+
+    #[test]
+    fn rounds_are_kept() {
+        let players = (1..6)
+            .map(|i| Player::new(format!("Player {}", i).as_str()))
+            .collect();
+        let mut tourn = Tournament::with_players(players).unwrap();
+
+        let first = tourn.next_round().unwrap().unwrap();
+        tourn.end_match(first[0].0, 2, 0, 1).unwrap();
+
+        // The next round can't be paired before all results are in
+        let status = tourn.round_status();
+        assert_eq!(status.round, 1);
+        assert_eq!(status.reported, vec![first[0].0]);
+        assert_eq!(status.outstanding, vec![first[1].0]);
+        assert!(!tourn.round(1).unwrap().is_complete());
+        assert_eq!(
+            tourn.next_round(),
+            Err(RoundError::ResultsOutstanding(vec![first[1].0]))
+        );
+        assert_eq!(tourn.current_round, 1);
+
+        tourn.end_match(first[1].0, 1, 2, 0).unwrap();
+        assert!(tourn.round_status().is_complete());
+        for (uuid, _, _) in tourn.next_round().unwrap().unwrap() {
+            tourn.end_match(uuid, 2, 1, 0).unwrap();
+        }
+
+        // Results of the current round show up as soon as they are entered
+        let last = tourn.next_round().unwrap().unwrap();
+        tourn.end_match(last[0].0, 0, 2, 0).unwrap();
+        assert_eq!(
+            tourn
+                .round(3)
+                .unwrap()
+                .pairings
+                .iter()
+                .filter(|pair| pair.result.is_some())
+                .map(|pair| pair.uuid)
+                .collect::<Vec<_>>(),
+            vec![last[0].0]
+        );
+        tourn.end_match(last[1].0, 2, 0, 0).unwrap();
+        assert!(tourn.next_round().unwrap().is_none());
+        assert!(tourn.round_status().is_complete());
+
+        let history = tourn.round_history();
+        assert_eq!(history.len(), 3);
+        assert_eq!(
+            history.iter().map(|r| r.number).collect::<Vec<_>>(),
+            vec![1, 2, 3]
+        );
+        assert!(history.iter().all(|r| r.pairings.len() == 2));
+        assert!(history.iter().all(|r| r.bye.is_some()));
+        assert!(history.iter().all(|r| r.is_complete()));
+
+        let round = tourn.round(1).unwrap();
+        let result = round
+            .pairings
+            .iter()
+            .find(|pair| pair.uuid == first[0].0)
+            .unwrap()
+            .result;
+        assert_eq!(
+            result,
+            Some(MatchResult {
+                home_score: 2,
+                away_score: 0,
+                drawn: 1,
+                time_called: false,
+                conceded: None,
+                double_loss: false,
+            })
+        );
+
+        let bye = round.bye.unwrap();
+        assert!(round.pairing_of(bye).is_none());
+
+        // The history survives saving and loading
+        let loaded = Tournament::from_json(&tourn.to_json()).unwrap();
+        assert_eq!(loaded.round_history(), history);
+    }
+
+    #[test]
+    fn match_times() {
+        let players = (1..5)
+            .map(|i| Player::new(format!("Player {}", i).as_str()))
+            .collect();
+        let mut tourn = Tournament::with_players(players).unwrap();
+        let pairings = tourn.next_round().unwrap().unwrap();
+        assert_eq!(tourn.match_times_report(), "");
+
+        for ((uuid, _, _), minutes) in pairings.iter().zip(&[10, 25]) {
+            tourn.end_match(*uuid, 2, 0, 0).unwrap();
+            let pair = tourn.pairings.get_mut(uuid).unwrap();
+            pair.created = 1000;
+            pair.finished.set(Some(1000 + minutes * 60));
+            assert_eq!(pair.duration(), Some(Duration::from_secs(minutes * 60)));
+        }
+
+        let round = tourn.round(1).unwrap();
+        assert_eq!(round.average_duration(), Some(Duration::from_secs(1050)));
+        assert_eq!(round.longest_duration(), Some(Duration::from_secs(1500)));
+        assert_eq!(
+            tourn.match_times_report(),
+            "Round 1: average 17:30, longest 25:00\n"
+        );
+
+        // Rounds loaded from files without timestamps have no match times
+        let mut untimed = round.clone();
+        untimed
+            .pairings
+            .iter_mut()
+            .for_each(|pair| pair.started = None);
+        assert_eq!(untimed.average_duration(), None);
+    }
+
+    #[test]
+    fn play_draw() {
+        let players = (1..5)
+            .map(|i| Player::new(format!("Player {}", i).as_str()))
+            .collect();
+        let mut tourn = Tournament::with_players(players).unwrap();
+        tourn.match_format = MatchFormat::BestOf(1);
+        tourn.first_player = FirstPlayer::Random;
+        assert_eq!(tourn.play_draw_stats().first_win_rate(), None);
+
+        let pairings = tourn.next_round().unwrap().unwrap();
+        for ((uuid, _, _), first_wins) in pairings.iter().zip(&[true, false]) {
+            let first = tourn.pairings[uuid].first_player().unwrap();
+            let (home, away) = match (first, first_wins) {
+                (PlayerSide::Home, true) | (PlayerSide::Away, false) => (1, 0),
+                _ => (0, 1),
+            };
+            tourn.end_match(*uuid, home, away, 0).unwrap();
+        }
+        let (uuid, _, _) = tourn.next_round().unwrap().unwrap()[0].clone();
+        tourn.end_match(uuid, 0, 0, 1).unwrap();
+
+        let stats = tourn.play_draw_stats();
+        assert_eq!(
+            stats,
+            PlayDrawStats {
+                matches: 3,
+                first_wins: 1,
+                second_wins: 1,
+                draws: 1
+            }
+        );
+        assert_eq!(stats.first_win_rate(), Some(1.0 / 3.0));
+    }
+
+    #[test]
+    fn standings_per_round() {
+        let players: Vec<_> = (1..5)
+            .map(|i| Player::new(format!("Player {}", i).as_str()))
+            .collect();
+        let mut tourn = Tournament::with_players(players).unwrap();
+        assert_eq!(tourn.standings_after(1), None);
+
+        let pairings = tourn.next_round().unwrap().unwrap();
+        tourn.end_match(pairings[0].0, 2, 0, 0).unwrap();
+        assert_eq!(tourn.standings_after(1), None);
+        tourn.end_match(pairings[1].0, 2, 1, 0).unwrap();
+
+        let after_one = tourn.standings_after(1).unwrap();
+        assert_eq!(after_one.len(), 4);
+        assert_eq!(after_one[0].match_points, 3);
+        assert_eq!(after_one[3].match_points, 0);
+
+        let pairings = tourn.next_round().unwrap().unwrap();
+        for (uuid, _, _) in &pairings {
+            tourn.end_match(*uuid, 0, 2, 0).unwrap();
+        }
+
+        // The first round's standings are kept, even after the players' records changed
+        let loaded = Tournament::from_json(&tourn.to_json()).unwrap();
+        assert_eq!(loaded.standings_after(1).unwrap(), after_one);
+
+        let leader = after_one[0].uuid;
+        let progression = tourn.rank_progression(leader);
+        assert_eq!(progression.len(), 2);
+        assert_eq!(progression[0], (1, 1));
+
+        let chart = tourn.progression_chart();
+        assert_eq!(chart.lines().count(), 5);
+        assert!(chart.starts_with("Name"));
+        assert!(chart.lines().next().unwrap().ends_with("R2"));
+    }
+}
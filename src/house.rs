@@ -0,0 +1,102 @@
+//! House players, as some rule sets use instead of byes: a stand-in, e.g. a judge or a volunteer,
+//! who is paired whenever the field is uneven and sits out otherwise, so that nobody ever gets a
+//! bye. Matches against the house player count for their opponents like any other, but the house
+//! player doesn't appear in the standings, the prizes, or any report ranking the players.
+
+use crate::{Player, PlayerError, Tournament};
+
+impl Tournament {
+    /// Adds a house player with the given name, who is paired from the next round on whenever an
+    /// uneven number of players is available, and returns their UUID. A tournament has at most
+    /// one house player, so adding another one replaces the first, who then plays as a regular
+    /// player.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use swyss::{Player, Tournament};
+    ///
+    /// let players = (1..4)
+    ///     .map(|i| Player::new(&format!("Player {}", i)))
+    ///     .collect();
+    /// let mut tourn = Tournament::with_players(players).unwrap();
+    /// let house = tourn.add_house_player("Judge Judy").unwrap();
+    ///
+    /// // Three players and the house player make two tables and no bye
+    /// assert_eq!(tourn.next_round().unwrap().unwrap().len(), 2);
+    /// assert_eq!(tourn.round(1).unwrap().bye, None);
+    /// assert_eq!(tourn.house_player(), Some(house));
+    /// assert!(tourn.ranking().iter().all(|p| p.uuid != house));
+    /// ```
+    pub fn add_house_player(&mut self, name: &str) -> Result<uuid::Uuid, PlayerError> {
+        let player = Player::new(name);
+        let uuid = player.uuid;
+        self.add_player(player)?;
+        self.house_player = Some(uuid);
+        Ok(uuid)
+    }
+
+    /// Returns the UUID of the house player, if the tournament has one
+    pub fn house_player(&self) -> Option<uuid::Uuid> {
+        self.house_player
+    }
+
+    /// Checks whether a player is the house player
+    pub fn is_house_player(&self, player: uuid::Uuid) -> bool {
+        self.house_player == Some(player)
+    }
+
+    /// Takes the house player out of the active players if the others are an even number
+    pub(crate) fn seat_house_player<'a>(
+        &self,
+        active: &mut Vec<&'a Player>,
+        resting: &mut Vec<&'a Player>,
+    ) {
+        let house = match self.house_player {
+            Some(house) if active.len() % 2 == 1 => house,
+            _ => return,
+        };
+        if let Some(i) = active.iter().position(|p| p.uuid == house) {
+            resting.push(active.remove(i));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn no_byes_and_no_standing() {
+        let players = (1..6)
+            .map(|i| Player::new(format!("Player {}", i).as_str()))
+            .collect();
+        let mut tourn = Tournament::with_players(players).unwrap();
+        let house = tourn.add_house_player("House").unwrap();
+        tourn.rounds = 3;
+
+        for round in 1..4 {
+            let pairings = tourn.next_round().unwrap().unwrap();
+            assert_eq!(pairings.len(), if round == 1 { 3 } else { 2 });
+            assert_eq!(tourn.round(round).unwrap().bye, None);
+            for (uuid, _, _) in pairings {
+                tourn.end_match(uuid, 2, 0, 0).unwrap();
+            }
+            // A player who leaves evens out the field, so the house player sits out
+            if round == 1 {
+                let leaving = tourn.players().find(|p| p.uuid != house).unwrap().uuid;
+                tourn.drop_player(leaving).unwrap();
+            }
+        }
+
+        let played = |round: u32| tourn.round(round).unwrap().pairing_of(house).is_some();
+        assert!(played(1));
+        assert!(!played(2) && !played(3));
+        let standings = tourn.standings();
+        assert_eq!(standings.len(), 5);
+        assert!(standings.iter().all(|row| row.uuid != house));
+
+        let loaded = Tournament::from_json(&tourn.to_json()).unwrap();
+        assert!(loaded.is_house_player(house));
+    }
+}
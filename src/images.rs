@@ -0,0 +1,178 @@
+//! Players of image mode, one for each image file in a directory and its subdirectories. Images
+//! are ordered by their path, so the same directory always yields the same players in the same
+//! order and a journal of an interrupted tournament can be replayed onto them. A player is named
+//! after the path of their image without the extension, e.g. "cats/tom" for `cats/tom.png`,
+//! unless a `names.toml` in the directory gives the image another name:
+//!
+//! ```toml
+//! "cats/tom.png" = "Tom"
+//! "dogs/rex.jpg" = "Rex"
+//! ```
+
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Extensions of the files that are images, compared ignoring case
+pub const IMAGE_EXTENSIONS: [&str; 5] = ["bmp", "gif", "jpeg", "jpg", "png"];
+
+/// Name of the sidecar file that names images
+pub const NAMES_FILE: &str = "names.toml";
+
+/// An image and the name of the player it stands for
+#[derive(Clone, Debug, PartialEq)]
+pub struct Image {
+    pub path: PathBuf,
+    pub name: String,
+}
+
+#[derive(Debug)]
+pub enum ImageError {
+    Io(io::Error),
+    /// The names file isn't a table of paths and names
+    Names(toml::de::Error),
+    /// The names file names a file that isn't an image of the directory
+    UnknownImage(String),
+    /// Two images have the same name
+    DuplicateName(String),
+}
+
+impl std::error::Error for ImageError {}
+
+impl fmt::Display for ImageError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ImageError::Io(e) => write!(f, "Could not read images: {}", e),
+            ImageError::Names(e) => write!(f, "Could not read {}: {}", NAMES_FILE, e),
+            ImageError::UnknownImage(path) => {
+                write!(f, "{} names {}, which is not an image", NAMES_FILE, path)
+            }
+            ImageError::DuplicateName(name) => {
+                write!(f, "More than one image is named {}", name)
+            }
+        }
+    }
+}
+
+impl From<io::Error> for ImageError {
+    fn from(e: io::Error) -> ImageError {
+        ImageError::Io(e)
+    }
+}
+
+/// Finds the images in a directory and its subdirectories, ordered by path, and names them.
+/// Hidden files and directories, whose names start with a dot, are skipped.
+pub fn images<P: AsRef<Path>>(dir: P) -> Result<Vec<Image>, ImageError> {
+    let dir = dir.as_ref();
+    let mut paths = Vec::new();
+    find_images(dir, &mut paths)?;
+    paths.sort();
+
+    let names_file = dir.join(NAMES_FILE);
+    let mut names: BTreeMap<String, String> = if names_file.exists() {
+        toml::from_str(&fs::read_to_string(names_file)?).map_err(ImageError::Names)?
+    } else {
+        BTreeMap::new()
+    };
+
+    let mut images = Vec::with_capacity(paths.len());
+    let mut taken = HashSet::new();
+    for path in paths {
+        let relative = relative_path(dir, &path);
+        let name = names.remove(&relative).unwrap_or_else(|| {
+            let extension = path.extension().map_or(0, |ext| ext.len() + 1);
+            String::from(&relative[..relative.len() - extension])
+        });
+        if !taken.insert(name.clone()) {
+            return Err(ImageError::DuplicateName(name));
+        }
+        images.push(Image { path, name });
+    }
+
+    match names.into_iter().next() {
+        Some((path, _)) => Err(ImageError::UnknownImage(path)),
+        None => Ok(images),
+    }
+}
+
+/// Returns whether a path has one of the `IMAGE_EXTENSIONS`
+pub fn is_image(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| IMAGE_EXTENSIONS.contains(&ext.to_lowercase().as_str()))
+}
+
+fn find_images(dir: &Path, paths: &mut Vec<PathBuf>) -> io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        let hidden = path
+            .file_name()
+            .is_some_and(|name| name.to_string_lossy().starts_with('.'));
+        if hidden {
+            continue;
+        }
+
+        if path.is_dir() {
+            find_images(&path, paths)?;
+        } else if is_image(&path) {
+            paths.push(path);
+        }
+    }
+
+    Ok(())
+}
+
+/// The path of an image relative to the directory, with forward slashes on every platform
+fn relative_path(dir: &Path, path: &Path) -> String {
+    let relative = path.strip_prefix(dir).unwrap_or(path);
+    relative
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nested_directory_with_names() {
+        let dir = std::env::temp_dir().join(format!("swyss-images-{}", uuid::Uuid::new_v4()));
+        for file in &[
+            "b.PNG",
+            "a.jpg",
+            "notes.txt",
+            ".hidden.png",
+            "cats/tom.gif",
+            "cats/felix.png",
+        ] {
+            let path = dir.join(file);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, b"").unwrap();
+        }
+
+        let names = |images: Vec<Image>| -> Vec<String> {
+            images.into_iter().map(|image| image.name).collect()
+        };
+        assert_eq!(
+            names(images(&dir).unwrap()),
+            vec!["a", "b", "cats/felix", "cats/tom"]
+        );
+
+        fs::write(dir.join(NAMES_FILE), "\"cats/tom.gif\" = \"Tom\"\n").unwrap();
+        let found = images(&dir).unwrap();
+        assert_eq!(found[3].path, dir.join("cats").join("tom.gif"));
+        assert_eq!(names(found), vec!["a", "b", "cats/felix", "Tom"]);
+
+        fs::write(dir.join(NAMES_FILE), "\"a.jpg\" = \"b\"\n").unwrap();
+        assert!(matches!(images(&dir), Err(ImageError::DuplicateName(name)) if name == "b"));
+
+        fs::write(dir.join(NAMES_FILE), "\"c.png\" = \"C\"\n").unwrap();
+        assert!(matches!(images(&dir), Err(ImageError::UnknownImage(path)) if path == "c.png"));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
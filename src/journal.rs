@@ -0,0 +1,347 @@
+//! Append-only journal of match results. Every accepted result is written as a single line of
+//! JSON as soon as it is entered, which is much cheaper than saving the whole tournament and easy
+//! to audit by hand. Each entry records the seed its round was paired with, so replaying the
+//! journal against the same players reconstructs the tournament.
+
+use crate::audit::{self, AuditEvent};
+use crate::{PairingResultError, Tournament};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::Path;
+
+/// A single result as recorded in the journal
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct JournalEntry {
+    pub round: u32,
+    /// The seed the round was paired with
+    pub seed: u64,
+    pub pairing: uuid::Uuid,
+    pub home: String,
+    pub away: String,
+    pub home_score: u8,
+    pub away_score: u8,
+    pub drawn: u8,
+    /// Seconds since the Unix epoch
+    pub timestamp: u64,
+}
+
+/// Reading a journal fails if the file can't be read or if a line isn't a valid entry
+#[derive(Debug)]
+pub enum JournalError {
+    Io(io::Error),
+    Parse {
+        line: usize,
+        error: serde_json::Error,
+    },
+}
+
+impl std::error::Error for JournalError {}
+
+impl fmt::Display for JournalError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            JournalError::Io(e) => write!(f, "Could not read journal: {}", e),
+            JournalError::Parse { line, error } => {
+                write!(f, "Invalid journal entry in line {}: {}", line, error)
+            }
+        }
+    }
+}
+
+impl From<io::Error> for JournalError {
+    fn from(e: io::Error) -> JournalError {
+        JournalError::Io(e)
+    }
+}
+
+/// Replaying a journal fails if it doesn't match the tournament it is replayed against, e.g.
+/// because it was written for a different list of players
+#[derive(Debug)]
+pub enum ReplayError {
+    /// An entry belongs to a round that can't be reached from the current one
+    UnexpectedRound(u32),
+    /// The replayed round has no pairing between the named players
+    UnknownPairing {
+        round: u32,
+        home: String,
+        away: String,
+    },
+    /// The same pairing has more than one result
+    DuplicateResult(uuid::Uuid),
+    /// The recorded score was rejected
+    Result(PairingResultError),
+}
+
+impl std::error::Error for ReplayError {}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ReplayError::UnexpectedRound(round) => {
+                write!(f, "Journal entry for unexpected round {}!", round)
+            }
+            ReplayError::UnknownPairing { round, home, away } => write!(
+                f,
+                "{} and {} are not paired in round {}!",
+                home, away, round
+            ),
+            ReplayError::DuplicateResult(uuid) => {
+                write!(f, "Pairing {} has more than one result!", uuid)
+            }
+            ReplayError::Result(e) => write!(f, "Invalid result in journal: {}", e),
+        }
+    }
+}
+
+/// A journal file that results are appended to
+pub struct Journal {
+    file: File,
+}
+
+impl Journal {
+    /// Opens the journal at `path` for appending, creating it if it doesn't exist yet
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Journal> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(Journal { file })
+    }
+
+    /// Appends an entry and flushes it to disk, so that it survives a crash right after
+    pub fn append(&mut self, entry: &JournalEntry) -> io::Result<()> {
+        let mut line =
+            serde_json::to_string(entry).expect("journal entries are always serializable");
+        line.push('\n');
+        self.file.write_all(line.as_bytes())?;
+        self.file.sync_data()
+    }
+}
+
+/// Reads all entries of the journal at `path`. A last line that was only partially written,
+/// e.g. because of a crash, is ignored.
+pub fn read<P: AsRef<Path>>(path: P) -> Result<Vec<JournalEntry>, JournalError> {
+    let contents = fs::read_to_string(path)?;
+    let complete = contents.ends_with('\n');
+    let lines: Vec<&str> = contents.lines().collect();
+
+    let mut entries = Vec::with_capacity(lines.len());
+    for (i, line) in lines.iter().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        match serde_json::from_str(line) {
+            Ok(entry) => entries.push(entry),
+            Err(_) if i + 1 == lines.len() && !complete => break,
+            Err(error) => return Err(JournalError::Parse { line: i + 1, error }),
+        }
+    }
+
+    Ok(entries)
+}
+
+impl Tournament {
+    /// Creates the journal entry for a result of the current round. Returns `None` if there is no
+    /// such pairing.
+    pub fn journal_entry(
+        &self,
+        pairing: uuid::Uuid,
+        home_score: u8,
+        away_score: u8,
+        drawn: u8,
+    ) -> Option<JournalEntry> {
+        let pair = self.pairings.get(&pairing)?;
+        let seed = self
+            .audit_log
+            .iter()
+            .rev()
+            .find_map(|entry| match entry.event {
+                AuditEvent::RoundSeed { seed } if entry.round == self.current_round => Some(seed),
+                _ => None,
+            })?;
+
+        let home = self.players.opponent(pair.home)?.name.clone();
+        let away = self.players.opponent(pair.away)?.name.clone();
+
+        Some(JournalEntry {
+            round: self.current_round,
+            seed,
+            pairing,
+            home,
+            away,
+            home_score,
+            away_score,
+            drawn,
+            timestamp: audit::unix_time(),
+        })
+    }
+
+    /// Replays journal entries against a tournament that is in the state the journal was
+    /// started in, usually freshly created from the same players. Rounds are paired with the
+    /// recorded seeds and results are matched to pairings by player names, since pairing UUIDs
+    /// are not reproducible. Returns the pairings of the current round that don't have a result
+    /// yet, in the same form as `next_round()`, so that their results can be entered next.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use swyss::{Player, Tournament};
+    ///
+    /// let new_tournament = || {
+    ///     let players = ["Alice", "Bob", "Carol", "Dave"]
+    ///         .iter()
+    ///         .map(|name| Player::new(name))
+    ///         .collect();
+    ///     Tournament::with_players(players).unwrap()
+    /// };
+    ///
+    /// let mut tourn = new_tournament();
+    /// let pairings = tourn.next_round().unwrap().unwrap();
+    /// let (uuid, _, _) = pairings[0];
+    /// tourn.end_match(uuid, 2, 0, 0).unwrap();
+    /// let entry = tourn.journal_entry(uuid, 2, 0, 0).unwrap();
+    ///
+    /// let mut replayed = new_tournament();
+    /// let open = replayed.replay(&[entry]).unwrap();
+    /// assert_eq!(open.len(), 1);
+    /// assert_eq!(replayed.current_round(), 1);
+    /// ```
+    pub fn replay(
+        &mut self,
+        entries: &[JournalEntry],
+    ) -> Result<Vec<(uuid::Uuid, String, String)>, ReplayError> {
+        let mut done = Vec::new();
+
+        for entry in entries {
+            if entry.round != self.current_round {
+                if entry.round != self.current_round + 1
+                    || !matches!(self.next_round_with_seed(entry.seed), Ok(Some(_)))
+                {
+                    return Err(ReplayError::UnexpectedRound(entry.round));
+                }
+                done.clear();
+            }
+
+            let named = |uuid: uuid::Uuid, name: &str| {
+                self.players.opponent(uuid).is_some_and(|p| p.name == name)
+            };
+            let uuid = self
+                .pairings
+                .values()
+                .find(|pair| named(pair.home, &entry.home) && named(pair.away, &entry.away))
+                .map(|pair| pair.uuid)
+                .ok_or_else(|| ReplayError::UnknownPairing {
+                    round: entry.round,
+                    home: entry.home.clone(),
+                    away: entry.away.clone(),
+                })?;
+
+            if done.contains(&uuid) {
+                return Err(ReplayError::DuplicateResult(uuid));
+            }
+
+            self.end_match(uuid, entry.home_score, entry.away_score, entry.drawn)
+                .map_err(ReplayError::Result)?;
+            done.push(uuid);
+        }
+
+        let open = self
+            .pairings
+            .values()
+            .filter(|pair| !done.contains(&pair.uuid))
+            .map(|pair| {
+                let name = |uuid| {
+                    self.players
+                        .opponent(uuid)
+                        .map_or_else(String::new, |p| p.name.clone())
+                };
+                (pair.uuid, name(pair.home), name(pair.away))
+            })
+            .collect();
+
+        Ok(open)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Player;
+
+    fn tournament() -> Tournament {
+        let players = (1..6)
+            .map(|i| Player::new(format!("Player {}", i).as_str()))
+            .collect();
+        Tournament::with_players(players).unwrap()
+    }
+
+    #[test]
+    fn replay_reconstructs_tournament() {
+        let path = std::env::temp_dir().join(format!("swyss-{}.ndjson", uuid::Uuid::new_v4()));
+        let mut tourn = tournament();
+        let mut journal = Journal::open(&path).unwrap();
+
+        // Two full rounds and one result of the third
+        for round in 1..4 {
+            let pairings = tourn.next_round().unwrap().unwrap();
+            let pairings = if round == 3 {
+                &pairings[..1]
+            } else {
+                &pairings[..]
+            };
+
+            for (uuid, _, _) in pairings {
+                tourn.end_match(*uuid, 2, 1, 0).unwrap();
+                let entry = tourn.journal_entry(*uuid, 2, 1, 0).unwrap();
+                journal.append(&entry).unwrap();
+            }
+        }
+
+        let entries = read(&path).unwrap();
+        assert_eq!(entries.len(), 5);
+
+        let mut replayed = tournament();
+        let open = replayed.replay(&entries).unwrap();
+        assert_eq!(open.len(), 1);
+        assert_eq!(replayed.current_round, 3);
+
+        let standings = |t: &Tournament| -> Vec<(String, u32, u32)> {
+            t.players
+                .iter()
+                .map(|p| (p.name.clone(), p.match_points, p.game_points))
+                .collect()
+        };
+        assert_eq!(standings(&replayed), standings(&tourn));
+
+        // A result that was journaled twice would count twice
+        let duplicated = vec![entries[0].clone(), entries[0].clone()];
+        assert!(matches!(
+            tournament().replay(&duplicated),
+            Err(ReplayError::DuplicateResult(_))
+        ));
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn partial_last_line() {
+        let path = std::env::temp_dir().join(format!("swyss-{}.ndjson", uuid::Uuid::new_v4()));
+        let mut tourn = tournament();
+        let pairings = tourn.next_round().unwrap().unwrap();
+        let entry = tourn.journal_entry(pairings[0].0, 2, 0, 0).unwrap();
+
+        let line = serde_json::to_string(&entry).unwrap();
+        fs::write(&path, format!("{}\n{}", line, &line[..10])).unwrap();
+        assert_eq!(read(&path).unwrap(), vec![entry]);
+
+        // A broken line that was followed by more writes is an error
+        fs::write(&path, format!("{}\n{}\n", &line[..10], line)).unwrap();
+        assert!(matches!(
+            read(&path),
+            Err(JournalError::Parse { line: 1, .. })
+        ));
+
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -1,25 +1,190 @@
 #![crate_name = "swyss"]
-use core::cell::RefCell;
-use rand::prelude::ThreadRng;
+#[macro_use]
+mod logging;
+
+pub mod achievements;
+pub mod archive;
+pub mod arena;
+pub mod audit;
+pub mod bracket;
+pub mod byes;
+pub mod clinch;
+pub mod color;
+pub mod config;
+pub mod constraints;
+pub mod crosstable;
+pub mod deadline;
+pub mod entry;
+pub mod event;
+pub mod explain;
+pub mod flights;
+pub mod graph;
+pub mod history;
+pub mod house;
+pub mod images;
+pub mod journal;
+pub mod locale;
+pub mod lookup;
+pub mod merge;
+pub mod multiplayer;
+pub mod numbers;
+pub mod observer;
+pub mod oracle;
+pub mod overlay;
+#[cfg(feature = "pdf")]
+pub mod pdf;
+pub mod penalties;
+pub mod persist;
+pub mod pods;
+pub mod presets;
+pub mod preview;
+pub mod prizes;
+pub mod prompt;
+#[cfg(feature = "qr")]
+pub mod qr;
+pub mod ratings;
+pub mod rationale;
+pub mod registration;
+pub mod remote;
+pub mod reporting;
+pub mod roster;
+pub mod roundrobin;
+#[cfg(feature = "schemars")]
+pub mod schema;
+pub mod season;
+pub mod sections;
+pub mod settings;
+pub mod simulate;
+pub mod spectator;
+pub mod stage;
+pub mod store;
+pub mod texttable;
+pub mod ticker;
+pub mod trf;
+pub mod variants;
+pub mod verify;
+pub mod viewer;
+pub mod webhook;
+
+use crate::arena::PlayerArena;
+use crate::crosstable::Outcome;
+use crate::explain::Criterion;
+use core::cell::{Cell, RefCell};
+use num_rational::BigRational;
+use num_traits::ToPrimitive;
+use rand::rngs::StdRng;
 use rand::seq::SliceRandom;
 use rand::thread_rng;
+use rand::Rng;
+use rand::SeedableRng;
 use range_check::{Check, OutOfRangeError};
-use std::cmp::Ordering;
-use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use std::cmp::{Ordering, Reverse};
+use std::collections::{HashMap, HashSet};
 use std::fmt;
 use std::rc::Rc;
+use std::str::FromStr;
+use std::time::Duration;
 use uuid::Uuid;
 
+/// Describes the rounds in which a player is able to take part. Constraints such as "arrives in
+/// round 2" or "must finish by 9pm" are expressed by the organizer as the first and last round the
+/// player can be paired in.
+///
+/// # Example
+///
+/// ```
+/// use swyss::Availability;
+/// let late = Availability::new(2, None);
+/// assert!(!late.is_available(1));
+/// assert!(late.is_available(5));
+///
+/// let early = Availability::new(1, Some(3));
+/// assert!(early.is_available(3));
+/// assert!(!early.is_available(4));
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct Availability {
+    pub first_round: u32,
+    pub last_round: Option<u32>,
+}
+
+impl Availability {
+    /// Returns a new availability window
+    ///
+    /// # Arguments
+    ///
+    /// * `first_round` - The first round the player can be paired in
+    /// * `last_round` - The last round the player can be paired in, or `None` if they can stay
+    ///   until the end of the tournament
+    pub fn new(first_round: u32, last_round: Option<u32>) -> Availability {
+        Availability {
+            first_round,
+            last_round,
+        }
+    }
+
+    /// Checks whether the player can be paired in the given round
+    pub fn is_available(&self, round: u32) -> bool {
+        round >= self.first_round && self.last_round.map(|last| round <= last).unwrap_or(true)
+    }
+
+    /// Checks whether the window overlaps any round of a tournament with the given number of
+    /// rounds
+    pub fn overlaps(&self, rounds: u32) -> bool {
+        (1..=rounds).any(|round| self.is_available(round))
+    }
+}
+
+impl Default for Availability {
+    fn default() -> Availability {
+        Availability::new(1, None)
+    }
+}
+
 /// Represents a player and their match history
+#[derive(Clone)]
 pub struct Player {
     pub uuid: uuid::Uuid,
+    /// The name the player registered with, as reports need it
     pub name: String,
+    /// Handle the player competes under, shown instead of their name to spectators, e.g. on the
+    /// spectator page, the overlay, and the ticker
+    pub alias: Option<String>,
     pub match_points: u32,
     pub game_points: u32,
     pub matches_played: u32,
+    pub matches_won: u32,
+    pub matches_drawn: u32,
     pub games_played: u32,
-    pub opponents: Vec<Rc<RefCell<Player>>>,
+    pub games_won: u32,
+    /// Sum of the match points the player had after each of their matches, which rewards early
+    /// wins over late ones
+    pub cumulative_points: u32,
+    /// Outcomes of the player's matches and byes in the order they were played
+    pub outcomes: Vec<Outcome>,
+    /// UUIDs of the players this player was paired against, in the order they met. Their records
+    /// are looked up in the tournament's `players` (see `PlayerArena::opponent()`).
+    pub opponents: Vec<uuid::Uuid>,
     pub has_bye: bool,
+    /// Byes granted for an uneven number of players, not counting requested byes
+    pub byes: u32,
+    pub availability: Availability,
+    /// Lowest win percentage the player's percentages count as. Set by the tournament the player
+    /// plays in.
+    pub percentage_floor: PercentageFloor,
+    /// Rating the player is seeded by, e.g. read from a CSV player list (see `roster`)
+    pub rating: Option<f64>,
+    /// Club or team of the player. Players of the same club are only paired against each other if
+    /// it can't be avoided.
+    pub club: Option<String>,
+    /// Age group the player competes in, e.g. "U12", which events split into sections by (see
+    /// `sections`)
+    pub age_group: Option<String>,
+    /// Players this player is never paired against, e.g. family members. See
+    /// `Tournament::avoid_pairing()`.
+    pub avoid: Vec<uuid::Uuid>,
 }
 
 impl Player {
@@ -32,12 +197,25 @@ impl Player {
         Player {
             uuid: Uuid::new_v4(),
             name: String::from(name),
+            alias: None,
             match_points: 0,
             game_points: 0,
             matches_played: 0,
+            matches_won: 0,
+            matches_drawn: 0,
             games_played: 0,
+            games_won: 0,
+            cumulative_points: 0,
+            outcomes: Vec::new(),
             opponents: Vec::new(),
             has_bye: false,
+            byes: 0,
+            availability: Availability::default(),
+            percentage_floor: PercentageFloor::default(),
+            rating: None,
+            club: None,
+            age_group: None,
+            avoid: Vec::new(),
         }
     }
 
@@ -74,8 +252,8 @@ impl Player {
         self.game_points += 1;
     }
 
-    /// Makes the player win a single game. Increases the number of games played and adds three
-    /// game points.
+    /// Makes the player win a single game. Increases the number of games played and games won and
+    /// adds three game points.
     ///
     /// # Example
     ///
@@ -88,11 +266,12 @@ impl Player {
     /// ```
     pub fn win_game(&mut self) {
         self.games_played += 1;
+        self.games_won += 1;
         self.game_points += 3;
     }
 
     /// Makes the player lose a single match. Has no effect other than increasing the number of
-    /// matches played, as lost matches are not tracked explicitly.
+    /// matches played and the cumulative points, as lost matches are not tracked explicitly.
     ///
     /// # Example
     ///
@@ -105,6 +284,8 @@ impl Player {
     /// ```
     pub fn lose_match(&mut self) {
         self.matches_played += 1;
+        self.cumulative_points += self.match_points;
+        self.outcomes.push(Outcome::Loss);
     }
 
     /// Makes the player draw a single match. Increases the number of matches played and adds one
@@ -117,413 +298,2406 @@ impl Player {
     /// let mut player = Player::new("Drawer");
     /// player.draw_match();
     /// assert_eq!(player.matches_played, 1);
+    /// assert_eq!(player.matches_drawn, 1);
     /// assert_eq!(player.match_points, 1);
     /// ```
     pub fn draw_match(&mut self) {
         self.matches_played += 1;
+        self.matches_drawn += 1;
         self.match_points += 1;
+        self.cumulative_points += self.match_points;
+        self.outcomes.push(Outcome::Draw);
+    }
+
+    /// Makes the player win a single match. Increases the number of matches played and adds three
+    /// match points.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use swyss::Player;
+    /// let mut player = Player::new("Winner");
+    /// player.win_match();
+    /// assert_eq!(player.matches_played, 1);
+    /// assert_eq!(player.matches_won, 1);
+    /// assert_eq!(player.match_points, 3);
+    /// ```
+    pub fn win_match(&mut self) {
+        self.matches_played += 1;
+        self.matches_won += 1;
+        self.match_points += 3;
+        self.cumulative_points += self.match_points;
+        self.outcomes.push(Outcome::Win);
+    }
+
+    /// Awards the player a bye. The player is considered to have won their match 2-0; see
+    /// `bye_with()` for byes worth less. No opponent
+    /// is added to the `opponents` vector. The player is recorded as having received a bye so that
+    /// the tournament manager can check that no player is awarded more than one bye.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use swyss::Player;
+    /// let mut player = Player::new("Byer");
+    /// player.bye();
+    /// assert_eq!(player.games_played, 2);
+    /// assert_eq!(player.game_points, 6);
+    /// assert_eq!(player.matches_played, 1);
+    /// assert_eq!(player.match_points, 3);
+    /// assert_eq!(player.opponents.len(), 0);
+    /// assert!(player.has_bye);
+    /// ```
+    pub fn bye(&mut self) {
+        self.bye_in(MatchFormat::default());
+    }
+
+    /// Awards the player a bye in a tournament with the given match format, which counts as
+    /// winning as many games as are needed to win a match, e.g. 1-0 in best-of-one.
+    pub fn bye_in(&mut self, format: MatchFormat) {
+        self.bye_with(format, ByeScore::default());
+    }
+
+    /// Awards the player a bye that is worth the given score, e.g. a half-point bye as in chess.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use swyss::{ByeScore, MatchFormat, Player};
+    /// let mut player = Player::new("Byer");
+    /// player.bye_with(MatchFormat::BestOf(1), ByeScore::Draw);
+    /// assert_eq!(player.games_played, 1);
+    /// assert_eq!(player.game_points, 1);
+    /// assert_eq!(player.matches_drawn, 1);
+    /// assert_eq!(player.match_points, 1);
+    /// assert!(player.has_bye);
+    /// ```
+    pub fn bye_with(&mut self, format: MatchFormat, score: ByeScore) {
+        self.score_bye(format, score);
+        self.has_bye = true;
+        self.byes += 1;
+    }
+
+    /// Byes granted for an uneven number of players, at least one if `has_bye` is set by hand
+    pub(crate) fn byes_granted(&self) -> u32 {
+        self.byes.max(u32::from(self.has_bye))
+    }
+
+    /// Records the games and the match of a bye, without recording that the player had a bye
+    pub(crate) fn score_bye(&mut self, format: MatchFormat, score: ByeScore) {
+        let (won, drawn) = score.games(format);
+        for _ in 0..won {
+            self.win_game();
+        }
+        for _ in 0..drawn {
+            self.draw_game();
+        }
+
+        match score {
+            ByeScore::Win => self.win_match(),
+            ByeScore::Draw => self.draw_match(),
+            ByeScore::Loss => self.lose_match(),
+        }
+        if let Some(outcome) = self.outcomes.last_mut() {
+            *outcome = Outcome::Bye;
+        }
+    }
+
+    /// Returns the outcome of the player's last match and how many matches in a row ended the
+    /// same way, e.g. `(Outcome::Win, 3)` after three wins in a row. Byes neither extend nor end
+    /// a streak. `None` if the player hasn't played a match yet.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use swyss::crosstable::Outcome;
+    /// use swyss::Player;
+    /// let mut player = Player::new("Streaker");
+    /// assert_eq!(player.current_streak(), None);
+    /// player.lose_match();
+    /// player.win_match();
+    /// player.bye();
+    /// player.win_match();
+    /// assert_eq!(player.current_streak(), Some((Outcome::Win, 2)));
+    /// ```
+    pub fn current_streak(&self) -> Option<(Outcome, u32)> {
+        let mut matches = self.outcomes.iter().rev().filter(|&&o| o != Outcome::Bye);
+        let last = *matches.next()?;
+        let length = 1 + matches.take_while(|&&o| o == last).count() as u32;
+        Some((last, length))
+    }
+
+    /// Returns the name the player is shown with: their alias if they have one, and their name
+    /// otherwise
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use swyss::Player;
+    /// let mut player = Player::new("Erika Mustermann");
+    /// assert_eq!(player.display_name(), "Erika Mustermann");
+    /// player.alias = Some(String::from("xErika"));
+    /// assert_eq!(player.display_name(), "xErika");
+    /// ```
+    pub fn display_name(&self) -> &str {
+        self.alias.as_deref().unwrap_or(&self.name)
+    }
+
+    /// Returns whether both players belong to the same club. Players without a club don't share it
+    /// with anyone.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use swyss::Player;
+    /// let mut alice = Player::new("Alice");
+    /// let mut bob = Player::new("Bob");
+    /// assert!(!alice.same_club(&bob));
+    /// alice.club = Some(String::from("Rooks"));
+    /// bob.club = Some(String::from("Rooks"));
+    /// assert!(alice.same_club(&bob));
+    /// ```
+    pub fn same_club(&self, other: &Player) -> bool {
+        self.club.is_some() && self.club == other.club
+    }
+
+    /// Returns whether either player must never be paired against the other
+    pub fn avoids(&self, other: &Player) -> bool {
+        self.avoid.contains(&other.uuid) || other.avoid.contains(&self.uuid)
+    }
+
+    /// Returns the outcomes of the player's last `n` matches, oldest first. Byes are left out.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use swyss::crosstable::Outcome;
+    /// use swyss::Player;
+    /// let mut player = Player::new("Form");
+    /// player.win_match();
+    /// player.draw_match();
+    /// player.bye();
+    /// player.lose_match();
+    /// assert_eq!(player.recent_form(2), [Outcome::Draw, Outcome::Loss]);
+    /// assert_eq!(player.recent_form(5).len(), 3);
+    /// ```
+    pub fn recent_form(&self, n: usize) -> Vec<Outcome> {
+        let mut form: Vec<Outcome> = self
+            .outcomes
+            .iter()
+            .rev()
+            .filter(|&&o| o != Outcome::Bye)
+            .take(n)
+            .copied()
+            .collect();
+        form.reverse();
+        form
+    }
+
+    /// Calculates the player's match win percentage, i.e. accumulated match points divided by
+    /// total match points possible in those rounds. The result is always between the player's
+    /// `percentage_floor`, by default 1/3, and 1: the minimum reduces the impact of low
+    /// performance on `opponents_match_win_percentage()`, and a player who hasn't played a match
+    /// yet gets the minimum.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use swyss::Player;
+    /// let mut player = Player::new("Newcomer");
+    /// assert_eq!(player.match_win_percentage(), 1.0 / 3.0);
+    /// player.win_match();
+    /// assert_eq!(player.match_win_percentage(), 1.0);
+    /// ```
+    pub fn match_win_percentage(&self) -> f64 {
+        to_f64(self.match_win_ratio())
+    }
+
+    /// Calculates the player's game win percentage, i.e. accumulated game points divided by total
+    /// game points possible in those rounds. Like the match win percentage, the result is always
+    /// between the floor and 1, and a player who hasn't played a game yet gets the minimum.
+    pub fn game_win_percentage(&self) -> f64 {
+        to_f64(self.game_win_ratio())
+    }
+
+    /// Calculates the player's opponents' match win percentage, i.e. the average match win
+    /// percentage of all opponents the player faced, ignoring byes. A player without opponents
+    /// gets the floor, the lowest possible value, so the result is always between the floor and 1.
+    /// Opponents are looked up in `players`, usually the tournament's; those who can't be found
+    /// are left out.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use swyss::arena::PlayerArena;
+    /// use swyss::Player;
+    /// let mut player = Player::new("Byer");
+    /// player.bye();
+    /// assert_eq!(player.opponents_match_win_percentage(&PlayerArena::new()), 1.0 / 3.0);
+    /// ```
+    pub fn opponents_match_win_percentage(&self, players: &PlayerArena) -> f64 {
+        to_f64(self.opponents_match_win_ratio(players))
+    }
+
+    /// Calculates the player's opponents' game win percentage, i.e. the average game win
+    /// percentage of all opponents the player faced, ignoring byes. A player without opponents
+    /// gets the floor, so the result is always between the floor and 1.
+    pub fn opponents_game_win_percentage(&self, players: &PlayerArena) -> f64 {
+        to_f64(self.opponents_game_win_ratio(players))
+    }
+
+    /// Calculates the player's opponents' opponents' match win percentage, i.e. the average
+    /// opponents' match win percentage of all opponents the player faced. It is the last
+    /// tiebreaker, and like the others, a player without opponents gets the floor.
+    pub fn opponents_opponents_match_win_percentage(&self, players: &PlayerArena) -> f64 {
+        to_f64(self.opponents_opponents_match_win_ratio(players))
+    }
+
+    /// Calculates the player's game differential, i.e. games won minus games lost. Drawn games
+    /// count for neither.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use swyss::Player;
+    /// let mut player = Player::new("Player");
+    /// player.win_game();
+    /// player.draw_game();
+    /// player.lose_game();
+    /// player.lose_game();
+    /// assert_eq!(player.game_differential(), -1);
+    /// ```
+    pub fn game_differential(&self) -> i64 {
+        // Game points are three per win and one per draw, so draws and losses follow from them
+        let drawn = i64::from(self.game_points) - 3 * i64::from(self.games_won);
+        let lost = i64::from(self.games_played) - i64::from(self.games_won) - drawn;
+        i64::from(self.games_won) - lost
+    }
+
+    /// The match win percentage as an exact fraction, which the standings are sorted by
+    pub(crate) fn match_win_ratio(&self) -> WinRatio {
+        win_ratio(
+            self.match_points,
+            self.matches_played,
+            self.percentage_floor,
+        )
+    }
+
+    /// The game win percentage as an exact fraction
+    pub(crate) fn game_win_ratio(&self) -> WinRatio {
+        win_ratio(self.game_points, self.games_played, self.percentage_floor)
+    }
+
+    /// The opponents' match win percentage as an exact fraction
+    pub(crate) fn opponents_match_win_ratio(&self, players: &PlayerArena) -> WinRatio {
+        TiebreakerCache::new(players).opponents_match_win_ratio(self)
+    }
+
+    /// The opponents' game win percentage as an exact fraction
+    pub(crate) fn opponents_game_win_ratio(&self, players: &PlayerArena) -> WinRatio {
+        average(
+            self.opponents
+                .iter()
+                .filter_map(|&opp| players.opponent(opp))
+                .map(|opp| opp.game_win_ratio()),
+            self.percentage_floor,
+        )
+    }
+
+    /// The opponents' opponents' match win percentage as an exact fraction
+    pub(crate) fn opponents_opponents_match_win_ratio(&self, players: &PlayerArena) -> WinRatio {
+        TiebreakerCache::new(players).opponents_opponents_match_win_ratio(self)
+    }
+}
+
+/// A snapshot of a player's record and tiebreakers, as returned by `Tournament::players()`. Unlike
+/// a reference to the player itself, it can be kept around while results are entered.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PlayerView {
+    pub uuid: uuid::Uuid,
+    pub name: String,
+    pub alias: Option<String>,
+    pub match_points: u32,
+    pub game_points: u32,
+    pub matches_played: u32,
+    pub matches_won: u32,
+    pub matches_drawn: u32,
+    pub games_played: u32,
+    pub has_bye: bool,
+    pub byes: u32,
+    pub opponents_match_win_percentage: f64,
+    pub game_win_percentage: f64,
+    pub opponents_game_win_percentage: f64,
+    pub opponents_opponents_match_win_percentage: f64,
+}
+
+impl PlayerView {
+    /// Takes a snapshot of a player, whose opponents are looked up in `players`
+    pub fn new(p: &Player, players: &PlayerArena) -> PlayerView {
+        PlayerView {
+            uuid: p.uuid,
+            name: p.name.clone(),
+            alias: p.alias.clone(),
+            match_points: p.match_points,
+            game_points: p.game_points,
+            matches_played: p.matches_played,
+            matches_won: p.matches_won,
+            matches_drawn: p.matches_drawn,
+            games_played: p.games_played,
+            has_bye: p.has_bye,
+            byes: p.byes,
+            opponents_match_win_percentage: p.opponents_match_win_percentage(players),
+            game_win_percentage: p.game_win_percentage(),
+            opponents_game_win_percentage: p.opponents_game_win_percentage(players),
+            opponents_opponents_match_win_percentage: p
+                .opponents_opponents_match_win_percentage(players),
+        }
+    }
+
+    /// Returns the player's alias if they have one, and their name otherwise
+    pub fn display_name(&self) -> &str {
+        self.alias.as_deref().unwrap_or(&self.name)
+    }
+}
+
+/// A tiebreaker as an exact fraction. Tiebreakers are compared exactly, so that the standings
+/// don't depend on the order floating-point numbers are summed in; they are only converted to
+/// floats for display. Counters can be arbitrarily large, so the fractions are unbounded.
+pub(crate) type WinRatio = BigRational;
+
+/// Points divided by the points possible, clamped to the range of valid tiebreakers. Counters
+/// can be edited or loaded freely, so nothing is assumed about how they relate to each other.
+fn win_ratio(points: u32, played: u32, floor: PercentageFloor) -> WinRatio {
+    if played == 0 {
+        floor.ratio()
+    } else {
+        let possible = 3 * u64::from(played);
+        BigRational::new(points.into(), possible.into())
+            .clamp(floor.ratio(), BigRational::from_integer(1.into()))
+    }
+}
+
+/// Average of win percentages, or the minimum if there are none
+fn average(percentages: impl Iterator<Item = WinRatio>, floor: PercentageFloor) -> WinRatio {
+    let (sum, count) = percentages.fold(
+        (BigRational::from_integer(0.into()), 0u32),
+        |(sum, count), p| (sum + p, count + 1),
+    );
+    if count == 0 {
+        floor.ratio()
+    } else {
+        sum / BigRational::from_integer(count.into())
+    }
+}
+
+/// Converts an exact tiebreaker to the closest float
+fn to_f64(ratio: WinRatio) -> f64 {
+    ratio.to_f64().expect("tiebreakers are between 0 and 1")
+}
+
+impl PartialEq for Player {
+    fn eq(&self, other: &Player) -> bool {
+        self.uuid == other.uuid
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub enum PlayerSide {
+    Home,
+    Away,
+}
+
+/// Determines which player of a pairing goes first (e.g. chooses to play or draw)
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub enum FirstPlayer {
+    /// The tournament does not decide; the players settle it themselves
+    #[default]
+    Undecided,
+    /// A random roll decides for every pairing
+    Random,
+    /// The player with the better standing going into the round goes first, with a random roll
+    /// if both are tied on all tiebreakers
+    HigherSeed,
+}
+
+/// What a bye is worth. The games awarded depend on the match format.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub enum ByeScore {
+    /// A match win, with as many games won as are needed to win a match, e.g. 2-0 in best-of-three
+    /// or 1-0 in best-of-one
+    #[default]
+    Win,
+    /// A drawn match with a single drawn game, e.g. a half-point bye in chess
+    Draw,
+    /// A lost match without any games, e.g. a zero-point bye
+    Loss,
+}
+
+impl ByeScore {
+    /// Games won and drawn by the player who gets the bye
+    pub fn games(self, format: MatchFormat) -> (u8, u8) {
+        match self {
+            ByeScore::Win => (format.wins_needed(), 0),
+            ByeScore::Draw => (0, 1),
+            ByeScore::Loss => (0, 0),
+        }
+    }
+}
+
+impl FromStr for ByeScore {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<ByeScore, String> {
+        match s {
+            "win" => Ok(ByeScore::Win),
+            "draw" | "half" => Ok(ByeScore::Draw),
+            "loss" | "zero" => Ok(ByeScore::Loss),
+            _ => Err(format!("Unknown bye score: {}", s)),
+        }
+    }
+}
+
+/// Lowest value a match or game win percentage counts as, e.g. 1/3 in Magic tournaments, so that
+/// a single poor opponent doesn't weigh too heavily on the opponents' percentages
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub enum PercentageFloor {
+    /// Percentages count as they are, as in chess
+    None,
+    /// Percentages below the fraction count as the fraction. Fractions above 1 count as 1, and a
+    /// denominator of 0 as no floor.
+    Fraction { numerator: u32, denominator: u32 },
+}
+
+impl Default for PercentageFloor {
+    fn default() -> PercentageFloor {
+        PercentageFloor::Fraction {
+            numerator: 1,
+            denominator: 3,
+        }
+    }
+}
+
+impl PercentageFloor {
+    /// The floor as an exact fraction between 0 and 1
+    fn ratio(self) -> WinRatio {
+        match self {
+            PercentageFloor::Fraction {
+                numerator,
+                denominator,
+            } if denominator > 0 => BigRational::new(numerator.into(), denominator.into())
+                .min(BigRational::from_integer(1.into())),
+            _ => BigRational::from_integer(0.into()),
+        }
+    }
+}
+
+impl fmt::Display for PercentageFloor {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PercentageFloor::None => write!(f, "none"),
+            PercentageFloor::Fraction {
+                numerator,
+                denominator,
+            } => {
+                write!(f, "{}/{}", numerator, denominator)
+            }
+        }
+    }
+}
+
+/// Decides who receives the bye when an odd number of players is paired. Only players who have
+/// had fewer byes than `Tournament::max_byes()`, one by default, are eligible.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub enum ByeSelection {
+    /// A random player among those with the fewest match points
+    #[default]
+    LowestScore,
+    /// The player ranked lowest by match points and all tiebreakers, with a random choice only
+    /// among players tied on everything
+    LowestTiebreakers,
+    /// The first of these players who is eligible and paired this round, in order of preference.
+    /// Falls back to `LowestScore` if none of them is.
+    Volunteers(Vec<uuid::Uuid>),
+}
+
+/// Decides the order of players who are tied on match points and all tiebreakers
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub enum FinalTiebreak {
+    /// A random draw, made the first time `Tournament::ranking()` needs it. Its seed is recorded
+    /// in the audit log and saved with the tournament, so the draw can be reproduced.
+    #[default]
+    Random,
+    /// The player with the lower player number, i.e. who registered first
+    RegistrationOrder,
+    /// The higher seed, given as the players in seed order. Players without a seed rank below
+    /// those with one, in registration order.
+    Seeds(Vec<uuid::Uuid>),
+}
+
+/// How many games a match consists of. In best-of-one, a match is a single game, so game and
+/// match win percentages coincide.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub enum MatchFormat {
+    /// The first player to win more than half of the games wins the match
+    BestOf(u8),
+}
+
+impl Default for MatchFormat {
+    fn default() -> MatchFormat {
+        MatchFormat::BestOf(3)
+    }
+}
+
+impl MatchFormat {
+    /// Number of games a player has to win to win the match
+    pub fn wins_needed(self) -> u8 {
+        match self {
+            MatchFormat::BestOf(games) => games / 2 + 1,
+        }
+    }
+
+    /// Maximum number of games in a match, including drawn games
+    pub fn games(self) -> u8 {
+        match self {
+            MatchFormat::BestOf(games) => games,
+        }
+    }
+}
+
+/// How the players of a tournament are paired each round
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub enum PairingSystem {
+    /// Players with equal match points are paired against each other
+    #[default]
+    Swiss,
+    /// Every player meets every other player exactly once, following a fixed schedule
+    RoundRobin,
+    /// Swiss variant for short events: the players are ranked by standings and paired first
+    /// against second, third against fourth, and so on, even if they already met
+    Danish,
+    /// Swiss variant for short events: the players are ranked by standings and each is paired,
+    /// from the top, with the next ranked player they haven't met yet
+    Monrad,
+}
+
+impl PairingSystem {
+    /// Number of rounds needed for the given number of players: enough rounds to find a single
+    /// winner in Swiss and its variants, and enough for everyone to meet everyone in a round robin
+    pub fn rounds(self, num_players: usize) -> u32 {
+        match self {
+            PairingSystem::Swiss | PairingSystem::Danish | PairingSystem::Monrad => {
+                registration::default_rounds(num_players)
+            }
+            PairingSystem::RoundRobin if num_players < 2 => 0,
+            PairingSystem::RoundRobin if num_players % 2 == 1 => num_players as u32,
+            PairingSystem::RoundRobin => num_players as u32 - 1,
+        }
+    }
+}
+
+impl FromStr for MatchFormat {
+    type Err = String;
+
+    /// Parses the number of games, e.g. "1" or "bo3". The number must be odd.
+    fn from_str(s: &str) -> Result<MatchFormat, String> {
+        let games = s.trim_start_matches("bo");
+        match games.parse::<u8>() {
+            Ok(games) if games % 2 == 1 => Ok(MatchFormat::BestOf(games)),
+            _ => Err(format!("Unknown match format: {}", s)),
+        }
+    }
+}
+
+impl FromStr for PairingSystem {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<PairingSystem, String> {
+        match s {
+            "swiss" => Ok(PairingSystem::Swiss),
+            "round-robin" => Ok(PairingSystem::RoundRobin),
+            "danish" => Ok(PairingSystem::Danish),
+            "monrad" => Ok(PairingSystem::Monrad),
+            _ => Err(format!("Unknown pairing system: {}", s)),
+        }
+    }
+}
+
+impl FromStr for FirstPlayer {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<FirstPlayer, String> {
+        match s {
+            "undecided" => Ok(FirstPlayer::Undecided),
+            "random" => Ok(FirstPlayer::Random),
+            "seed" => Ok(FirstPlayer::HigherSeed),
+            _ => Err(format!("Unknown first player policy: {}", s)),
+        }
+    }
+}
+
+pub struct Pairing {
+    uuid: uuid::Uuid,
+    home: uuid::Uuid,
+    away: uuid::Uuid,
+    first: Option<PlayerSide>,
+    /// Secrets that let the home and away player report the result
+    tokens: Option<(String, String)>,
+    /// Where the result reported by the players stands
+    report: Option<reporting::ReportState>,
+    /// Games awarded to the home and away player by game loss penalties
+    penalty_games: (u8, u8),
+    /// The result, once it was entered
+    result: Cell<Option<history::MatchResult>>,
+    /// When the pairing was created, in seconds since the Unix epoch
+    created: u64,
+    /// When the result was entered, in seconds since the Unix epoch
+    finished: Cell<Option<u64>>,
+    format: MatchFormat,
+}
+
+impl Pairing {
+    /// Creates a new pairing between two players and records them as each other's opponents.
+    /// Results of the pairing are recorded in the arena the players are stored in.
+    ///
+    /// # Arguments
+    ///
+    /// * `home` - The left-hand player
+    /// * `away` - The right-hand player
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use swyss::arena::PlayerArena;
+    /// use swyss::{Pairing, Player};
+    ///
+    /// let mut players: PlayerArena = vec![Player::new("Alice"), Player::new("Bob")].into();
+    /// let (alice, bob) = (players[0].uuid, players[1].uuid);
+    /// let (home, away) = players.pair_mut(alice, bob).unwrap();
+    /// let pair = Pairing::new(home, away);
+    ///
+    /// pair.end_match(&mut players, 2, 1, 0).unwrap();
+    /// assert_eq!(players.get(alice).unwrap().match_points, 3);
+    /// assert_eq!(players.get(bob).unwrap().opponents, [alice]);
+    /// ```
+    pub fn new(home: &mut Player, away: &mut Player) -> Pairing {
+        home.opponents.push(away.uuid);
+        away.opponents.push(home.uuid);
+        Pairing {
+            uuid: Uuid::new_v4(),
+            home: home.uuid,
+            away: away.uuid,
+            first: None,
+            tokens: None,
+            report: None,
+            penalty_games: (0, 0),
+            result: Cell::new(None),
+            created: audit::unix_time(),
+            finished: Cell::new(None),
+            format: MatchFormat::default(),
+        }
+    }
+
+    /// Returns the UUIDs of the home and away player
+    pub fn players(&self) -> (uuid::Uuid, uuid::Uuid) {
+        (self.home, self.away)
+    }
+
+    /// Returns how many games the match consists of. Pairings made by a tournament use its
+    /// match format; others are best-of-three.
+    pub fn format(&self) -> MatchFormat {
+        self.format
+    }
+
+    /// Returns which player goes first, if the tournament decided it
+    pub fn first_player(&self) -> Option<PlayerSide> {
+        self.first
+    }
+
+    /// Records which player goes first
+    pub fn set_first_player(&mut self, side: PlayerSide) {
+        self.first = Some(side);
+    }
+
+    /// Applies a change to both players of the pairing, home first. A player who isn't in
+    /// `players` is left out.
+    fn update(&self, players: &mut PlayerArena, mut f: impl FnMut(PlayerSide, &mut Player)) {
+        for (side, uuid) in [(PlayerSide::Home, self.home), (PlayerSide::Away, self.away)] {
+            if let Some(player) = players.get_mut(uuid) {
+                f(side, player);
+            }
+        }
+    }
+
+    /// Registers a won game for one of the players. Implies registering a lost game for the other
+    /// player.
+    ///
+    /// # Arguments
+    ///
+    /// `players` - The arena the players of the pairing are stored in
+    /// `side` - A `PlayerSide` that specifies whether the home or away player won the game
+    pub fn win_game(&self, players: &mut PlayerArena, side: PlayerSide) {
+        self.update(players, |s, p| {
+            if s == side {
+                p.win_game();
+            } else {
+                p.lose_game();
+            }
+        });
+    }
+
+    /// Registers a drawn game.
+    pub fn draw_game(&self, players: &mut PlayerArena) {
+        self.update(players, |_, p| p.draw_game());
+    }
+
+    /// Checks whether a result was entered for this pairing
+    pub fn is_completed(&self) -> bool {
+        self.result.get().is_some()
+    }
+
+    /// Returns how long the match took from pairing to entering the result, or `None` if there
+    /// is no result yet
+    pub fn duration(&self) -> Option<Duration> {
+        let finished = self.finished.get()?;
+        Some(Duration::from_secs(finished.saturating_sub(self.created)))
+    }
+
+    /// Records the result of the match in the records of its players, which are looked up in
+    /// `players`
+    pub fn end_match(
+        &self,
+        players: &mut PlayerArena,
+        home_score: u8,
+        away_score: u8,
+        drawn: u8,
+    ) -> Result<(), OutOfRangeError<u8>> {
+        check_scores(self.format, home_score, away_score, drawn)?;
+
+        for _ in 0..home_score {
+            self.win_game(players, PlayerSide::Home);
+        }
+
+        for _ in 0..away_score {
+            self.win_game(players, PlayerSide::Away);
+        }
+
+        for _ in 0..drawn {
+            self.draw_game(players);
+        }
+
+        let winner = match home_score.cmp(&away_score) {
+            Ordering::Greater => Some(PlayerSide::Home),
+            Ordering::Less => Some(PlayerSide::Away),
+            Ordering::Equal => None,
+        };
+        self.update(players, |side, p| match winner {
+            Some(winner) if winner == side => p.win_match(),
+            Some(_) => p.lose_match(),
+            None => p.draw_match(),
+        });
+
+        self.result.set(Some(history::MatchResult {
+            home_score,
+            away_score,
+            drawn,
+            time_called: false,
+            conceded: None,
+            double_loss: false,
+        }));
+        self.finished.set(Some(audit::unix_time()));
+
+        Ok(())
+    }
+
+    /// Ends the match because one of the players concedes, e.g. when they retire from the event
+    /// mid-match. Their opponent is awarded the games they needed to win, while the conceding
+    /// player keeps only games awarded by game loss penalties. Like `end_match()`, this doesn't
+    /// check whether the match already has a result.
+    pub fn concede(&self, players: &mut PlayerArena, side: PlayerSide) {
+        let (home_score, away_score) = self.concession(side);
+        self.end_match(players, home_score, away_score, 0)
+            .expect("a concession is a valid result");
+        self.mark_conceded(side);
+    }
+
+    /// Gives both players a loss without any games, e.g. because neither reported a result
+    /// before the deadline. Like `end_match()`, this doesn't check whether the match already has
+    /// a result.
+    pub fn double_loss(&self, players: &mut PlayerArena) {
+        self.update(players, |_, p| p.lose_match());
+
+        self.result.set(Some(history::MatchResult {
+            home_score: 0,
+            away_score: 0,
+            drawn: 0,
+            time_called: false,
+            conceded: None,
+            double_loss: true,
+        }));
+        self.finished.set(Some(audit::unix_time()));
+    }
+
+    /// Game scores of the home and away player if one of them concedes, including games awarded
+    /// by game loss penalties
+    fn concession(&self, side: PlayerSide) -> (u8, u8) {
+        let wins = self.format.wins_needed();
+        let kept = self.format.games() - wins;
+        match side {
+            PlayerSide::Home => (self.penalty_games.0.min(kept), wins),
+            PlayerSide::Away => (wins, self.penalty_games.1.min(kept)),
+        }
+    }
+
+    /// Records in the result that the match was conceded
+    fn mark_conceded(&self, side: PlayerSide) {
+        let mut result = self.result.get().expect("the result was recorded");
+        result.conceded = Some(side);
+        self.result.set(Some(result));
+    }
+
+    /// Takes back the recorded result, undoing everything `end_match()` did to the players'
+    /// records. Returns the result, or `None` if there was none.
+    fn revert_match(&self, players: &mut PlayerArena) -> Option<history::MatchResult> {
+        let result = self.result.take()?;
+
+        self.update(players, |side, player| {
+            let (won, lost) = match side {
+                PlayerSide::Home => (result.home_score, result.away_score),
+                PlayerSide::Away => (result.away_score, result.home_score),
+            };
+            let games = u32::from(result.home_score + result.away_score + result.drawn);
+            player.games_played = player.games_played.saturating_sub(games);
+            player.games_won = player.games_won.saturating_sub(u32::from(won));
+            player.game_points = player
+                .game_points
+                .saturating_sub(3 * u32::from(won) + u32::from(result.drawn));
+            player.matches_played = player.matches_played.saturating_sub(1);
+            // The reverted match is the player's last, so it added their current match points
+            player.cumulative_points = player.cumulative_points.saturating_sub(player.match_points);
+            player.outcomes.pop();
+
+            if result.double_loss {
+                return;
+            }
+            if won > lost {
+                player.matches_won = player.matches_won.saturating_sub(1);
+                player.match_points = player.match_points.saturating_sub(3);
+            } else if won == lost {
+                player.matches_drawn = player.matches_drawn.saturating_sub(1);
+                player.match_points = player.match_points.saturating_sub(1);
+            }
+        });
+
+        Some(result)
+    }
+}
+
+/// Checks that game scores are valid for a match of the given format
+pub(crate) fn check_scores(
+    format: MatchFormat,
+    home_score: u8,
+    away_score: u8,
+    drawn: u8,
+) -> Result<(), OutOfRangeError<u8>> {
+    let wins = format.wins_needed().saturating_add(1);
+    let games = format.games().saturating_add(1);
+
+    // Ensure that game scores are valid both individually and overall
+    home_score.check_range(0..wins)?;
+    away_score.check_range(0..wins)?;
+    drawn.check_range(0..games)?;
+
+    // At least one game needs to have been completed, even if it's a draw
+    home_score
+        .saturating_add(away_score)
+        .saturating_add(drawn)
+        .check_range(1..games)?;
+
+    Ok(())
+}
+
+/// Recording the result of a pairing can fail for one of two reasons: Either the pairing does not
+/// exist, or the supplied results are invalid
+#[derive(Debug)]
+pub enum PairingResultError {
+    NotFound(uuid::Uuid),
+    OutOfRange(u8),
+    /// The pairing already has a result, which can only be changed with `amend_match()`
+    AlreadyReported(uuid::Uuid),
+    /// The pairing has no result that could be amended
+    NotReported(uuid::Uuid),
+    /// The tournament is complete, so its results can no longer be changed
+    Finished,
+    /// The pairing belongs to an earlier round, whose results can no longer be entered
+    RoundClosed(uuid::Uuid),
+    /// The tournament was opened read-only, so its results can't be entered or amended
+    ReadOnly,
+}
+
+impl std::error::Error for PairingResultError {}
+
+impl fmt::Display for PairingResultError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", locale::Locale::English.text(self.message()))
+    }
+}
+
+impl From<LockError> for PairingResultError {
+    fn from(e: LockError) -> PairingResultError {
+        match e {
+            LockError::Finished => PairingResultError::Finished,
+            LockError::ReadOnly => PairingResultError::ReadOnly,
+        }
+    }
+}
+
+/// Reasons why a tournament can't be changed anymore, as returned by
+/// `Tournament::check_unlocked()`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LockError {
+    /// The tournament is complete, so its players, settings, and results are final
+    Finished,
+    /// The tournament was opened with `Tournament::load_readonly()`, e.g. to be looked at in an
+    /// archive, and can't be changed at all
+    ReadOnly,
+}
+
+impl std::error::Error for LockError {}
+
+impl fmt::Display for LockError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", locale::Locale::English.text(self.message()))
+    }
+}
+
+/// Reasons why players can't be entered into a tournament, as returned by
+/// `Tournament::with_players().unwrap()` and `Tournament::add_player()`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PlayerError {
+    /// A player with the same UUID is already in the tournament. A saved tournament with such a
+    /// player couldn't be loaded again.
+    DuplicatePlayer(uuid::Uuid),
+    /// The tournament is complete, so its players are final
+    Finished,
+    /// The tournament is read-only
+    ReadOnly,
+}
+
+impl std::error::Error for PlayerError {}
+
+impl fmt::Display for PlayerError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PlayerError::DuplicatePlayer(uuid) => write!(f, "Duplicate player {}!", uuid),
+            PlayerError::Finished => LockError::Finished.fmt(f),
+            PlayerError::ReadOnly => LockError::ReadOnly.fmt(f),
+        }
+    }
+}
+
+impl From<LockError> for PlayerError {
+    fn from(e: LockError) -> PlayerError {
+        match e {
+            LockError::Finished => PlayerError::Finished,
+            LockError::ReadOnly => PlayerError::ReadOnly,
+        }
+    }
+}
+
+/// A player availability constraint that could not be honored. These are collected by the
+/// tournament so that the organizer can resolve them manually instead of having them silently
+/// ignored.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub enum AvailabilityConflict {
+    /// The player's availability window does not overlap any round of the tournament
+    NeverAvailable(uuid::Uuid),
+    /// The player was available in the given round but could neither be paired nor awarded a bye,
+    /// e.g. because everyone else available had already received a bye
+    Unpaired(uuid::Uuid, u32),
+}
+
+impl fmt::Display for AvailabilityConflict {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AvailabilityConflict::NeverAvailable(_) => {
+                write!(f, "Player is not available in any round!")
+            }
+            AvailabilityConflict::Unpaired(_, round) => {
+                write!(f, "Player could not be paired in round {}!", round)
+            }
+        }
+    }
+}
+
+/// The pairings of a round as returned by `Tournament::next_round()`: the UUID of each pairing and
+/// the names of its home and away player
+pub type Pairings = Vec<(uuid::Uuid, String, String)>;
+
+/// How the Swiss pairing of a round went, as returned by `Tournament::last_pairing_stats()`
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct PairingStats {
+    pub round: u32,
+    /// Players paired, not counting the bye
+    pub players: u32,
+    /// Rematches left by pairing greedily from the top
+    pub greedy_rematches: u32,
+    /// Steps the search for a pairing with fewer rematches took, 0 if it wasn't needed
+    pub search_steps: u32,
+    /// The search ran out of steps before it found a pairing without rematches and without
+    /// players who must avoid each other
+    pub search_exhausted: bool,
+    /// The brute-force oracle found a better pairing than the search
+    pub oracle_improved: bool,
+    /// Rematches in the pairing that was used
+    pub rematches: u32,
+}
+
+/// Manages the whole tournament. Holds players and their ranking and constructs pairings on demand
+pub struct Tournament {
+    /// Name, date and other details of the event, which are carried into exports and reports
+    event: event::EventInfo,
+    rounds: u32,
+    current_round: u32,
+    /// The players, including those who dropped. Results are recorded here, so references to
+    /// players can't be held while the tournament changes.
+    players: PlayerArena,
+    pairings: HashMap<uuid::Uuid, Pairing>,
+    needs_bye: bool,
+    availability_conflicts: Vec<AvailabilityConflict>,
+    first_player: FirstPlayer,
+    /// How many games each match consists of. Change it before the first round only.
+    match_format: MatchFormat,
+    /// How players are paired. Change it before the first round only.
+    pairing_system: PairingSystem,
+    /// Who receives the bye
+    bye_selection: ByeSelection,
+    /// What the bye is worth
+    bye_score: ByeScore,
+    /// How many byes a player may be granted for an uneven number of players, by default 1
+    max_byes: u32,
+    /// Byes that broke `max_byes` or were withheld because of it, see `bye_warnings()`
+    bye_warnings: Vec<byes::ByeWarning>,
+    /// Byes requested by players for single rounds
+    requested_byes: Vec<byes::RequestedBye>,
+    /// Lowest value the players' win percentages count as
+    percentage_floor: PercentageFloor,
+    /// Criteria that break ties between players with the same match points, in the order they
+    /// are applied
+    tiebreakers: Vec<Criterion>,
+    /// Decides the order of players tied on all tiebreakers
+    final_tiebreak: FinalTiebreak,
+    /// Decimal places tiebreaker values are shown with, by default 2 and at most
+    /// `MAX_TIEBREAK_DECIMALS`
+    tiebreak_decimals: u32,
+    /// Seed of the random draw between fully tied players, once it has been made
+    tiebreak_seed: Option<u64>,
+    /// Stand-in player who is paired instead of granting a bye, if the organizer added one
+    house_player: Option<uuid::Uuid>,
+    /// Pairs of players who are paired against each other in the first round if possible
+    preferred_pairs: Vec<(uuid::Uuid, uuid::Uuid)>,
+    /// Length of a round in minutes, if the organizer set one
+    round_length: Option<u32>,
+    /// What happens to results that aren't reported in time, if the organizer set a deadline
+    deadline: Option<deadline::DeadlinePolicy>,
+    /// Table number the featured pairing of each round is moved to, starting at 1
+    featured_table: u32,
+    /// Language of the texts shown to players and organizers
+    locale: locale::Locale,
+    /// Prizes paid out over the final standings, if the organizer set them
+    prizes: Option<prizes::PayoutStructure>,
+    audit_log: Vec<audit::AuditEntry>,
+    penalties: Vec<penalties::Penalty>,
+    /// Players who checked in, while the tournament is open for registration
+    checked_in: Option<Vec<uuid::Uuid>>,
+    /// All rounds paired so far
+    history: Vec<history::Round>,
+    /// UUIDs of the players in the order of their player numbers, starting at 1
+    player_numbers: Vec<uuid::Uuid>,
+    rng: StdRng,
+    /// How the last round was paired, if it was paired by the Swiss system
+    pairing_stats: Option<PairingStats>,
+    observers: Vec<Box<dyn observer::TournamentObserver>>,
+    /// Loaded with `load_readonly()`, so rounds can't be paired and results can't be changed
+    read_only: bool,
+}
+
+impl Tournament {
+    /// Creates a tournament with the given players, as constructors took them before players were
+    /// stored in an arena. The tournament takes the players over: players that are still shared
+    /// elsewhere are copied, and the copies kept by the caller no longer follow the tournament.
+    /// See `with_players()`.
+    pub fn new(players: Vec<Rc<RefCell<Player>>>) -> Result<Tournament, PlayerError> {
+        Tournament::with_players(players.into_iter().map(arena::unwrap_player).collect())
+    }
+
+    /// Creates a tournament with the given players and enough rounds to find a single winner.
+    /// Fails if two of the players have the same UUID.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use swyss::{Player, Tournament};
+    ///
+    /// let players = (1..9).map(|i| Player::new(&format!("Player {}", i))).collect();
+    /// let mut tourn = Tournament::with_players(players).unwrap();
+    /// assert_eq!(tourn.rounds(), 3);
+    ///
+    /// let (uuid, _, _) = tourn.next_round().unwrap().unwrap()[0];
+    /// tourn.end_match(uuid, 2, 0, 0).unwrap();
+    /// let leader = &tourn.ranking()[0];
+    /// assert_eq!(leader.match_points, 3);
+    /// ```
+    pub fn with_players(players: Vec<Player>) -> Result<Tournament, PlayerError> {
+        let mut seen = HashSet::new();
+        if let Some(p) = players.iter().find(|p| !seen.insert(p.uuid)) {
+            return Err(PlayerError::DuplicatePlayer(p.uuid));
+        }
+        Ok(Tournament::from_arena(players.into()))
+    }
+
+    /// Creates a tournament with the players of the given arena, including its former players
+    pub(crate) fn from_arena(players: PlayerArena) -> Tournament {
+        let num_players = players.len();
+        let rounds = registration::default_rounds(num_players);
+        let needs_bye = !num_players.is_multiple_of(2);
+        let player_numbers = players.iter().map(|p| p.uuid).collect();
+
+        Tournament {
+            event: event::EventInfo::default(),
+            players,
+            rounds,
+            current_round: 0,
+            pairings: HashMap::with_capacity(num_players / 2),
+            needs_bye,
+            availability_conflicts: Vec::new(),
+            first_player: FirstPlayer::default(),
+            match_format: MatchFormat::default(),
+            pairing_system: PairingSystem::default(),
+            bye_selection: ByeSelection::default(),
+            bye_score: ByeScore::default(),
+            max_byes: 1,
+            bye_warnings: Vec::new(),
+            requested_byes: Vec::new(),
+            percentage_floor: PercentageFloor::default(),
+            tiebreakers: Criterion::DEFAULT_TIEBREAKERS.to_vec(),
+            final_tiebreak: FinalTiebreak::default(),
+            tiebreak_decimals: 2,
+            tiebreak_seed: None,
+            house_player: None,
+            preferred_pairs: Vec::new(),
+            round_length: None,
+            deadline: None,
+            featured_table: 1,
+            locale: locale::Locale::default(),
+            prizes: None,
+            audit_log: Vec::new(),
+            penalties: Vec::new(),
+            checked_in: None,
+            history: Vec::new(),
+            player_numbers,
+            rng: StdRng::from_entropy(),
+            pairing_stats: None,
+            observers: Vec::new(),
+            read_only: false,
+        }
+    }
+
+    /// Grants a player a bye if the number of players is odd, otherwise returns `None`. The player
+    /// is chosen according to the tournament's `bye_selection`. Ensures that a player is granted
+    /// at most one bye during a tournament. Removes the player who got the bye from the given
+    /// player list and returns them, so the bye can be recorded once pairings are complete.
+    /// Opponents are looked up in `arena`, which the players are taken from.
+    fn grant_bye<'a>(
+        &mut self,
+        players: &mut Vec<&'a Player>,
+        arena: &PlayerArena,
+    ) -> Option<&'a Player> {
+        if self.needs_bye {
+            players.shuffle(&mut self.rng);
+
+            // Get all players who have not yet received a bye
+            let iter = players
+                .iter()
+                .copied()
+                .filter(|x| x.byes_granted() < self.max_byes);
+
+            // Get the player with the lowest match points among those players
+            let lowest_score = iter.clone().min_by_key(|x| x.match_points);
+
+            let bye = match &self.bye_selection {
+                ByeSelection::LowestScore => lowest_score,
+                // The shuffle above makes the last of several fully tied players a random one
+                ByeSelection::LowestTiebreakers => {
+                    iter.max_by(|a, b| compare_standing(a, b, arena, &self.tiebreakers))
+                }
+                ByeSelection::Volunteers(volunteers) => volunteers
+                    .iter()
+                    .find_map(|uuid| iter.clone().find(|x| x.uuid == *uuid))
+                    .or(lowest_score),
+            };
+
+            if let Some(bye) = bye {
+                debug_event!(
+                    player = %bye.name,
+                    match_points = bye.match_points,
+                    selection = ?self.bye_selection,
+                    "bye granted"
+                );
+                if let Some(i) = players.iter().position(|p| p.uuid == bye.uuid) {
+                    return Some(players.remove(i));
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Records a conflict for every player whose availability window does not overlap any round
+    /// of the tournament. Each conflict is only recorded once.
+    fn check_availability(&mut self) {
+        for p in &self.players {
+            if !p.availability.overlaps(self.rounds) {
+                let conflict = AvailabilityConflict::NeverAvailable(p.uuid);
+                if !self.availability_conflicts.contains(&conflict) {
+                    self.availability_conflicts.push(conflict);
+                }
+            }
+        }
+    }
+
+    /// Decides which of two players about to be paired goes first according to the tournament's
+    /// `first_player` policy. Opponents are looked up in `arena`.
+    fn decide_first_player(
+        &mut self,
+        home: &Player,
+        away: &Player,
+        arena: &PlayerArena,
+    ) -> Option<PlayerSide> {
+        let random = |rng: &mut StdRng| {
+            if rng.gen_bool(0.5) {
+                PlayerSide::Home
+            } else {
+                PlayerSide::Away
+            }
+        };
+
+        match self.first_player {
+            FirstPlayer::Undecided => None,
+            FirstPlayer::Random => Some(random(&mut self.rng)),
+            FirstPlayer::HigherSeed => match compare_standing(home, away, arena, &self.tiebreakers)
+            {
+                Ordering::Less => Some(PlayerSide::Home),
+                Ordering::Greater => Some(PlayerSide::Away),
+                Ordering::Equal => Some(random(&mut self.rng)),
+            },
+        }
+    }
+
+    /// Pairs the given players based on match points, so that every player is in exactly one
+    /// pair. If the number of players is odd, the lowest-ranked player is left over. Rematches are
+    /// only allowed if every remaining opponent is a previous one, and are then kept to the fewest
+    /// the search finds within `MAX_PAIRING_SEARCH` steps; `rematches()` lists them once the round
+    /// is paired. For fields of up to `oracle::MAX_PLAYERS` players, the result is checked against
+    /// the brute-force oracle and replaced by the oracle's pairing if that one is better. Players
+    /// who must avoid each other are kept apart before anything else, and preferred pairs are
+    /// paired first in the first round.
+    fn pair_players<'a>(&mut self, active: &[&'a Player]) -> Vec<oracle::PlayerPair<'a>> {
+        let players = active.len() as u32;
+        let (mut preferred, rest) = self.take_preferred_pairs(active);
+        let active = rest.as_slice();
+        let mut queue = active.to_vec();
+        queue.shuffle(&mut self.rng);
+        let seeded = queue.iter().any(|p| p.rating.is_some());
+        if seeded {
+            sort_by_rating(&mut queue);
+        }
+        queue.sort_by_key(|p| Reverse(p.match_points));
+
+        debug_event!(players = queue.len(), seeded, "pairing by match points");
+        let mut pairs = if seeded {
+            seeded_pairs(&queue)
+        } else {
+            greedy_pairs(&queue)
+        };
+        let mut stats = PairingStats {
+            round: self.current_round,
+            players,
+            ..PairingStats::default()
+        };
+
+        // Pairing greedily from the top can leave the last players with nobody but previous
+        // opponents or players of their own club, so search for a pairing with fewer of them
+        let greedy = oracle::cost(active, &pairs);
+        debug_event!(
+            rematches = greedy.rematches,
+            same_club = greedy.same_club,
+            "paired greedily"
+        );
+        stats.greedy_rematches = greedy.rematches;
+        let conflicts = (greedy.avoided, greedy.rematches, greedy.same_club);
+        if conflicts != (0, 0, 0) {
+            let mut best = (conflicts, None);
+            let mut budget = MAX_PAIRING_SEARCH;
+            search_pairs(
+                &mut queue,
+                &mut Vec::new(),
+                (0, 0, 0),
+                &mut best,
+                &mut budget,
+            );
+            stats.search_steps = MAX_PAIRING_SEARCH - budget;
+            stats.search_exhausted = budget == 0 && best.0 .0 + best.0 .1 > 0;
+            debug_event!(
+                steps = stats.search_steps,
+                exhausted = stats.search_exhausted,
+                avoided = best.0 .0,
+                rematches = best.0 .1,
+                same_club = best.0 .2,
+                "fell back to searching for fewer rematches"
+            );
+            if let (_, Some(better)) = best {
+                pairs = better;
+            }
+        }
+
+        if let Some((optimal, cost)) = oracle::optimal(active) {
+            if cost < oracle::cost(active, &pairs) {
+                debug_event!(cost = ?cost, "the oracle found a better pairing");
+                stats.oracle_improved = true;
+                pairs = optimal;
+            }
+        }
+
+        stats.rematches = oracle::cost(active, &pairs).rematches;
+        self.pairing_stats = Some(stats);
+        preferred.extend(pairs);
+        preferred
+    }
+
+    /// Returns how the last round was paired: how many rematches pairing greedily left, how long
+    /// the search for fewer rematches took, and how many rematches remained. `None` if the last
+    /// round wasn't paired by the Swiss system, e.g. because it was published from a preview or the
+    /// tournament uses a variant that pairs by rank.
+    /// Only kept while the tournament is in memory.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use swyss::{Player, Tournament};
+    ///
+    /// let players = (1..9)
+    ///     .map(|i| Player::new(&format!("Player {}", i)))
+    ///     .collect();
+    /// let mut tourn = Tournament::with_players(players).unwrap();
+    /// assert!(tourn.last_pairing_stats().is_none());
+    ///
+    /// tourn.next_round().unwrap();
+    /// let stats = tourn.last_pairing_stats().unwrap();
+    /// assert_eq!((stats.round, stats.players), (1, 8));
+    /// assert_eq!(stats.rematches, 0);
+    /// ```
+    pub fn last_pairing_stats(&self) -> Option<PairingStats> {
+        self.pairing_stats
+    }
+
+    /// Advances the tournament by one round. If there are still rounds left to play, construct new
+    /// player pairings based on match points and return them. Players who are not available in
+    /// this round sit it out. If there is an uneven number of available players, the lowest-ranked
+    /// player who has not yet received a bye receives a bye. A tournament that is still open for
+    /// registration is started first, so only players who checked in are paired.
+    ///
+    /// All random decisions of the round are derived from a freshly drawn seed, which is recorded
+    /// in the audit log so that the pairings can be reproduced with `next_round_with_seed()`.
+    ///
+    /// Pairing always completes: every available player is paired at most once, and the search
+    /// for pairings without rematches is bounded. A player who can't be paired because nobody is
+    /// eligible for the bye anymore is recorded as an `AvailabilityConflict::Unpaired`.
+    ///
+    /// Returns `None` once all rounds have been played or the tournament was finished, and an error
+    /// if results of the current round are still outstanding (see `round_status()`).
+    pub fn next_round(&mut self) -> Result<Option<Pairings>, history::RoundError> {
+        let seed = thread_rng().gen();
+        self.next_round_with_seed(seed)
+    }
+
+    /// Advances the tournament by one round like `next_round()`, but derives all random decisions
+    /// of the round from the given seed. Given the same tournament state, e.g. loaded from the
+    /// same file, the same seed always produces the same pairings.
+    pub fn next_round_with_seed(
+        &mut self,
+        seed: u64,
+    ) -> Result<Option<Pairings>, history::RoundError> {
+        self.pair_next_round(seed, None)
+    }
+
+    /// Advances the tournament by one round, pairing the players as planned if a plan is given and
+    /// according to the pairing system otherwise
+    pub(crate) fn pair_next_round(
+        &mut self,
+        seed: u64,
+        plan: Option<&preview::RoundPreview>,
+    ) -> Result<Option<Pairings>, history::RoundError> {
+        if self.read_only {
+            return Err(history::RoundError::ReadOnly);
+        }
+        if self.is_finished() {
+            return Ok(None);
+        }
+        let status = self.round_status();
+        if !status.is_complete() {
+            return Err(history::RoundError::ResultsOutstanding(status.outstanding));
+        }
+        self.record_standings();
+
+        if self.is_registering() {
+            self.start().expect("registration is open");
+        }
+
+        self.current_round += 1;
+        if self.current_round > self.rounds {
+            self.notify(|o| o.on_complete(self));
+            return Ok(None);
+        }
+
+        debug_span!("round", number = self.current_round, seed);
+        self.rng = StdRng::seed_from_u64(seed);
+        self.log(audit::AuditEvent::RoundSeed { seed });
+        self.pairing_stats = None;
+
+        self.check_availability();
+
+        let round = self.current_round;
+        let requested: Vec<uuid::Uuid> = self
+            .requested_byes
+            .iter()
+            .filter(|bye| bye.round == round)
+            .map(|bye| bye.player)
+            .collect();
+        // The players are paired by reference and only changed once the round is decided
+        let mut arena = std::mem::take(&mut self.players);
+        let (mut active, mut resting): (Vec<&Player>, Vec<&Player>) = arena
+            .iter()
+            .partition(|p| p.availability.is_available(round) && !requested.contains(&p.uuid));
+        // A round robin schedule pairs everyone, so the house player stands in for the bye there
+        if self.pairing_system != PairingSystem::RoundRobin {
+            self.seat_house_player(&mut active, &mut resting);
+        }
+        debug_event!(
+            available = active.len(),
+            resting = resting.len(),
+            requested_byes = requested.len(),
+            "players available"
+        );
+        // Before the bye adds its points
+        let points: Vec<(uuid::Uuid, u32)> =
+            active.iter().map(|p| (p.uuid, p.match_points)).collect();
+        let requested_byes = self.requested_byes_in_round(&resting);
+
+        let planned = match (plan, self.pairing_system) {
+            (Some(plan), _) => {
+                self.needs_bye = active.len() % 2 == 1;
+                self.planned_pairs(plan, &mut active)
+            }
+            (None, PairingSystem::Swiss) => {
+                self.needs_bye = active.len() % 2 == 1;
+                let bye = self.grant_bye(&mut active, &arena);
+                Ok((bye, self.pair_players(&active)))
+            }
+            (None, PairingSystem::RoundRobin) => {
+                // The schedule decides who sits out, and records its own conflicts
+                self.needs_bye = false;
+                Ok(self.pair_round_robin(&mut active, &resting))
+            }
+            (None, system @ PairingSystem::Danish) | (None, system @ PairingSystem::Monrad) => {
+                self.needs_bye = active.len() % 2 == 1;
+                let bye = self.grant_bye(&mut active, &arena);
+                Ok((
+                    bye,
+                    self.pair_by_rank(&active, &arena, system == PairingSystem::Monrad),
+                ))
+            }
+        };
+        let (bye, pairs) = match planned {
+            Ok((bye, pairs)) => (
+                bye.map(|p| p.uuid),
+                pairs
+                    .iter()
+                    .map(|(home, away)| (home.uuid, away.uuid))
+                    .collect::<Vec<_>>(),
+            ),
+            Err(e) => {
+                self.players = arena;
+                return Err(e);
+            }
+        };
+        let active: Vec<uuid::Uuid> = active.iter().map(|p| p.uuid).collect();
+        let mut order = active.clone();
+        order.extend(bye);
+        order.extend(resting.iter().map(|p| p.uuid));
+
+        for requested in &requested_byes {
+            if let Some(p) = arena.get_mut(requested.player) {
+                p.score_bye(self.match_format, requested.score);
+            }
+        }
+        if let Some(p) = bye.and_then(|bye| arena.get_mut(bye)) {
+            p.bye_with(self.match_format, self.bye_score);
+        }
+
+        self.close_round();
+        self.pairings.clear();
+        let mut ret: Vec<(uuid::Uuid, String, String)> = Vec::with_capacity(pairs.len());
+
+        for (home, away) in pairs {
+            // Decide before pairing so that the standings going into the round are used
+            let first = match (arena.get(home), arena.get(away)) {
+                (Some(h), Some(a)) => self.decide_first_player(h, a, &arena),
+                _ => None,
+            };
+
+            let (h, a) = match arena.pair_mut(home, away) {
+                Some(players) => players,
+                None => continue,
+            };
+            let mut pair = Pairing::new(h, a);
+            pair.format = self.match_format;
+
+            if let Some(side) = first {
+                pair.set_first_player(side);
+            }
+
+            let uuid = pair.uuid;
+            let home_str = h.name.clone();
+            let away_str = a.name.clone();
+
+            debug_event!(
+                home = %home_str,
+                away = %away_str,
+                first = ?first,
+                "paired"
+            );
+            self.pairings.insert(uuid, pair);
+            ret.push((uuid, home_str, away_str));
+        }
+
+        arena.reorder(&order);
+        self.players = arena;
+
+        // If a bye was needed but nobody was eligible, someone was left without an opponent
+        if self.needs_bye && bye.is_none() {
+            for &uuid in &active {
+                let paired = self
+                    .pairings
+                    .values()
+                    .any(|pair| pair.home == uuid || pair.away == uuid);
+                if !paired {
+                    debug_event!(player = %uuid, "nobody left to pair with");
+                    self.availability_conflicts
+                        .push(AvailabilityConflict::Unpaired(uuid, round));
+                    self.bye_warnings.push(byes::ByeWarning::Withheld {
+                        player: uuid,
+                        round,
+                    });
+                }
+            }
+        }
+
+        ret.shuffle(&mut self.rng);
+
+        if let Some(player) = bye.and_then(|bye| self.players.get(bye)) {
+            if player.byes > self.max_byes {
+                self.bye_warnings.push(byes::ByeWarning::OverLimit {
+                    player: player.uuid,
+                    round,
+                    byes: player.byes,
+                });
+            }
+        }
+
+        self.open_round(bye, requested_byes);
+        let rationale = self.pairing_rationale(&points, plan.is_some());
+        if let Some(round) = self.history.last_mut() {
+            round.rationale = Some(rationale);
+        }
+
+        self.notify(|o| o.on_round_paired(self, &ret));
+        if let Some(bye) = bye.and_then(|bye| self.players.get(bye)) {
+            self.notify(|o| o.on_bye(self, bye));
+        }
+
+        Ok(Some(ret))
+    }
+
+    /// Record the result of a pairing, specified by its UUID. Basically just a wrapper around
+    /// `Pairing::end_match()`, extended by the `NotFound` error type. Games awarded by game loss
+    /// penalties are added to the given scores. A pairing can only be reported once; use
+    /// `amend_match()` to correct its result. Pairings of earlier rounds are rejected with
+    /// `RoundClosed`.
+    pub fn end_match(
+        &mut self,
+        uuid: uuid::Uuid,
+        home_score: u8,
+        away_score: u8,
+        drawn: u8,
+    ) -> Result<(), PairingResultError> {
+        self.open_pairing(uuid)?;
+        let pair = &self.pairings[&uuid];
+        if pair.is_completed() {
+            return Err(PairingResultError::AlreadyReported(uuid));
+        }
+
+        let home_score = home_score.saturating_add(pair.penalty_games.0);
+        let away_score = away_score.saturating_add(pair.penalty_games.1);
+        match pair.end_match(&mut self.players, home_score, away_score, drawn) {
+            Ok(_) => {
+                self.notify(|o| o.on_result(self, uuid, home_score, away_score, drawn));
+                Ok(())
+            }
+            Err(e) => Err(PairingResultError::OutOfRange(e.outside_value)),
+        }
+    }
+
+    /// Looks up a pairing of the current round that results can be entered for. A pairing of an
+    /// earlier round, e.g. from a client that still shows an old round, is rejected with
+    /// `RoundClosed` instead of `NotFound`.
+    fn open_pairing(&self, uuid: uuid::Uuid) -> Result<&Pairing, PairingResultError> {
+        self.check_unlocked()?;
+
+        match self.pairings.get(&uuid) {
+            Some(pair) => Ok(pair),
+            None if self.in_closed_round(uuid) => Err(PairingResultError::RoundClosed(uuid)),
+            None => Err(PairingResultError::NotFound(uuid)),
+        }
+    }
+
+    /// Records the result of a match in which time was called during an unfinished game, e.g. at
+    /// the end of the round. The scores are the games finished before; the unfinished game counts
+    /// as a drawn game, so 1-0 is a match win and 1-1 a draw. Fails with `OutOfRange` if either
+    /// player had already won the match, since then time didn't matter.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use swyss::{Player, Tournament};
+    ///
+    /// let players = vec![
+    ///     Player::new("Alice"),
+    ///     Player::new("Bob"),
+    /// ];
+    /// let mut tourn = Tournament::with_players(players).unwrap();
+    /// let (uuid, _, _) = tourn.next_round().unwrap().unwrap()[0];
+    ///
+    /// assert!(tourn.end_match_at_time(uuid, 2, 0, 0).is_err());
+    /// tourn.end_match_at_time(uuid, 1, 0, 0).unwrap();
+    ///
+    /// let result = tourn.round(1).unwrap().pairings[0].result.unwrap();
+    /// assert_eq!((result.home_score, result.away_score, result.drawn), (1, 0, 1));
+    /// assert!(result.time_called);
+    /// ```
+    pub fn end_match_at_time(
+        &mut self,
+        uuid: uuid::Uuid,
+        home_score: u8,
+        away_score: u8,
+        drawn: u8,
+    ) -> Result<(), PairingResultError> {
+        let pair = self.open_pairing(uuid)?;
+
+        let wins = pair.format.wins_needed();
+        for score in &[
+            home_score.saturating_add(pair.penalty_games.0),
+            away_score.saturating_add(pair.penalty_games.1),
+        ] {
+            if *score >= wins {
+                return Err(PairingResultError::OutOfRange(*score));
+            }
+        }
+
+        self.end_match(uuid, home_score, away_score, drawn.saturating_add(1))?;
+
+        let pair = &self.pairings[&uuid];
+        let mut result = pair.result.get().expect("the result was just recorded");
+        result.time_called = true;
+        pair.result.set(Some(result));
+
+        Ok(())
     }
 
-    /// Makes the player win a single match. Increases the number of matches played and adds three
-    /// match points.
+    /// Records that one player of a pairing concedes the match, as described for
+    /// `Pairing::concede()`, so that no game scores have to be made up. Returns the game scores
+    /// recorded for the home and away player, without games awarded by penalties, as they would
+    /// have been passed to `end_match()`.
     ///
     /// # Example
     ///
     /// ```
-    /// use swyss::Player;
-    /// let mut player = Player::new("Winner");
-    /// player.win_match();
-    /// assert_eq!(player.matches_played, 1);
-    /// assert_eq!(player.match_points, 3);
+    /// use swyss::{Player, PlayerSide, Tournament};
+    ///
+    /// let players = vec![
+    ///     Player::new("Alice"),
+    ///     Player::new("Bob"),
+    /// ];
+    /// let mut tourn = Tournament::with_players(players).unwrap();
+    /// let (uuid, _, _) = tourn.next_round().unwrap().unwrap()[0];
+    ///
+    /// assert_eq!(tourn.concede(uuid, PlayerSide::Away).unwrap(), (2, 0));
+    ///
+    /// let result = tourn.round(1).unwrap().pairings[0].result.unwrap();
+    /// assert_eq!((result.home_score, result.away_score), (2, 0));
+    /// assert_eq!(result.conceded, Some(PlayerSide::Away));
     /// ```
-    pub fn win_match(&mut self) {
-        self.matches_played += 1;
-        self.match_points += 3;
+    pub fn concede(
+        &mut self,
+        uuid: uuid::Uuid,
+        side: PlayerSide,
+    ) -> Result<(u8, u8), PairingResultError> {
+        let pair = self.open_pairing(uuid)?;
+
+        let (home_score, away_score) = pair.concession(side);
+        let home_score = home_score.saturating_sub(pair.penalty_games.0);
+        let away_score = away_score.saturating_sub(pair.penalty_games.1);
+        self.end_match(uuid, home_score, away_score, 0)?;
+        self.pairings[&uuid].mark_conceded(side);
+
+        Ok((home_score, away_score))
     }
 
-    /// Awards the player a bye. The player is considered to have won their match 2-0. No opponent
-    /// is added to the `opponents` vector. The player is recorded as having received a bye so that
-    /// the tournament manager can check that no player is awarded more than one bye.
+    /// Corrects the result of a pairing of the current round that was already reported. The
+    /// previous result is taken back from both players' records and the correction is recorded in
+    /// the audit log. Like in `end_match()`, games awarded by game loss penalties are added.
+    /// Results of earlier rounds are final, so their pairings are rejected with `RoundClosed`.
     ///
     /// # Example
     ///
     /// ```
-    /// use swyss::Player;
-    /// let mut player = Player::new("Byer");
-    /// player.bye();
-    /// assert_eq!(player.games_played, 2);
-    /// assert_eq!(player.game_points, 6);
-    /// assert_eq!(player.matches_played, 1);
-    /// assert_eq!(player.match_points, 3);
-    /// assert_eq!(player.opponents.len(), 0);
-    /// assert!(player.has_bye);
+    /// use swyss::{PairingResultError, Player, Tournament};
+    ///
+    /// let players = vec![
+    ///     Player::new("Alice"),
+    ///     Player::new("Bob"),
+    /// ];
+    /// let mut tourn = Tournament::with_players(players).unwrap();
+    /// let (uuid, _, _) = tourn.next_round().unwrap().unwrap()[0];
+    ///
+    /// tourn.end_match(uuid, 2, 0, 0).unwrap();
+    /// assert!(matches!(
+    ///     tourn.end_match(uuid, 0, 2, 0),
+    ///     Err(PairingResultError::AlreadyReported(_))
+    /// ));
+    ///
+    /// tourn.amend_match(uuid, 0, 2, 0).unwrap();
+    /// let points: Vec<u32> = tourn.player_arena().iter().map(|p| p.match_points).collect();
+    /// assert_eq!(points.iter().sum::<u32>(), 3);
     /// ```
-    pub fn bye(&mut self) {
-        self.win_game();
-        self.win_game();
-        self.win_match();
-        self.has_bye = true;
-    }
-
-    /// Calculates the player's match win percentage, i.e. accumulated match points divided by
-    /// total match points possible in those rounds. Minimum MWP returned is always 1/3 to reduce
-    /// the impact of low performance on `opponents_match_win_percentage()`.
-    pub fn match_win_percentage(&self) -> f64 {
-        let default = 1.0 / 3.0;
-        if self.matches_played == 0 {
-            default
-        } else {
-            default.max(self.match_points as f64 / (3.0 * self.matches_played as f64))
+    pub fn amend_match(
+        &mut self,
+        uuid: uuid::Uuid,
+        home_score: u8,
+        away_score: u8,
+        drawn: u8,
+    ) -> Result<(), PairingResultError> {
+        self.open_pairing(uuid)?;
+        let pair = &self.pairings[&uuid];
+        if !pair.is_completed() {
+            return Err(PairingResultError::NotReported(uuid));
         }
-    }
 
-    /// Calculates the player's game win percentage, i.e. accumulated game points divided by total
-    /// game points possible in those rounds. Minimum GWP returned is always 1/3 to reduce the
-    /// impact of low performance on `opponents_game_win_percentage()`.
-    pub fn game_win_percentage(&self) -> f64 {
-        let default = 1.0 / 3.0;
-        if self.games_played == 0 {
-            default
-        } else {
-            default.max(self.game_points as f64 / (3.0 * self.games_played as f64))
-        }
-    }
+        let home_score = home_score.saturating_add(pair.penalty_games.0);
+        let away_score = away_score.saturating_add(pair.penalty_games.1);
+        check_scores(pair.format, home_score, away_score, drawn)
+            .map_err(|e| PairingResultError::OutOfRange(e.outside_value))?;
+
+        // The match still ended when its result was first entered
+        let finished = pair.finished.get();
+        let old = pair
+            .revert_match(&mut self.players)
+            .expect("the pairing has a result");
+        pair.end_match(&mut self.players, home_score, away_score, drawn)
+            .expect("the scores were checked");
+        pair.finished.set(finished);
+        let new = pair.result.get().expect("the result was just recorded");
+
+        self.log(audit::AuditEvent::ResultAmended {
+            pairing: uuid,
+            old,
+            new,
+        });
+        self.notify(|o| o.on_result(self, uuid, home_score, away_score, drawn));
 
-    /// Calculates the player's opponents' match win percentage, i.e. the average match win
-    /// percentage of all opponents the player faced, ignoring byes.
-    pub fn opponents_match_win_percentage(&self) -> f64 {
-        let mut omwp = 0.0;
+        Ok(())
+    }
 
-        for opp in &self.opponents {
-            omwp += opp.borrow().match_win_percentage();
+    /// Drops a player from the tournament, specified by their UUID. A player who drops before the
+    /// first round is removed entirely. Otherwise, they are no longer paired after the current
+    /// round but stay in the standings, and their results still count towards their opponents'
+    /// tiebreakers. Returns a copy of the dropped player, or `None` if there is no such player.
+    pub fn drop_player(&mut self, uuid: uuid::Uuid) -> Result<Option<Player>, LockError> {
+        self.check_unlocked()?;
+        if !self.players.contains(uuid) {
+            return Ok(None);
         }
 
-        omwp / self.opponents.len() as f64
-    }
-
-    /// Calculates the player's opponents' game win percentage, i.e. the average game win
-    /// percentage of all opponents the player faced, ignoring byes.
-    pub fn opponents_game_win_percentage(&self) -> f64 {
-        let mut omwp = 0.0;
+        let player = if self.current_round == 0 {
+            self.player_numbers.retain(|&number| number != uuid);
+            self.players.remove(uuid)
+        } else {
+            let round = self.current_round;
+            self.players.get_mut(uuid).map(|p| {
+                let last_round = p.availability.last_round.map(|last| last.min(round));
+                p.availability.last_round = Some(last_round.unwrap_or(round));
+                p.clone()
+            })
+        };
 
-        for opp in &self.opponents {
-            omwp += opp.borrow().game_win_percentage();
+        if let Some(player) = &player {
+            self.notify(|o| o.on_drop(self, player));
         }
 
-        omwp / self.opponents.len() as f64
+        Ok(player)
     }
-}
 
-impl PartialEq for Player {
-    fn eq(&self, other: &Player) -> bool {
-        self.uuid == other.uuid
+    /// Brings back a player who dropped after the first round, e.g. because they dropped by
+    /// mistake. They are paired again from the next round on and keep their record, including the
+    /// byes they already had, so they don't get another bye beyond `max_byes`. Returns the player,
+    /// or `None` if there is no such player.
+    pub fn readd_player(&mut self, uuid: uuid::Uuid) -> Result<Option<&Player>, LockError> {
+        self.check_unlocked()?;
+        Ok(self.players.get_mut(uuid).map(|player| {
+            player.availability.last_round = None;
+            &*player
+        }))
     }
-}
-
-pub enum PlayerSide {
-    Home,
-    Away,
-}
-
-pub struct Pairing {
-    uuid: uuid::Uuid,
-    home: Rc<RefCell<Player>>,
-    away: Rc<RefCell<Player>>,
-}
 
-impl Pairing {
-    /// Creates a new pairing between two players
+    /// Returns a snapshot of every player in the tournament, in their current order. Call
+    /// `ranking()` first to get them in the order of the standings.
     ///
-    /// # Arguments
+    /// # Example
     ///
-    /// * `home` - The left-hand player, wrapped in a `RefCell`
-    /// * `away` - The right-hand player, wrapped in a `RefCell`
-    pub fn new(home: Rc<RefCell<Player>>, away: Rc<RefCell<Player>>) -> Pairing {
-        let uuid = Uuid::new_v4();
-        let home = Rc::clone(&home);
-        let away = Rc::clone(&away);
-        home.borrow_mut().opponents.push(Rc::clone(&away));
-        away.borrow_mut().opponents.push(Rc::clone(&home));
-        Pairing { uuid, home, away }
-    }
-
-    /// Registers a won game for one of the players. Implies registering a lost game for the other
-    /// player.
+    /// ```
+    /// use swyss::{Player, Tournament};
     ///
-    /// # Arguments
+    /// let players = vec![
+    ///     Player::new("Alice"),
+    ///     Player::new("Bob"),
+    /// ];
+    /// let mut tourn = Tournament::with_players(players).unwrap();
+    /// let pairings = tourn.next_round().unwrap().unwrap();
+    /// tourn.end_match(pairings[0].0, 2, 0, 0).unwrap();
     ///
-    /// `side` - A `PlayerSide` that specifies whether the home or away player won the game
-    pub fn win_game(&self, side: PlayerSide) {
-        let (winner, loser) = match side {
-            PlayerSide::Home => (&self.home, &self.away),
-            PlayerSide::Away => (&self.away, &self.home),
-        };
+    /// let points: u32 = tourn.players().map(|p| p.match_points).sum();
+    /// assert_eq!(points, 3);
+    /// ```
+    pub fn players(&self) -> impl Iterator<Item = PlayerView> + '_ {
+        let players = &self.players;
+        players.iter().map(move |p| PlayerView::new(p, players))
+    }
 
-        winner.borrow_mut().win_game();
-        loser.borrow_mut().lose_game();
+    /// Returns a snapshot of a single player, if they are in the tournament
+    pub fn player(&self, uuid: uuid::Uuid) -> Option<PlayerView> {
+        self.players().find(|p| p.uuid == uuid)
     }
 
-    /// Registers a drawn game.
-    pub fn draw_game(&self) {
-        &self.home.borrow_mut().draw_game();
-        &self.away.borrow_mut().draw_game();
+    /// Adds a player to a running tournament. The player is first paired in the next round and
+    /// enters the tournament with whatever results they already have. The number of rounds is not
+    /// changed. Fails if a player with the same UUID is already in the tournament, including one
+    /// who dropped.
+    pub fn add_player(&mut self, mut player: Player) -> Result<(), PlayerError> {
+        self.check_unlocked()?;
+        if self.players.opponent(player.uuid).is_some() {
+            return Err(PlayerError::DuplicatePlayer(player.uuid));
+        }
+        let first_round = player.availability.first_round;
+        player.availability.first_round = first_round.max(self.current_round + 1);
+        player.percentage_floor = self.percentage_floor;
+        self.player_numbers.push(player.uuid);
+        self.players.push(player);
+        Ok(())
     }
 
-    pub fn end_match(
-        &self,
-        home_score: u8,
-        away_score: u8,
-        drawn: u8,
-    ) -> Result<(), OutOfRangeError<u8>> {
-        // Ensure that game scores are valid both individually and overall
-        home_score.check_range(0..3)?;
-        away_score.check_range(0..3)?;
-        drawn.check_range(0..4)?;
+    /// Lowest value the players' match and game win percentages count as, by default 1/3
+    pub fn percentage_floor(&self) -> PercentageFloor {
+        self.percentage_floor
+    }
 
-        // At least one game needs to have been completed, even if it's a draw
-        (home_score + away_score + drawn).check_range(1..4)?;
+    /// Changes the lowest value the players' win percentages count as, e.g. to
+    /// `PercentageFloor::None` for chess. The floor applies to all tiebreakers right away,
+    /// including those of rounds already played.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use swyss::{PercentageFloor, Player, Tournament};
+    ///
+    /// let players = (1..3)
+    ///     .map(|i| Player::new(&format!("Player {}", i)))
+    ///     .collect();
+    /// let mut tourn = Tournament::with_players(players).unwrap();
+    /// let (uuid, _, _) = tourn.next_round().unwrap().unwrap()[0];
+    /// tourn.end_match(uuid, 2, 0, 0).unwrap();
+    ///
+    /// // The winner's only opponent lost, which counts as 1/3 by default
+    /// assert_eq!(tourn.standings()[0].opponents_match_win_percentage, 1.0 / 3.0);
+    /// tourn.set_percentage_floor(PercentageFloor::None).unwrap();
+    /// assert_eq!(tourn.standings()[0].opponents_match_win_percentage, 0.0);
+    /// ```
+    pub fn set_percentage_floor(&mut self, floor: PercentageFloor) -> Result<(), LockError> {
+        self.check_unlocked()?;
+        self.apply_percentage_floor(floor);
+        Ok(())
+    }
 
-        for _ in 0..home_score {
-            self.win_game(PlayerSide::Home);
+    /// Changes the percentage floor of the tournament and all of its players
+    pub(crate) fn apply_percentage_floor(&mut self, floor: PercentageFloor) {
+        self.percentage_floor = floor;
+        for p in &mut self.players {
+            p.percentage_floor = floor;
         }
+    }
 
-        for _ in 0..away_score {
-            self.win_game(PlayerSide::Away);
-        }
+    /// Formats a tiebreaker value with the tournament's `tiebreak_decimals`, e.g. with three
+    /// decimals like official Magic software prints them
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use swyss::Tournament;
+    ///
+    /// let mut tourn = Tournament::with_players(Vec::new()).unwrap();
+    /// assert_eq!(tourn.format_tiebreak(2.0 / 3.0), "0.67");
+    /// tourn.set_tiebreak_decimals(3).unwrap();
+    /// assert_eq!(tourn.format_tiebreak(2.0 / 3.0), "0.667");
+    /// ```
+    pub fn format_tiebreak(&self, value: f64) -> String {
+        format!("{:.*}", self.decimals(), value)
+    }
 
-        for _ in 0..drawn {
-            self.draw_game();
+    /// Formats the exact value of a tiebreaker with the tournament's `tiebreak_decimals`, rounding
+    /// halves away from zero, so that the rounded value always agrees with the exact one
+    pub(crate) fn format_rational(&self, value: &BigRational) -> String {
+        let decimals = self.decimals();
+        let negative = *value < BigRational::from_integer(0.into());
+        let mut scaled = if negative {
+            -value.clone()
+        } else {
+            value.clone()
+        };
+        for _ in 0..decimals {
+            scaled *= BigRational::from_integer(10.into());
         }
 
-        if home_score > away_score {
-            &self.home.borrow_mut().win_match();
-            &self.away.borrow_mut().lose_match();
-        } else if away_score > home_score {
-            &self.home.borrow_mut().lose_match();
-            &self.away.borrow_mut().win_match();
+        let digits = scaled.round().to_integer().to_string();
+        let digits = format!("{:0>width$}", digits, width = decimals + 1);
+        let (whole, fraction) = digits.split_at(digits.len() - decimals);
+        let sign = if negative && digits.bytes().any(|b| b != b'0') {
+            "-"
         } else {
-            &self.home.borrow_mut().draw_match();
-            &self.away.borrow_mut().draw_match();
+            ""
+        };
+        if decimals == 0 {
+            format!("{}{}", sign, whole)
+        } else {
+            format!("{}{}.{}", sign, whole, fraction)
         }
-
-        Ok(())
     }
-}
-
-/// Recording the result of a pairing can fail for one of two reasons: Either the pairing does not
-/// exist, or the supplied results are invalid
-#[derive(Debug)]
-pub enum PairingResultError {
-    NotFound(uuid::Uuid),
-    OutOfRange(u8),
-}
 
-impl std::error::Error for PairingResultError {}
+    /// Decimal places tiebreaker values are formatted with, at most `MAX_TIEBREAK_DECIMALS`
+    fn decimals(&self) -> usize {
+        self.tiebreak_decimals.min(MAX_TIEBREAK_DECIMALS) as usize
+    }
 
-impl fmt::Display for PairingResultError {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        match self {
-            PairingResultError::NotFound(_) => write!(f, "Pairing not found!"),
-            PairingResultError::OutOfRange(_) => write!(f, "Score(s) out of range!"),
+    /// Rank all players using all tiebreakers. This only needs to be called if the ranking
+    /// actually needs to be displayed (i.e. at the end of the tournament) or if results between
+    /// rounds are desired; it is not necessary when progressing rounds as `next_round()`
+    /// automatically performs a simpler ranking using just match points before creating new
+    /// pairings.
+    ///
+    /// Players tied on everything are ordered by the `final_tiebreak`. A random draw is made the
+    /// first time it's needed and recorded, so every later ranking orders them the same way.
+    /// The house player isn't ranked.
+    ///
+    /// The players are returned in the order of the standings and are borrowed from the
+    /// tournament, so they have to be let go of before results are recorded.
+    pub fn ranking(&mut self) -> Vec<&Player> {
+        if self.final_tiebreak == FinalTiebreak::Random && self.tiebreak_seed.is_none() {
+            let seed = thread_rng().gen();
+            self.tiebreak_seed = Some(seed);
+            self.log(audit::AuditEvent::TiebreakSeed { seed });
         }
+
+        // The house player follows the ranked players
+        let ranked: Vec<uuid::Uuid> = self.sorted_players().iter().map(|p| p.uuid).collect();
+        self.players.reorder(&ranked);
+        self.players.iter().take(ranked.len()).collect()
     }
-}
 
-/// Manages the whole tournament. Holds players and their ranking and constructs pairings on demand
-pub struct Tournament {
-    pub rounds: u32,
-    pub current_round: u32,
-    pub players: Vec<Rc<RefCell<Player>>>,
-    pub pairings: HashMap<uuid::Uuid, Pairing>,
-    pub needs_bye: bool,
-    rng: ThreadRng,
-}
+    /// Returns the seed of the random draw between fully tied players, if it has been made
+    pub fn tiebreak_seed(&self) -> Option<u64> {
+        self.tiebreak_seed
+    }
 
-impl Tournament {
-    pub fn new(players: Vec<Rc<RefCell<Player>>>) -> Tournament {
-        let num_players = players.len();
-        let rounds = (num_players as f64).log2().ceil() as u32;
-        let needs_bye = if num_players % 2 == 0 { false } else { true };
+    /// Puts the players in the order of the final standings, so that archived events list them
+    /// the way they ended. The house player isn't ranked, but is kept among the former players
+    /// because it still counts as an opponent. Results and settings are left alone, so this is
+    /// allowed on a locked tournament.
+    pub(crate) fn order_by_final_standings(&mut self) {
+        let ranked: Vec<_> = self.sorted_players().iter().map(|p| p.uuid).collect();
+        let house = self.house_player.and_then(|uuid| self.players.remove(uuid));
+        self.players.retire(house);
+        self.players.reorder(&ranked);
+    }
 
-        Tournament {
-            players,
-            rounds,
-            current_round: 0,
-            pairings: HashMap::with_capacity(num_players / 2),
-            needs_bye,
-            rng: thread_rng(),
+    /// All players in ranking order, without reordering the tournament's players. Players tied on
+    /// everything are ordered by the `final_tiebreak`, or keep their current order if the random
+    /// draw hasn't been made yet. The house player isn't ranked.
+    pub(crate) fn sorted_players(&self) -> Vec<&Player> {
+        let mut players: Vec<&Player> = self
+            .players
+            .iter()
+            .filter(|p| !self.is_house_player(p.uuid))
+            .collect();
+
+        // The sort is stable, so ordering by the final tiebreak first makes it the last resort
+        let by = |players: &mut Vec<&Player>, order: &[uuid::Uuid]| {
+            players.sort_by_cached_key(|p| {
+                order
+                    .iter()
+                    .position(|&u| u == p.uuid)
+                    .unwrap_or(order.len())
+            })
+        };
+        match (&self.final_tiebreak, self.tiebreak_seed) {
+            (FinalTiebreak::Random, None) => {}
+            (FinalTiebreak::Random, Some(seed)) => {
+                let mut lots = self.player_numbers.clone();
+                lots.shuffle(&mut StdRng::seed_from_u64(seed));
+                by(&mut players, &lots);
+            }
+            (FinalTiebreak::RegistrationOrder, _) => by(&mut players, &self.player_numbers),
+            (FinalTiebreak::Seeds(seeds), _) => {
+                by(&mut players, &self.player_numbers);
+                by(&mut players, seeds);
+            }
         }
-    }
 
-    /// Grants a player a bye if the number of player is odd, otherwise returns `None`. Ensures
-    /// that a player is granted at most one bye during a tournament. Removes the player who got
-    /// the bye from the player list and returns them so they can be re-entered into the player
-    /// list after pairings are complete.
-    fn grant_bye(&mut self) -> Option<Rc<RefCell<Player>>> {
-        if self.needs_bye {
-            self.players.shuffle(&mut self.rng);
+        sort_by_tiebreakers(&mut players, &self.players, &self.tiebreakers);
+        players
+    }
 
-            // Get all players who have not yet received a bye
-            let iter = self.players.iter().cloned().filter(|x| !x.borrow().has_bye);
+    /// Returns the seed of every round played so far, as recorded in the audit log
+    pub fn round_seeds(&self) -> Vec<(u32, u64)> {
+        self.audit_log
+            .iter()
+            .filter_map(|entry| match entry.event {
+                audit::AuditEvent::RoundSeed { seed } => Some((entry.round, seed)),
+                _ => None,
+            })
+            .collect()
+    }
 
-            // Get the player with the lowest match points among those players
-            let bye = iter.min_by_key(|x| x.borrow().match_points);
+    /// Checks whether all rounds have been played, i.e. `next_round()` has returned `None`, or
+    /// the tournament was ended early with `finish()`
+    pub fn is_finished(&self) -> bool {
+        self.current_round > self.rounds
+    }
 
-            if let Some(bye) = bye {
-                let mut i = 0;
-                while i < self.players.len() {
-                    if self.players[i] == bye {
-                        self.players[i].borrow_mut().bye();
-                        return Some(self.players.remove(i));
-                    }
-                    i += 1;
-                }
-            }
+    /// Checks whether the tournament can still be changed. Once it is finished or if it was
+    /// loaded with `load_readonly()`, its players, settings, and results are final, and every
+    /// method that would change them fails with the reason returned here.
+    pub fn check_unlocked(&self) -> Result<(), LockError> {
+        if self.read_only {
+            return Err(LockError::ReadOnly);
         }
-
-        None
+        if self.is_finished() {
+            return Err(LockError::Finished);
+        }
+        Ok(())
     }
+}
 
-    /// Advances the tournament by one round. If there are still rounds left to play, construct new
-    /// player pairings based on match points and return them. If there is an uneven number of
-    /// player, the lowest-ranked player who has not yet received a bye receives a bye.
-    pub fn next_round(&mut self) -> Option<Vec<(uuid::Uuid, String, String)>> {
-        self.current_round += 1;
-        if self.current_round > self.rounds {
-            return None;
-        }
+/// Most decimal places tiebreaker values are shown with. Larger settings are capped to it.
+pub const MAX_TIEBREAK_DECIMALS: u32 = 10;
 
-        let bye = self.grant_bye();
+/// Largest number of partial pairings `search_pairs()` looks at before settling for the best
+/// pairing found so far
+const MAX_PAIRING_SEARCH: u32 = 100_000;
 
-        let mut player_queue;
+/// Orders players by rating, highest first, with unrated players last. The sort is stable, so
+/// players with equal ratings keep their relative order.
+fn sort_by_rating(players: &mut [&Player]) {
+    players.sort_by(|a, b| match (a.rating, b.rating) {
+        (Some(a), Some(b)) => b.total_cmp(&a),
+        (a, b) => b.is_some().cmp(&a.is_some()),
+    });
+}
 
-        let mut ret: Vec<(uuid::Uuid, String, String)> =
-            Vec::with_capacity(self.pairings.capacity());
+/// Pairs each score group of the queued players top half against bottom half, like seeded
+/// Swiss events do: the first seed of the group with the first seed of the bottom half, unless
+/// they already played each other or are from the same club, in which case the next seed of the
+/// bottom half is tried. The lowest player of an uneven group floats down to the next group. The
+/// queue must be in ranking order.
+fn seeded_pairs<'a>(queue: &[&'a Player]) -> Vec<oracle::PlayerPair<'a>> {
+    let mut rest = queue.to_vec();
+    let mut pairs = Vec::with_capacity(queue.len() / 2);
+
+    while rest.len() >= 2 {
+        // The group of the second player, so that a player who floated down joins it
+        let points = rest[1].match_points;
+        let len = 1 + rest[1..]
+            .iter()
+            .take_while(|p| p.match_points == points)
+            .count();
+        let mut bottom: Vec<_> = rest.drain(..len - len % 2).collect();
+        let top: Vec<_> = bottom.drain(..len / 2).collect();
+
+        for home in top {
+            let i = closest_opponent(home, &bottom);
+            let away = bottom.remove(i);
+            pairs.push((home, away));
+        }
+    }
 
-        let mut repeat = true;
+    pairs
+}
 
-        while repeat {
-            player_queue = self.players.to_vec();
+/// Pairs the queued players from the top, each with the closest ranked player they haven't played
+/// yet and who isn't from their club, or with the closest ranked player they haven't played, or
+/// with the closest ranked player if they already played everyone left. The queue must be in
+/// ranking order. Every player ends up in exactly one pair, except the last player of an uneven
+/// queue.
+fn greedy_pairs<'a>(queue: &[&'a Player]) -> Vec<oracle::PlayerPair<'a>> {
+    let mut queue = queue.to_vec();
+    let mut pairs = Vec::with_capacity(queue.len() / 2);
+
+    while queue.len() >= 2 {
+        let home = queue.remove(0);
+        let i = closest_opponent(home, &queue);
+        let away = queue.remove(i);
+        pairs.push((home, away));
+    }
 
-            player_queue.shuffle(&mut self.rng);
-            player_queue.sort_by(|a, b| a.borrow().match_points.cmp(&b.borrow().match_points));
+    pairs
+}
 
-            self.pairings.clear();
-            ret.clear();
+/// Returns the index of the first candidate the player may be paired against who they haven't
+/// played yet and who isn't from their club, or failing that the first they haven't played, or
+/// the first they may be paired against at all. Players the player must avoid are only chosen if
+/// there is nobody else.
+fn closest_opponent(home: &Player, candidates: &[&Player]) -> usize {
+    let allowed = |away: &&Player| !home.avoids(away);
+    let new_opponent = |away: &&Player| allowed(away) && !home.opponents.contains(&away.uuid);
+    candidates
+        .iter()
+        .position(|away| new_opponent(away) && !home.same_club(away))
+        .or_else(|| candidates.iter().position(new_opponent))
+        .or_else(|| candidates.iter().position(allowed))
+        .unwrap_or(0)
+}
 
-            while let Some(home) = player_queue.pop() {
-                if player_queue.len() == 0 {
-                    break;
-                }
+/// Pairings of players who must avoid each other, rematches, and pairings of players from the
+/// same club, which pairing avoids in this order
+type Conflicts = (u32, u32, u32);
+
+/// Searches for a pairing of the queued players with fewer conflicts than the best one so far:
+/// fewer players paired against someone they must avoid, or as many but fewer rematches, or as
+/// many of both but fewer players paired against their own club, pairing each player with the
+/// closest ranked player possible. The queue must be in ranking order. Stops as soon as a pairing
+/// without any conflicts is found or the budget runs out.
+fn search_pairs<'a>(
+    queue: &mut Vec<&'a Player>,
+    pairs: &mut Vec<oracle::PlayerPair<'a>>,
+    conflicts: Conflicts,
+    best: &mut (Conflicts, Option<Vec<oracle::PlayerPair<'a>>>),
+    budget: &mut u32,
+) {
+    if *budget == 0 || conflicts >= best.0 {
+        return;
+    }
+    *budget -= 1;
 
-                let mut away;
-                let mut i = player_queue.len() - 1;
-                loop {
-                    away = Rc::clone(&player_queue[i]);
-                    if !home.borrow().opponents.contains(&away) {
-                        player_queue.remove(i);
-                        break;
-                    }
+    if queue.len() < 2 {
+        *best = (conflicts, Some(pairs.clone()));
+        return;
+    }
 
-                    if i == 0 {
-                        break;
-                    }
+    let home = queue.remove(0);
+    for i in 0..queue.len() {
+        let away = queue.remove(i);
+        let avoided = home.avoids(away);
+        let rematch = home.opponents.contains(&away.uuid);
+        let same_club = home.same_club(away);
+        pairs.push((home, away));
+
+        let next = (
+            conflicts.0 + u32::from(avoided),
+            conflicts.1 + u32::from(rematch),
+            conflicts.2 + u32::from(same_club),
+        );
+        search_pairs(queue, pairs, next, best, budget);
 
-                    i -= 1;
-                }
+        pairs.pop();
+        queue.insert(i, away);
+        if best.0 == (0, 0, 0) {
+            break;
+        }
+    }
+    queue.insert(0, home);
+}
 
-                let home = Rc::clone(&home);
-                let away = Rc::clone(&away);
-                let pair = Pairing::new(home, away);
+/// Sorts players by match points and then by the given tiebreakers. The sort is stable, so players
+/// that are tied on everything keep their previous relative order. Opponents are looked up in
+/// `arena`.
+pub(crate) fn sort_by_tiebreakers(
+    players: &mut [&Player],
+    arena: &PlayerArena,
+    tiebreakers: &[Criterion],
+) {
+    // The exact tiebreakers are costly to compute, so they are computed once per player
+    let mut cache = TiebreakerCache::new(arena);
+    players.sort_by_cached_key(|p| cache.standing_key(p, tiebreakers));
+}
 
-                let uuid = pair.uuid;
-                let home_str = String::from(&pair.home.borrow().name);
-                let away_str = String::from(&pair.away.borrow().name);
+/// Match points and the tiebreakers, in the order they are applied, reversed so that players who
+/// are ranked higher have the smaller key
+type StandingKey = (Reverse<u32>, Vec<Reverse<BigRational>>);
+
+/// The player's value of a criterion as an exact fraction, which players are ranked by.
+/// Opponents are looked up in `arena`.
+pub(crate) fn criterion_value(
+    p: &Player,
+    arena: &PlayerArena,
+    criterion: Criterion,
+) -> BigRational {
+    TiebreakerCache::new(arena).value(p, criterion)
+}
 
-                self.pairings.insert(uuid, pair);
-                ret.push((uuid, home_str, away_str));
-            }
+/// Match win percentages and opponents' match win percentages by player. The opponents' opponents'
+/// match win percentage reaches two rounds of opponents deep, so computing it for every player
+/// from scratch would compute the same percentages over and over again.
+struct TiebreakerCache<'a> {
+    /// Where opponents are looked up
+    arena: &'a PlayerArena,
+    match_win: HashMap<Uuid, WinRatio>,
+    opponents_match_win: HashMap<Uuid, WinRatio>,
+}
 
-            if self.pairings.len() == self.players.len() / 2 {
-                repeat = false;
-            }
+impl<'a> TiebreakerCache<'a> {
+    fn new(arena: &'a PlayerArena) -> TiebreakerCache<'a> {
+        TiebreakerCache {
+            arena,
+            match_win: HashMap::new(),
+            opponents_match_win: HashMap::new(),
         }
+    }
 
-        ret.shuffle(&mut self.rng);
-
-        if let Some(bye) = bye {
-            self.players.push(bye);
-        }
+    /// The player's opponents that can be found in the arena
+    fn opponents(&self, p: &Player) -> Vec<&'a Player> {
+        p.opponents
+            .iter()
+            .filter_map(|&opp| self.arena.opponent(opp))
+            .collect()
+    }
 
-        Some(ret)
+    fn match_win_ratio(&mut self, p: &Player) -> WinRatio {
+        self.match_win
+            .entry(p.uuid)
+            .or_insert_with(|| p.match_win_ratio())
+            .clone()
     }
 
-    /// Record the result of a pairing, specified by its UUID. Basically just a wrapper around
-    /// `Pairing::end_match()`, extended by the `NotFound` error type.
-    pub fn end_match(
-        &self,
-        uuid: uuid::Uuid,
-        home_score: u8,
-        away_score: u8,
-        drawn: u8,
-    ) -> Result<(), PairingResultError> {
-        if let Some(pair) = self.pairings.get(&uuid) {
-            return match pair.end_match(home_score, away_score, drawn) {
-                Ok(_) => Ok(()),
-                Err(e) => Err(PairingResultError::OutOfRange(e.outside_value)),
-            };
+    fn opponents_match_win_ratio(&mut self, p: &Player) -> WinRatio {
+        if let Some(ratio) = self.opponents_match_win.get(&p.uuid) {
+            return ratio.clone();
         }
+        let ratios: Vec<WinRatio> = self
+            .opponents(p)
+            .into_iter()
+            .map(|opp| self.match_win_ratio(opp))
+            .collect();
+        let ratio = average(ratios.into_iter(), p.percentage_floor);
+        self.opponents_match_win.insert(p.uuid, ratio.clone());
+        ratio
+    }
 
-        Err(PairingResultError::NotFound(uuid))
+    fn opponents_opponents_match_win_ratio(&mut self, p: &Player) -> WinRatio {
+        let ratios: Vec<WinRatio> = self
+            .opponents(p)
+            .into_iter()
+            .map(|opp| self.opponents_match_win_ratio(opp))
+            .collect();
+        average(ratios.into_iter(), p.percentage_floor)
     }
 
-    /// Rank all players using all tiebreakers. This only needs to be called if the ranking
-    /// actually needs to be displayed (i.e. at the end of the tournament) or if results between
-    /// rounds are desired; it is not necessary when progressing rounds as `next_round()`
-    /// automatically performs a simpler ranking using just match points before creating new
-    /// pairings.
-    pub fn ranking(&mut self) -> Vec<Rc<RefCell<Player>>> {
-        // Start with a shuffle so that any previous order does not affect the new order in case of
-        // full ties
-        self.players.shuffle(&mut self.rng);
-
-        // Vec::sort_by() is stable, so we start with the last tiebreaker and sort upwards from
-        // there.
-
-        self.players.sort_by(|a, b| {
-            b.borrow()
-                .opponents_game_win_percentage()
-                .partial_cmp(&a.borrow().opponents_game_win_percentage())
-                .unwrap_or(Ordering::Equal)
-        });
-        self.players.sort_by(|a, b| {
-            b.borrow()
-                .game_win_percentage()
-                .partial_cmp(&a.borrow().game_win_percentage())
-                .unwrap_or(Ordering::Equal)
-        });
-        self.players.sort_by(|a, b| {
-            b.borrow()
-                .opponents_match_win_percentage()
-                .partial_cmp(&a.borrow().opponents_match_win_percentage())
-                .unwrap_or(Ordering::Equal)
-        });
-        self.players.sort_by(|a, b| {
-            b.borrow()
-                .match_points
-                .partial_cmp(&a.borrow().match_points)
-                .unwrap_or(Ordering::Equal)
-        });
+    fn value(&mut self, p: &Player, criterion: Criterion) -> BigRational {
+        match criterion {
+            Criterion::MatchPoints => BigRational::from_integer(p.match_points.into()),
+            Criterion::OpponentsMatchWinPercentage => self.opponents_match_win_ratio(p),
+            Criterion::GameWinPercentage => p.game_win_ratio(),
+            Criterion::OpponentsGameWinPercentage => p.opponents_game_win_ratio(self.arena),
+            Criterion::OpponentsOpponentsMatchWinPercentage => {
+                self.opponents_opponents_match_win_ratio(p)
+            }
+            Criterion::GameDifferential => BigRational::from_integer(p.game_differential().into()),
+            Criterion::CumulativeScore => BigRational::from_integer(p.cumulative_points.into()),
+            Criterion::Wins => BigRational::from_integer(p.matches_won.into()),
+        }
+    }
 
-        self.players.clone()
+    fn standing_key(&mut self, p: &Player, tiebreakers: &[Criterion]) -> StandingKey {
+        (
+            Reverse(p.match_points),
+            tiebreakers
+                .iter()
+                .map(|c| Reverse(self.value(p, *c)))
+                .collect(),
+        )
     }
 }
 
+/// Compares two players by match points and then by the given tiebreakers, in the order they are
+/// applied. Players that are ranked higher compare as `Ordering::Less`. Opponents are looked up in
+/// `arena`.
+pub(crate) fn compare_standing(
+    a: &Player,
+    b: &Player,
+    arena: &PlayerArena,
+    tiebreakers: &[Criterion],
+) -> Ordering {
+    let mut cache = TiebreakerCache::new(arena);
+    cache
+        .standing_key(a, tiebreakers)
+        .cmp(&cache.standing_key(b, tiebreakers))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::collection;
+    use proptest::prelude::{any, prop_assert, prop_assert_eq, prop_assert_ne, proptest, Strategy};
     use regex::Regex;
 
+    /// Pairs the players at two positions of the arena
+    fn pair_up(players: &mut PlayerArena, home: usize, away: usize) -> Pairing {
+        let (home, away) = (players[home].uuid, players[away].uuid);
+        let (home, away) = players.pair_mut(home, away).unwrap();
+        Pairing::new(home, away)
+    }
+
+    /// Pairs two new players, who are the only players of the returned arena
+    fn paired(home: &str, away: &str) -> (PlayerArena, Pairing) {
+        let mut players: PlayerArena = vec![Player::new(home), Player::new(away)].into();
+        let pair = pair_up(&mut players, 0, 1);
+        (players, pair)
+    }
+
     #[test]
     /// Player goes 5-2-1
     fn mwp_521() {
@@ -579,31 +2753,32 @@ mod tests {
     #[test]
     /// Player goes 2-0, 2-1, 1-2, 2-0
     fn gwp_21_10() {
-        let player = Rc::new(RefCell::new(Player::new("21-10")));
-
-        let o1 = Rc::new(RefCell::new(Player::new("Opponent 1")));
-        let o2 = Rc::new(RefCell::new(Player::new("Opponent 2")));
-        let o3 = Rc::new(RefCell::new(Player::new("Opponent 3")));
-        let o4 = Rc::new(RefCell::new(Player::new("Opponent 4")));
+        let mut players: PlayerArena = vec![
+            Player::new("21-10"),
+            Player::new("Opponent 1"),
+            Player::new("Opponent 2"),
+            Player::new("Opponent 3"),
+            Player::new("Opponent 4"),
+        ]
+        .into();
 
         // 2-0 (6 points)
-        let pair = Pairing::new(Rc::clone(&player), o1);
-        assert!(pair.end_match(2, 0, 0).is_ok());
+        let pair = pair_up(&mut players, 0, 1);
+        assert!(pair.end_match(&mut players, 2, 0, 0).is_ok());
 
         // 2-1 (6 points)
-        let pair = Pairing::new(Rc::clone(&player), o2);
-        assert!(pair.end_match(2, 1, 0).is_ok());
+        let pair = pair_up(&mut players, 0, 2);
+        assert!(pair.end_match(&mut players, 2, 1, 0).is_ok());
 
         // 1-2 (3 points)
-        let pair = Pairing::new(Rc::clone(&player), o3);
-        assert!(pair.end_match(1, 2, 0).is_ok());
+        let pair = pair_up(&mut players, 0, 3);
+        assert!(pair.end_match(&mut players, 1, 2, 0).is_ok());
 
         // 2-0 (6 points)
-        let pair = Pairing::new(Rc::clone(&player), o4);
-        assert!(pair.end_match(2, 0, 0).is_ok());
-
-        let player = player.borrow();
+        let pair = pair_up(&mut players, 0, 4);
+        assert!(pair.end_match(&mut players, 2, 0, 0).is_ok());
 
+        let player = &players[0];
         assert_eq!(player.opponents.len(), 4);
         assert_eq!(player.game_points, 21);
         assert_eq!(player.games_played, 10);
@@ -613,31 +2788,32 @@ mod tests {
     #[test]
     /// Player goes 1-2, 1-2, 0-2, 1-2
     fn gwp_9_11() {
-        let player = Rc::new(RefCell::new(Player::new("9-11")));
-
-        let o1 = Rc::new(RefCell::new(Player::new("Opponent 1")));
-        let o2 = Rc::new(RefCell::new(Player::new("Opponent 2")));
-        let o3 = Rc::new(RefCell::new(Player::new("Opponent 3")));
-        let o4 = Rc::new(RefCell::new(Player::new("Opponent 4")));
+        let mut players: PlayerArena = vec![
+            Player::new("9-11"),
+            Player::new("Opponent 1"),
+            Player::new("Opponent 2"),
+            Player::new("Opponent 3"),
+            Player::new("Opponent 4"),
+        ]
+        .into();
 
         // 1-2 (3 points)
-        let pair = Pairing::new(Rc::clone(&player), o1);
-        assert!(pair.end_match(1, 2, 0).is_ok());
+        let pair = pair_up(&mut players, 0, 1);
+        assert!(pair.end_match(&mut players, 1, 2, 0).is_ok());
 
         // 1-2 (3 points)
-        let pair = Pairing::new(Rc::clone(&player), o2);
-        assert!(pair.end_match(1, 2, 0).is_ok());
+        let pair = pair_up(&mut players, 0, 2);
+        assert!(pair.end_match(&mut players, 1, 2, 0).is_ok());
 
         // 0-2 (0 points)
-        let pair = Pairing::new(Rc::clone(&player), o3);
-        assert!(pair.end_match(0, 2, 0).is_ok());
+        let pair = pair_up(&mut players, 0, 3);
+        assert!(pair.end_match(&mut players, 0, 2, 0).is_ok());
 
         // 1-2 (3 points)
-        let pair = Pairing::new(Rc::clone(&player), o4);
-        assert!(pair.end_match(1, 2, 0).is_ok());
-
-        let player = player.borrow();
+        let pair = pair_up(&mut players, 0, 4);
+        assert!(pair.end_match(&mut players, 1, 2, 0).is_ok());
 
+        let player = &players[0];
         assert_eq!(player.opponents.len(), 4);
         assert_eq!(player.game_points, 9);
         assert_eq!(player.games_played, 11);
@@ -649,6 +2825,7 @@ mod tests {
     /// 4-3-1, and 6-1-1
     fn omwp_normal() {
         let mut player = Player::new("Normal");
+        let mut opponents = PlayerArena::new();
 
         // Opponent 1 goes 4-4-0
         let mut o1 = Player::new("Opponent 1");
@@ -657,7 +2834,8 @@ mod tests {
             o1.lose_match();
         }
         assert_eq!(o1.match_win_percentage(), 0.5);
-        player.opponents.push(Rc::new(RefCell::new(o1)));
+        player.opponents.push(o1.uuid);
+        opponents.push(o1);
 
         // Opponent 2 goes 7-1-0
         let mut o2 = Player::new("Opponent 2");
@@ -666,7 +2844,8 @@ mod tests {
         }
         o2.lose_match();
         assert_eq!(o2.match_win_percentage(), 21.0 / 24.0);
-        player.opponents.push(Rc::new(RefCell::new(o2)));
+        player.opponents.push(o2.uuid);
+        opponents.push(o2);
 
         // Opponent 3 goes 1-3-1
         let mut o3 = Player::new("Opponent 3");
@@ -676,7 +2855,8 @@ mod tests {
         }
         o3.draw_match();
         assert_eq!(o3.match_win_percentage(), 1.0 / 3.0);
-        player.opponents.push(Rc::new(RefCell::new(o3)));
+        player.opponents.push(o3.uuid);
+        opponents.push(o3);
 
         // Opponent 4 goes 3-3-1
         let mut o4 = Player::new("Opponent 4");
@@ -686,7 +2866,8 @@ mod tests {
         }
         o4.draw_match();
         assert_eq!(o4.match_win_percentage(), 10.0 / 21.0);
-        player.opponents.push(Rc::new(RefCell::new(o4)));
+        player.opponents.push(o4.uuid);
+        opponents.push(o4);
 
         // Opponent 5 goes 6-2-0
         let mut o5 = Player::new("Opponent 5");
@@ -696,7 +2877,8 @@ mod tests {
         o5.lose_match();
         o5.lose_match();
         assert_eq!(o5.match_win_percentage(), 18.0 / 24.0);
-        player.opponents.push(Rc::new(RefCell::new(o5)));
+        player.opponents.push(o5.uuid);
+        opponents.push(o5);
 
         // Opponent 6 goes 5-2-1
         let mut o6 = Player::new("Opponent 6");
@@ -707,7 +2889,8 @@ mod tests {
         o6.lose_match();
         o6.draw_match();
         assert_eq!(o6.match_win_percentage(), 16.0 / 24.0);
-        player.opponents.push(Rc::new(RefCell::new(o6)));
+        player.opponents.push(o6.uuid);
+        opponents.push(o6);
 
         // Opponent 7 goes 4-3-1
         let mut o7 = Player::new("Opponent 7");
@@ -719,7 +2902,8 @@ mod tests {
         }
         o7.draw_match();
         assert_eq!(o7.match_win_percentage(), 13.0 / 24.0);
-        player.opponents.push(Rc::new(RefCell::new(o7)));
+        player.opponents.push(o7.uuid);
+        opponents.push(o7);
 
         // Opponent 8 goes 6-1-1
         let mut o8 = Player::new("Opponent 8");
@@ -729,7 +2913,8 @@ mod tests {
         o8.lose_match();
         o8.draw_match();
         assert_eq!(o8.match_win_percentage(), 19.0 / 24.0);
-        player.opponents.push(Rc::new(RefCell::new(o8)));
+        player.opponents.push(o8.uuid);
+        opponents.push(o8);
 
         let expected_omwp = (12.0 / 24.0
             + 21.0 / 24.0
@@ -741,7 +2926,10 @@ mod tests {
             + 19.0 / 24.0)
             / 8.0;
 
-        assert_eq!(player.opponents_match_win_percentage(), expected_omwp);
+        assert_eq!(
+            player.opponents_match_win_percentage(&opponents),
+            expected_omwp
+        );
     }
 
     #[test]
@@ -749,6 +2937,7 @@ mod tests {
     /// 4-3-1, and 6-1-1
     fn omwp_with_bye() {
         let mut player = Player::new("Bye");
+        let mut opponents = PlayerArena::new();
 
         // Player gets bye in round 1
         player.bye();
@@ -760,7 +2949,8 @@ mod tests {
         }
         o2.lose_match();
         assert_eq!(o2.match_win_percentage(), 21.0 / 24.0);
-        player.opponents.push(Rc::new(RefCell::new(o2)));
+        player.opponents.push(o2.uuid);
+        opponents.push(o2);
 
         // Opponent 3 goes 1-3-1
         let mut o3 = Player::new("Opponent 3");
@@ -770,7 +2960,8 @@ mod tests {
         }
         o3.draw_match();
         assert_eq!(o3.match_win_percentage(), 1.0 / 3.0);
-        player.opponents.push(Rc::new(RefCell::new(o3)));
+        player.opponents.push(o3.uuid);
+        opponents.push(o3);
 
         // Opponent 4 goes 3-3-1
         let mut o4 = Player::new("Opponent 4");
@@ -780,7 +2971,8 @@ mod tests {
         }
         o4.draw_match();
         assert_eq!(o4.match_win_percentage(), 10.0 / 21.0);
-        player.opponents.push(Rc::new(RefCell::new(o4)));
+        player.opponents.push(o4.uuid);
+        opponents.push(o4);
 
         // Opponent 5 goes 6-2-0
         let mut o5 = Player::new("Opponent 5");
@@ -790,7 +2982,8 @@ mod tests {
         o5.lose_match();
         o5.lose_match();
         assert_eq!(o5.match_win_percentage(), 18.0 / 24.0);
-        player.opponents.push(Rc::new(RefCell::new(o5)));
+        player.opponents.push(o5.uuid);
+        opponents.push(o5);
 
         // Opponent 6 goes 5-2-1
         let mut o6 = Player::new("Opponent 6");
@@ -801,7 +2994,8 @@ mod tests {
         o6.lose_match();
         o6.draw_match();
         assert_eq!(o6.match_win_percentage(), 16.0 / 24.0);
-        player.opponents.push(Rc::new(RefCell::new(o6)));
+        player.opponents.push(o6.uuid);
+        opponents.push(o6);
 
         // Opponent 7 goes 4-3-1
         let mut o7 = Player::new("Opponent 7");
@@ -813,7 +3007,8 @@ mod tests {
         }
         o7.draw_match();
         assert_eq!(o7.match_win_percentage(), 13.0 / 24.0);
-        player.opponents.push(Rc::new(RefCell::new(o7)));
+        player.opponents.push(o7.uuid);
+        opponents.push(o7);
 
         // Opponent 8 goes 6-1-1
         let mut o8 = Player::new("Opponent 8");
@@ -823,7 +3018,8 @@ mod tests {
         o8.lose_match();
         o8.draw_match();
         assert_eq!(o8.match_win_percentage(), 19.0 / 24.0);
-        player.opponents.push(Rc::new(RefCell::new(o8)));
+        player.opponents.push(o8.uuid);
+        opponents.push(o8);
 
         let expected_omwp = (21.0 / 24.0
             + 1.0 / 3.0
@@ -834,24 +3030,22 @@ mod tests {
             + 19.0 / 24.0)
             / 7.0;
 
-        assert_eq!(player.opponents_match_win_percentage(), expected_omwp);
+        assert_eq!(
+            player.opponents_match_win_percentage(&opponents),
+            expected_omwp
+        );
     }
 
     #[test]
     /// Alice 2-0 Bob
     fn pairing_games_20() {
-        let alice = Rc::new(RefCell::new(Player::new("Alice")));
-        let bob = Rc::new(RefCell::new(Player::new("Bob")));
-        let alice_clone = Rc::clone(&alice);
-        let bob_clone = Rc::clone(&bob);
+        let (mut players, pair) = paired("Alice", "Bob");
 
-        let pair = Pairing::new(alice, bob);
+        pair.win_game(&mut players, PlayerSide::Home);
+        pair.win_game(&mut players, PlayerSide::Home);
 
-        pair.win_game(PlayerSide::Home);
-        pair.win_game(PlayerSide::Home);
-
-        let alice = alice_clone.borrow();
-        let bob = bob_clone.borrow();
+        let alice = &players[0];
+        let bob = &players[1];
 
         assert_eq!(alice.game_points, 6);
         assert_eq!(bob.game_points, 0);
@@ -873,19 +3067,14 @@ mod tests {
     #[test]
     /// Charlie 2-1 Dan
     fn pairing_games_21() {
-        let charlie = Rc::new(RefCell::new(Player::new("Charlie")));
-        let dan = Rc::new(RefCell::new(Player::new("Dan")));
-        let charlie_clone = Rc::clone(&charlie);
-        let dan_clone = Rc::clone(&dan);
-
-        let pair = Pairing::new(charlie, dan);
+        let (mut players, pair) = paired("Charlie", "Dan");
 
-        pair.win_game(PlayerSide::Home);
-        pair.win_game(PlayerSide::Away);
-        pair.win_game(PlayerSide::Home);
+        pair.win_game(&mut players, PlayerSide::Home);
+        pair.win_game(&mut players, PlayerSide::Away);
+        pair.win_game(&mut players, PlayerSide::Home);
 
-        let charlie = charlie_clone.borrow();
-        let dan = dan_clone.borrow();
+        let charlie = &players[0];
+        let dan = &players[1];
 
         assert_eq!(charlie.game_points, 6);
         assert_eq!(dan.game_points, 3);
@@ -905,19 +3094,14 @@ mod tests {
     #[test]
     /// Eve 2-0-1 Frank
     fn pairing_games_201() {
-        let eve = Rc::new(RefCell::new(Player::new("Eve")));
-        let frank = Rc::new(RefCell::new(Player::new("Frank")));
-        let eve_clone = Rc::clone(&eve);
-        let frank_clone = Rc::clone(&frank);
+        let (mut players, pair) = paired("Eve", "Frank");
 
-        let pair = Pairing::new(eve, frank);
+        pair.win_game(&mut players, PlayerSide::Home);
+        pair.draw_game(&mut players);
+        pair.win_game(&mut players, PlayerSide::Home);
 
-        pair.win_game(PlayerSide::Home);
-        pair.draw_game();
-        pair.win_game(PlayerSide::Home);
-
-        let eve = eve_clone.borrow();
-        let frank = frank_clone.borrow();
+        let eve = &players[0];
+        let frank = &players[1];
 
         assert_eq!(eve.game_points, 7);
         assert_eq!(frank.game_points, 1);
@@ -937,18 +3121,13 @@ mod tests {
     #[test]
     /// Alice 2-0 Bob
     fn pairing_match_20() {
-        let alice = Rc::new(RefCell::new(Player::new("Alice")));
-        let bob = Rc::new(RefCell::new(Player::new("Bob")));
-        let alice_clone = Rc::clone(&alice);
-        let bob_clone = Rc::clone(&bob);
-
-        let pair = Pairing::new(alice, bob);
+        let (mut players, pair) = paired("Alice", "Bob");
 
-        let result = pair.end_match(2, 0, 0);
+        let result = pair.end_match(&mut players, 2, 0, 0);
         assert!(result.is_ok());
 
-        let alice = alice_clone.borrow();
-        let bob = bob_clone.borrow();
+        let alice = &players[0];
+        let bob = &players[1];
 
         assert_eq!(alice.game_points, 6);
         assert_eq!(bob.game_points, 0);
@@ -969,18 +3148,13 @@ mod tests {
     #[test]
     /// Alice 1-2 Bob
     fn pairing_match_12() {
-        let alice = Rc::new(RefCell::new(Player::new("Alice")));
-        let bob = Rc::new(RefCell::new(Player::new("Bob")));
-        let alice_clone = Rc::clone(&alice);
-        let bob_clone = Rc::clone(&bob);
+        let (mut players, pair) = paired("Alice", "Bob");
 
-        let pair = Pairing::new(alice, bob);
-
-        let result = pair.end_match(1, 2, 0);
+        let result = pair.end_match(&mut players, 1, 2, 0);
         assert!(result.is_ok());
 
-        let alice = alice_clone.borrow();
-        let bob = bob_clone.borrow();
+        let alice = &players[0];
+        let bob = &players[1];
 
         assert_eq!(alice.game_points, 3);
         assert_eq!(bob.game_points, 6);
@@ -1001,18 +3175,13 @@ mod tests {
     #[test]
     /// Alice 1-1-1 Bob
     fn pairing_match_111() {
-        let alice = Rc::new(RefCell::new(Player::new("Alice")));
-        let bob = Rc::new(RefCell::new(Player::new("Bob")));
-        let alice_clone = Rc::clone(&alice);
-        let bob_clone = Rc::clone(&bob);
-
-        let pair = Pairing::new(alice, bob);
+        let (mut players, pair) = paired("Alice", "Bob");
 
-        let result = pair.end_match(1, 1, 1);
+        let result = pair.end_match(&mut players, 1, 1, 1);
         assert!(result.is_ok());
 
-        let alice = alice_clone.borrow();
-        let bob = bob_clone.borrow();
+        let alice = &players[0];
+        let bob = &players[1];
 
         assert_eq!(alice.game_points, 4);
         assert_eq!(bob.game_points, 4);
@@ -1033,18 +3202,13 @@ mod tests {
     #[test]
     /// Alice 0-0-3 Bob
     fn pairing_match_003() {
-        let alice = Rc::new(RefCell::new(Player::new("Alice")));
-        let bob = Rc::new(RefCell::new(Player::new("Bob")));
-        let alice_clone = Rc::clone(&alice);
-        let bob_clone = Rc::clone(&bob);
+        let (mut players, pair) = paired("Alice", "Bob");
 
-        let pair = Pairing::new(alice, bob);
-
-        let result = pair.end_match(0, 0, 3);
+        let result = pair.end_match(&mut players, 0, 0, 3);
         assert!(result.is_ok());
 
-        let alice = alice_clone.borrow();
-        let bob = bob_clone.borrow();
+        let alice = &players[0];
+        let bob = &players[1];
 
         assert_eq!(alice.game_points, 3);
         assert_eq!(bob.game_points, 3);
@@ -1065,18 +3229,13 @@ mod tests {
     #[test]
     /// Alice 4-0 Bob
     fn pairing_match_40() {
-        let alice = Rc::new(RefCell::new(Player::new("Alice")));
-        let bob = Rc::new(RefCell::new(Player::new("Bob")));
-        let alice_clone = Rc::clone(&alice);
-        let bob_clone = Rc::clone(&bob);
-
-        let pair = Pairing::new(alice, bob);
+        let (mut players, pair) = paired("Alice", "Bob");
 
-        let result = pair.end_match(4, 0, 0);
+        let result = pair.end_match(&mut players, 4, 0, 0);
         assert!(result.is_err());
 
-        let alice = alice_clone.borrow();
-        let bob = bob_clone.borrow();
+        let alice = &players[0];
+        let bob = &players[1];
 
         assert_eq!(alice.game_points, 0);
         assert_eq!(bob.game_points, 0);
@@ -1097,18 +3256,13 @@ mod tests {
     #[test]
     /// Alice 2-1-2 Bob
     fn pairing_match_212() {
-        let alice = Rc::new(RefCell::new(Player::new("Alice")));
-        let bob = Rc::new(RefCell::new(Player::new("Bob")));
-        let alice_clone = Rc::clone(&alice);
-        let bob_clone = Rc::clone(&bob);
+        let (mut players, pair) = paired("Alice", "Bob");
 
-        let pair = Pairing::new(alice, bob);
-
-        let result = pair.end_match(2, 1, 2);
+        let result = pair.end_match(&mut players, 2, 1, 2);
         assert!(result.is_err());
 
-        let alice = alice_clone.borrow();
-        let bob = bob_clone.borrow();
+        let alice = &players[0];
+        let bob = &players[1];
 
         assert_eq!(alice.game_points, 0);
         assert_eq!(bob.game_points, 0);
@@ -1129,18 +3283,13 @@ mod tests {
     #[test]
     /// Alice 0-0-4 Bob
     fn pairing_match_004() {
-        let alice = Rc::new(RefCell::new(Player::new("Alice")));
-        let bob = Rc::new(RefCell::new(Player::new("Bob")));
-        let alice_clone = Rc::clone(&alice);
-        let bob_clone = Rc::clone(&bob);
-
-        let pair = Pairing::new(alice, bob);
+        let (mut players, pair) = paired("Alice", "Bob");
 
-        let result = pair.end_match(0, 0, 4);
+        let result = pair.end_match(&mut players, 0, 0, 4);
         assert!(result.is_err());
 
-        let alice = alice_clone.borrow();
-        let bob = bob_clone.borrow();
+        let alice = &players[0];
+        let bob = &players[1];
 
         assert_eq!(alice.game_points, 0);
         assert_eq!(bob.game_points, 0);
@@ -1161,15 +3310,15 @@ mod tests {
     #[test]
     fn tournament_2_players() {
         let mut players = Vec::with_capacity(2);
-        let p1 = Rc::new(RefCell::new(Player::new("Player 1")));
-        let p2 = Rc::new(RefCell::new(Player::new("Player 2")));
+        let p1 = Player::new("Player 1");
+        let p2 = Player::new("Player 2");
         players.push(p1);
         players.push(p2);
 
-        let mut tourn = Tournament::new(players);
+        let mut tourn = Tournament::with_players(players).unwrap();
         assert_eq!(tourn.rounds, 1);
         let pairings = tourn.next_round();
-        let pair = &pairings.unwrap()[0];
+        let pair = &pairings.unwrap().unwrap()[0];
         let uuid = pair.0;
         let home = String::from(&pair.1);
         let away = String::from(&pair.2);
@@ -1181,25 +3330,25 @@ mod tests {
 
         assert!(tourn.end_match(uuid, 2, 1, 0).is_ok());
 
-        assert_eq!(tourn.next_round(), None);
+        assert_eq!(tourn.next_round(), Ok(None));
 
         let players = tourn.ranking();
 
         let mut bye_count = 0;
         for p in &players {
-            if p.borrow().has_bye {
+            if p.has_bye {
                 bye_count += 1;
             }
         }
         assert_eq!(bye_count, 0);
 
-        let winner = players[0].borrow();
+        let winner = players[0];
         assert_eq!(winner.matches_played, 1);
         assert_eq!(winner.match_points, 3);
         assert_eq!(winner.games_played, 3);
         assert_eq!(winner.game_points, 6);
 
-        let loser = players[1].borrow();
+        let loser = players[1];
         assert_eq!(loser.matches_played, 1);
         assert_eq!(loser.match_points, 0);
         assert_eq!(loser.games_played, 3);
@@ -1209,19 +3358,19 @@ mod tests {
     #[test]
     fn tournament_3_players() {
         let mut players = Vec::with_capacity(2);
-        let p1 = Rc::new(RefCell::new(Player::new("Player 1")));
-        let p2 = Rc::new(RefCell::new(Player::new("Player 2")));
-        let p3 = Rc::new(RefCell::new(Player::new("Player 3")));
+        let p1 = Player::new("Player 1");
+        let p2 = Player::new("Player 2");
+        let p3 = Player::new("Player 3");
         players.push(p1);
         players.push(p2);
         players.push(p3);
 
-        let mut tourn = Tournament::new(players);
+        let mut tourn = Tournament::with_players(players).unwrap();
         assert_eq!(tourn.rounds, 2);
 
         let re = Regex::new(r"Player (\d)").unwrap();
 
-        while let Some(pairings) = tourn.next_round() {
+        while let Some(pairings) = tourn.next_round().unwrap() {
             for pair in &pairings {
                 let uuid = pair.0;
 
@@ -1268,20 +3417,20 @@ mod tests {
 
         let mut bye_count = 0;
         for p in &players {
-            if p.borrow().has_bye {
+            if p.has_bye {
                 bye_count += 1;
             }
         }
         assert_eq!(bye_count, 2);
 
-        let winner = players[0].borrow();
+        let winner = players[0];
         assert_eq!(winner.name, "Player 3");
         assert_eq!(winner.matches_played, 2);
         assert!(winner.games_played >= 5);
         assert_eq!(winner.match_points, 6);
         assert_eq!(winner.game_points, 12);
 
-        let loser = players[1].borrow();
+        let loser = players[1];
         assert!(loser.name == "Player 1" || loser.name == "Player 2");
         assert_eq!(loser.matches_played, 2);
         assert!(loser.games_played >= 5);
@@ -1293,22 +3442,22 @@ mod tests {
     fn tournament_4_players() {
         let mut players = Vec::with_capacity(4);
 
-        let p1 = Rc::new(RefCell::new(Player::new("Player 1")));
-        let p2 = Rc::new(RefCell::new(Player::new("Player 2")));
-        let p3 = Rc::new(RefCell::new(Player::new("Player 3")));
-        let p4 = Rc::new(RefCell::new(Player::new("Player 4")));
+        let p1 = Player::new("Player 1");
+        let p2 = Player::new("Player 2");
+        let p3 = Player::new("Player 3");
+        let p4 = Player::new("Player 4");
 
         players.push(p1);
         players.push(p2);
         players.push(p3);
         players.push(p4);
 
-        let mut tourn = Tournament::new(players);
+        let mut tourn = Tournament::with_players(players).unwrap();
         assert_eq!(tourn.rounds, 2);
 
         let re = Regex::new(r"Player (\d)").unwrap();
 
-        while let Some(pairings) = tourn.next_round() {
+        while let Some(pairings) = tourn.next_round().unwrap() {
             for pair in &pairings {
                 let uuid = pair.0;
 
@@ -1355,34 +3504,34 @@ mod tests {
 
         let mut bye_count = 0;
         for p in &players {
-            if p.borrow().has_bye {
+            if p.has_bye {
                 bye_count += 1;
             }
         }
         assert_eq!(bye_count, 0);
 
-        let p1 = players[0].borrow();
+        let p1 = players[0];
         assert_eq!(p1.name, "Player 4");
         assert_eq!(p1.matches_played, 2);
         assert_eq!(p1.games_played, 6);
         assert_eq!(p1.match_points, 6);
         assert_eq!(p1.game_points, 12);
 
-        let p2 = players[1].borrow();
+        let p2 = players[1];
         assert!(p2.name == "Player 3" || p2.name == "Player 2");
         assert_eq!(p2.matches_played, 2);
         assert_eq!(p2.games_played, 6);
         assert_eq!(p2.match_points, 3);
         assert_eq!(p2.game_points, 9);
 
-        let p3 = players[2].borrow();
+        let p3 = players[2];
         assert!(p3.name == "Player 3" || p3.name == "Player 2");
         assert_eq!(p3.matches_played, 2);
         assert_eq!(p3.games_played, 6);
         assert_eq!(p3.match_points, 3);
         assert_eq!(p3.game_points, 9);
 
-        let p4 = players[3].borrow();
+        let p4 = players[3];
         assert_eq!(p4.name, "Player 1");
         assert_eq!(p4.matches_played, 2);
         assert_eq!(p4.games_played, 6);
@@ -1395,16 +3544,16 @@ mod tests {
         let mut players = Vec::with_capacity(8);
 
         for i in 1..9 {
-            let p = Rc::new(RefCell::new(Player::new(format!("Player {}", i).as_str())));
+            let p = Player::new(format!("Player {}", i).as_str());
             players.push(p);
         }
 
-        let mut tourn = Tournament::new(players);
+        let mut tourn = Tournament::with_players(players).unwrap();
         assert_eq!(tourn.rounds, 3);
 
         let re = Regex::new(r"Player (\d)").unwrap();
 
-        while let Some(pairings) = tourn.next_round() {
+        while let Some(pairings) = tourn.next_round().unwrap() {
             for pair in &pairings {
                 let uuid = pair.0;
 
@@ -1449,14 +3598,14 @@ mod tests {
 
         let players = tourn.ranking();
 
-        let p1 = players[0].borrow();
+        let p1 = players[0];
         assert_eq!(p1.name, "Player 8");
         assert_eq!(p1.matches_played, 3);
         assert_eq!(p1.games_played, 9);
         assert_eq!(p1.match_points, 9);
         assert_eq!(p1.game_points, 18);
 
-        let p2 = players[7].borrow();
+        let p2 = players[7];
         assert_eq!(p2.matches_played, 3);
         assert_eq!(p2.games_played, 9);
         assert_eq!(p2.match_points, 0);
@@ -1464,7 +3613,7 @@ mod tests {
 
         let mut bye_count = 0;
         for p in &players {
-            if p.borrow().has_bye {
+            if p.has_bye {
                 bye_count += 1;
             }
         }
@@ -1476,16 +3625,16 @@ mod tests {
         let mut players = Vec::with_capacity(13);
 
         for i in 1..14 {
-            let p = Rc::new(RefCell::new(Player::new(format!("Player {}", i).as_str())));
+            let p = Player::new(format!("Player {}", i).as_str());
             players.push(p);
         }
 
-        let mut tourn = Tournament::new(players);
+        let mut tourn = Tournament::with_players(players).unwrap();
         assert_eq!(tourn.rounds, 4);
 
         let re = Regex::new(r"Player (\d+)").unwrap();
 
-        while let Some(pairings) = tourn.next_round() {
+        while let Some(pairings) = tourn.next_round().unwrap() {
             for pair in &pairings {
                 let uuid = pair.0;
 
@@ -1532,20 +3681,20 @@ mod tests {
 
         let mut bye_count = 0;
         for p in &players {
-            if p.borrow().has_bye {
+            if p.has_bye {
                 bye_count += 1;
             }
         }
         assert_eq!(bye_count, 4);
 
-        let winner = players[0].borrow();
+        let winner = players[0];
         assert_eq!(winner.name, "Player 13");
         assert_eq!(winner.matches_played, 4);
         assert!(winner.games_played >= 11);
         assert_eq!(winner.match_points, 12);
         assert_eq!(winner.game_points, 24);
 
-        let loser = players[12].borrow();
+        let loser = players[12];
         assert_eq!(loser.matches_played, 4);
         assert!(loser.games_played >= 11);
         assert!(loser.match_points == 0 || loser.match_points == 3);
@@ -1557,16 +3706,16 @@ mod tests {
         let mut players = Vec::with_capacity(60);
 
         for i in 1..61 {
-            let p = Rc::new(RefCell::new(Player::new(format!("Player {}", i).as_str())));
+            let p = Player::new(format!("Player {}", i).as_str());
             players.push(p);
         }
 
-        let mut tourn = Tournament::new(players);
+        let mut tourn = Tournament::with_players(players).unwrap();
         assert_eq!(tourn.rounds, 6);
 
         let re = Regex::new(r"Player (\d+)").unwrap();
 
-        while let Some(pairings) = tourn.next_round() {
+        while let Some(pairings) = tourn.next_round().unwrap() {
             for pair in &pairings {
                 let uuid = pair.0;
 
@@ -1613,23 +3762,1109 @@ mod tests {
 
         let mut bye_count = 0;
         for p in &players {
-            if p.borrow().has_bye {
+            if p.has_bye {
                 bye_count += 1;
             }
         }
         assert_eq!(bye_count, 0);
 
-        let winner = players[0].borrow();
+        let winner = players[0];
         assert_eq!(winner.name, "Player 60");
         assert_eq!(winner.matches_played, 6);
         assert!(winner.games_played >= 17);
         assert_eq!(winner.match_points, 18);
         assert_eq!(winner.game_points, 36);
 
-        let loser = players[59].borrow();
+        let loser = players[59];
         assert_eq!(loser.matches_played, 6);
         assert!(loser.games_played >= 17);
         assert!(loser.match_points == 0 || loser.match_points == 3);
         assert!(loser.game_points == 18 || loser.game_points == 21);
     }
+
+    #[test]
+    /// Player 3 leaves after round 1, Player 4 arrives for round 2
+    fn tournament_availability() {
+        let mut players = Vec::with_capacity(4);
+
+        for i in 1..5 {
+            let p = Player::new(format!("Player {}", i).as_str());
+            players.push(p);
+        }
+
+        players[2].availability = Availability::new(1, Some(1));
+        players[3].availability = Availability::new(2, None);
+
+        let mut tourn = Tournament::with_players(players).unwrap();
+        assert_eq!(tourn.rounds, 2);
+
+        while let Some(pairings) = tourn.next_round().unwrap() {
+            assert_eq!(pairings.len(), 1);
+
+            let (uuid, home, away) = &pairings[0];
+            if tourn.current_round == 1 {
+                assert_ne!(home, "Player 4");
+                assert_ne!(away, "Player 4");
+            } else {
+                assert_ne!(home, "Player 3");
+                assert_ne!(away, "Player 3");
+            }
+
+            assert!(tourn.end_match(*uuid, 2, 0, 0).is_ok());
+        }
+
+        assert!(tourn.availability_conflicts.is_empty());
+
+        let players = tourn.ranking();
+
+        let p3 = players.iter().find(|p| p.name == "Player 3").unwrap();
+        assert_eq!(p3.matches_played, 1);
+
+        let p4 = players.iter().find(|p| p.name == "Player 4").unwrap();
+        assert_eq!(p4.matches_played, 1);
+    }
+
+    #[test]
+    fn pairing_matches_oracle() {
+        let mut rng = thread_rng();
+
+        for n in 2..=oracle::MAX_PLAYERS {
+            for _ in 0..10 {
+                let mut players: PlayerArena = (1..=n)
+                    .map(|i| Player::new(format!("Player {}", i).as_str()))
+                    .collect();
+                let mut tourn = Tournament::from_arena(players.clone());
+
+                // Play more rounds than there are distinct opponents so rematches become
+                // unavoidable
+                for _ in 0..n {
+                    let active: Vec<&Player> = players.iter().collect();
+                    let pairs = tourn.pair_players(&active);
+                    let (_, optimal) = oracle::optimal(&active).unwrap();
+                    assert_eq!(oracle::cost(&active, &pairs), optimal);
+                    let pairs: Vec<(Uuid, Uuid)> =
+                        pairs.iter().map(|(h, a)| (h.uuid, a.uuid)).collect();
+
+                    for (home, away) in pairs {
+                        let home_score = rng.gen_range(0, 3);
+                        let away_score = if home_score == 2 {
+                            rng.gen_range(0, 2)
+                        } else {
+                            2
+                        };
+                        let (home, away) = players.pair_mut(home, away).unwrap();
+                        let pair = Pairing::new(home, away);
+                        assert!(pair
+                            .end_match(&mut players, home_score, away_score, 0)
+                            .is_ok());
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn reproducible_rounds() {
+        let mut players = Vec::with_capacity(7);
+
+        for i in 1..8 {
+            let p = Player::new(format!("Player {}", i).as_str());
+            players.push(p);
+        }
+
+        let mut tourn = Tournament::with_players(players).unwrap();
+        tourn.first_player = FirstPlayer::Random;
+        let pairings = tourn.next_round().unwrap().unwrap();
+        for (uuid, _, _) in &pairings {
+            assert!(tourn.end_match(*uuid, 2, 1, 0).is_ok());
+        }
+
+        // The same state and seed always produce the same round
+        let json = tourn.to_json();
+        let names = |pairings: Vec<(uuid::Uuid, String, String)>| -> Vec<(String, String)> {
+            pairings.into_iter().map(|(_, h, a)| (h, a)).collect()
+        };
+
+        let mut first = Tournament::from_json(&json).unwrap();
+        let mut second = Tournament::from_json(&json).unwrap();
+        let expected = names(first.next_round_with_seed(42).unwrap().unwrap());
+        assert_eq!(
+            names(second.next_round_with_seed(42).unwrap().unwrap()),
+            expected
+        );
+
+        let sides = |t: &Tournament| -> Vec<Option<PlayerSide>> {
+            let mut sides: Vec<_> = t
+                .pairings
+                .values()
+                .map(|p| {
+                    (
+                        t.players.get(p.home).unwrap().name.clone(),
+                        p.first_player(),
+                    )
+                })
+                .collect();
+            sides.sort_by(|a, b| a.0.cmp(&b.0));
+            sides.into_iter().map(|(_, side)| side).collect()
+        };
+        assert_eq!(sides(&first), sides(&second));
+
+        // Every round's seed is disclosed in the audit log, and survives saving
+        let seeds = first.round_seeds();
+        assert_eq!(seeds.len(), 2);
+        assert_eq!(seeds[0], tourn.round_seeds()[0]);
+        assert_eq!(seeds[1], (2, 42));
+
+        let loaded = Tournament::from_json(&first.to_json()).unwrap();
+        assert_eq!(loaded.round_seeds(), seeds);
+    }
+
+    #[test]
+    fn tournament_unavoidable_rematch() {
+        let mut players = Vec::with_capacity(4);
+
+        for i in 1..5 {
+            let p = Player::new(format!("Player {}", i).as_str());
+            players.push(p);
+        }
+
+        // After three rounds everyone has played everyone else
+        let mut tourn = Tournament::with_players(players).unwrap();
+        tourn.rounds = 5;
+
+        while let Some(pairings) = tourn.next_round().unwrap() {
+            assert_eq!(pairings.len(), 2);
+
+            let mut names: Vec<&String> =
+                pairings.iter().flat_map(|(_, h, a)| vec![h, a]).collect();
+            names.sort();
+            names.dedup();
+            assert_eq!(names.len(), 4);
+
+            for (uuid, _, _) in &pairings {
+                assert!(tourn.end_match(*uuid, 2, 1, 0).is_ok());
+            }
+        }
+
+        for p in &tourn.players {
+            assert_eq!(p.opponents.len(), 5);
+        }
+    }
+
+    /// Compares two players by the default tiebreakers, looking up their opponents in `arena`
+    fn compare(a: &Player, b: &Player, arena: &PlayerArena) -> Ordering {
+        compare_standing(a, b, arena, &Criterion::DEFAULT_TIEBREAKERS)
+    }
+
+    /// Pairs the players at two positions of the arena and ends their match with the given score
+    fn play(players: &mut PlayerArena, home: usize, away: usize, score: (u8, u8)) {
+        let pair = pair_up(players, home, away);
+        assert!(pair.end_match(players, score.0, score.1, 0).is_ok());
+    }
+
+    #[test]
+    fn oomwp_breaks_full_ties() {
+        let mut players: PlayerArena = ["X", "Z", "Y1", "Y2", "S1", "S2", "T"]
+            .iter()
+            .map(|name| Player::new(name))
+            .collect();
+        let (x, z, y1, y2, s1, s2, t) = (0, 1, 2, 3, 4, 5, 6);
+        play(&mut players, x, y1, (2, 0));
+        play(&mut players, z, y2, (2, 0));
+        play(&mut players, s1, y1, (2, 0));
+        play(&mut players, s2, y2, (2, 0));
+        play(&mut players, t, s2, (2, 0));
+
+        // X and Z are tied on everything but their opponents' opponents: Y1 lost to S1, who is
+        // undefeated, and Y2 lost to S2, who lost to T
+        let (x, z) = (&players[x], &players[z]);
+        assert_eq!(x.opponents_match_win_percentage(&players), 1.0 / 3.0);
+        assert_eq!(z.opponents_match_win_percentage(&players), 1.0 / 3.0);
+        assert_eq!(x.opponents_opponents_match_win_percentage(&players), 1.0);
+        assert_eq!(z.opponents_opponents_match_win_percentage(&players), 0.75);
+
+        let mut ranked = vec![z, x];
+        sort_by_tiebreakers(&mut ranked, &players, &Criterion::DEFAULT_TIEBREAKERS);
+        assert_eq!(ranked[0].uuid, x.uuid);
+        assert_eq!(compare(x, z, &players), Ordering::Less);
+    }
+
+    #[test]
+    fn pairing_stats() {
+        let players = (1..3)
+            .map(|i| Player::new(format!("Player {}", i).as_str()))
+            .collect();
+        let mut tourn = Tournament::with_players(players).unwrap();
+        tourn.rounds = 2;
+
+        let (uuid, _, _) = tourn.next_round().unwrap().unwrap()[0];
+        assert_eq!(tourn.last_pairing_stats().unwrap().rematches, 0);
+        tourn.end_match(uuid, 2, 0, 0).unwrap();
+
+        // With only two players, the second round can only be a rematch
+        tourn.next_round().unwrap();
+        let stats = tourn.last_pairing_stats().unwrap();
+        assert_eq!((stats.round, stats.players), (2, 2));
+        assert_eq!((stats.greedy_rematches, stats.rematches), (1, 1));
+        assert!(stats.search_steps > 0);
+        assert!(!stats.search_exhausted);
+        assert!(!stats.oracle_improved);
+    }
+
+    #[test]
+    fn streak_and_form() {
+        let players = (1..9)
+            .map(|i| Player::new(format!("Player {}", i).as_str()))
+            .collect();
+        let mut tourn = Tournament::with_players(players).unwrap();
+        for _ in 0..3 {
+            for (uuid, _, _) in tourn.next_round().unwrap().unwrap() {
+                tourn.end_match(uuid, 2, 1, 0).unwrap();
+            }
+        }
+
+        let standings = tourn.standings();
+        assert_eq!(standings[0].streak, "W3");
+        assert_eq!(standings[0].form, "WWW");
+        assert_eq!(standings[7].streak, "L3");
+        for row in &standings {
+            let player = tourn.player(row.uuid).unwrap();
+            assert_eq!(row.form.len(), 3);
+            assert!(row.form.ends_with(&row.streak[..1]));
+            assert_eq!(
+                player.match_points,
+                3 * row.form.matches('W').count() as u32
+            );
+        }
+    }
+
+    #[test]
+    fn casual_tiebreakers() {
+        let mut players: PlayerArena = ["A", "B", "C", "D"]
+            .iter()
+            .map(|name| Player::new(name))
+            .collect();
+        let (a, b, c, d) = (0, 1, 2, 3);
+
+        // A wins first and narrowly, B wins last and clearly
+        play(&mut players, a, c, (2, 1));
+        play(&mut players, b, d, (1, 2));
+        play(&mut players, a, d, (0, 2));
+        play(&mut players, b, c, (2, 0));
+        let (a, b) = (&players[a], &players[b]);
+        assert_eq!((a.cumulative_points, b.cumulative_points), (6, 3));
+        assert_eq!((a.game_differential(), b.game_differential()), (-1, 1));
+
+        let by = |tiebreakers: &[Criterion]| compare_standing(a, b, &players, tiebreakers);
+        assert_eq!(by(&[Criterion::Wins]), Ordering::Equal);
+        assert_eq!(
+            by(&[Criterion::Wins, Criterion::CumulativeScore]),
+            Ordering::Less
+        );
+        assert_eq!(
+            by(&[Criterion::Wins, Criterion::GameDifferential]),
+            Ordering::Greater
+        );
+    }
+
+    #[test]
+    fn ranking_without_opponents() {
+        let mut players: PlayerArena = ["A", "B", "D"]
+            .iter()
+            .map(|name| Player::new(name))
+            .collect();
+        play(&mut players, 0, 1, (2, 0));
+        play(&mut players, 1, 2, (2, 0));
+        let a = &players[0];
+
+        // A player without opponents gets the lowest opponent tiebreakers, and ranks below a
+        // player with the same match points whose opponent did better than that
+        let mut c = Player::new("C");
+        c.match_points = 3;
+        assert_eq!(c.opponents_match_win_percentage(&players), 1.0 / 3.0);
+        assert_eq!(c.opponents_game_win_percentage(&players), 1.0 / 3.0);
+        assert_eq!(
+            c.opponents_opponents_match_win_percentage(&players),
+            1.0 / 3.0
+        );
+
+        assert_eq!(compare(&c, a, &players), Ordering::Greater);
+        assert_eq!(compare(a, &c, &players), Ordering::Less);
+        assert_eq!(compare(&c, &c, &players), Ordering::Equal);
+    }
+
+    /// Match points, matches played, game points and games played
+    type Counters = (u32, u32, u32, u32);
+
+    /// Builds a player from arbitrary counters, which need not be consistent with each other, and
+    /// arbitrary opponents, who are added to `arena`
+    fn player(counters: Counters, opponents: &[Counters], arena: &mut PlayerArena) -> Player {
+        let (match_points, matches_played, game_points, games_played) = counters;
+        let mut p = Player::new("Arbitrary");
+        p.match_points = match_points;
+        p.matches_played = matches_played;
+        p.game_points = game_points;
+        p.games_played = games_played;
+        for &o in opponents {
+            let opponent = player(o, &[], arena);
+            p.opponents.push(opponent.uuid);
+            arena.push(opponent);
+        }
+        p
+    }
+
+    fn history() -> impl Strategy<Value = (Counters, Vec<Counters>)> {
+        (any::<Counters>(), collection::vec(any::<Counters>(), 0..8))
+    }
+
+    proptest! {
+        #[test]
+        fn tiebreakers_are_total((p, opponents) in history()) {
+            let mut arena = PlayerArena::new();
+            let p = player(p, &opponents, &mut arena);
+            for value in &[
+                p.match_win_percentage(),
+                p.game_win_percentage(),
+                p.opponents_match_win_percentage(&arena),
+                p.opponents_game_win_percentage(&arena),
+                p.opponents_opponents_match_win_percentage(&arena),
+            ] {
+                prop_assert!(value.is_finite());
+                prop_assert!(*value >= 1.0 / 3.0 && *value <= 1.0);
+            }
+        }
+
+        #[test]
+        fn standing_is_total_order(
+            a in history(),
+            b in history(),
+            c in history(),
+        ) {
+            let mut arena = PlayerArena::new();
+            let a = player(a.0, &a.1, &mut arena);
+            let b = player(b.0, &b.1, &mut arena);
+            let c = player(c.0, &c.1, &mut arena);
+            let compare = |x, y| compare(x, y, &arena);
+            prop_assert_eq!(compare(&a, &a), Ordering::Equal);
+            prop_assert_eq!(compare(&a, &b), compare(&b, &a).reverse());
+            if compare(&a, &b) != Ordering::Greater
+                && compare(&b, &c) != Ordering::Greater
+            {
+                prop_assert_ne!(compare(&a, &c), Ordering::Greater);
+            }
+        }
+    }
+
+    #[test]
+    fn tiebreakers_are_exact() {
+        // Summed as floats, these match win percentages add up differently in either order
+        let opponents = [(1, 1, 0, 0), (2, 1, 0, 0), (3, 1, 0, 0)];
+        let mut reversed = opponents;
+        reversed.reverse();
+        let mut arena = PlayerArena::new();
+        let a = player((3, 1, 0, 0), &opponents, &mut arena);
+        let b = player((3, 1, 0, 0), &reversed, &mut arena);
+
+        assert_eq!(compare(&a, &b, &arena), Ordering::Equal);
+        assert_eq!(a.opponents_match_win_percentage(&arena), 2.0 / 3.0);
+        assert_eq!(
+            a.opponents_match_win_percentage(&arena),
+            b.opponents_match_win_percentage(&arena)
+        );
+    }
+
+    #[test]
+    fn tournament_drop_and_add() {
+        let mut players = Vec::with_capacity(4);
+
+        for i in 1..5 {
+            let p = Player::new(format!("Player {}", i).as_str());
+            players.push(p);
+        }
+
+        let early = players[0].uuid;
+        let late = players[1].uuid;
+
+        let mut tourn = Tournament::with_players(players).unwrap();
+        tourn.rounds = 3;
+
+        // Dropping before the tournament starts removes the player entirely
+        assert!(tourn.drop_player(early).unwrap().is_some());
+        assert_eq!(tourn.players.len(), 3);
+        assert!(tourn.drop_player(early).unwrap().is_none());
+
+        let pairings = tourn.next_round().unwrap().unwrap();
+        assert_eq!(pairings.len(), 1);
+        for (uuid, _, _) in &pairings {
+            assert!(tourn.end_match(*uuid, 2, 0, 0).is_ok());
+        }
+
+        // A player who drops mid-event stays in the standings but isn't paired anymore
+        let dropped = tourn.drop_player(late).unwrap().unwrap();
+        let newcomer = Player::new("Player 5");
+        let newcomer_uuid = newcomer.uuid;
+        tourn.add_player(newcomer).unwrap();
+        let newcomer = tourn.players.get(newcomer_uuid).unwrap();
+        assert_eq!(newcomer.availability.first_round, 2);
+        assert_eq!(tourn.players.len(), 4);
+
+        while let Some(pairings) = tourn.next_round().unwrap() {
+            let name = &dropped.name;
+            for (uuid, home, away) in &pairings {
+                assert_ne!(home, name);
+                assert_ne!(away, name);
+                assert!(tourn.end_match(*uuid, 2, 1, 0).is_ok());
+            }
+        }
+
+        assert_eq!(tourn.ranking().len(), 4);
+        assert!(tourn.players.get(newcomer_uuid).unwrap().matches_played > 0);
+    }
+
+    #[test]
+    fn duplicate_players_are_refused() {
+        let alice = Player::new("Alice");
+        let uuid = alice.uuid;
+        let twice = vec![alice.clone(), Player::new("Bob"), alice.clone()];
+        assert!(matches!(
+            Tournament::with_players(twice),
+            Err(PlayerError::DuplicatePlayer(u)) if u == uuid
+        ));
+
+        let mut tourn = Tournament::with_players(vec![alice.clone(), Player::new("Bob")]).unwrap();
+        assert_eq!(
+            tourn.add_player(alice),
+            Err(PlayerError::DuplicatePlayer(uuid))
+        );
+        assert_eq!(tourn.players.len(), 2);
+        assert!(Tournament::from_json(&tourn.to_json()).is_ok());
+    }
+
+    #[test]
+    fn finished_tournament_is_locked() {
+        let players: Vec<_> = (1..5)
+            .map(|i| Player::new(format!("Player {}", i).as_str()))
+            .collect();
+        let uuids: Vec<_> = players.iter().map(|p| p.uuid).collect();
+        let mut tourn = Tournament::with_players(players).unwrap();
+        tourn.rounds = 1;
+
+        for (uuid, _, _) in tourn.next_round().unwrap().unwrap() {
+            tourn.end_match(uuid, 2, 0, 0).unwrap();
+        }
+        tourn.finish().unwrap();
+        assert_eq!(tourn.check_unlocked(), Err(LockError::Finished));
+
+        let newcomer = Player::new("Player 5");
+        assert_eq!(tourn.add_player(newcomer), Err(PlayerError::Finished));
+        assert!(matches!(
+            tourn.drop_player(uuids[0]),
+            Err(LockError::Finished)
+        ));
+        assert!(matches!(
+            tourn.readd_player(uuids[0]),
+            Err(LockError::Finished)
+        ));
+        assert_eq!(
+            tourn.set_percentage_floor(PercentageFloor::None),
+            Err(LockError::Finished)
+        );
+        assert_eq!(
+            tourn.avoid_pairing(uuids[0], uuids[1]),
+            Err(constraints::ConstraintError::Finished)
+        );
+        assert!(matches!(
+            tourn.penalize(uuids[0], penalties::PenaltyKind::Warning, "Slow play"),
+            Err(penalties::PenaltyError::Finished)
+        ));
+        assert_eq!(
+            tourn.request_bye(uuids[0], 2, ByeScore::Win),
+            Err(byes::ByeRequestError::Finished)
+        );
+        assert_eq!(tourn.report_tokens(), Err(reporting::ReportError::Finished));
+        let config = config::Config {
+            rounds: Some(3),
+            ..config::Config::default()
+        };
+        assert!(matches!(
+            tourn.apply_config(&config),
+            Err(config::ConfigError::Finished)
+        ));
+
+        assert_eq!(tourn.players.len(), 4);
+        assert_eq!(tourn.rounds, 1);
+        assert!(tourn.penalties.is_empty());
+    }
+
+    #[test]
+    fn tournament_availability_conflicts() {
+        let mut players = Vec::with_capacity(3);
+
+        for i in 1..4 {
+            let p = Player::new(format!("Player {}", i).as_str());
+            players.push(p);
+        }
+
+        // Nobody is eligible for a bye anymore, so the odd player out can't be accommodated
+        for p in &mut players {
+            p.has_bye = true;
+        }
+
+        let mut late = Player::new("Late");
+        late.availability = Availability::new(10, None);
+        let late_uuid = late.uuid;
+        players.push(late);
+
+        let mut tourn = Tournament::with_players(players).unwrap();
+        tourn.rounds = 1;
+
+        let pairings = tourn.next_round().unwrap().unwrap();
+        assert_eq!(pairings.len(), 1);
+
+        assert_eq!(tourn.availability_conflicts.len(), 2);
+        assert_eq!(
+            tourn.availability_conflicts[0],
+            AvailabilityConflict::NeverAvailable(late_uuid)
+        );
+
+        match tourn.availability_conflicts[1] {
+            AvailabilityConflict::Unpaired(_, round) => assert_eq!(round, 1),
+            _ => panic!("Expected unpaired player"),
+        }
+    }
+
+    #[test]
+    fn first_player_undecided() {
+        let players = vec![Player::new("Player 1"), Player::new("Player 2")];
+
+        let mut tourn = Tournament::with_players(players).unwrap();
+        let pairings = tourn.next_round().unwrap().unwrap();
+        assert_eq!(tourn.pairings[&pairings[0].0].first_player(), None);
+    }
+
+    #[test]
+    fn first_player_random() {
+        let players = vec![Player::new("Player 1"), Player::new("Player 2")];
+
+        let mut tourn = Tournament::with_players(players).unwrap();
+        tourn.first_player = FirstPlayer::Random;
+        let pairings = tourn.next_round().unwrap().unwrap();
+        assert!(tourn.pairings[&pairings[0].0].first_player().is_some());
+    }
+
+    #[test]
+    fn first_player_higher_seed() {
+        let p1 = Player::new("Player 1");
+        let mut p2 = Player::new("Player 2");
+        p2.win_match();
+
+        let mut tourn = Tournament::with_players(vec![p1, p2]).unwrap();
+        tourn.first_player = FirstPlayer::HigherSeed;
+
+        let pairings = tourn.next_round().unwrap().unwrap();
+        let (uuid, home, _) = &pairings[0];
+        let expected = if home == "Player 2" {
+            PlayerSide::Home
+        } else {
+            PlayerSide::Away
+        };
+        assert_eq!(tourn.pairings[uuid].first_player(), Some(expected));
+    }
+
+    #[test]
+    fn first_player_from_str() {
+        assert_eq!("random".parse(), Ok(FirstPlayer::Random));
+        assert_eq!("seed".parse(), Ok(FirstPlayer::HigherSeed));
+        assert!("coin".parse::<FirstPlayer>().is_err());
+    }
+
+    #[test]
+    fn shared_players_are_copied() {
+        let p1 = Rc::new(RefCell::new(Player::new("Player 1")));
+        let p2 = Rc::new(RefCell::new(Player::new("Player 2")));
+        let mut tourn = Tournament::new(vec![Rc::clone(&p1), p2]).unwrap();
+        let (uuid, _, _) = tourn.next_round().unwrap().unwrap()[0].clone();
+        assert!(tourn.end_match(uuid, 2, 0, 0).is_ok());
+
+        // The tournament records results in its own copy of a player who is still shared
+        assert_eq!(p1.borrow().matches_played, 0);
+        let copy = tourn.players.get(p1.borrow().uuid).unwrap();
+        assert_eq!(copy.matches_played, 1);
+        assert_eq!(tourn.players().map(|p| p.matches_played).sum::<u32>(), 2);
+    }
+
+    #[test]
+    fn final_tiebreak() {
+        let players = (1..=6)
+            .map(|i| Player::new(&format!("Player {}", i)))
+            .collect();
+        let mut tourn = Tournament::with_players(players).unwrap();
+        let registered: Vec<Uuid> = tourn.players.iter().map(|p| p.uuid).collect();
+        let order =
+            |players: Vec<&Player>| -> Vec<Uuid> { players.iter().map(|p| p.uuid).collect() };
+        let reverse = |players: &mut PlayerArena| {
+            let reversed: Vec<Uuid> = players.iter().rev().map(|p| p.uuid).collect();
+            players.reorder(&reversed);
+        };
+
+        // Nobody has played yet, so everyone is tied on everything
+        reverse(&mut tourn.players);
+        tourn.final_tiebreak = FinalTiebreak::RegistrationOrder;
+        assert_eq!(order(tourn.ranking()), registered);
+
+        let seeds = vec![registered[4], registered[2]];
+        tourn.final_tiebreak = FinalTiebreak::Seeds(seeds);
+        let ranked = order(tourn.ranking());
+        assert_eq!(ranked[..2], [registered[4], registered[2]]);
+        assert_eq!(
+            ranked[2..],
+            [registered[0], registered[1], registered[3], registered[5]]
+        );
+
+        // The random draw is made once and recorded, and a saved tournament reproduces it
+        tourn.final_tiebreak = FinalTiebreak::Random;
+        assert_eq!(tourn.tiebreak_seed(), None);
+        let drawn = order(tourn.ranking());
+        let seed = tourn.tiebreak_seed().unwrap();
+        assert_eq!(
+            tourn.audit_log.last().unwrap().event,
+            audit::AuditEvent::TiebreakSeed { seed }
+        );
+        reverse(&mut tourn.players);
+        assert_eq!(order(tourn.ranking()), drawn);
+        let logged = tourn
+            .audit_log
+            .iter()
+            .filter(|entry| matches!(entry.event, audit::AuditEvent::TiebreakSeed { .. }))
+            .count();
+        assert_eq!(logged, 1);
+
+        let mut loaded = Tournament::from_json(&tourn.to_json()).unwrap();
+        reverse(&mut loaded.players);
+        assert_eq!(loaded.tiebreak_seed(), Some(seed));
+        assert_eq!(order(loaded.ranking()), drawn);
+        let standings: Vec<Uuid> = loaded.standings().iter().map(|row| row.uuid).collect();
+        assert_eq!(standings, drawn);
+    }
+
+    #[test]
+    fn amend_result() {
+        let p1 = Player::new("Player 1");
+        let p2 = Player::new("Player 2");
+        let mut tourn = Tournament::with_players(vec![p1, p2]).unwrap();
+        let (uuid, _, _) = tourn.next_round().unwrap().unwrap()[0].clone();
+
+        assert!(matches!(
+            tourn.amend_match(uuid, 2, 0, 0),
+            Err(PairingResultError::NotReported(_))
+        ));
+        assert!(matches!(
+            tourn.amend_match(Uuid::new_v4(), 2, 0, 0),
+            Err(PairingResultError::NotFound(_))
+        ));
+
+        assert!(tourn.end_match(uuid, 2, 1, 0).is_ok());
+        assert!(matches!(
+            tourn.end_match(uuid, 2, 1, 0),
+            Err(PairingResultError::AlreadyReported(_))
+        ));
+        assert!(matches!(
+            tourn.amend_match(uuid, 3, 0, 0),
+            Err(PairingResultError::OutOfRange(3))
+        ));
+
+        // The amended result replaces the previous one entirely
+        assert!(tourn.amend_match(uuid, 0, 2, 1).is_ok());
+        let pair = &tourn.pairings[&uuid];
+        let (home, away) = (
+            tourn.players.get(pair.home).unwrap(),
+            tourn.players.get(pair.away).unwrap(),
+        );
+        assert_eq!(home.games_played, 3);
+        assert_eq!(home.game_points, 1);
+        assert_eq!(home.matches_played, 1);
+        assert_eq!(home.matches_won, 0);
+        assert_eq!(home.match_points, 0);
+        assert_eq!(home.games_won, 0);
+        assert_eq!(home.cumulative_points, 0);
+        assert_eq!(home.outcomes, [Outcome::Loss]);
+        assert_eq!(away.games_played, 3);
+        assert_eq!(away.game_points, 7);
+        assert_eq!(away.matches_played, 1);
+        assert_eq!(away.matches_won, 1);
+        assert_eq!(away.match_points, 3);
+        assert_eq!(away.games_won, 2);
+        assert_eq!(away.cumulative_points, 3);
+        assert_eq!(away.outcomes, [Outcome::Win]);
+
+        let round = tourn.round(1).unwrap();
+        assert_eq!(
+            round.pairings[0].result,
+            Some(history::MatchResult {
+                home_score: 0,
+                away_score: 2,
+                drawn: 1,
+                time_called: false,
+                conceded: None,
+                double_loss: false,
+            })
+        );
+        assert!(matches!(
+            tourn.audit_log.last().unwrap().event,
+            audit::AuditEvent::ResultAmended { pairing, .. } if pairing == uuid
+        ));
+    }
+
+    #[test]
+    fn stale_pairing() {
+        let players = (1..5)
+            .map(|i| Player::new(&format!("Player {}", i)))
+            .collect();
+        let mut tourn = Tournament::with_players(players).unwrap();
+        let first = tourn.next_round().unwrap().unwrap();
+        for (uuid, _, _) in &first {
+            tourn.end_match(*uuid, 2, 0, 0).unwrap();
+        }
+        tourn.next_round().unwrap().unwrap();
+
+        // A result for the previous round doesn't reach the players' records
+        let (stale, _, _) = first[0];
+        assert!(matches!(
+            tourn.end_match(stale, 0, 2, 0),
+            Err(PairingResultError::RoundClosed(uuid)) if uuid == stale
+        ));
+        assert!(matches!(
+            tourn.concede(stale, PlayerSide::Home),
+            Err(PairingResultError::RoundClosed(_))
+        ));
+        assert!(matches!(
+            tourn.amend_match(stale, 0, 2, 0),
+            Err(PairingResultError::RoundClosed(_))
+        ));
+        assert!(matches!(
+            tourn.end_match(Uuid::new_v4(), 2, 0, 0),
+            Err(PairingResultError::NotFound(_))
+        ));
+        let points: u32 = tourn.players.iter().map(|p| p.match_points).sum();
+        assert_eq!(points, 6);
+    }
+
+    #[test]
+    fn best_of_one() {
+        let format = MatchFormat::BestOf(1);
+        assert!(check_scores(format, 1, 0, 0).is_ok());
+        assert!(check_scores(format, 0, 1, 0).is_ok());
+        assert!(check_scores(format, 0, 0, 1).is_ok());
+        assert!(check_scores(format, 1, 1, 0).is_err());
+        assert!(check_scores(format, 2, 0, 0).is_err());
+        assert!(check_scores(format, 1, 0, 1).is_err());
+        assert!(check_scores(format, 0, 0, 0).is_err());
+
+        let players = (1..4)
+            .map(|i| Player::new(format!("Player {}", i).as_str()))
+            .collect();
+        let mut tourn = Tournament::with_players(players).unwrap();
+        tourn.match_format = format;
+        let (uuid, _, _) = tourn.next_round().unwrap().unwrap()[0].clone();
+        assert!(matches!(
+            tourn.end_match(uuid, 2, 0, 0),
+            Err(PairingResultError::OutOfRange(2))
+        ));
+        assert!(tourn.end_match(uuid, 1, 0, 0).is_ok());
+
+        // Games and matches coincide, and a bye is a single game won
+        for p in &tourn.players {
+            assert_eq!(p.games_played, p.matches_played);
+            assert_eq!(p.game_points, p.match_points);
+            assert_eq!(p.game_win_percentage(), p.match_win_percentage());
+        }
+
+        let loaded = Tournament::from_json(&tourn.to_json()).unwrap();
+        assert_eq!(loaded.match_format, format);
+        assert_eq!(loaded.pairings[&uuid].format(), format);
+    }
+
+    #[test]
+    fn match_format_from_str() {
+        assert_eq!("1".parse(), Ok(MatchFormat::BestOf(1)));
+        assert_eq!("bo5".parse(), Ok(MatchFormat::BestOf(5)));
+        assert!("2".parse::<MatchFormat>().is_err());
+        assert_eq!(MatchFormat::BestOf(5).wins_needed(), 3);
+    }
+
+    #[test]
+    fn time_called() {
+        let players = (1..5)
+            .map(|i| Player::new(format!("Player {}", i).as_str()))
+            .collect();
+        let mut tourn = Tournament::with_players(players).unwrap();
+        let pairings = tourn.next_round().unwrap().unwrap();
+        let (drawn, unfinished) = (pairings[0].0, pairings[1].0);
+
+        // 1-1 with the third game unfinished is a draw
+        assert!(matches!(
+            tourn.end_match_at_time(drawn, 1, 2, 0),
+            Err(PairingResultError::OutOfRange(2))
+        ));
+        assert!(tourn.end_match_at_time(drawn, 1, 1, 0).is_ok());
+        let pair = &tourn.pairings[&drawn];
+        assert_eq!(tourn.players.get(pair.home).unwrap().match_points, 1);
+        assert_eq!(tourn.players.get(pair.away).unwrap().games_played, 3);
+
+        // Time called during the first game
+        assert!(tourn.end_match_at_time(unfinished, 0, 0, 0).is_ok());
+        let result = tourn.pairings[&unfinished].result.get().unwrap();
+        assert_eq!(
+            result,
+            history::MatchResult {
+                home_score: 0,
+                away_score: 0,
+                drawn: 1,
+                time_called: true,
+                conceded: None,
+                double_loss: false,
+            }
+        );
+
+        let loaded = Tournament::from_json(&tourn.to_json()).unwrap();
+        assert!(
+            loaded.round(1).unwrap().pairings[0]
+                .result
+                .unwrap()
+                .time_called
+        );
+    }
+
+    #[test]
+    fn concede() {
+        let players = (1..5)
+            .map(|i| Player::new(format!("Player {}", i).as_str()))
+            .collect();
+        let mut tourn = Tournament::with_players(players).unwrap();
+        let pairings = tourn.next_round().unwrap().unwrap();
+        let (plain, penalized) = (pairings[0].0, pairings[1].0);
+
+        assert_eq!(tourn.concede(plain, PlayerSide::Home).unwrap(), (0, 2));
+        assert!(matches!(
+            tourn.concede(plain, PlayerSide::Away),
+            Err(PairingResultError::AlreadyReported(_))
+        ));
+        let pair = &tourn.pairings[&plain];
+        let (home, away) = (
+            tourn.players.get(pair.home).unwrap(),
+            tourn.players.get(pair.away).unwrap(),
+        );
+        assert_eq!(home.match_points, 0);
+        assert_eq!(away.match_points, 3);
+        assert_eq!(away.games_played, 2);
+
+        // The conceding player keeps the game awarded by their opponent's game loss
+        let away = tourn.pairings[&penalized].away;
+        tourn
+            .penalize(away, penalties::PenaltyKind::GameLoss, "Late")
+            .unwrap();
+        assert_eq!(tourn.concede(penalized, PlayerSide::Home).unwrap(), (0, 2));
+        let result = tourn.pairings[&penalized].result.get().unwrap();
+        assert_eq!((result.home_score, result.away_score), (1, 2));
+        assert_eq!(result.conceded, Some(PlayerSide::Home));
+
+        // Amending the result replaces the concession
+        tourn.amend_match(penalized, 1, 1, 0).unwrap();
+        let result = tourn.pairings[&penalized].result.get().unwrap();
+        assert_eq!(result.conceded, None);
+
+        let loaded = Tournament::from_json(&tourn.to_json()).unwrap();
+        let round = loaded.round(1).unwrap();
+        let conceded = round.pairings.iter().find(|p| p.uuid == plain).unwrap();
+        assert_eq!(conceded.result.unwrap().conceded, Some(PlayerSide::Home));
+    }
+
+    #[test]
+    fn bye_selection() {
+        let players: Vec<_> = (1..6)
+            .map(|i| Player::new(format!("Player {}", i).as_str()))
+            .collect();
+        let uuids: Vec<_> = players.iter().map(|p| p.uuid).collect();
+        let mut tourn = Tournament::with_players(players).unwrap();
+        tourn.rounds = 3;
+
+        // The first volunteer already had a bye when the second round is paired
+        tourn.bye_selection = ByeSelection::Volunteers(vec![uuids[3], uuids[1]]);
+        let pairings = tourn.next_round().unwrap().unwrap();
+        assert_eq!(tourn.round(1).unwrap().bye, Some(uuids[3]));
+
+        // Every home player wins 2-1 and every away player loses, so they only differ by
+        // tiebreakers after the second round
+        for (uuid, _, _) in pairings {
+            tourn.end_match(uuid, 2, 1, 0).unwrap();
+        }
+        tourn.next_round().unwrap();
+        assert_eq!(tourn.round(2).unwrap().bye, Some(uuids[1]));
+
+        let open: Vec<_> = tourn.pairings.keys().copied().collect();
+        for uuid in open {
+            tourn.end_match(uuid, 2, 1, 0).unwrap();
+        }
+        tourn.bye_selection = ByeSelection::LowestTiebreakers;
+        let eligible: Vec<_> = tourn.players.iter().filter(|p| !p.has_bye).collect();
+        // Players who nobody else without a bye ranks below
+        let lowest: Vec<_> = eligible
+            .iter()
+            .filter(|p| {
+                eligible.iter().all(|q| {
+                    compare_standing(q, p, &tourn.players, &tourn.tiebreakers) != Ordering::Greater
+                })
+            })
+            .map(|p| p.uuid)
+            .collect();
+
+        tourn.next_round().unwrap();
+        assert!(lowest.contains(&tourn.round(3).unwrap().bye.unwrap()));
+    }
+
+    #[test]
+    fn bye_score() {
+        let players: Vec<_> = (1..4)
+            .map(|i| Player::new(format!("Player {}", i).as_str()))
+            .collect();
+        let mut tourn = Tournament::with_players(players).unwrap();
+        tourn.bye_score = ByeScore::Draw;
+
+        let (uuid, _, _) = tourn.next_round().unwrap().unwrap()[0];
+        tourn.end_match(uuid, 2, 0, 0).unwrap();
+        let bye = tourn.round(1).unwrap().bye.unwrap();
+        let record = tourn.player(bye).unwrap();
+        assert_eq!(record.match_points, 1);
+        assert_eq!(record.matches_drawn, 1);
+        assert_eq!(record.game_points, 1);
+
+        // Changing the score later doesn't change byes already granted
+        tourn.bye_score = ByeScore::Loss;
+        let (uuid, _, _) = tourn.next_round().unwrap().unwrap()[0];
+        tourn.end_match(uuid, 2, 0, 0).unwrap();
+        let second = tourn.round(2).unwrap().bye.unwrap();
+        assert_eq!(tourn.player(second).unwrap().games_played, 2);
+        assert_eq!(tourn.round(1).unwrap().bye_score, ByeScore::Draw);
+        assert_eq!(tourn.check_invariants(), Ok(()));
+
+        let loaded = Tournament::from_json(&tourn.to_json()).unwrap();
+        assert_eq!(loaded.bye_score, ByeScore::Loss);
+    }
+
+    #[test]
+    fn forced_rematches() {
+        let players: Vec<_> = (1..5)
+            .map(|i| Player::new(format!("Player {}", i).as_str()))
+            .collect();
+        let mut tourn = Tournament::with_players(players).unwrap();
+        tourn.rounds = 5;
+
+        while let Some(pairings) = tourn.next_round().unwrap() {
+            let mut paired: Vec<_> = tourn
+                .pairings
+                .values()
+                .flat_map(|pair| vec![pair.home, pair.away])
+                .collect();
+            paired.sort();
+            paired.dedup();
+            assert_eq!(paired.len(), 4);
+
+            // Everyone met everyone after three rounds, so every later pairing is a rematch
+            let expected = if tourn.current_round <= 3 { 0 } else { 2 };
+            assert_eq!(tourn.rematches(tourn.current_round).len(), expected);
+
+            for (uuid, _, _) in pairings {
+                tourn.end_match(uuid, 2, 1, 0).unwrap();
+            }
+        }
+
+        assert_eq!(tourn.check_invariants(), Ok(()));
+    }
+
+    #[test]
+    fn greedy_pairs_everyone_once() {
+        let mut players: Vec<_> = (1..6)
+            .map(|i| Player::new(format!("Player {}", i).as_str()))
+            .collect();
+        // The top player already played everyone but the last, and the second player everyone
+        let mut played = |a: usize, b: usize| {
+            let (ua, ub) = (players[a].uuid, players[b].uuid);
+            players[a].opponents.push(ub);
+            players[b].opponents.push(ua);
+        };
+        for i in 1..4 {
+            played(0, i);
+        }
+        for i in 2..5 {
+            played(1, i);
+        }
+
+        let queue: Vec<&Player> = players.iter().collect();
+        let pairs = greedy_pairs(&queue);
+        let uuids = |(home, away): oracle::PlayerPair| (home.uuid, away.uuid);
+        assert_eq!(pairs.len(), 2);
+        assert_eq!(uuids(pairs[0]), (players[0].uuid, players[4].uuid));
+        // Nobody left is new to the second player, so they face the closest ranked one
+        assert_eq!(uuids(pairs[1]), (players[1].uuid, players[2].uuid));
+    }
+
+    #[test]
+    fn seeded_by_rating_apart_from_clubs() {
+        let mut players: Vec<_> = (1..9)
+            .map(|i| {
+                let mut p = Player::new(format!("Player {}", i).as_str());
+                p.rating = Some(2000.0 - 100.0 * i as f64);
+                p
+            })
+            .collect();
+        let names: Vec<String> = players.iter().map(|p| p.name.clone()).collect();
+        let paired = |pairings: &Pairings, a: usize, b: usize| {
+            let (a, b) = (&names[a], &names[b]);
+            pairings
+                .iter()
+                .any(|(_, home, away)| (home == a && away == b) || (home == b && away == a))
+        };
+
+        // The top half meets the bottom half, seed 1 against seed 5
+        let mut tourn = Tournament::with_players(players.iter().rev().cloned().collect()).unwrap();
+        let pairings = tourn.next_round_with_seed(1).unwrap().unwrap();
+        assert!((0..4).all(|i| paired(&pairings, i, i + 4)));
+
+        // Seed 5 is from the club of seed 1, so seed 1 meets seed 6 instead
+        for i in [0, 4] {
+            players[i].club = Some(String::from("Rooks"));
+        }
+        let mut tourn = Tournament::with_players(players.clone()).unwrap();
+        let pairings = tourn.next_round_with_seed(1).unwrap().unwrap();
+        assert!(paired(&pairings, 0, 5) && !paired(&pairings, 0, 4));
+    }
+
+    #[test]
+    fn odd_field_after_drops() {
+        let players: Vec<_> = (1..6)
+            .map(|i| Player::new(format!("Player {}", i).as_str()))
+            .collect();
+        let uuids: Vec<_> = players.iter().map(|p| p.uuid).collect();
+        let mut tourn = Tournament::with_players(players).unwrap();
+        tourn.rounds = 6;
+
+        while let Some(pairings) = tourn.next_round().unwrap() {
+            for (uuid, _, _) in pairings {
+                tourn.end_match(uuid, 2, 0, 0).unwrap();
+            }
+            if tourn.current_round <= 2 {
+                tourn
+                    .drop_player(uuids[tourn.current_round as usize])
+                    .unwrap();
+            }
+            assert_eq!(tourn.check_invariants(), Ok(()));
+        }
+
+        // Three players are left for four rounds, so eventually none of them can get the bye and
+        // one of them is left without an opponent instead of being paired twice
+        assert!(tourn
+            .availability_conflicts
+            .iter()
+            .any(|conflict| matches!(conflict, AvailabilityConflict::Unpaired(_, _))));
+    }
 }
@@ -0,0 +1,520 @@
+//! Translations of the texts players and organizers see, so that the CLI, the spectator page, and
+//! the PDF report can be used at venues where English isn't spoken. Every text is a `Message` of
+//! the catalog, looked up in the tournament's `Locale`. Blanks in a text, written `{}`, are filled
+//! in order by `Locale::format()`.
+
+use crate::{LockError, PairingResultError};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// Language of the texts of a tournament
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub enum Locale {
+    #[default]
+    English,
+    German,
+}
+
+impl FromStr for Locale {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Locale, String> {
+        match s {
+            "en" | "english" => Ok(Locale::English),
+            "de" | "german" => Ok(Locale::German),
+            _ => Err(format!("Unknown locale: {}", s)),
+        }
+    }
+}
+
+/// The texts of the catalog. Texts that are printed on a line of their own are given without
+/// punctuation around them, annotations like "(rematch)" without a leading space.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Message {
+    // Headers of the CLI
+    RoundHeader,
+    StandingsAfterRoundHeader,
+    ResultsHeader,
+    PrizesHeader,
+    AchievementsHeader,
+    WallChartHeader,
+    MatchTimesHeader,
+    // Running a round
+    Seed,
+    RoundLength,
+    PairingPrompt,
+    ResultHelp,
+    PlaysFirst,
+    FirstWinRate,
+    Table,
+    TableResult,
+    Concedes,
+    NoDeadline,
+    DeadlinePending,
+    DoubleLossConfirm,
+    DeadlineDrawConfirm,
+    DeadlineRecorded,
+    Rematch,
+    Featured,
+    Bye,
+    RequestedBye,
+    ByeGranted,
+    PairingAvoided,
+    PairingAllowed,
+    PairingPreferred,
+    ByeOverLimit,
+    ByeWithheld,
+    Finished,
+    Created,
+    Imported,
+    // Standings and reports
+    Standings,
+    Round,
+    Rank,
+    Name,
+    Record,
+    Streak,
+    Form,
+    TableColumn,
+    Home,
+    Away,
+    Result,
+    Prizes,
+    Achievements,
+    Penalties,
+    Page,
+    Players,
+    RoundsOf,
+    PairingsFormat,
+    Swiss,
+    RoundRobin,
+    Danish,
+    Monrad,
+    ByeCounts,
+    Win,
+    Draw,
+    Loss,
+    Conceded,
+    TimeCalled,
+    // Errors
+    ResultsMissing,
+    NoSuchTable,
+    NotAtTable,
+    ErrorRecordingResult,
+    ErrorFeaturing,
+    CouldNotReadInput,
+    CouldNotParseScore,
+    TooManyWins,
+    TooManyGames,
+    NoGames,
+    StateExists,
+    ConfigNotApplied,
+    PairingNotFound,
+    ScoreOutOfRange,
+    ResultAlreadyReported,
+    NoResultYet,
+    TournamentFinished,
+    RoundClosed,
+    ReadOnly,
+    ResultsMerged,
+    MergeConflict,
+    ServingTerminals,
+    RemotePrompt,
+}
+
+impl Message {
+    /// Every message of the catalog, e.g. to check a translation for completeness
+    pub const ALL: [Message; 85] = [
+        Message::RoundHeader,
+        Message::StandingsAfterRoundHeader,
+        Message::ResultsHeader,
+        Message::PrizesHeader,
+        Message::AchievementsHeader,
+        Message::WallChartHeader,
+        Message::MatchTimesHeader,
+        Message::Seed,
+        Message::RoundLength,
+        Message::PairingPrompt,
+        Message::ResultHelp,
+        Message::PlaysFirst,
+        Message::FirstWinRate,
+        Message::Table,
+        Message::TableResult,
+        Message::Concedes,
+        Message::NoDeadline,
+        Message::DeadlinePending,
+        Message::DoubleLossConfirm,
+        Message::DeadlineDrawConfirm,
+        Message::DeadlineRecorded,
+        Message::Rematch,
+        Message::Featured,
+        Message::Bye,
+        Message::RequestedBye,
+        Message::ByeGranted,
+        Message::PairingAvoided,
+        Message::PairingAllowed,
+        Message::PairingPreferred,
+        Message::ByeOverLimit,
+        Message::ByeWithheld,
+        Message::Finished,
+        Message::Created,
+        Message::Imported,
+        Message::Standings,
+        Message::Round,
+        Message::Rank,
+        Message::Name,
+        Message::Record,
+        Message::Streak,
+        Message::Form,
+        Message::TableColumn,
+        Message::Home,
+        Message::Away,
+        Message::Result,
+        Message::Prizes,
+        Message::Achievements,
+        Message::Penalties,
+        Message::Page,
+        Message::Players,
+        Message::RoundsOf,
+        Message::PairingsFormat,
+        Message::Swiss,
+        Message::RoundRobin,
+        Message::Danish,
+        Message::Monrad,
+        Message::ByeCounts,
+        Message::Win,
+        Message::Draw,
+        Message::Loss,
+        Message::Conceded,
+        Message::TimeCalled,
+        Message::ResultsMissing,
+        Message::NoSuchTable,
+        Message::NotAtTable,
+        Message::ErrorRecordingResult,
+        Message::ErrorFeaturing,
+        Message::CouldNotReadInput,
+        Message::CouldNotParseScore,
+        Message::TooManyWins,
+        Message::TooManyGames,
+        Message::NoGames,
+        Message::StateExists,
+        Message::ConfigNotApplied,
+        Message::PairingNotFound,
+        Message::ScoreOutOfRange,
+        Message::ResultAlreadyReported,
+        Message::NoResultYet,
+        Message::TournamentFinished,
+        Message::RoundClosed,
+        Message::ReadOnly,
+        Message::ResultsMerged,
+        Message::MergeConflict,
+        Message::ServingTerminals,
+        Message::RemotePrompt,
+    ];
+}
+
+impl Locale {
+    /// Returns the text of a message, with its blanks unfilled
+    pub fn text(self, message: Message) -> &'static str {
+        match self {
+            Locale::English => english(message),
+            Locale::German => german(message),
+        }
+    }
+
+    /// Returns the text of a message with its blanks filled by the given values, in order.
+    /// Blanks without a value are left out.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use swyss::locale::{Locale, Message};
+    ///
+    /// assert_eq!(
+    ///     Locale::German.format(Message::Table, &[&3, &"Alice", &"Bob"]),
+    ///     "Tisch 3: Alice gegen Bob"
+    /// );
+    /// ```
+    pub fn format(self, message: Message, values: &[&dyn fmt::Display]) -> String {
+        let mut parts = self.text(message).split("{}");
+        let mut out = String::from(parts.next().unwrap_or_default());
+        let mut values = values.iter();
+        for part in parts {
+            if let Some(value) = values.next() {
+                out.push_str(&value.to_string());
+            }
+            out.push_str(part);
+        }
+        out
+    }
+}
+
+impl PairingResultError {
+    /// The message of the catalog describing the error
+    pub fn message(&self) -> Message {
+        match self {
+            PairingResultError::NotFound(_) => Message::PairingNotFound,
+            PairingResultError::OutOfRange(_) => Message::ScoreOutOfRange,
+            PairingResultError::AlreadyReported(_) => Message::ResultAlreadyReported,
+            PairingResultError::NotReported(_) => Message::NoResultYet,
+            PairingResultError::Finished => Message::TournamentFinished,
+            PairingResultError::RoundClosed(_) => Message::RoundClosed,
+            PairingResultError::ReadOnly => Message::ReadOnly,
+        }
+    }
+}
+
+impl LockError {
+    /// The message of the catalog describing the error
+    pub fn message(&self) -> Message {
+        match self {
+            LockError::Finished => Message::TournamentFinished,
+            LockError::ReadOnly => Message::ReadOnly,
+        }
+    }
+}
+
+fn english(message: Message) -> &'static str {
+    match message {
+        Message::RoundHeader => "=== ROUND {}/{} ===",
+        Message::StandingsAfterRoundHeader => "=== STANDINGS AFTER ROUND {} ===",
+        Message::ResultsHeader => "=== RESULTS ===",
+        Message::PrizesHeader => "=== PRIZES ===",
+        Message::AchievementsHeader => "=== ACHIEVEMENTS ===",
+        Message::WallChartHeader => "=== WALL CHART ===",
+        Message::MatchTimesHeader => "=== MATCH TIMES ===",
+        Message::Seed => "Seed: {}",
+        Message::RoundLength => "Round length: {} minutes",
+        Message::PairingPrompt => "PAIRING:",
+        Message::ResultHelp => {
+            "Enter a result like 2-1 or 2:0 (1 draw), id for an intentional draw, bye 1 or bye 2 \
+             if the opponent didn't show up, c 1 or c 2 to concede, or s for the standings"
+        }
+        Message::PlaysFirst => "(plays first)",
+        Message::FirstWinRate => "The player going first won {} of {} matches ({}%)",
+        Message::Table => "Table {}: {} vs. {}",
+        Message::TableResult => "Table {}: {} {} {}",
+        Message::Concedes => "Table {}: {} concedes",
+        Message::NoDeadline => "The tournament has no round length or no result deadline",
+        Message::DeadlinePending => "Results are due in {} minutes",
+        Message::DoubleLossConfirm => "Record a loss for both players at these {} tables? [y/N]",
+        Message::DeadlineDrawConfirm => "Record a draw at these {} tables? [y/N]",
+        Message::DeadlineRecorded => "Recorded {} overdue results",
+        Message::Rematch => "(rematch)",
+        Message::Featured => "(featured)",
+        Message::Bye => "Bye: {}",
+        Message::RequestedBye => "Requested bye: {}",
+        Message::ByeGranted => "{} has a bye in round {}",
+        Message::PairingAvoided => "{} and {} will never be paired against each other",
+        Message::PairingAllowed => "{} and {} may be paired against each other again",
+        Message::PairingPreferred => "{} and {} will be paired in the first round if possible",
+        Message::ByeOverLimit => "{} now has {} byes, more than the limit of {}",
+        Message::ByeWithheld => {
+            "{} sits out: everyone available has had the maximum number of byes"
+        }
+        Message::Finished => "The tournament is finished.",
+        Message::Created => "Created tournament with {} players and {} rounds",
+        Message::Imported => "Imported {} players and {} of {} rounds",
+        Message::Standings => "Standings",
+        Message::Round => "Round {}",
+        Message::Rank => "Rank",
+        Message::Name => "Name",
+        Message::Record => "W-L-D",
+        Message::Streak => "Streak",
+        Message::Form => "Form",
+        Message::TableColumn => "Table",
+        Message::Home => "Home",
+        Message::Away => "Away",
+        Message::Result => "Result",
+        Message::Prizes => "Prizes",
+        Message::Achievements => "Achievements",
+        Message::Penalties => "Penalties",
+        Message::Page => "Page {}",
+        Message::Players => "Players: {}",
+        Message::RoundsOf => "Rounds: {} of {}",
+        Message::PairingsFormat => "Pairings: {}, best of {}",
+        Message::Swiss => "Swiss",
+        Message::RoundRobin => "Round robin",
+        Message::Danish => "Danish system",
+        Message::Monrad => "Monrad system",
+        Message::ByeCounts => "Bye: counts as a {}",
+        Message::Win => "win",
+        Message::Draw => "draw",
+        Message::Loss => "loss",
+        Message::Conceded => "(conceded)",
+        Message::TimeCalled => "(time)",
+        Message::ResultsMissing => "Results of round {} are still missing:",
+        Message::NoSuchTable => "There is no table {} in the current round!",
+        Message::NotAtTable => "{} does not play at table {}!",
+        Message::ErrorRecordingResult => "Error recording result: {}",
+        Message::ErrorFeaturing => "Error featuring table {}: {}",
+        Message::CouldNotReadInput => "Could not read input!",
+        Message::CouldNotParseScore => "Could not parse score: {}",
+        Message::TooManyWins => "{} games won, but the match ends after {} wins",
+        Message::TooManyGames => "{} games played, but the match has at most {}",
+        Message::NoGames => "At least one game must have been played",
+        Message::StateExists => "{} already exists! Pass --force to replace it.",
+        Message::ConfigNotApplied => "Configuration not applied: {}",
+        Message::PairingNotFound => "Pairing not found!",
+        Message::ScoreOutOfRange => "Score(s) out of range!",
+        Message::ResultAlreadyReported => "Result already reported!",
+        Message::NoResultYet => "No result reported yet!",
+        Message::TournamentFinished => "The tournament is already finished!",
+        Message::RoundClosed => "The round of this pairing is already over!",
+        Message::ReadOnly => "The tournament was opened read-only!",
+        Message::ResultsMerged => "{} result(s) merged.",
+        Message::MergeConflict => "Table {}: {} here, but {} in the other file",
+        Message::ServingTerminals => {
+            "Waiting for results from other terminals on port {}, which connect with token {}"
+        }
+        Message::RemotePrompt => "Table and result, e.g. 3 2-1 (empty to refresh):",
+    }
+}
+
+fn german(message: Message) -> &'static str {
+    match message {
+        Message::RoundHeader => "=== RUNDE {}/{} ===",
+        Message::StandingsAfterRoundHeader => "=== TABELLE NACH RUNDE {} ===",
+        Message::ResultsHeader => "=== ENDSTAND ===",
+        Message::PrizesHeader => "=== PREISE ===",
+        Message::AchievementsHeader => "=== AUSZEICHNUNGEN ===",
+        Message::WallChartHeader => "=== KREUZTABELLE ===",
+        Message::MatchTimesHeader => "=== SPIELDAUER ===",
+        Message::Seed => "Startwert: {}",
+        Message::RoundLength => "Rundenlänge: {} Minuten",
+        Message::PairingPrompt => "PAARUNG:",
+        Message::ResultHelp => {
+            "Ergebnis eingeben, z. B. 2-1 oder 2:0 (1 draw), id für ein vereinbartes \
+             Unentschieden, bye 1 oder bye 2, wenn der Gegner nicht erschienen ist, c 1 oder c 2 \
+             zum Aufgeben oder s für die Tabelle"
+        }
+        Message::PlaysFirst => "(beginnt)",
+        Message::FirstWinRate => "Der beginnende Spieler gewann {} von {} Matches ({} %)",
+        Message::Table => "Tisch {}: {} gegen {}",
+        Message::TableResult => "Tisch {}: {} {} {}",
+        Message::Concedes => "Tisch {}: {} gibt auf",
+        Message::NoDeadline => "Das Turnier hat keine Rundenlänge oder keine Ergebnisfrist",
+        Message::DeadlinePending => "Die Ergebnisse sind in {} Minuten fällig",
+        Message::DoubleLossConfirm => {
+            "Für beide Spieler an diesen {} Tischen eine Niederlage eintragen? [j/N]"
+        }
+        Message::DeadlineDrawConfirm => "An diesen {} Tischen ein Unentschieden eintragen? [j/N]",
+        Message::DeadlineRecorded => "{} überfällige Ergebnisse eingetragen",
+        Message::Rematch => "(erneute Begegnung)",
+        Message::Featured => "(Topspiel)",
+        Message::Bye => "Freilos: {}",
+        Message::RequestedBye => "Beantragtes Freilos: {}",
+        Message::ByeGranted => "{} hat in Runde {} ein Freilos",
+        Message::PairingAvoided => "{} und {} werden nie gegeneinander gepaart",
+        Message::PairingAllowed => "{} und {} können wieder gegeneinander gepaart werden",
+        Message::PairingPreferred => "{} und {} werden in der ersten Runde gepaart, wenn möglich",
+        Message::ByeOverLimit => "{} hat jetzt {} Freilose, mehr als die erlaubten {}",
+        Message::ByeWithheld => {
+            "{} setzt aus: alle verfügbaren Spieler hatten schon die Höchstzahl an Freilosen"
+        }
+        Message::Finished => "Das Turnier ist beendet.",
+        Message::Created => "Turnier mit {} Spielern und {} Runden erstellt",
+        Message::Imported => "{} Spieler und {} von {} Runden importiert",
+        Message::Standings => "Tabelle",
+        Message::Round => "Runde {}",
+        Message::Rank => "Platz",
+        Message::Name => "Name",
+        Message::Record => "S-N-U",
+        Message::Streak => "Serie",
+        Message::Form => "Form",
+        Message::TableColumn => "Tisch",
+        Message::Home => "Heim",
+        Message::Away => "Gast",
+        Message::Result => "Ergebnis",
+        Message::Prizes => "Preise",
+        Message::Achievements => "Auszeichnungen",
+        Message::Penalties => "Strafen",
+        Message::Page => "Seite {}",
+        Message::Players => "Spieler: {}",
+        Message::RoundsOf => "Runden: {} von {}",
+        Message::PairingsFormat => "Paarungen: {}, Best of {}",
+        Message::Swiss => "Schweizer System",
+        Message::RoundRobin => "Jeder gegen jeden",
+        Message::Danish => "Dänisches System",
+        Message::Monrad => "Monrad-System",
+        Message::ByeCounts => "Freilos: zählt als {}",
+        Message::Win => "Sieg",
+        Message::Draw => "Unentschieden",
+        Message::Loss => "Niederlage",
+        Message::Conceded => "(aufgegeben)",
+        Message::TimeCalled => "(Zeit)",
+        Message::ResultsMissing => "Ergebnisse der Runde {} fehlen noch:",
+        Message::NoSuchTable => "In der aktuellen Runde gibt es keinen Tisch {}!",
+        Message::NotAtTable => "{} spielt nicht an Tisch {}!",
+        Message::ErrorRecordingResult => "Fehler beim Eintragen des Ergebnisses: {}",
+        Message::ErrorFeaturing => "Fehler beim Hervorheben von Tisch {}: {}",
+        Message::CouldNotReadInput => "Eingabe konnte nicht gelesen werden!",
+        Message::CouldNotParseScore => "Ergebnis nicht lesbar: {}",
+        Message::TooManyWins => "{} gewonnene Spiele, aber das Match endet nach {} Siegen",
+        Message::TooManyGames => "{} Spiele gespielt, aber das Match hat höchstens {}",
+        Message::NoGames => "Mindestens ein Spiel muss gespielt worden sein",
+        Message::StateExists => "{} existiert bereits! Mit --force wird es ersetzt.",
+        Message::ConfigNotApplied => "Konfiguration nicht übernommen: {}",
+        Message::PairingNotFound => "Paarung nicht gefunden!",
+        Message::ScoreOutOfRange => "Ergebnis außerhalb des gültigen Bereichs!",
+        Message::ResultAlreadyReported => "Ergebnis bereits eingetragen!",
+        Message::NoResultYet => "Noch kein Ergebnis eingetragen!",
+        Message::TournamentFinished => "Das Turnier ist bereits beendet!",
+        Message::RoundClosed => "Die Runde dieser Paarung ist bereits vorbei!",
+        Message::ReadOnly => "Das Turnier wurde schreibgeschützt geöffnet!",
+        Message::ResultsMerged => "{} Ergebnis(se) übernommen.",
+        Message::MergeConflict => "Tisch {}: hier {}, in der anderen Datei aber {}",
+        Message::ServingTerminals => {
+            "Warte auf Ergebnisse anderer Terminals auf Port {}, die sich mit Token {} verbinden"
+        }
+        Message::RemotePrompt => "Tisch und Ergebnis, z. B. 3 2-1 (leer zum Aktualisieren):",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Player, Tournament};
+
+    #[test]
+    fn translations_are_complete() {
+        for &message in &Message::ALL {
+            let english = Locale::English.text(message);
+            let german = Locale::German.text(message);
+            assert!(!german.is_empty(), "{:?}", message);
+            assert_eq!(
+                english.matches("{}").count(),
+                german.matches("{}").count(),
+                "{:?}",
+                message
+            );
+        }
+
+        assert_eq!(
+            Locale::English.format(Message::RoundsOf, &[&2]),
+            "Rounds: 2 of "
+        );
+        assert_eq!("de".parse(), Ok(Locale::German));
+        assert!("fr".parse::<Locale>().is_err());
+    }
+
+    #[test]
+    fn pages_follow_the_locale() {
+        let players = (1..5)
+            .map(|i| Player::new(format!("Player {}", i).as_str()))
+            .collect();
+        let mut tourn = Tournament::with_players(players).unwrap();
+        tourn.locale = Locale::German;
+        tourn.next_round().unwrap();
+
+        let page = tourn.spectator_html(30);
+        assert!(page.contains("<h2>Runde 1/2</h2>"));
+        assert!(page.contains("<h2>Tabelle</h2>"));
+        assert!(page.contains("<th>Platz</th>"));
+
+        let loaded = Tournament::from_json(&tourn.to_json()).unwrap();
+        assert_eq!(loaded.locale, Locale::German);
+    }
+}
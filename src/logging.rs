@@ -0,0 +1,22 @@
+//! Structured logging of pairing decisions with `tracing`, if the `logging` feature is enabled:
+//! each round is a span with its number and seed, and the events in it tell which players were
+//! available, who got the bye and why, how many rematches pairing greedily left and whether the
+//! search or the oracle found a better pairing, and finally each pairing. Without the feature,
+//! the macros expand to nothing.
+
+/// Emits a debug event, taking the same arguments as `tracing::debug!`
+macro_rules! debug_event {
+    ($($arg:tt)*) => {{
+        #[cfg(feature = "logging")]
+        tracing::debug!($($arg)*);
+    }};
+}
+
+/// Enters a debug span until the end of the enclosing block, taking the same arguments as
+/// `tracing::debug_span!`
+macro_rules! debug_span {
+    ($($arg:tt)*) => {
+        #[cfg(feature = "logging")]
+        let _span = tracing::debug_span!($($arg)*).entered();
+    };
+}
@@ -0,0 +1,192 @@
+//! Finding players by name, so that organizers can refer to players the way they know them
+//! instead of by UUID. Names are matched ignoring case, by prefix, and finally allowing for typos.
+
+use crate::{PlayerView, Tournament};
+use std::fmt;
+
+/// Reasons why a name doesn't lead to a single player
+#[derive(Clone, Debug, PartialEq)]
+pub enum LookupError {
+    /// No name comes close
+    NotFound(String),
+    /// Several players match equally well
+    Ambiguous(Vec<PlayerView>),
+}
+
+impl std::error::Error for LookupError {}
+
+impl fmt::Display for LookupError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LookupError::NotFound(name) => write!(f, "No player named {}!", name),
+            LookupError::Ambiguous(candidates) => {
+                let names: Vec<&str> = candidates.iter().map(|p| p.name.as_str()).collect();
+                write!(f, "Several players match: {}", names.join(", "))
+            }
+        }
+    }
+}
+
+/// Number of single-character insertions, deletions, or substitutions to turn one string into
+/// the other
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = diagonal + usize::from(ca != *cb);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(diagonal + 1);
+        }
+    }
+
+    row[b.len()]
+}
+
+/// Picks the single candidate, or reports all of them as ambiguous. Returns `None` without
+/// candidates.
+fn single(mut candidates: Vec<PlayerView>) -> Option<Result<PlayerView, LookupError>> {
+    match candidates.len() {
+        0 => None,
+        1 => Some(Ok(candidates.remove(0))),
+        _ => Some(Err(LookupError::Ambiguous(candidates))),
+    }
+}
+
+impl Tournament {
+    /// Finds a player by name. Case is ignored, and the first of these that matches any player
+    /// decides:
+    ///
+    /// 1. The whole name, e.g. "alice smith"
+    /// 2. The start of the name or of any word in it, e.g. "ali" or "smi"
+    /// 3. The name or any word in it with a typo or two, e.g. "alise"
+    ///
+    /// Fails with `Ambiguous` listing the candidates if several players match equally well.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use swyss::lookup::LookupError;
+    /// use swyss::{Player, Tournament};
+    ///
+    /// let players = ["Alice Smith", "Alan Jones", "Bob"]
+    ///     .iter()
+    ///     .map(|name| Player::new(name))
+    ///     .collect();
+    /// let tourn = Tournament::with_players(players).unwrap();
+    ///
+    /// assert_eq!(tourn.find_player("bob").unwrap().name, "Bob");
+    /// assert_eq!(tourn.find_player("smi").unwrap().name, "Alice Smith");
+    /// assert_eq!(tourn.find_player("Jomes").unwrap().name, "Alan Jones");
+    /// assert!(matches!(tourn.find_player("al"), Err(LookupError::Ambiguous(c)) if c.len() == 2));
+    /// ```
+    pub fn find_player(&self, name: &str) -> Result<PlayerView, LookupError> {
+        let query = name.trim().to_lowercase();
+        let players: Vec<PlayerView> = self.players().collect();
+        let words = |p: &PlayerView| -> Vec<String> {
+            let name = p.name.to_lowercase();
+            let mut words: Vec<String> = name.split_whitespace().map(String::from).collect();
+            words.push(name);
+            words
+        };
+
+        let exact = players
+            .iter()
+            .filter(|p| p.name.to_lowercase() == query)
+            .cloned()
+            .collect();
+        if let Some(found) = single(exact) {
+            return found;
+        }
+
+        if !query.is_empty() {
+            let prefix = players
+                .iter()
+                .filter(|p| words(p).iter().any(|w| w.starts_with(&query)))
+                .cloned()
+                .collect();
+            if let Some(found) = single(prefix) {
+                return found;
+            }
+        }
+
+        // Allow one typo in short names and more in longer ones
+        let max_distance = (query.chars().count() / 4).max(1);
+        let distances: Vec<(usize, &PlayerView)> = players
+            .iter()
+            .map(|p| {
+                let distance = words(p)
+                    .iter()
+                    .map(|w| edit_distance(&query, w))
+                    .min()
+                    .unwrap_or(usize::MAX);
+                (distance, p)
+            })
+            .filter(|(distance, _)| *distance <= max_distance)
+            .collect();
+        let best = distances.iter().map(|(distance, _)| *distance).min();
+        let fuzzy = distances
+            .iter()
+            .filter(|(distance, _)| Some(*distance) == best)
+            .map(|(_, p)| (*p).clone())
+            .collect();
+
+        single(fuzzy).unwrap_or_else(|| Err(LookupError::NotFound(String::from(name))))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Player;
+
+    fn tournament(names: &[&str]) -> Tournament {
+        Tournament::with_players(names.iter().map(|name| Player::new(name)).collect()).unwrap()
+    }
+
+    #[test]
+    fn edit_distances() {
+        assert_eq!(edit_distance("", ""), 0);
+        assert_eq!(edit_distance("bob", "bob"), 0);
+        assert_eq!(edit_distance("bob", "rob"), 1);
+        assert_eq!(edit_distance("alcie", "alice"), 2);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("", "abc"), 3);
+    }
+
+    #[test]
+    fn exact_match_beats_prefix() {
+        let tourn = tournament(&["Ann", "Anna", "Annabel"]);
+        assert_eq!(tourn.find_player("ANN").unwrap().name, "Ann");
+        assert_eq!(tourn.find_player("annab").unwrap().name, "Annabel");
+
+        match tourn.find_player("anna") {
+            Ok(p) => assert_eq!(p.name, "Anna"),
+            Err(e) => panic!("{}", e),
+        }
+    }
+
+    #[test]
+    fn ambiguous_and_missing() {
+        let tourn = tournament(&["Bob", "Rob", "Carol"]);
+
+        // One typo away from both
+        match tourn.find_player("Hob") {
+            Err(LookupError::Ambiguous(candidates)) => {
+                let mut names: Vec<_> = candidates.iter().map(|p| p.name.as_str()).collect();
+                names.sort_unstable();
+                assert_eq!(names, vec!["Bob", "Rob"]);
+            }
+            other => panic!("unexpected lookup result {:?}", other),
+        }
+
+        assert_eq!(
+            tourn.find_player("Dave"),
+            Err(LookupError::NotFound(String::from("Dave")))
+        );
+        assert_eq!(tourn.find_player("carl").unwrap().name, "Carol");
+    }
+}
@@ -1,195 +1,2163 @@
-#[macro_use]
-extern crate prettytable;
 use clap::Clap;
-use prettytable::Table;
-use std::cell::RefCell;
+use std::collections::HashMap;
 use std::fs;
 use std::io;
-use std::io::Write;
-use std::path::Path;
+use std::io::{BufRead, Write};
+use std::net::IpAddr;
+use std::path::{Path, PathBuf};
 use std::process::exit;
-use std::process::Command;
 use std::rc::Rc;
-use std::thread;
+use std::str::FromStr;
+use std::sync::OnceLock;
+use swyss::color::{Colors, Style};
+use swyss::entry::{ResultInput, ScoreError};
+use swyss::locale::{Locale, Message};
+use swyss::texttable::{Align, TextTable, MAX_NAME_WIDTH};
 use swyss::*;
 
 #[derive(Clap)]
 struct Opts {
+    /// The tournament file that `new`, `pair`, `report`, `bye`, `standings`, `opponents`,
+    /// `explain` and `export` work on
+    #[clap(long, default_value = "tournament.json")]
+    state: String,
+    /// Don't color the output. Colors are also off if the NO_COLOR environment variable is set or
+    /// the output isn't a terminal.
+    #[clap(long)]
+    no_color: bool,
+    #[clap(subcommand)]
+    command: SubCommand,
+}
+
+#[derive(Clap)]
+enum SubCommand {
+    /// Run a whole tournament interactively in one sitting
+    Run(RunOpts),
+    /// Create a new tournament from a file of player names, one per line
+    New(NewOpts),
+    /// Continue an event started in other pairing software from its FIDE TRF file
+    Import(ImportOpts),
+    /// Pair the next round and print its tables
+    Pair(PairOpts),
+    /// Record the result of a table of the current round, e.g. "2-1", or "1-1-1" with a drawn game
+    Report(ReportOpts),
+    /// Feature a table of the current round, e.g. the match shown on stream, and move it to the
+    /// featured table
+    Feature(FeatureOpts),
+    /// End the tournament after the current round, e.g. when time runs out before all rounds are
+    /// played, and print the final standings
+    Finish,
+    /// Once the result deadline of the current round has passed, record the outcome set in the
+    /// configuration for every table still missing a result, after asking for confirmation
+    Deadline(DeadlineOpts),
+    /// Take over the results of the current round that were entered on a copy of the
+    /// tournament, e.g. by a second judge on another laptop, and list results that differ
+    Merge(MergeOpts),
+    /// Let other terminals enter results of the current round with `connect`, recording them in
+    /// the order they arrive, until stopped with Ctrl-C
+    Serve(ServeOpts),
+    /// Enter results on a tournament served by `serve` on another machine
+    Connect(ConnectOpts),
+    /// Request a bye for a player in a future round, so they aren't paired in it
+    Bye(ByeOpts),
+    /// Never pair two players against each other, e.g. family members
+    Avoid(AvoidOpts),
+    /// Pair two players against each other in the first round if possible
+    Prefer(PreferOpts),
+    /// Print the current standings
+    Standings,
+    /// Print a player's past opponents and results, or whether two players already played
+    Opponents(OpponentsOpts),
+    /// Explain which tiebreaker separates a player from the players ranked directly above and
+    /// below them
+    Explain(ExplainOpts),
+    /// Export the tournament
+    Export(ExportOpts),
+    /// Export a past tournament from its saved file without changing it, e.g. to regenerate
+    /// its wall chart or PDF report after the fact
+    Recap(RecapOpts),
+    /// Play a whole tournament of synthetic players with random results, e.g. to try out swyss or
+    /// to time pairing and ranking of large fields
+    Demo(DemoOpts),
+}
+
+#[derive(Clap)]
+struct RunOpts {
     #[clap(short, long, parse(from_occurrences))]
     img: i32,
+    /// Save the finished tournament to this file, e.g. to add it to an archive
+    #[clap(short, long)]
+    save: Option<String>,
+    /// Settings for a kind of event: "mtg-competitive", "chess-club", "image-bracket", or
+    /// "casual". Other flags and the configuration file override them.
+    #[clap(long)]
+    preset: Option<presets::Preset>,
+    /// How players are paired: "swiss", "danish", "monrad", or "round-robin". Defaults to Swiss.
+    #[clap(long)]
+    system: Option<PairingSystem>,
+    /// Decide who goes first in each pairing: "random" or "seed" (better standing goes first)
+    #[clap(long)]
+    first: Option<FirstPlayer>,
+    /// Number of games per match, e.g. 1 for best-of-one. Defaults to best-of-three.
+    #[clap(long)]
+    best_of: Option<MatchFormat>,
+    /// Language of prompts, headers, and errors: "en" or "de"
+    #[clap(long)]
+    locale: Option<Locale>,
+    /// Read settings from this TOML file. The file is re-read before every round, so settings
+    /// like the round length can be changed while the tournament is running.
+    #[clap(short, long)]
+    config: Option<String>,
+    /// Append every result to this journal as it is entered. If the journal already exists, it
+    /// is replayed first, so an interrupted tournament continues where it left off.
+    #[clap(short, long)]
+    journal: Option<String>,
+    /// Serve a page with the current pairings and standings on this port, so that players can
+    /// check them on their phones. The page reloads itself every 30 seconds.
+    #[clap(long)]
+    serve_standings: Option<u16>,
+    /// Also save a QR code linking to the standings page to this image file, e.g. "qr.png", to
+    /// post it at the venue
+    #[cfg(feature = "qr")]
+    #[clap(long, requires = "serve-standings")]
+    standings_qr: Option<String>,
+    /// POST the pairings of every round and the final standings as JSON to this URL
+    #[cfg(feature = "webhooks")]
+    #[clap(long)]
+    webhook: Option<String>,
+    /// Append a line for every pairing, result, bye, and drop to this file as it happens, e.g. for
+    /// a text source of streaming software, or print them to standard output if it is "-"
+    #[clap(long)]
+    ticker: Option<String>,
+    /// Keep this file up to date with the top 8, the current round, and the top table as JSON for
+    /// broadcast overlays
+    #[clap(long)]
+    overlay: Option<String>,
+    /// Image viewer for image mode: "feh", "xdg-open", "preview", "windows", or "integrated" to
+    /// vote with the keys 1 and 2 in a window of swyss itself. Defaults to the
+    /// viewer set in the configuration file, or to the usual viewer of the platform.
+    #[clap(long)]
+    viewer: Option<viewer::ViewerKind>,
+    /// Read the results from this file instead of prompting for them, or from standard input if
+    /// it is "-". Each line holds "round,table,home,away" with optional drawn games at the end.
+    #[clap(long)]
+    results: Option<String>,
+    /// Ratings from before the event, as saved by swyss, to find the biggest upset for the
+    /// achievements at the end
+    #[clap(long)]
+    ratings: Option<String>,
+    /// File of player names, one per line, or a CSV file of names, ratings, and clubs
+    file: String,
+}
+
+#[derive(Clap)]
+struct NewOpts {
+    /// Settings for a kind of event: "mtg-competitive", "chess-club", "image-bracket", or
+    /// "casual". Other flags and the configuration file override them.
+    #[clap(long)]
+    preset: Option<presets::Preset>,
+    /// How players are paired: "swiss", "danish", "monrad", or "round-robin". Defaults to Swiss.
+    #[clap(long)]
+    system: Option<PairingSystem>,
+    /// Decide who goes first in each pairing: "random" or "seed" (better standing goes first)
+    #[clap(long)]
+    first: Option<FirstPlayer>,
+    /// Number of games per match, e.g. 1 for best-of-one. Defaults to best-of-three.
+    #[clap(long)]
+    best_of: Option<MatchFormat>,
+    /// Language of prompts, headers, and errors: "en" or "de"
+    #[clap(long)]
+    locale: Option<Locale>,
+    /// Read settings from this TOML file
+    #[clap(short, long)]
+    config: Option<String>,
+    /// Name of the event, which is carried into exports and reports
+    #[clap(long)]
+    name: Option<String>,
+    /// Date of the event, e.g. "2024-05-18"
+    #[clap(long)]
+    date: Option<String>,
+    /// What is played, e.g. the game or its format
+    #[clap(long)]
+    game_format: Option<String>,
+    /// Who organizes the event
+    #[clap(long)]
+    organizer: Option<String>,
+    /// Where the event takes place
+    #[clap(long)]
+    location: Option<String>,
+    /// Replace an existing tournament file
+    #[clap(long)]
+    force: bool,
+    /// Read the file as a participant list in the CSV layout of Challonge or start.gg
+    #[clap(long)]
+    participants: bool,
+    /// Add a house player with this name, who is paired instead of granting byes
+    #[clap(long)]
+    house_player: Option<String>,
+    /// File of player names, one per line, or a CSV file of names, ratings, and clubs
+    file: String,
+}
+
+#[derive(Clap)]
+struct ImportOpts {
+    /// Replace an existing tournament file
+    #[clap(long)]
+    force: bool,
+    /// The TRF file exported by the other software
+    file: String,
+}
+
+#[derive(Clap)]
+struct DeadlineOpts {
+    /// Record the outcome without asking for confirmation
+    #[clap(short, long)]
+    yes: bool,
+}
+
+#[derive(Clap)]
+struct PairOpts {
+    /// Only print the pairings the next round would have, without pairing it. Run again to
+    /// re-roll them.
+    #[clap(long)]
+    preview: bool,
+    /// Derive the pairings from this seed, e.g. to publish the pairings of a preview
+    #[clap(long)]
+    seed: Option<u64>,
+}
+
+#[derive(Clap)]
+struct ReportOpts {
+    /// Correct a result that was already reported
+    #[clap(long)]
+    amend: bool,
+    /// Time was called during an unfinished game, which counts as a drawn game. The score is
+    /// that of the games finished before, e.g. "1-0" for a match win or "1-1" for a draw.
+    #[clap(long, conflicts_with = "amend")]
+    time: bool,
+    /// The player who concedes the match, by player number or name, instead of a score
+    #[clap(long, conflicts_with_all = &["amend", "time"])]
+    concede: Option<String>,
+    /// The table number as printed by `pair`, or the UUID of the pairing
+    table: String,
+    /// Games won by the home and away player, and optionally drawn games, e.g. "2-1" or "1-1-1"
+    #[clap(required_unless = "concede")]
+    score: Option<String>,
+}
+
+#[derive(Clap)]
+struct FeatureOpts {
+    /// The table number as printed by `pair`, or the UUID of the pairing
+    table: String,
+}
+
+#[derive(Clap)]
+struct MergeOpts {
+    /// The copy of the tournament to take results from
+    file: String,
+}
+
+#[derive(Clap)]
+struct ServeOpts {
+    /// Port to accept terminals on
+    #[clap(long, default_value = "7878")]
+    port: u16,
+    /// Address to accept terminals on; "0.0.0.0" lets in other machines
+    #[clap(long, default_value = "127.0.0.1")]
+    bind: IpAddr,
+}
+
+#[derive(Clap)]
+struct ConnectOpts {
+    /// The machine running `serve`, e.g. "192.168.0.10", or "192.168.0.10:7878" with a port
+    host: String,
+    /// The token `serve` printed when it started
+    #[clap(long)]
+    token: String,
+    /// Language of the prompt and tables: "en" or "de"
+    #[clap(long)]
+    locale: Option<Locale>,
+}
+
+#[derive(Clap)]
+struct AvoidOpts {
+    /// Allow the two players to be paired again instead
+    #[clap(long)]
+    cancel: bool,
+    /// Name or player number of the first player
+    first: String,
+    /// Name or player number of the second player
+    second: String,
+}
+
+#[derive(Clap)]
+struct PreferOpts {
+    /// Name or player number of the first player
+    first: String,
+    /// Name or player number of the second player
+    second: String,
+}
+
+#[derive(Clap)]
+struct ByeOpts {
+    /// Withdraw the request instead
+    #[clap(long)]
+    cancel: bool,
+    /// What the bye is worth: "half" (a drawn match), "zero", or "win"
+    #[clap(long, default_value = "half")]
+    score: ByeScore,
+    /// Name or player number of the player
+    player: String,
+    /// The round the player will miss
+    round: u32,
+}
+
+#[derive(Clap)]
+struct OpponentsOpts {
+    /// Name or player number of the player
+    player: String,
+    /// Name or player number of another player, to only show matches between the two
+    other: Option<String>,
+}
+
+#[derive(Clap)]
+struct ExplainOpts {
+    /// Name or player number of the player
+    player: String,
+}
+
+#[derive(Clap)]
+struct ExportOpts {
+    /// "tournament" (the whole tournament as JSON), "standings" (JSON), "wallchart" (text),
+    /// "html" (wall chart as an HTML table), "progression" (each player's rank after every
+    /// round as text), "strength" (each player's opponents' record and match points as text),
+    /// "strength-html" (the same as an HTML table), "dot" or "graphml" (who played whom in which
+    /// round as a graph), "challonge" (results as CSV for bulk upload to Challonge or start.gg)
+    /// or, with the `pdf` feature, "pdf" (a printable event report)
+    #[clap(long, default_value = "standings")]
+    format: ExportFormat,
+    /// Write to this file instead of standard output
+    output: Option<String>,
+}
+
+#[derive(Clap)]
+struct RecapOpts {
+    /// The saved tournament, e.g. a file of an archive
     file: String,
+    #[clap(flatten)]
+    export: ExportOpts,
+}
+
+#[derive(Clap)]
+struct DemoOpts {
+    /// Number of players
+    #[clap(long, default_value = "64")]
+    players: u32,
+    /// How far the players' ratings spread around 1500. Higher rated players are more likely to
+    /// win; with a spread of 0, every match is a coin flip.
+    #[clap(long, default_value = "200")]
+    spread: f64,
+    /// Seed for the players' ratings and all results, to play the same tournament again
+    #[clap(long)]
+    seed: Option<u64>,
+    /// Save the finished tournament to this file
+    #[clap(short, long)]
+    save: Option<String>,
+}
+
+enum ExportFormat {
+    Tournament,
+    Standings,
+    WallChart,
+    Html,
+    Progression,
+    Strength,
+    StrengthHtml,
+    Dot,
+    GraphMl,
+    Challonge,
+    #[cfg(feature = "pdf")]
+    Pdf,
+}
+
+impl FromStr for ExportFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<ExportFormat, String> {
+        match s.to_lowercase().as_str() {
+            "tournament" => Ok(ExportFormat::Tournament),
+            "standings" => Ok(ExportFormat::Standings),
+            "wallchart" => Ok(ExportFormat::WallChart),
+            "html" => Ok(ExportFormat::Html),
+            "progression" => Ok(ExportFormat::Progression),
+            "strength" => Ok(ExportFormat::Strength),
+            "strength-html" => Ok(ExportFormat::StrengthHtml),
+            "dot" => Ok(ExportFormat::Dot),
+            "graphml" => Ok(ExportFormat::GraphMl),
+            "challonge" => Ok(ExportFormat::Challonge),
+            #[cfg(feature = "pdf")]
+            "pdf" => Ok(ExportFormat::Pdf),
+            _ => Err(format!("Unknown export format: {}", s)),
+        }
+    }
 }
 
-/// Prompts and reads the score for a single player from the command line. Inputs that can't be
-/// parsed into scores are rejected immediately, while inputs that are valid integers but invalid
-/// scores will be rejected by the pairing after both scores have been entered.
-fn read_score(num: u32, name: &String) -> Result<u8, String> {
-    print!("[{}] {} > ", num, name);
-    io::stdout().flush().unwrap();
+/// Colors of standard output and standard error, decided once at startup
+static COLORS: OnceLock<(Colors, Colors)> = OnceLock::new();
 
-    let mut score = String::new();
+/// Returns text for standard output in the given style, if colors are on
+fn paint(style: Style, text: &str) -> String {
+    COLORS
+        .get()
+        .copied()
+        .unwrap_or_default()
+        .0
+        .paint(style, text)
+}
+
+/// Returns text for standard error in the given style, if colors are on
+fn paint_error(text: &str) -> String {
+    COLORS
+        .get()
+        .copied()
+        .unwrap_or_default()
+        .1
+        .paint(Style::Red, text)
+}
 
-    match io::stdin().read_line(&mut score) {
-        Ok(_) => {}
-        Err(_) => return Err(String::from("Could not read input!")),
+/// Prints an error to standard error, in red if colors are on
+macro_rules! error {
+    ($($arg:tt)*) => {
+        eprintln!("{}", paint_error(&format!($($arg)*)))
     };
+}
 
-    let score = match score.trim().parse() {
-        Ok(s) => s,
-        Err(_) => return Err(String::from("Could not parse score into integer!")),
+/// Prompts for and reads the result of a pairing, whose players may be named instead of
+/// numbered. Inputs that can't be parsed are rejected with the reason, while scores are only
+/// checked by the caller, against the format of the match. Quits when the input ends or is
+/// interrupted with Ctrl-C.
+fn read_result(
+    prompt: &mut prompt::Prompt,
+    home: &str,
+    away: &str,
+    locale: Locale,
+) -> Result<ResultInput, String> {
+    let input = match prompt.read("> ") {
+        Ok(Some(input)) => input,
+        Ok(None) => exit(130),
+        Err(_) => return Err(String::from(locale.text(Message::CouldNotReadInput))),
     };
 
-    Ok(score)
+    ResultInput::parse_with_names(&input, home, away).map_err(|e: ScoreError| e.text(locale))
 }
 
-pub fn main() -> io::Result<()> {
-    let opts = Opts::parse();
+/// Re-reads the configuration file and applies it to the tournament. Changes that aren't allowed
+/// at this point of the tournament are rejected and the previous settings are kept.
+fn reload_config(tourn: &mut Tournament, path: &str) {
+    let config = match config::Config::load(path) {
+        Ok(c) => c,
+        Err(e) => {
+            error!("{}", e);
+            return;
+        }
+    };
 
-    let filename = opts.file;
+    match tourn.apply_config(&config) {
+        Ok(changes) => {
+            for change in changes {
+                println!("{}", change);
+            }
+        }
+        Err(e) => error!(
+            "{}",
+            tourn.locale().format(Message::ConfigNotApplied, &[&e])
+        ),
+    }
+}
+
+/// Records a result, appending it to the journal if there is one. Returns whether the result
+/// was accepted.
+fn record_result(
+    tourn: &mut Tournament,
+    uuid: uuid::Uuid,
+    (home_score, away_score, drawn): Score,
+    journal: &mut Option<journal::Journal>,
+) -> bool {
+    if let Some(pair) = tourn.pairings().get(&uuid) {
+        if let Err(e) = entry::validate(pair.format(), home_score, away_score, drawn) {
+            error!(
+                "{}",
+                tourn
+                    .locale()
+                    .format(Message::ErrorRecordingResult, &[&e.text(tourn.locale())])
+            );
+            return false;
+        }
+    }
+
+    if let Err(e) = tourn.end_match(uuid, home_score, away_score, drawn) {
+        error!("{}", result_error(tourn, &e));
+        return false;
+    }
+
+    append_to_journal(tourn, uuid, (home_score, away_score, drawn), journal);
+    true
+}
+
+/// Records that a player concedes, appending the resulting score to the journal if there is
+/// one. Returns whether the concession was accepted.
+fn record_concession(
+    tourn: &mut Tournament,
+    uuid: uuid::Uuid,
+    side: PlayerSide,
+    journal: &mut Option<journal::Journal>,
+) -> bool {
+    match tourn.concede(uuid, side) {
+        Ok((home_score, away_score)) => {
+            append_to_journal(tourn, uuid, (home_score, away_score, 0), journal);
+            true
+        }
+        Err(e) => {
+            error!("{}", result_error(tourn, &e));
+            false
+        }
+    }
+}
+
+/// Describes why a result couldn't be recorded, in the tournament's locale
+fn result_error(tourn: &Tournament, e: &PairingResultError) -> String {
+    tourn.locale().format(
+        Message::ErrorRecordingResult,
+        &[&tourn.locale().text(e.message())],
+    )
+}
+
+/// Appends a recorded result to the journal, if there is one
+fn append_to_journal(
+    tourn: &Tournament,
+    uuid: uuid::Uuid,
+    (home_score, away_score, drawn): Score,
+    journal: &mut Option<journal::Journal>,
+) {
+    if let Some(journal) = journal.as_mut() {
+        let entry = tourn
+            .journal_entry(uuid, home_score, away_score, drawn)
+            .expect("the pairing belongs to the current round");
+        if let Err(e) = journal.append(&entry) {
+            error!("Could not write to journal: {}", e);
+        }
+    }
+}
+
+/// Prompts for the results of the given pairings and records them, appending each accepted
+/// result to the journal if there is one. In image mode, the images of each pairing are shown
+/// with the viewer while its result is entered. Viewers that support voting decide the pairing
+/// themselves, with the chosen image winning 1-0.
+fn enter_results(
+    tourn: &mut Tournament,
+    pairing: &[(uuid::Uuid, String, String)],
+    prompt: &mut prompt::Prompt,
+    viewer: &mut Option<Box<dyn viewer::ImageViewer>>,
+    image_paths: &HashMap<String, PathBuf>,
+    journal: &mut Option<journal::Journal>,
+) {
+    for (uuid, home, away) in pairing {
+        let mut read = true;
+
+        let uuid = *uuid;
+
+        if let Some(viewer) = viewer.as_mut() {
+            for (name, side) in &[(home, PlayerSide::Home), (away, PlayerSide::Away)] {
+                let file = &image_paths[*name];
+                if let Err(e) = viewer.show(file, *side) {
+                    error!("Could not show {}: {}", file.display(), e);
+                }
+            }
+
+            match viewer.vote() {
+                Ok(Some(PlayerSide::Home)) => {
+                    read = !record_result(tourn, uuid, (1, 0, 0), journal)
+                }
+                Ok(Some(PlayerSide::Away)) => {
+                    read = !record_result(tourn, uuid, (0, 1, 0), journal)
+                }
+                Ok(None) => {}
+                Err(e) => error!("Could not vote in the viewer: {}", e),
+            }
+        }
+
+        let plays_first = format!(" {}", tourn.locale().text(Message::PlaysFirst));
+        let (home_first, away_first) = match tourn.pairings()[&uuid].first_player() {
+            Some(PlayerSide::Home) => (plays_first.as_str(), ""),
+            Some(PlayerSide::Away) => ("", plays_first.as_str()),
+            None => ("", ""),
+        };
+
+        let format = tourn.pairings()[&uuid].format();
+        prompt.complete_names(&[home, away]);
+        while read {
+            println!(
+                "\n{}\n[1] {}{}\n[2] {}{}\n{}",
+                tourn.locale().text(Message::PairingPrompt),
+                home,
+                home_first,
+                away,
+                away_first,
+                tourn.locale().text(Message::ResultHelp)
+            );
+
+            let input = match read_result(prompt, home, away, tourn.locale()) {
+                Ok(input) => input,
+                Err(e) => {
+                    error!("{}", e);
+                    continue;
+                }
+            };
+
+            // Rejected inputs don't set `read` to `false`, resulting in another prompt
+            match input {
+                ResultInput::Standings => print_standings(tourn),
+                ResultInput::Concede(side) => {
+                    read = !record_concession(tourn, uuid, side, journal);
+                }
+                ResultInput::Bye(side) => {
+                    let absent = match side {
+                        PlayerSide::Home => PlayerSide::Away,
+                        PlayerSide::Away => PlayerSide::Home,
+                    };
+                    read = !record_concession(tourn, uuid, absent, journal);
+                }
+                _ => {
+                    let scores = input.scores(format).expect("the input is a score");
+                    read = !record_result(tourn, uuid, scores, journal);
+                }
+            }
+        }
+
+        if let Some(viewer) = viewer.as_mut() {
+            if let Err(e) = viewer.close_all() {
+                error!("Could not close images: {}", e);
+            }
+        }
+    }
+}
+
+/// Numbered lines of a results file
+type ResultLines = Box<dyn Iterator<Item = (usize, io::Result<String>)>>;
+
+/// Opens a results file, or standard input for "-"
+fn open_results(path: &str) -> io::Result<ResultLines> {
+    let reader: Box<dyn BufRead> = match path {
+        "-" => Box::new(io::BufReader::new(io::stdin())),
+        path => Box::new(io::BufReader::new(fs::File::open(path)?)),
+    };
 
-    let img = match opts.img {
-        0 => false,
-        _ => true,
+    Ok(Box::new(reader.lines().enumerate()))
+}
+
+/// Parses a line like "2,1,2,1" or "2,3,1,1,1" into the round, the table, and the score
+fn parse_result_line(line: &str, locale: Locale) -> Result<(u32, usize, Score), String> {
+    let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+    if fields.len() < 2 {
+        return Err(format!("Expected round,table,home,away[,draws]: {}", line));
+    }
+
+    let round = fields[0]
+        .parse()
+        .map_err(|_| format!("Could not parse round: {}", fields[0]))?;
+    let table = fields[1]
+        .parse()
+        .map_err(|_| format!("Could not parse table: {}", fields[1]))?;
+
+    Ok((
+        round,
+        table,
+        parse_score(&fields[2..].join("-"), false, locale)?,
+    ))
+}
+
+/// Reads results from the results file until every table of the current round has one. Blank
+/// lines and lines starting with '#' are skipped. Exits if a line is invalid, belongs to another
+/// round, or the file ends before the round is complete.
+fn read_results(
+    tourn: &mut Tournament,
+    results: &mut ResultLines,
+    journal: &mut Option<journal::Journal>,
+) {
+    let tables = tables(tourn);
+
+    while tourn
+        .round(tourn.current_round())
+        .is_some_and(|round| !round.is_complete())
+    {
+        let (num, line) = match results.next() {
+            Some((num, Ok(line))) => (num + 1, line),
+            Some((_, Err(e))) => {
+                error!("Could not read results: {}", e);
+                exit(1);
+            }
+            None => {
+                error!("Missing results for round {}!", tourn.current_round());
+                exit(1);
+            }
+        };
+
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let (round, table, score) = match parse_result_line(line, tourn.locale()) {
+            Ok(result) => result,
+            Err(e) => {
+                error!("Line {}: {}", num, e);
+                exit(1);
+            }
+        };
+
+        if round != tourn.current_round() {
+            error!(
+                "Line {}: expected a result for round {}, got round {}",
+                num,
+                tourn.current_round(),
+                round
+            );
+            exit(1);
+        }
+
+        let pair = match table.checked_sub(1).and_then(|i| tables.get(i)) {
+            Some(pair) => pair,
+            None => {
+                error!(
+                    "Line {}: there is no table {} in round {}",
+                    num, table, round
+                );
+                exit(1);
+            }
+        };
+
+        if !record_result(tourn, pair.uuid, score, journal) {
+            error!("Line {}: result rejected", num);
+            exit(1);
+        }
+
+        println!("{}", result_line(tourn, table, pair, score));
+    }
+}
+
+/// Describes the result of a table, e.g. "Table 3: Alice 2-1 Bob", with the winner in green.
+/// Drawn games are only shown if there were any, e.g. "Table 2: Alice 1-1-1 Dave".
+fn result_line(
+    tourn: &Tournament,
+    table: usize,
+    pair: &history::RoundPairing,
+    score: Score,
+) -> String {
+    let (home_score, away_score, drawn) = score;
+    let (mut home, mut away) = (name(tourn, pair.home), name(tourn, pair.away));
+    match home_score.cmp(&away_score) {
+        std::cmp::Ordering::Greater => home = paint(Style::Green, &home),
+        std::cmp::Ordering::Less => away = paint(Style::Green, &away),
+        std::cmp::Ordering::Equal => {}
+    }
+    let score = match drawn {
+        0 => format!("{}-{}", home_score, away_score),
+        _ => format!("{}-{}-{}", home_score, away_score, drawn),
+    };
+    tourn
+        .locale()
+        .format(Message::TableResult, &[&table, &home, &score, &away])
+}
+
+/// Prints a QR code linking to the standings page, and saves it to an image file if one is given
+#[cfg(feature = "qr")]
+fn print_qr_code(url: &str, file: &Option<String>) {
+    match qr::terminal_qr_code(url) {
+        Ok(code) => println!("\n{}", code),
+        Err(e) => error!("{}", e),
+    }
+
+    if let Some(file) = file {
+        match qr::save_qr_code(url, Path::new(file)) {
+            Ok(()) => println!("QR code saved to {}", file),
+            Err(e) => error!("{}", e),
+        }
+    }
+}
+
+/// Prints the errors of all webhook requests that failed since the last call
+#[cfg(feature = "webhooks")]
+fn report_webhook_errors(hook: &Option<Rc<webhook::Webhook>>) {
+    if let Some(hook) = hook {
+        for e in hook.take_errors() {
+            error!("Webhook request failed: {}", e);
+        }
+    }
+}
+
+/// Prints the errors of all ticker writes that failed since the last call
+fn report_ticker_errors(ticker: &Option<Rc<ticker::Ticker<Box<dyn Write>>>>) {
+    if let Some(ticker) = ticker {
+        for e in ticker.take_errors() {
+            error!("Could not write ticker: {}", e);
+        }
+    }
+}
+
+/// Prints the errors of all overlay writes that failed since the last call
+fn report_overlay_errors(overlay: &Option<Rc<overlay::OverlayFile>>) {
+    if let Some(overlay) = overlay {
+        for e in overlay.take_errors() {
+            error!("Could not write overlay: {}", e);
+        }
+    }
+}
+
+/// Reads the players from a file of names, one per line, or from a CSV file of names, ratings,
+/// and clubs in seeding order (see `swyss::roster`)
+fn read_players(filename: &str) -> Result<Vec<Player>, Box<dyn std::error::Error>> {
+    if filename.ends_with(".csv") {
+        return Ok(roster::load(filename)?);
+    }
+
+    let mut players: Vec<Player> = Vec::new();
+
+    let contents = fs::read_to_string(filename)?;
+
+    for line in contents.lines() {
+        // Maybe we could add checks for duplicate entries here
+        let p = Player::new(line.trim());
+        players.push(p);
+    }
+
+    Ok(players)
+}
+
+/// Loads the configuration file and applies it to a new tournament, exiting on errors. Returns
+/// the configuration for the settings that don't belong to the tournament.
+fn apply_config_or_exit(tourn: &mut Tournament, path: &str) -> config::Config {
+    let config = match config::Config::load(path) {
+        Ok(c) => c,
+        Err(e) => {
+            error!("{}", e);
+            exit(1);
+        }
     };
 
-    let mut players: Vec<Rc<RefCell<Player>>> = Vec::new();
+    if let Err(e) = tourn.apply_config(&config) {
+        error!("{}", e);
+        exit(1);
+    }
+
+    config
+}
+
+/// Prints the standings with their tiebreakers, each player's record as wins-losses-draws, and
+/// how their rank changed since the last round before the current one
+fn print_standings(tourn: &Tournament) {
+    let previous = tourn
+        .current_round()
+        .min(tourn.rounds())
+        .checked_sub(1)
+        .and_then(|round| tourn.standings_after(round))
+        .unwrap_or_default();
+
+    // Names are left-aligned, everything else is a number and right-aligned
+    let text = |message| tourn.locale().text(message);
+    let mut table = TextTable::new(&[
+        (text(Message::Rank), Align::Right),
+        ("+/-", Align::Right),
+        (text(Message::Name), Align::Left),
+        (text(Message::Record), Align::Right),
+        ("MP", Align::Right),
+        ("OMWP", Align::Right),
+        ("GWP", Align::Right),
+        ("OGWP", Align::Right),
+        ("OOMWP", Align::Right),
+        (text(Message::Streak), Align::Right),
+        (text(Message::Form), Align::Right),
+    ]);
+    table.set_max_width(2, MAX_NAME_WIDTH);
+
+    for row in tourn.standings() {
+        let before = previous.iter().find(|r| r.uuid == row.uuid);
+        table.push(vec![
+            row.rank.to_string(),
+            movement(before.map(|r| r.rank), row.rank),
+            row.name,
+            format!(
+                "{}-{}-{}",
+                row.matches_won,
+                row.matches_played - row.matches_won - row.matches_drawn,
+                row.matches_drawn
+            ),
+            row.match_points.to_string(),
+            tourn.format_tiebreak(row.opponents_match_win_percentage),
+            tourn.format_tiebreak(row.game_win_percentage),
+            tourn.format_tiebreak(row.opponents_game_win_percentage),
+            tourn.format_tiebreak(row.opponents_opponents_match_win_percentage),
+            row.streak,
+            row.form,
+        ]);
+    }
+
+    let table = table.to_string();
+    let (header, rows) = table.split_once('\n').unwrap_or((&table, ""));
+    print!("{}\n{}", paint(Style::Bold, header), rows);
+}
+
+/// Shows how a player's rank changed, e.g. "▲2" for moving up two places, or "–" if it didn't
+/// change or there is no previous rank
+fn movement(before: Option<u32>, now: u32) -> String {
+    match before {
+        Some(before) if before > now => format!("▲{}", before - now),
+        Some(before) if before < now => format!("▼{}", now - before),
+        _ => String::from("–"),
+    }
+}
 
-    if img {
-        let files = fs::read_dir(filename)?
-            .map(|res| res.map(|e| e.path()))
-            .collect::<Result<Vec<_>, io::Error>>()?;
+/// Runs a whole tournament interactively
+fn run(opts: RunOpts) -> io::Result<()> {
+    let filename = opts.file;
+
+    let img = opts.img != 0;
 
-        for f in files {
-            let os_str = f.into_os_string();
-            let name = os_str.into_string().unwrap();
-            let p = Rc::new(RefCell::new(Player::new(&name)));
-            players.push(p);
+    // Images of the players in image mode, by name
+    let mut image_paths = HashMap::new();
+
+    let players = if img {
+        match images::images(&filename) {
+            Ok(found) => found
+                .into_iter()
+                .map(|image| {
+                    let player = Player::new(&image.name);
+                    image_paths.insert(image.name, image.path);
+                    player
+                })
+                .collect(),
+            Err(e) => {
+                error!("{}", e);
+                exit(1);
+            }
         }
     } else {
-        let contents = match fs::read_to_string(filename) {
-            Ok(c) => c,
+        match read_players(&filename) {
+            Ok(players) => players,
+            Err(e) => {
+                error!("{}", e);
+                exit(1);
+            }
+        }
+    };
+
+    let ratings = opts
+        .ratings
+        .as_ref()
+        .map(|path| match ratings::Ratings::load(path) {
+            Ok(ratings) => ratings,
             Err(e) => {
-                eprintln!("{}", e);
+                error!("Could not load ratings from {}: {}", path, e);
                 exit(1);
             }
+        });
+
+    let mut tourn = match Tournament::with_players(players) {
+        Ok(tourn) => tourn,
+        Err(e) => {
+            error!("{}", e);
+            exit(1);
+        }
+    };
+
+    if let Some(preset) = opts.preset {
+        tourn
+            .apply_preset(preset)
+            .expect("a new tournament isn't finished");
+    }
+
+    apply_settings(
+        &mut tourn,
+        opts.system,
+        opts.first,
+        opts.best_of,
+        opts.locale,
+    )
+    .expect("a new tournament isn't finished");
+
+    #[cfg(feature = "webhooks")]
+    let hook = opts.webhook.as_ref().map(|url| {
+        let hook = Rc::new(webhook::Webhook::new(url));
+        tourn.add_observer(Box::new(Rc::clone(&hook)));
+        hook
+    });
+
+    let ticker = opts.ticker.as_ref().map(|path| {
+        let writer: Box<dyn Write> = if path == "-" {
+            Box::new(io::stdout())
+        } else {
+            match fs::OpenOptions::new().create(true).append(true).open(path) {
+                Ok(file) => Box::new(file),
+                Err(e) => {
+                    error!("Could not open ticker {}: {}", path, e);
+                    exit(1);
+                }
+            }
         };
+        let ticker = Rc::new(ticker::Ticker::new(writer));
+        tourn.add_observer(Box::new(Rc::clone(&ticker)));
+        ticker
+    });
 
-        for line in contents.lines() {
-            // Maybe we could add checks for duplicate entries here
-            let p = Rc::new(RefCell::new(Player::new(line.trim())));
-            players.push(p);
+    let overlay = opts.overlay.as_ref().map(|path| {
+        let overlay = Rc::new(overlay::OverlayFile::new(path));
+        tourn.add_observer(Box::new(Rc::clone(&overlay)));
+        overlay
+    });
+
+    if let Some(port) = opts.serve_standings {
+        match spectator::SpectatorServer::start(port, &tourn) {
+            Ok(server) => {
+                println!("Serving pairings and standings at {}", server.url());
+                #[cfg(feature = "qr")]
+                print_qr_code(&server.url(), &opts.standings_qr);
+                tourn.add_observer(Box::new(server));
+            }
+            Err(e) => {
+                error!("Could not serve standings on port {}: {}", port, e);
+                exit(1);
+            }
         }
     }
 
-    let mut tourn = Tournament::new(players);
+    let config = match &opts.config {
+        Some(path) => apply_config_or_exit(&mut tourn, path),
+        None => config::Config::default(),
+    };
+
+    let mut viewer = if img {
+        let kind = opts.viewer.or(config.viewer).unwrap_or_default();
+        Some(viewer::viewer(kind))
+    } else {
+        None
+    };
 
-    while let Some(pairing) = tourn.next_round() {
-        println!(
-            "\n\n=== ROUND {}/{} ===\n",
-            tourn.current_round, tourn.rounds
-        );
+    // Pairings of a replayed round that still need a result
+    let mut resumed = None;
 
-        for pair in &pairing {
-            let mut read = true;
-
-            let uuid = pair.0;
-
-            let home_file = String::from(&pair.1);
-            let away_file = String::from(&pair.2);
-
-            let home = String::from(Path::new(&home_file).file_stem().unwrap().to_str().unwrap());
-            let away = String::from(Path::new(&away_file).file_stem().unwrap().to_str().unwrap());
-
-            if img {
-                thread::spawn(|| {
-                    Command::new("feh")
-                        .arg("-g")
-                        .arg("960x1080+0+0")
-                        .arg(home_file)
-                        .arg("--scale-down")
-                        .arg("--title")
-                        .arg("1")
-                        .output()
-                        .expect("failed to execute process");
-                });
-
-                thread::spawn(|| {
-                    Command::new("feh")
-                        .arg("-g")
-                        .arg("960x1080+1920+0")
-                        .arg(away_file)
-                        .arg("--scale-down")
-                        .arg("--title")
-                        .arg("2")
-                        .output()
-                        .expect("failed to execute process");
-                });
-            }
-
-            while read {
-                println!("\nPAIRING:\n[1] {}\n[2] {}\n", home, away);
-
-                let home_score = match read_score(1, &home) {
-                    Ok(s) => s,
+    let mut journal = match &opts.journal {
+        Some(path) => {
+            if Path::new(path).exists() {
+                let entries = match journal::read(path) {
+                    Ok(entries) => entries,
                     Err(e) => {
-                        eprintln!("{}", e);
-                        continue;
+                        error!("{}", e);
+                        exit(1);
                     }
                 };
 
-                let away_score = match read_score(2, &away) {
-                    Ok(s) => s,
+                let open = match tourn.replay(&entries) {
+                    Ok(open) => open,
                     Err(e) => {
-                        eprintln!("{}", e);
-                        continue;
+                        error!("{}", e);
+                        exit(1);
                     }
                 };
 
-                let mut drawn = 0;
-                if home_score == 1 && away_score == 1 {
-                    drawn = 1;
+                println!("Replayed journal up to round {}", tourn.current_round());
+                if !open.is_empty() {
+                    resumed = Some(open);
                 }
-
-                // `end_match()` returns an `Err` if the scores were invalid, in which case we do
-                // not set `read` to `false`, resulting in another round
-                match tourn.end_match(uuid, home_score, away_score, drawn) {
-                    Ok(_) => read = false,
-                    Err(e) => eprintln!("Error recording result: {}", e),
-                };
             }
 
-            if img {
-                Command::new("killall")
-                    .arg("feh")
-                    .output()
-                    .expect("failed to kill feh");
-            }
+            Some(journal::Journal::open(path)?)
         }
-    }
+        None => None,
+    };
+
+    let mut results = match &opts.results {
+        Some(path) => Some(open_results(path)?),
+        None => None,
+    };
 
-    let players = tourn.ranking();
+    let mut prompt = prompt::Prompt::new()?;
 
-    println!("\n=== RESULTS ===\n");
+    loop {
+        let pairing = match resumed.take() {
+            Some(p) => p,
+            None => {
+                if tourn.current_round() > 0 {
+                    if let Some(path) = &opts.config {
+                        reload_config(&mut tourn, path);
+                    }
+                }
 
-    let mut table = Table::new();
+                let next = tourn.next_round();
 
-    table.add_row(row!["Rank", "Name", "MP", "OMWP", "GWP", "OGWP"]);
+                #[cfg(feature = "webhooks")]
+                report_webhook_errors(&hook);
+                report_ticker_errors(&ticker);
+                report_overlay_errors(&overlay);
 
-    let mut rank = 1;
-    for p in &players {
-        let p = p.borrow();
-        table.add_row(row![
-            rank,
-            p.name,
-            p.match_points,
-            (p.opponents_match_win_percentage() * 100.0).round() / 100.0,
-            (p.game_win_percentage() * 100.0).round() / 100.0,
-            (p.opponents_game_win_percentage() * 100.0).round() / 100.0,
-        ]);
-        rank += 1;
-    }
+                match next {
+                    Ok(Some(p)) => p,
+                    Ok(None) => break,
+                    Err(e) => {
+                        error!("{}", e);
+                        exit(1);
+                    }
+                }
+            }
+        };
 
-    table.printstd();
+        let locale = tourn.locale();
+        let header = locale.format(
+            Message::RoundHeader,
+            &[&tourn.current_round(), &tourn.rounds()],
+        );
+        println!("\n\n{}\n", paint(Style::Bold, &header));
 
-    Ok(())
+        if let Some((_, seed)) = tourn.round_seeds().last() {
+            println!("{}\n", locale.format(Message::Seed, &[seed]));
+        }
+
+        if let Some(length) = tourn.round_length() {
+            println!("{}\n", locale.format(Message::RoundLength, &[&length]));
+        }
+
+        match results.as_mut() {
+            Some(results) => read_results(&mut tourn, results, &mut journal),
+            None => enter_results(
+                &mut tourn,
+                &pairing,
+                &mut prompt,
+                &mut viewer,
+                &image_paths,
+                &mut journal,
+            ),
+        }
+
+        if tourn.current_round() < tourn.rounds() {
+            let header = locale.format(
+                Message::StandingsAfterRoundHeader,
+                &[&tourn.current_round()],
+            );
+            println!("\n{}\n", paint(Style::Bold, &header));
+            print_standings(&tourn);
+        }
+    }
+
+    let locale = tourn.locale();
+    let header = |message| println!("\n{}\n", paint(Style::Bold, locale.text(message)));
+    header(Message::ResultsHeader);
+
+    tourn.ranking();
+    print_standings(&tourn);
+
+    if tourn.prizes().is_some() {
+        header(Message::PrizesHeader);
+        print!("{}", tourn.prize_report());
+    }
+
+    header(Message::AchievementsHeader);
+    print!("{}", tourn.achievements(ratings.as_ref()));
+
+    header(Message::WallChartHeader);
+    print!("{}", tourn.wall_chart());
+
+    header(Message::MatchTimesHeader);
+    print!("{}", tourn.match_times_report());
+
+    let stats = tourn.play_draw_stats();
+    if let Some(rate) = stats.first_win_rate() {
+        println!(
+            "\n{}",
+            locale.format(
+                Message::FirstWinRate,
+                &[
+                    &stats.first_wins,
+                    &stats.matches,
+                    &format!("{:.0}", rate * 100.0)
+                ]
+            )
+        );
+    }
+
+    if let Some(path) = opts.save {
+        tourn.save(path)?;
+    }
+
+    Ok(())
+}
+
+/// Loads the tournament the subcommands work on, exiting if it can't be loaded
+fn load_state(path: &str) -> Tournament {
+    match Tournament::load(path) {
+        Ok(tourn) => tourn,
+        Err(e) => {
+            error!("{}", e);
+            exit(1);
+        }
+    }
+}
+
+/// Returns the pairings of the current round, numbered by table starting at 1
+fn tables(tourn: &Tournament) -> Vec<history::RoundPairing> {
+    if tourn.is_finished() {
+        return Vec::new();
+    }
+
+    tourn
+        .round(tourn.current_round())
+        .map(|round| round.pairings)
+        .unwrap_or_default()
+}
+
+/// Returns the name of a player
+fn name(tourn: &Tournament, uuid: uuid::Uuid) -> String {
+    tourn
+        .player_arena()
+        .iter()
+        .find(|p| p.uuid == uuid)
+        .map(|p| p.name.clone())
+        .unwrap_or_default()
+}
+
+/// Returns the name of a player followed by their player number, e.g. "Alice (#3)"
+fn numbered_name(tourn: &Tournament, uuid: uuid::Uuid) -> String {
+    match tourn.player_number(uuid) {
+        Some(number) => format!("{} (#{})", name(tourn, uuid), number),
+        None => name(tourn, uuid),
+    }
+}
+
+/// Games won by the home and away player and drawn games
+type Score = (u8, u8, u8);
+
+/// Parses a score like "2-1" or "1-1-1" into home, away and drawn games. Equal scores like "1-1"
+/// count as a draw with one drawn game, like in the interactive mode, unless time was called, in
+/// which case the unfinished game is the drawn one.
+fn parse_score(score: &str, time_called: bool, locale: Locale) -> Result<Score, String> {
+    let error = || locale.format(Message::CouldNotParseScore, &[&score]);
+    let games = score
+        .split('-')
+        .map(|n| n.trim().parse::<u8>())
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|_| error())?;
+
+    match games[..] {
+        [home, away] if home == away && !time_called => Ok((home, away, 1)),
+        [home, away] => Ok((home, away, 0)),
+        [home, away, drawn] => Ok((home, away, drawn)),
+        _ => Err(error()),
+    }
+}
+
+fn new(state: &str, opts: NewOpts) -> io::Result<()> {
+    if Path::new(state).exists() && !opts.force {
+        let locale = opts.locale.unwrap_or_default();
+        error!("{}", locale.format(Message::StateExists, &[&state]));
+        exit(1);
+    }
+
+    let players = if opts.participants {
+        bracket::load_participants(&opts.file).map_err(Box::from)
+    } else {
+        read_players(&opts.file)
+    };
+    let players = match players {
+        Ok(players) => players,
+        Err(e) => {
+            error!("{}", e);
+            exit(1);
+        }
+    };
+
+    let mut tourn = match Tournament::with_players(players) {
+        Ok(tourn) => tourn,
+        Err(e) => {
+            error!("{}", e);
+            exit(1);
+        }
+    };
+
+    if let Some(preset) = opts.preset {
+        tourn
+            .apply_preset(preset)
+            .expect("a new tournament isn't finished");
+    }
+
+    apply_settings(
+        &mut tourn,
+        opts.system,
+        opts.first,
+        opts.best_of,
+        opts.locale,
+    )
+    .expect("a new tournament isn't finished");
+
+    tourn
+        .set_event(event::EventInfo {
+            name: opts.name,
+            date: opts.date,
+            format: opts.game_format,
+            organizer: opts.organizer,
+            location: opts.location,
+        })
+        .expect("a new tournament isn't finished");
+
+    if let Some(name) = &opts.house_player {
+        tourn
+            .add_house_player(name)
+            .expect("a new tournament isn't finished");
+    }
+
+    if let Some(path) = &opts.config {
+        apply_config_or_exit(&mut tourn, path);
+    }
+
+    tourn.save(state)?;
+    println!(
+        "{}",
+        tourn.locale().format(
+            Message::Created,
+            &[&tourn.player_arena().len(), &tourn.rounds()]
+        )
+    );
+
+    Ok(())
+}
+
+/// Applies the pairing system, who goes first, the match format, and the language chosen on the
+/// command line to a new tournament. Choosing a pairing system also sets the rounds it needs.
+fn apply_settings(
+    tourn: &mut Tournament,
+    system: Option<PairingSystem>,
+    first: Option<FirstPlayer>,
+    best_of: Option<MatchFormat>,
+    locale: Option<Locale>,
+) -> Result<(), LockError> {
+    if let Some(system) = system {
+        tourn.set_pairing_system(system)?;
+        tourn.set_rounds(system.rounds(tourn.player_arena().len()))?;
+    }
+    if let Some(first) = first {
+        tourn.set_first_player(first)?;
+    }
+    if let Some(format) = best_of {
+        tourn.set_match_format(format)?;
+    }
+    if let Some(locale) = locale {
+        tourn.set_locale(locale)?;
+    }
+    Ok(())
+}
+
+fn import(state: &str, opts: ImportOpts) -> io::Result<()> {
+    if Path::new(state).exists() && !opts.force {
+        error!(
+            "{}",
+            Locale::default().format(Message::StateExists, &[&state])
+        );
+        exit(1);
+    }
+
+    let tourn = match Tournament::from_trf(&opts.file) {
+        Ok(tourn) => tourn,
+        Err(e) => {
+            error!("{}", e);
+            exit(1);
+        }
+    };
+
+    tourn.save(state)?;
+    println!(
+        "{}",
+        tourn.locale().format(
+            Message::Imported,
+            &[
+                &tourn.player_arena().len(),
+                &tourn.current_round(),
+                &tourn.rounds()
+            ]
+        )
+    );
+
+    Ok(())
+}
+
+/// Prints the tables of the current round that are still missing a result and exits
+fn exit_outstanding(tourn: &Tournament, outstanding: &[uuid::Uuid]) -> ! {
+    error!(
+        "{}",
+        tourn
+            .locale()
+            .format(Message::ResultsMissing, &[&tourn.current_round()])
+    );
+    for (i, pair) in tables(tourn).iter().enumerate() {
+        if outstanding.contains(&pair.uuid) {
+            error!(
+                "{}",
+                tourn.locale().format(
+                    Message::Table,
+                    &[&(i + 1), &name(tourn, pair.home), &name(tourn, pair.away)]
+                )
+            );
+        }
+    }
+    exit(1);
+}
+
+fn preview(tourn: &Tournament, seed: Option<u64>) {
+    let preview = match seed {
+        Some(seed) => tourn.preview_round_with_seed(seed),
+        None => tourn.preview_round(),
+    };
+
+    let preview = match preview {
+        Ok(Some(preview)) => preview,
+        Ok(None) => {
+            println!("{}", tourn.locale().text(Message::Finished));
+            return;
+        }
+        Err(history::RoundError::ResultsOutstanding(outstanding)) => {
+            exit_outstanding(tourn, &outstanding)
+        }
+        Err(e) => {
+            error!("{}", e);
+            exit(1);
+        }
+    };
+
+    let header = format!(
+        "=== PREVIEW OF ROUND {}/{} ===",
+        preview.round,
+        tourn.rounds()
+    );
+    println!("{}\n", paint(Style::Bold, &header));
+
+    for (home, away) in &preview.pairings {
+        println!(
+            "{} vs. {}",
+            numbered_name(tourn, *home),
+            numbered_name(tourn, *away)
+        );
+    }
+    if let Some(bye) = preview.bye {
+        let line = format!("Bye: {}", numbered_name(tourn, bye));
+        println!("{}", paint(Style::Yellow, &line));
+    }
+
+    println!(
+        "\nRun `swyss pair --seed {}` to pair the round like this.",
+        preview.seed
+    );
+}
+
+fn pair(state: &str, opts: PairOpts) -> io::Result<()> {
+    let mut tourn = load_state(state);
+
+    if opts.preview {
+        preview(&tourn, opts.seed);
+        return Ok(());
+    }
+
+    let paired = match opts.seed {
+        Some(seed) => tourn.next_round_with_seed(seed),
+        None => tourn.next_round(),
+    };
+
+    match paired {
+        Ok(Some(_)) => {}
+        Ok(None) => {
+            tourn.save(state)?;
+            println!("{}", tourn.locale().text(Message::Finished));
+            print_standings(&tourn);
+            return Ok(());
+        }
+        Err(history::RoundError::ResultsOutstanding(outstanding)) => {
+            exit_outstanding(&tourn, &outstanding)
+        }
+        Err(e) => {
+            error!("{}", e);
+            exit(1);
+        }
+    }
+
+    print_tables(&tourn);
+    print_bye_warnings(&tourn);
+    tourn.save(state)
+}
+
+/// Prints the byes of the current round that broke the bye limit or were withheld because of it
+fn print_bye_warnings(tourn: &Tournament) {
+    let locale = tourn.locale();
+    let warnings = tourn.bye_warnings().iter();
+    for warning in warnings.filter(|w| w.round() == tourn.current_round()) {
+        let line = match *warning {
+            byes::ByeWarning::OverLimit { player, byes, .. } => locale.format(
+                Message::ByeOverLimit,
+                &[&name(tourn, player), &byes, &tourn.max_byes()],
+            ),
+            byes::ByeWarning::Withheld { player, .. } => {
+                locale.format(Message::ByeWithheld, &[&name(tourn, player)])
+            }
+        };
+        println!("{}", paint(Style::Yellow, &line));
+    }
+}
+
+fn finish(state: &str) -> io::Result<()> {
+    let mut tourn = load_state(state);
+
+    match tourn.finish() {
+        Ok(()) => {}
+        Err(history::RoundError::ResultsOutstanding(outstanding)) => {
+            exit_outstanding(&tourn, &outstanding)
+        }
+        Err(e) => {
+            error!("{}", e);
+            exit(1);
+        }
+    }
+
+    println!("{}", tourn.locale().text(Message::Finished));
+    print_standings(&tourn);
+    tourn.save(state)
+}
+
+fn deadline(state: &str, opts: DeadlineOpts) -> io::Result<()> {
+    let mut tourn = load_state(state);
+    let locale = tourn.locale();
+
+    let due = match tourn.result_deadline() {
+        Some(due) => due,
+        None => {
+            error!("{}", locale.text(Message::NoDeadline));
+            exit(1);
+        }
+    };
+    let now = audit::unix_time();
+    if now < due {
+        let minutes = (due - now).div_ceil(60);
+        println!("{}", locale.format(Message::DeadlinePending, &[&minutes]));
+        return Ok(());
+    }
+
+    let mut prompt = prompt::Prompt::new()?;
+    // The tables are described up front, as the tournament is busy while asking
+    let tables: Vec<(uuid::Uuid, String)> = tables(&tourn)
+        .iter()
+        .enumerate()
+        .map(|(i, pair)| {
+            let line = locale.format(
+                Message::Table,
+                &[&(i + 1), &name(&tourn, pair.home), &name(&tourn, pair.away)],
+            );
+            (pair.uuid, line)
+        })
+        .collect();
+    let confirm = |overdue: &[uuid::Uuid], outcome: deadline::DeadlineOutcome| {
+        for (uuid, line) in &tables {
+            if overdue.contains(uuid) {
+                println!("{}", line);
+            }
+        }
+        if opts.yes {
+            return true;
+        }
+
+        let question = match outcome {
+            deadline::DeadlineOutcome::DoubleLoss => Message::DoubleLossConfirm,
+            deadline::DeadlineOutcome::Draw => Message::DeadlineDrawConfirm,
+        };
+        let question = locale.format(question, &[&overdue.len()]);
+        match prompt.read(&format!("{} ", question)) {
+            Ok(Some(answer)) => ["y", "yes", "j", "ja"]
+                .iter()
+                .any(|yes| answer.trim().eq_ignore_ascii_case(yes)),
+            _ => false,
+        }
+    };
+
+    match tourn.apply_deadline(now, confirm) {
+        Ok(recorded) => println!(
+            "{}",
+            locale.format(Message::DeadlineRecorded, &[&recorded.len()])
+        ),
+        Err(e) => {
+            error!("{}", e);
+            exit(1);
+        }
+    }
+
+    tourn.save(state)
+}
+
+fn merge(state: &str, opts: MergeOpts) -> io::Result<()> {
+    let mut tourn = load_state(state);
+    let other = load_state(&opts.file);
+    let locale = tourn.locale();
+
+    let report = match tourn.merge_results(&other) {
+        Ok(report) => report,
+        Err(e) => {
+            error!("{}", e);
+            exit(1);
+        }
+    };
+
+    println!(
+        "{}",
+        locale.format(Message::ResultsMerged, &[&report.merged.len()])
+    );
+    let tables = tables(&tourn);
+    for conflict in &report.conflicts {
+        let table = tables
+            .iter()
+            .position(|pair| pair.uuid == conflict.pairing)
+            .map_or(0, |i| i + 1);
+        let score = |result: history::MatchResult| {
+            format!(
+                "{}-{}-{}",
+                result.home_score, result.away_score, result.drawn
+            )
+        };
+        let line = locale.format(
+            Message::MergeConflict,
+            &[&table, &score(conflict.ours), &score(conflict.theirs)],
+        );
+        println!("{}", paint(Style::Yellow, &line));
+    }
+
+    tourn.save(state)
+}
+
+fn serve(state: &str, opts: ServeOpts) -> io::Result<()> {
+    let mut tourn = load_state(state);
+    let server = remote::RemoteServer::start(opts.bind, opts.port)?;
+    println!(
+        "{}",
+        tourn.locale().format(
+            Message::ServingTerminals,
+            &[&server.local_addr().port(), &server.token()]
+        )
+    );
+
+    loop {
+        match server.handle_next(&mut tourn)? {
+            remote::Handled::Reported(uuid) => {
+                let tables = tables(&tourn);
+                let i = tables
+                    .iter()
+                    .position(|pair| pair.uuid == uuid)
+                    .expect("results are only recorded for the current round");
+                let pair = &tables[i];
+                let result = pair.result.expect("the result was just recorded");
+                println!(
+                    "{}",
+                    result_line(
+                        &tourn,
+                        i + 1,
+                        pair,
+                        (result.home_score, result.away_score, result.drawn)
+                    )
+                );
+                tourn.save(state)?;
+            }
+            remote::Handled::Rejected(e) => error!("{}", e),
+            remote::Handled::Tables => {}
+        }
+    }
+}
+
+fn connect(opts: ConnectOpts) -> io::Result<()> {
+    let locale = opts.locale.unwrap_or_default();
+    let mut client = remote::RemoteClient::connect(&opts.host, &opts.token)?;
+    let mut prompt = prompt::Prompt::new()?;
+
+    loop {
+        for table in client.tables()? {
+            let mut line = locale.format(Message::Table, &[&table.table, &table.home, &table.away]);
+            if let Some(result) = &table.result {
+                line = format!("{} ({})", line, result);
+            }
+            println!("{}", line);
+        }
+
+        let input = match prompt.read(&format!("{} ", locale.text(Message::RemotePrompt)))? {
+            Some(input) => input,
+            None => return Ok(()),
+        };
+        let input = input.trim();
+        if input.is_empty() {
+            continue;
+        }
+
+        let (table, result) = input.split_at(input.find(' ').unwrap_or(input.len()));
+        match client.report(table, result)? {
+            Ok(()) => println!("{}", paint(Style::Green, "OK")),
+            Err(e) => error!("{}", e),
+        }
+    }
+}
+
+/// Prints the tables and byes of the current round
+fn print_tables(tourn: &Tournament) {
+    let locale = tourn.locale();
+    let header = locale.format(
+        Message::RoundHeader,
+        &[&tourn.current_round(), &tourn.rounds()],
+    );
+    println!("{}\n", paint(Style::Bold, &header));
+
+    let rematches = tourn.rematches(tourn.current_round());
+    let featured = tourn.featured_pairing();
+    for (i, pair) in tables(tourn).iter().enumerate() {
+        let mut line = locale.format(
+            Message::Table,
+            &[
+                &(i + 1),
+                &numbered_name(tourn, pair.home),
+                &numbered_name(tourn, pair.away),
+            ],
+        );
+        if rematches.contains(&pair.uuid) {
+            line.push(' ');
+            line.push_str(locale.text(Message::Rematch));
+        }
+        if featured == Some(pair.uuid) {
+            line.push(' ');
+            line.push_str(locale.text(Message::Featured));
+        }
+        println!("{}", line);
+    }
+
+    if let Some(round) = tourn.round(tourn.current_round()) {
+        if let Some(bye) = round.bye {
+            let line = locale.format(Message::Bye, &[&numbered_name(tourn, bye)]);
+            println!("{}", paint(Style::Yellow, &line));
+        }
+        for bye in round.requested_byes {
+            let line = locale.format(Message::RequestedBye, &[&numbered_name(tourn, bye.player)]);
+            println!("{}", paint(Style::Yellow, &line));
+        }
+    }
+}
+
+fn feature(state: &str, opts: FeatureOpts) -> io::Result<()> {
+    let mut tourn = load_state(state);
+
+    let uuid = match tourn.resolve_pairing(&opts.table) {
+        Some(uuid) if !tourn.is_finished() => uuid,
+        _ => {
+            error!(
+                "{}",
+                tourn.locale().format(Message::NoSuchTable, &[&opts.table])
+            );
+            exit(1);
+        }
+    };
+    if let Err(e) = tourn.feature_pairing(uuid) {
+        error!(
+            "{}",
+            tourn.locale().format(
+                Message::ErrorFeaturing,
+                &[&opts.table, &tourn.locale().text(e.message())]
+            )
+        );
+        exit(1);
+    }
+
+    print_tables(&tourn);
+    tourn.save(state)
+}
+
+fn report(state: &str, opts: ReportOpts) -> io::Result<()> {
+    let mut tourn = load_state(state);
+
+    // A finished tournament has no current round, so its tables can't be looked up
+    if let Err(e) = tourn.check_unlocked() {
+        error!("{}", result_error(&tourn, &e.into()));
+        exit(1);
+    }
+
+    let uuid = tourn.resolve_pairing(&opts.table);
+    let found = tables(&tourn)
+        .into_iter()
+        .enumerate()
+        .find(|(_, pair)| Some(pair.uuid) == uuid);
+    let (i, pair) = match found {
+        Some(found) => found,
+        None => {
+            error!(
+                "{}",
+                tourn.locale().format(Message::NoSuchTable, &[&opts.table])
+            );
+            exit(1);
+        }
+    };
+
+    if let Some(player) = &opts.concede {
+        let player = find_player_or_exit(&tourn, player);
+        let side = if player == pair.home {
+            PlayerSide::Home
+        } else if player == pair.away {
+            PlayerSide::Away
+        } else {
+            error!(
+                "{}",
+                tourn
+                    .locale()
+                    .format(Message::NotAtTable, &[&name(&tourn, player), &(i + 1)])
+            );
+            exit(1);
+        };
+
+        if let Err(e) = tourn.concede(pair.uuid, side) {
+            error!("{}", result_error(&tourn, &e));
+            exit(1);
+        }
+
+        println!(
+            "{}",
+            tourn
+                .locale()
+                .format(Message::Concedes, &[&(i + 1), &name(&tourn, player)])
+        );
+        return tourn.save(state);
+    }
+
+    let score = opts.score.as_deref().unwrap_or_default();
+    let (home_score, away_score, drawn) = match parse_score(score, opts.time, tourn.locale()) {
+        Ok(score) => score,
+        Err(e) => {
+            error!("{}", e);
+            exit(1);
+        }
+    };
+
+    let recorded = if opts.amend {
+        tourn.amend_match(pair.uuid, home_score, away_score, drawn)
+    } else if opts.time {
+        tourn.end_match_at_time(pair.uuid, home_score, away_score, drawn)
+    } else {
+        tourn.end_match(pair.uuid, home_score, away_score, drawn)
+    };
+
+    if let Err(e) = recorded {
+        error!("{}", result_error(&tourn, &e));
+        exit(1);
+    }
+
+    println!(
+        "{}",
+        result_line(&tourn, i + 1, &pair, (home_score, away_score, drawn))
+    );
+
+    tourn.save(state)
+}
+
+fn avoid(state: &str, opts: AvoidOpts) -> io::Result<()> {
+    let mut tourn = load_state(state);
+    let first = find_player_or_exit(&tourn, &opts.first);
+    let second = find_player_or_exit(&tourn, &opts.second);
+
+    let message = if opts.cancel {
+        if let Err(e) = tourn.allow_pairing(first, second) {
+            error!("{}", e);
+            exit(1);
+        }
+        Message::PairingAllowed
+    } else {
+        if let Err(e) = tourn.avoid_pairing(first, second) {
+            error!("{}", e);
+            exit(1);
+        }
+        Message::PairingAvoided
+    };
+    println!(
+        "{}",
+        tourn
+            .locale()
+            .format(message, &[&name(&tourn, first), &name(&tourn, second)])
+    );
+
+    tourn.save(state)
+}
+
+fn prefer(state: &str, opts: PreferOpts) -> io::Result<()> {
+    let mut tourn = load_state(state);
+    let first = find_player_or_exit(&tourn, &opts.first);
+    let second = find_player_or_exit(&tourn, &opts.second);
+
+    if let Err(e) = tourn.prefer_pairing(first, second) {
+        error!("{}", e);
+        exit(1);
+    }
+    println!(
+        "{}",
+        tourn.locale().format(
+            Message::PairingPreferred,
+            &[&name(&tourn, first), &name(&tourn, second)]
+        )
+    );
+
+    tourn.save(state)
+}
+
+fn request_bye(state: &str, opts: ByeOpts) -> io::Result<()> {
+    let mut tourn = load_state(state);
+    let player = find_player_or_exit(&tourn, &opts.player);
+
+    if opts.cancel {
+        let cancelled = tourn.cancel_bye(player, opts.round).unwrap_or_else(|e| {
+            error!("{}", e);
+            exit(1);
+        });
+        if !cancelled {
+            error!(
+                "{} has no bye to cancel in round {}!",
+                name(&tourn, player),
+                opts.round
+            );
+            exit(1);
+        }
+        println!(
+            "Cancelled the bye of {} in round {}",
+            name(&tourn, player),
+            opts.round
+        );
+    } else {
+        if let Err(e) = tourn.request_bye(player, opts.round, opts.score) {
+            error!("{}", e);
+            exit(1);
+        }
+        println!(
+            "{}",
+            tourn
+                .locale()
+                .format(Message::ByeGranted, &[&name(&tourn, player), &opts.round])
+        );
+    }
+
+    tourn.save(state)
+}
+
+/// Looks up a player by player number or by name, allowing for prefixes and typos, and exits if
+/// the name doesn't lead to a single player
+fn find_player_or_exit(tourn: &Tournament, name: &str) -> uuid::Uuid {
+    match tourn.resolve_player(name) {
+        Ok(uuid) => uuid,
+        Err(e) => {
+            error!("{}", e);
+            exit(1);
+        }
+    }
+}
+
+/// Renders a match from one player's point of view, e.g. "W 2-1 vs. Bob"
+fn describe_match(tourn: &Tournament, record: &crosstable::MatchRecord) -> String {
+    let score = format!(
+        "{}-{}{}",
+        record.games_won,
+        record.games_lost,
+        match record.games_drawn {
+            0 => String::new(),
+            drawn => format!("-{}", drawn),
+        }
+    );
+
+    let opponent = match record.opponent {
+        Some(opponent) => name(tourn, opponent),
+        None => return String::from("bye"),
+    };
+    // Players who dropped are no longer in the tournament
+    let opponent = if opponent.is_empty() {
+        String::from("(dropped)")
+    } else {
+        opponent
+    };
+
+    match record.outcome {
+        crosstable::Outcome::Win => format!("W {} vs. {}", score, opponent),
+        crosstable::Outcome::Loss => format!("L {} vs. {}", score, opponent),
+        crosstable::Outcome::Draw => format!("D {} vs. {}", score, opponent),
+        crosstable::Outcome::Pending => format!("playing vs. {}", opponent),
+        crosstable::Outcome::Bye => String::from("bye"),
+    }
+}
+
+fn opponents(state: &str, opts: OpponentsOpts) {
+    let tourn = load_state(state);
+    let player = find_player_or_exit(&tourn, &opts.player);
+
+    let matches = match &opts.other {
+        Some(other) => {
+            let other = find_player_or_exit(&tourn, other);
+            let matches = tourn.head_to_head(player, other);
+            if matches.is_empty() {
+                println!(
+                    "{} and {} have not played each other",
+                    name(&tourn, player),
+                    name(&tourn, other)
+                );
+                return;
+            }
+            matches
+        }
+        None => {
+            let record = tourn.player_record(player).expect("the player was found");
+            println!(
+                "{}: {}-{}-{}",
+                record.name, record.wins, record.losses, record.draws
+            );
+            record.matches
+        }
+    };
+
+    for record in &matches {
+        println!("Round {}: {}", record.round, describe_match(&tourn, record));
+    }
+}
+
+fn export(state: &str, opts: ExportOpts) -> io::Result<()> {
+    let mut tourn = load_state(state);
+    write_export(&mut tourn, opts)
+}
+
+fn recap(opts: RecapOpts) -> io::Result<()> {
+    let mut tourn = match Tournament::load_readonly(&opts.file) {
+        Ok(tourn) => tourn,
+        Err(e) => {
+            error!("{}", e);
+            exit(1);
+        }
+    };
+    write_export(&mut tourn, opts.export)
+}
+
+/// Writes a tournament in the requested export format
+fn write_export(tourn: &mut Tournament, opts: ExportOpts) -> io::Result<()> {
+    let out = match opts.format {
+        ExportFormat::Tournament => tourn.to_json(),
+        ExportFormat::Standings => tourn.standings_to_json(),
+        ExportFormat::WallChart => tourn.wall_chart(),
+        ExportFormat::Html => tourn.wall_chart_html(),
+        ExportFormat::Progression => tourn.progression_chart(),
+        ExportFormat::Strength => tourn.strength_of_schedule_report(),
+        ExportFormat::StrengthHtml => tourn.strength_of_schedule_html(),
+        ExportFormat::Dot => tourn.pairing_graph_dot(),
+        ExportFormat::GraphMl => tourn.pairing_graph_graphml(),
+        ExportFormat::Challonge => tourn.results_csv(),
+        #[cfg(feature = "pdf")]
+        ExportFormat::Pdf => return export_pdf(tourn, opts.output),
+    };
+
+    match opts.output {
+        Some(path) => fs::write(path, out),
+        None => {
+            println!("{}", out.trim_end());
+            Ok(())
+        }
+    }
+}
+
+fn demo(opts: DemoOpts) -> io::Result<()> {
+    let seed = opts.seed.unwrap_or_else(rand::random);
+    let start = std::time::Instant::now();
+    let (mut tourn, ratings) = simulate::demo(opts.players, opts.spread, seed);
+    let elapsed = start.elapsed();
+
+    tourn.ranking();
+    print_standings(&tourn);
+
+    println!("\n{}\n", paint(Style::Bold, "=== ACHIEVEMENTS ==="));
+    print!("{}", tourn.achievements(Some(&ratings)));
+
+    println!(
+        "\nPlayed {} rounds of {} players in {} ms (seed {})",
+        tourn.rounds(),
+        opts.players,
+        elapsed.as_millis(),
+        seed
+    );
+
+    if let Some(path) = opts.save {
+        tourn.save(path)?;
+    }
+
+    Ok(())
+}
+
+/// Writes the PDF report of the tournament to a file, since it is binary
+#[cfg(feature = "pdf")]
+fn export_pdf(tourn: &Tournament, output: Option<String>) -> io::Result<()> {
+    let path = match output {
+        Some(path) => path,
+        None => {
+            error!("A PDF report needs an output file!");
+            exit(1);
+        }
+    };
+
+    match tourn.report_pdf() {
+        Ok(pdf) => fs::write(path, pdf),
+        Err(e) => {
+            error!("Could not create PDF report: {}", e);
+            exit(1);
+        }
+    }
+}
+
+pub fn main() -> io::Result<()> {
+    let opts = Opts::parse();
+    #[cfg(feature = "logging")]
+    tracing_subscriber::fmt()
+        .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
+        .with_writer(io::stderr)
+        .init();
+    let state = opts.state;
+    let no_color = opts.no_color;
+    COLORS.get_or_init(|| {
+        (
+            Colors::detect(no_color, &io::stdout()),
+            Colors::detect(no_color, &io::stderr()),
+        )
+    });
+
+    match opts.command {
+        SubCommand::Run(opts) => run(opts),
+        SubCommand::New(opts) => new(&state, opts),
+        SubCommand::Import(opts) => import(&state, opts),
+        SubCommand::Pair(opts) => pair(&state, opts),
+        SubCommand::Report(opts) => report(&state, opts),
+        SubCommand::Feature(opts) => feature(&state, opts),
+        SubCommand::Finish => finish(&state),
+        SubCommand::Deadline(opts) => deadline(&state, opts),
+        SubCommand::Merge(opts) => merge(&state, opts),
+        SubCommand::Serve(opts) => serve(&state, opts),
+        SubCommand::Connect(opts) => connect(opts),
+        SubCommand::Bye(opts) => request_bye(&state, opts),
+        SubCommand::Avoid(opts) => avoid(&state, opts),
+        SubCommand::Prefer(opts) => prefer(&state, opts),
+        SubCommand::Standings => {
+            let tourn = load_state(&state);
+            print_standings(&tourn);
+            Ok(())
+        }
+        SubCommand::Opponents(opts) => {
+            opponents(&state, opts);
+            Ok(())
+        }
+        SubCommand::Explain(opts) => {
+            let tourn = load_state(&state);
+            let player = find_player_or_exit(&tourn, &opts.player);
+            let explanation = tourn.explain_rank(player).expect("the player was found");
+            print!("{}", explanation);
+            Ok(())
+        }
+        SubCommand::Export(opts) => export(&state, opts),
+        SubCommand::Recap(opts) => recap(opts),
+        SubCommand::Demo(opts) => demo(opts),
+    }
 }
@@ -0,0 +1,194 @@
+//! Merging results that were entered on more than one copy of a tournament, e.g. by two judges
+//! entering result slips on two laptops. Both copies are saved from the same round, so their
+//! pairings share UUIDs. Results only the other copy has are taken over; results both copies have
+//! but that differ are left alone and reported as conflicts to resolve with `amend_match()`.
+
+use crate::history::MatchResult;
+use crate::{PairingResultError, Tournament};
+use std::fmt;
+
+/// A pairing for which both copies have a result, but not the same one
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MergeConflict {
+    pub pairing: uuid::Uuid,
+    pub ours: MatchResult,
+    pub theirs: MatchResult,
+}
+
+/// What `Tournament::merge_results()` did, with pairings in the order of `Tournament::round()`
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MergeReport {
+    /// Pairings whose result was taken over from the other copy
+    pub merged: Vec<uuid::Uuid>,
+    /// Pairings with differing results, which were left as they were
+    pub conflicts: Vec<MergeConflict>,
+}
+
+/// Reasons why results can't be merged. Nothing is merged if any of them applies.
+#[derive(Debug)]
+pub enum MergeError {
+    /// The copies are in different rounds
+    DifferentRound { ours: u32, theirs: u32 },
+    /// A result of the other copy can't be recorded, e.g. because its pairing doesn't exist here
+    Pairing(PairingResultError),
+}
+
+impl std::error::Error for MergeError {}
+
+impl fmt::Display for MergeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MergeError::DifferentRound { ours, theirs } => write!(
+                f,
+                "The other file is in round {}, but this tournament is in round {}!",
+                theirs, ours
+            ),
+            MergeError::Pairing(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl From<PairingResultError> for MergeError {
+    fn from(e: PairingResultError) -> MergeError {
+        MergeError::Pairing(e)
+    }
+}
+
+impl Tournament {
+    /// Takes over the results of the current round that were entered on another copy of the
+    /// tournament but not on this one. Results that were entered on both copies but differ are
+    /// reported as conflicts and keep the result of this copy.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use swyss::{Player, Tournament};
+    ///
+    /// let players = (1..5)
+    ///     .map(|i| Player::new(&format!("Player {}", i)))
+    ///     .collect();
+    /// let mut tourn = Tournament::with_players(players).unwrap();
+    /// let pairings = tourn.next_round().unwrap().unwrap();
+    ///
+    /// // A second judge enters the other table on a copy
+    /// let mut copy = Tournament::from_json(&tourn.to_json()).unwrap();
+    /// tourn.end_match(pairings[0].0, 2, 0, 0).unwrap();
+    /// copy.end_match(pairings[1].0, 2, 1, 0).unwrap();
+    ///
+    /// let report = tourn.merge_results(&copy).unwrap();
+    /// assert_eq!(report.merged, vec![pairings[1].0]);
+    /// assert!(report.conflicts.is_empty());
+    /// assert!(tourn.round_status().is_complete());
+    /// ```
+    pub fn merge_results(&mut self, other: &Tournament) -> Result<MergeReport, MergeError> {
+        if other.current_round != self.current_round {
+            return Err(MergeError::DifferentRound {
+                ours: self.current_round,
+                theirs: other.current_round,
+            });
+        }
+
+        let theirs: Vec<(uuid::Uuid, MatchResult)> = other
+            .round(other.current_round)
+            .map(|round| round.pairings)
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|pair| Some((pair.uuid, pair.result?)))
+            .collect();
+
+        // Check every pairing first, so that the merge happens entirely or not at all
+        for (uuid, _) in &theirs {
+            self.open_pairing(*uuid)?;
+        }
+
+        let mut report = MergeReport::default();
+        for (uuid, result) in theirs {
+            self.open_pairing(uuid)?;
+            let pair = &self.pairings[&uuid];
+            match pair.result.get() {
+                Some(ours) if ours == result => {}
+                Some(ours) => report.conflicts.push(MergeConflict {
+                    pairing: uuid,
+                    ours,
+                    theirs: result,
+                }),
+                None => {
+                    // The result already includes games awarded by penalties
+                    if result.double_loss {
+                        pair.double_loss(&mut self.players);
+                    } else {
+                        pair.end_match(
+                            &mut self.players,
+                            result.home_score,
+                            result.away_score,
+                            result.drawn,
+                        )
+                        .map_err(|e| PairingResultError::OutOfRange(e.outside_value))?;
+                    }
+                    pair.result.set(Some(result));
+                    self.notify(|o| {
+                        o.on_result(
+                            self,
+                            uuid,
+                            result.home_score,
+                            result.away_score,
+                            result.drawn,
+                        )
+                    });
+                    report.merged.push(uuid);
+                }
+            }
+        }
+
+        Ok(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Player, PlayerSide};
+
+    #[test]
+    fn conflicts_and_rounds() {
+        let players = (1..7)
+            .map(|i| Player::new(format!("Player {}", i).as_str()))
+            .collect();
+        let mut tourn = Tournament::with_players(players).unwrap();
+        let pairings = tourn.next_round().unwrap().unwrap();
+        let mut copy = Tournament::from_json(&tourn.to_json()).unwrap();
+        let uuids: Vec<uuid::Uuid> = pairings.iter().map(|(uuid, _, _)| *uuid).collect();
+
+        tourn.end_match(uuids[0], 2, 0, 0).unwrap();
+        tourn.end_match(uuids[1], 2, 1, 0).unwrap();
+        copy.end_match(uuids[0], 2, 0, 0).unwrap();
+        copy.end_match(uuids[1], 1, 2, 0).unwrap();
+        copy.concede(uuids[2], PlayerSide::Away).unwrap();
+
+        let report = tourn.merge_results(&copy).unwrap();
+        assert_eq!(report.merged, vec![uuids[2]]);
+        assert_eq!(report.conflicts.len(), 1);
+        assert_eq!(report.conflicts[0].pairing, uuids[1]);
+        assert_eq!(report.conflicts[0].ours.home_score, 2);
+        assert_eq!(report.conflicts[0].theirs.home_score, 1);
+
+        // The concession is taken over as it was entered
+        let round = tourn.round(1).unwrap();
+        let pair = round.pairings.iter().find(|pair| pair.uuid == uuids[2]);
+        let merged = pair.unwrap().result.unwrap();
+        assert_eq!(merged.conceded, Some(PlayerSide::Away));
+        assert_eq!(tourn.check_invariants(), Ok(()));
+
+        // Merging again changes nothing
+        let again = tourn.merge_results(&copy).unwrap();
+        assert!(again.merged.is_empty());
+        assert_eq!(again.conflicts, report.conflicts);
+
+        tourn.amend_match(uuids[1], 1, 2, 0).unwrap();
+        tourn.next_round().unwrap().unwrap();
+        assert!(matches!(
+            tourn.merge_results(&copy),
+            Err(MergeError::DifferentRound { ours: 2, theirs: 1 })
+        ));
+    }
+}
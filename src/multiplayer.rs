@@ -0,0 +1,328 @@
+//! Tournaments where more than two players sit at a table, e.g. Commander or board games. Instead
+//! of winning games, players are scored by the place they finish in at their table. Players are
+//! ranked with the same tiebreakers as in a regular tournament, with everyone they shared a table
+//! with counting as an opponent.
+
+use crate::arena::PlayerArena;
+use crate::crosstable::Outcome;
+use crate::explain::Criterion;
+use crate::history::RoundError;
+use crate::{sort_by_tiebreakers, Player};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::fmt;
+
+/// The tables of a round as returned by `MultiplayerTournament::next_round()`: the UUID of each
+/// table and the names of its players in seating order
+pub type TablePairings = Vec<(uuid::Uuid, Vec<String>)>;
+
+/// Match points awarded for each finishing place, starting with first place. A smaller table
+/// awards the points of its places only, so a three player table uses the first three entries.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PlacementPoints(pub Vec<u32>);
+
+impl Default for PlacementPoints {
+    /// 3 points for first place, 2 for second, 1 for third, and none for fourth
+    fn default() -> PlacementPoints {
+        PlacementPoints(vec![3, 2, 1, 0])
+    }
+}
+
+impl PlacementPoints {
+    /// Points for the given place, starting at 1. Places without an entry award no points.
+    pub fn for_place(&self, place: usize) -> u32 {
+        self.0.get(place - 1).copied().unwrap_or(0)
+    }
+}
+
+/// Reasons why the result of a table can't be recorded
+#[derive(Debug, PartialEq)]
+pub enum TableResultError {
+    NotFound(uuid::Uuid),
+    /// The table already has a result
+    AlreadyReported(uuid::Uuid),
+    /// There must be one place per seat, each between 1 and the number of seats
+    InvalidPlacements(Vec<usize>),
+}
+
+impl std::error::Error for TableResultError {}
+
+impl fmt::Display for TableResultError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TableResultError::NotFound(_) => write!(f, "Table not found!"),
+            TableResultError::AlreadyReported(_) => write!(f, "Table already has a result!"),
+            TableResultError::InvalidPlacements(places) => {
+                write!(f, "Invalid placements: {:?}", places)
+            }
+        }
+    }
+}
+
+/// A table of players playing a single game against each other
+pub struct Table {
+    pub uuid: uuid::Uuid,
+    /// UUIDs of the players in seating order
+    pub players: Vec<uuid::Uuid>,
+    /// Finishing place of each seat, starting at 1, once the result is in
+    pub placements: Option<Vec<usize>>,
+}
+
+impl Table {
+    fn new(players: Vec<uuid::Uuid>) -> Table {
+        Table {
+            uuid: uuid::Uuid::new_v4(),
+            players,
+            placements: None,
+        }
+    }
+
+    /// Checks whether the table's result has been recorded
+    pub fn is_completed(&self) -> bool {
+        self.placements.is_some()
+    }
+
+    /// Records the finishing place of every seat. Players who tie share a place and both receive
+    /// its points, e.g. `[1, 2, 2, 4]`. Everyone at the table is recorded as an opponent of
+    /// everyone else, and the player in first place is credited with a match win.
+    fn finish(
+        &mut self,
+        players: &mut PlayerArena,
+        placements: &[usize],
+        points: &PlacementPoints,
+    ) -> Result<(), TableResultError> {
+        let seats = self.players.len();
+        if placements.len() != seats || placements.iter().any(|&p| p < 1 || p > seats) {
+            return Err(TableResultError::InvalidPlacements(placements.to_vec()));
+        }
+
+        for (seat, &uuid) in self.players.iter().enumerate() {
+            let p = match players.get_mut(uuid) {
+                Some(p) => p,
+                None => continue,
+            };
+            p.matches_played += 1;
+            p.match_points += points.for_place(placements[seat]);
+            p.cumulative_points += p.match_points;
+            if placements[seat] == 1 {
+                p.matches_won += 1;
+                p.outcomes.push(Outcome::Win);
+            } else {
+                p.outcomes.push(Outcome::Loss);
+            }
+
+            for &other in &self.players {
+                if other != uuid {
+                    p.opponents.push(other);
+                }
+            }
+        }
+
+        self.placements = Some(placements.to_vec());
+        Ok(())
+    }
+}
+
+/// Manages a tournament of multi-player tables. Each round, players are seated with others of
+/// similar standing, avoiding players they already shared a table with.
+pub struct MultiplayerTournament {
+    pub rounds: u32,
+    pub current_round: u32,
+    pub players: PlayerArena,
+    pub tables: HashMap<uuid::Uuid, Table>,
+    /// Largest number of players at a table
+    pub table_size: usize,
+    pub placement_points: PlacementPoints,
+    rng: StdRng,
+}
+
+impl MultiplayerTournament {
+    /// Creates a new tournament with tables of at most the given size, playing enough rounds to
+    /// find a single winner if only the winner of each table advanced
+    pub fn new(players: Vec<Player>, table_size: usize) -> MultiplayerTournament {
+        assert!(table_size >= 2, "A table needs at least two seats");
+
+        let rounds = (players.len() as f64)
+            .log(table_size as f64)
+            .ceil()
+            .max(0.0) as u32;
+
+        MultiplayerTournament {
+            rounds,
+            current_round: 0,
+            players: players.into(),
+            tables: HashMap::new(),
+            table_size,
+            placement_points: PlacementPoints::default(),
+            rng: StdRng::from_entropy(),
+        }
+    }
+
+    /// Sizes of the tables for the given number of players: as few tables as possible, with the
+    /// players spread evenly so that tables differ by at most one seat
+    fn table_sizes(&self, num_players: usize) -> Vec<usize> {
+        let num_tables = (num_players as f64 / self.table_size as f64).ceil() as usize;
+        (0..num_tables)
+            .map(|i| num_players / num_tables + usize::from(i < num_players % num_tables))
+            .collect()
+    }
+
+    /// Advances the tournament by one round and seats all players at new tables. Tables are filled
+    /// one at a time from the top of the standings: each seat goes to the highest ranked player
+    /// who has met the fewest of the players already seated.
+    ///
+    /// Returns `None` once all rounds have been played, and an error if tables of the current
+    /// round are still missing a result.
+    pub fn next_round(&mut self) -> Result<Option<TablePairings>, RoundError> {
+        let outstanding: Vec<_> = self
+            .tables
+            .values()
+            .filter(|t| !t.is_completed())
+            .map(|t| t.uuid)
+            .collect();
+        if !outstanding.is_empty() {
+            return Err(RoundError::ResultsOutstanding(outstanding));
+        }
+
+        self.current_round += 1;
+        if self.current_round > self.rounds {
+            return Ok(None);
+        }
+
+        let mut queue: Vec<&Player> = self.players.iter().collect();
+        queue.shuffle(&mut self.rng);
+        queue.sort_by_key(|p| Reverse(p.match_points));
+
+        let meetings =
+            |a: &Player, b: &Player| a.opponents.iter().filter(|&&o| o == b.uuid).count();
+
+        self.tables.clear();
+        let mut ret = Vec::new();
+
+        for size in self.table_sizes(queue.len()) {
+            let mut seated = vec![queue.remove(0)];
+
+            while seated.len() < size {
+                // min_by_key returns the first minimum, i.e. the highest ranked candidate
+                let (i, _) = queue
+                    .iter()
+                    .enumerate()
+                    .min_by_key(|(_, p)| seated.iter().map(|s| meetings(s, p)).sum::<usize>())
+                    .expect("enough players for every seat");
+                seated.push(queue.remove(i));
+            }
+
+            seated.shuffle(&mut self.rng);
+
+            let names = seated.iter().map(|p| p.name.clone()).collect();
+            let table = Table::new(seated.iter().map(|p| p.uuid).collect());
+            ret.push((table.uuid, names));
+            self.tables.insert(table.uuid, table);
+        }
+
+        Ok(Some(ret))
+    }
+
+    /// Records the result of a table, given as the finishing place of each seat in seating order
+    pub fn end_table(
+        &mut self,
+        uuid: uuid::Uuid,
+        placements: &[usize],
+    ) -> Result<(), TableResultError> {
+        let table = self
+            .tables
+            .get_mut(&uuid)
+            .ok_or(TableResultError::NotFound(uuid))?;
+
+        if table.is_completed() {
+            return Err(TableResultError::AlreadyReported(uuid));
+        }
+
+        table.finish(&mut self.players, placements, &self.placement_points)
+    }
+
+    /// Rank all players by match points and the default tiebreakers
+    pub fn ranking(&mut self) -> Vec<&Player> {
+        let mut ranking: Vec<&Player> = self.players.iter().collect();
+        ranking.shuffle(&mut self.rng);
+        sort_by_tiebreakers(&mut ranking, &self.players, &Criterion::DEFAULT_TIEBREAKERS);
+        let order: Vec<uuid::Uuid> = ranking.iter().map(|p| p.uuid).collect();
+
+        self.players.reorder(&order);
+        self.players.iter().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn players(n: u32) -> Vec<Player> {
+        (1..=n)
+            .map(|i| Player::new(format!("Player {}", i).as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn no_repeat_table_mates() {
+        let mut tourn = MultiplayerTournament::new(players(9), 3);
+        assert_eq!(tourn.rounds, 2);
+
+        while let Some(tables) = tourn.next_round().unwrap() {
+            assert_eq!(tables.len(), 3);
+            for (uuid, names) in tables {
+                assert_eq!(names.len(), 3);
+                tourn.end_table(uuid, &[1, 2, 3]).unwrap();
+            }
+        }
+
+        // Three players can each meet six others over two rounds without repeats
+        for p in &tourn.players {
+            assert_eq!(p.opponents.len(), 4);
+            for o in &p.opponents {
+                assert_eq!(p.opponents.iter().filter(|x| *x == o).count(), 1);
+            }
+        }
+
+        let ranking = tourn.ranking();
+        assert!(ranking[0].match_points >= ranking[8].match_points);
+    }
+
+    #[test]
+    fn placement_points() {
+        let mut tourn = MultiplayerTournament::new(players(10), 4);
+        tourn.placement_points = PlacementPoints(vec![5, 3, 1]);
+
+        let tables = tourn.next_round().unwrap().unwrap();
+        let mut sizes: Vec<_> = tables.iter().map(|(_, names)| names.len()).collect();
+        sizes.sort();
+        assert_eq!(sizes, vec![3, 3, 4]);
+
+        let (uuid, _) = tables.iter().find(|(_, n)| n.len() == 4).unwrap();
+        assert_eq!(
+            tourn.end_table(*uuid, &[1, 2, 5, 3]),
+            Err(TableResultError::InvalidPlacements(vec![1, 2, 5, 3]))
+        );
+        assert!(tourn.next_round().is_err());
+
+        // Second place is shared, and fourth place has no points configured
+        tourn.end_table(*uuid, &[2, 1, 4, 2]).unwrap();
+        assert_eq!(
+            tourn.end_table(*uuid, &[1, 2, 3, 4]),
+            Err(TableResultError::AlreadyReported(*uuid))
+        );
+
+        let seats: Vec<&Player> = tourn.tables[uuid]
+            .players
+            .iter()
+            .map(|&p| tourn.players.get(p).unwrap())
+            .collect();
+        let points: Vec<_> = seats.iter().map(|p| p.match_points).collect();
+        assert_eq!(points, vec![3, 5, 0, 3]);
+        assert_eq!(seats[1].matches_won, 1);
+        assert_eq!(seats[0].opponents.len(), 3);
+    }
+}
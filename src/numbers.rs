@@ -0,0 +1,245 @@
+//! Short numbers for players and tables, so that organizers and players don't have to deal with
+//! UUIDs. Every player gets a player number when entering the event, like a pairing number in
+//! chess, and every pairing is numbered by its table within its round. Anything that refers to a
+//! player or a pairing can be given as a number, a UUID, or for players, a name.
+
+use crate::lookup::LookupError;
+use crate::{PairingResultError, Tournament};
+
+/// Parses a number like "3" or "#3"
+fn parse_number(text: &str) -> Option<u32> {
+    let text = text.trim();
+    text.strip_prefix('#').unwrap_or(text).parse().ok()
+}
+
+impl Tournament {
+    /// Returns the player number of a player, starting at 1. Players are numbered in the order
+    /// they entered the tournament. Players who leave before the first round give up their number
+    /// and the players after them move up; afterwards, numbers never change.
+    pub fn player_number(&self, uuid: uuid::Uuid) -> Option<u32> {
+        self.player_numbers
+            .iter()
+            .position(|&player| player == uuid)
+            .map(|i| i as u32 + 1)
+    }
+
+    /// Returns the player with the given player number
+    pub fn player_by_number(&self, number: u32) -> Option<uuid::Uuid> {
+        let i = number.checked_sub(1)? as usize;
+        self.player_numbers.get(i).copied()
+    }
+
+    /// Returns the round and table number of a pairing. Tables are numbered starting at 1 in the
+    /// order of the pairings of `round()`.
+    pub fn table_number(&self, pairing: uuid::Uuid) -> Option<(u32, u32)> {
+        self.round_history().into_iter().find_map(|round| {
+            let i = round.pairings.iter().position(|p| p.uuid == pairing)?;
+            Some((round.number, i as u32 + 1))
+        })
+    }
+
+    /// Returns the pairing at the given table of a round
+    pub fn pairing_at_table(&self, round: u32, table: u32) -> Option<uuid::Uuid> {
+        let i = table.checked_sub(1)? as usize;
+        self.round(round)?.pairings.get(i).map(|p| p.uuid)
+    }
+
+    /// Features a pairing of the current round, e.g. the match shown on stream, and moves it to
+    /// the `featured_table` so that the broadcast table keeps its number from round to round. The
+    /// pairing that sat there takes over the featured pairing's table. Featuring another pairing
+    /// of the round replaces it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use swyss::{Player, Tournament};
+    ///
+    /// let players = (1..9)
+    ///     .map(|i| Player::new(&format!("Player {}", i)))
+    ///     .collect();
+    /// let mut tourn = Tournament::with_players(players).unwrap();
+    /// let (uuid, _, _) = tourn.next_round().unwrap().unwrap()[2];
+    ///
+    /// tourn.feature_pairing(uuid).unwrap();
+    /// assert_eq!(tourn.featured_pairing(), Some(uuid));
+    /// assert_eq!(tourn.table_number(uuid), Some((1, 1)));
+    /// ```
+    pub fn feature_pairing(&mut self, pairing: uuid::Uuid) -> Result<(), PairingResultError> {
+        self.check_unlocked()?;
+        let featured_table = self.featured_table.max(1) as usize;
+        let current_round = self.current_round;
+        let round = self
+            .history
+            .last_mut()
+            .filter(|round| round.number == current_round)
+            .ok_or(PairingResultError::NotFound(pairing))?;
+        let i = round
+            .pairings
+            .iter()
+            .position(|p| p.uuid == pairing)
+            .ok_or(PairingResultError::NotFound(pairing))?;
+
+        let table = (featured_table - 1).min(round.pairings.len() - 1);
+        round.pairings.swap(i, table);
+        round.featured = Some(pairing);
+        Ok(())
+    }
+
+    /// Returns the featured pairing of the current round, if one was featured
+    pub fn featured_pairing(&self) -> Option<uuid::Uuid> {
+        self.history
+            .last()
+            .filter(|round| round.number == self.current_round)
+            .and_then(|round| round.featured)
+    }
+
+    /// Finds a player given their player number, e.g. "7" or "#7", their UUID, or their name as
+    /// with `find_player()`
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use swyss::{Player, Tournament};
+    ///
+    /// let players = ["Alice", "Bob", "Carol"]
+    ///     .iter()
+    ///     .map(|name| Player::new(name))
+    ///     .collect();
+    /// let tourn = Tournament::with_players(players).unwrap();
+    /// let bob = tourn.find_player("Bob").unwrap().uuid;
+    ///
+    /// assert_eq!(tourn.player_number(bob), Some(2));
+    /// assert_eq!(tourn.resolve_player("#2"), Ok(bob));
+    /// assert_eq!(tourn.resolve_player(&bob.to_string()), Ok(bob));
+    /// assert_eq!(tourn.resolve_player("bo"), Ok(bob));
+    /// ```
+    pub fn resolve_player(&self, player: &str) -> Result<uuid::Uuid, LookupError> {
+        if let Some(number) = parse_number(player) {
+            return self
+                .player_by_number(number)
+                .ok_or_else(|| LookupError::NotFound(String::from(player)));
+        }
+
+        if let Ok(uuid) = uuid::Uuid::parse_str(player.trim()) {
+            return match self.player(uuid) {
+                Some(p) => Ok(p.uuid),
+                None => Err(LookupError::NotFound(String::from(player))),
+            };
+        }
+
+        self.find_player(player).map(|p| p.uuid)
+    }
+
+    /// Finds a pairing of the current round given its table number, e.g. "3" or "#3", or its
+    /// UUID. Pairings of earlier rounds can be given by UUID as well.
+    pub fn resolve_pairing(&self, pairing: &str) -> Option<uuid::Uuid> {
+        match parse_number(pairing) {
+            Some(table) => self.pairing_at_table(self.current_round, table),
+            None => {
+                let uuid = uuid::Uuid::parse_str(pairing.trim()).ok()?;
+                self.table_number(uuid).map(|_| uuid)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::persist::TournamentData;
+    use crate::Player;
+
+    fn players(n: usize) -> Vec<Player> {
+        (1..=n)
+            .map(|i| Player::new(format!("Player {}", i).as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn featured_table_is_fixed() {
+        let mut tourn = Tournament::with_players(players(8)).unwrap();
+        tourn.rounds = 3;
+        tourn.featured_table = 3;
+
+        let pairings = tourn.next_round().unwrap().unwrap();
+        let (first, _, _) = pairings[0];
+        let at_three = tourn.pairing_at_table(1, 3).unwrap();
+        let first_table = tourn.table_number(first).unwrap().1;
+        tourn.feature_pairing(first).unwrap();
+        assert_eq!(tourn.table_number(first), Some((1, 3)));
+        assert_eq!(tourn.table_number(at_three), Some((1, first_table)));
+
+        // The feature survives results and saving, but not the next round
+        tourn.end_match(first, 2, 0, 0).unwrap();
+        let mut loaded = Tournament::from_json(&tourn.to_json()).unwrap();
+        assert_eq!(loaded.featured_pairing(), Some(first));
+        assert_eq!(loaded.pairing_at_table(1, 3), Some(first));
+        assert_eq!(loaded.featured_table, 3);
+
+        for (uuid, _, _) in &pairings[1..] {
+            loaded.end_match(*uuid, 1, 2, 0).unwrap();
+        }
+        loaded.next_round().unwrap();
+        assert_eq!(loaded.featured_pairing(), None);
+        assert!(matches!(
+            loaded.feature_pairing(first),
+            Err(PairingResultError::NotFound(_))
+        ));
+        assert_eq!(loaded.round(1).unwrap().featured, Some(first));
+    }
+
+    #[test]
+    fn player_numbers_are_stable() {
+        let players = players(5);
+        let uuids: Vec<_> = players.iter().map(|p| p.uuid).collect();
+        let mut tourn = Tournament::with_players(players).unwrap();
+
+        // Leaving before the first round closes the gap
+        tourn.drop_player(uuids[1]).unwrap();
+        assert_eq!(tourn.player_number(uuids[2]), Some(2));
+        assert_eq!(tourn.player_number(uuids[1]), None);
+
+        tourn.next_round().unwrap();
+        tourn.ranking();
+        tourn.drop_player(uuids[0]).unwrap();
+        assert_eq!(tourn.player_number(uuids[0]), Some(1));
+        assert_eq!(tourn.player_by_number(4), Some(uuids[4]));
+        assert_eq!(tourn.player_by_number(5), None);
+        assert_eq!(tourn.player_by_number(0), None);
+
+        let late = Player::new("Latecomer");
+        let late_uuid = late.uuid;
+        tourn.add_player(late).unwrap();
+        assert_eq!(tourn.resolve_player("5"), Ok(late_uuid));
+        assert_eq!(
+            tourn.resolve_player("#6"),
+            Err(LookupError::NotFound(String::from("#6")))
+        );
+
+        let reloaded = TournamentData::from(&tourn).into_tournament().unwrap();
+        assert_eq!(reloaded.player_by_number(5), Some(late_uuid));
+    }
+
+    #[test]
+    fn tables() {
+        let mut tourn = Tournament::with_players(players(6)).unwrap();
+        let pairings = tourn.next_round().unwrap().unwrap();
+
+        for (uuid, _, _) in &pairings {
+            let (round, table) = tourn.table_number(*uuid).unwrap();
+            assert_eq!(round, 1);
+            assert_eq!(tourn.pairing_at_table(1, table), Some(*uuid));
+            assert_eq!(tourn.resolve_pairing(&format!("#{}", table)), Some(*uuid));
+            assert_eq!(tourn.resolve_pairing(&uuid.to_string()), Some(*uuid));
+            tourn.end_match(*uuid, 2, 0, 0).unwrap();
+        }
+        assert_eq!(tourn.pairing_at_table(1, 4), None);
+
+        // Earlier rounds keep their table numbers
+        let (first, _, _) = pairings[0];
+        let table = tourn.table_number(first).unwrap().1;
+        tourn.next_round().unwrap();
+        assert_eq!(tourn.table_number(first), Some((1, table)));
+        assert_ne!(tourn.resolve_pairing(&table.to_string()), Some(first));
+    }
+}
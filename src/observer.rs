@@ -0,0 +1,219 @@
+//! Hooks for integrations that react to changes of a tournament, such as logging, webhooks or
+//! stream overlays, without having to poll it.
+
+use crate::{Player, Tournament};
+use std::rc::Rc;
+
+/// Callbacks that are invoked as a tournament progresses. All methods do nothing by default, so
+/// an observer only implements the ones it is interested in. Observers are called with a shared
+/// reference, so an observer that needs to keep state uses interior mutability.
+///
+/// # Example
+///
+/// ```
+/// use core::cell::Cell;
+/// use std::rc::Rc;
+/// use swyss::observer::TournamentObserver;
+/// use swyss::{Player, Tournament};
+///
+/// struct RoundCounter(Rc<Cell<u32>>);
+///
+/// impl TournamentObserver for RoundCounter {
+///     fn on_round_paired(&self, _: &Tournament, _: &[(uuid::Uuid, String, String)]) {
+///         self.0.set(self.0.get() + 1);
+///     }
+/// }
+///
+/// let players = (1..5)
+///     .map(|i| Player::new(&format!("Player {}", i)))
+///     .collect();
+/// let mut tourn = Tournament::with_players(players).unwrap();
+/// let rounds = Rc::new(Cell::new(0));
+/// tourn.add_observer(Box::new(RoundCounter(Rc::clone(&rounds))));
+///
+/// while let Some(pairings) = tourn.next_round().unwrap() {
+///     for (uuid, _, _) in pairings {
+///         tourn.end_match(uuid, 2, 0, 0).unwrap();
+///     }
+/// }
+/// assert_eq!(rounds.get(), 2);
+/// ```
+pub trait TournamentObserver {
+    /// A new round was paired. `pairings` are the pairings as returned by `next_round()`.
+    fn on_round_paired(
+        &self,
+        _tournament: &Tournament,
+        _pairings: &[(uuid::Uuid, String, String)],
+    ) {
+    }
+
+    /// The result of a pairing of the current round was recorded or amended
+    fn on_result(
+        &self,
+        _tournament: &Tournament,
+        _pairing: uuid::Uuid,
+        _home_score: u8,
+        _away_score: u8,
+        _drawn: u8,
+    ) {
+    }
+
+    /// A player was awarded a bye in the round that was just paired
+    fn on_bye(&self, _tournament: &Tournament, _player: &Player) {}
+
+    /// A player dropped from the tournament
+    fn on_drop(&self, _tournament: &Tournament, _player: &Player) {}
+
+    /// The last round is over, i.e. `next_round()` returned `None` for the first time
+    fn on_complete(&self, _tournament: &Tournament) {}
+}
+
+/// Lets the caller keep a handle to an observer after registering it, e.g. to read state it
+/// collected
+impl<T: TournamentObserver> TournamentObserver for Rc<T> {
+    fn on_round_paired(&self, tournament: &Tournament, pairings: &[(uuid::Uuid, String, String)]) {
+        (**self).on_round_paired(tournament, pairings)
+    }
+
+    fn on_result(
+        &self,
+        tournament: &Tournament,
+        pairing: uuid::Uuid,
+        home_score: u8,
+        away_score: u8,
+        drawn: u8,
+    ) {
+        (**self).on_result(tournament, pairing, home_score, away_score, drawn)
+    }
+
+    fn on_bye(&self, tournament: &Tournament, player: &Player) {
+        (**self).on_bye(tournament, player)
+    }
+
+    fn on_drop(&self, tournament: &Tournament, player: &Player) {
+        (**self).on_drop(tournament, player)
+    }
+
+    fn on_complete(&self, tournament: &Tournament) {
+        (**self).on_complete(tournament)
+    }
+}
+
+impl Tournament {
+    /// Registers an observer that is notified of all further changes to the tournament.
+    /// Observers are not saved along with the tournament.
+    pub fn add_observer(&mut self, observer: Box<dyn TournamentObserver>) {
+        self.observers.push(observer);
+    }
+
+    /// Calls `f` for every registered observer
+    pub(crate) fn notify<F: Fn(&dyn TournamentObserver)>(&self, f: F) {
+        for observer in &self.observers {
+            f(observer.as_ref());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::cell::RefCell;
+
+    /// Records every callback as a line of text
+    struct Recorder(Rc<RefCell<Vec<String>>>);
+
+    impl TournamentObserver for Recorder {
+        fn on_round_paired(&self, t: &Tournament, pairings: &[(uuid::Uuid, String, String)]) {
+            self.0.borrow_mut().push(format!(
+                "round {} paired: {}",
+                t.current_round,
+                pairings.len()
+            ));
+        }
+
+        fn on_result(&self, _: &Tournament, _: uuid::Uuid, home: u8, away: u8, drawn: u8) {
+            self.0
+                .borrow_mut()
+                .push(format!("result {}-{}-{}", home, away, drawn));
+        }
+
+        fn on_bye(&self, _: &Tournament, player: &Player) {
+            self.0.borrow_mut().push(format!("bye {}", player.name));
+        }
+
+        fn on_drop(&self, _: &Tournament, player: &Player) {
+            self.0.borrow_mut().push(format!("drop {}", player.name));
+        }
+
+        fn on_complete(&self, _: &Tournament) {
+            self.0.borrow_mut().push(String::from("complete"));
+        }
+    }
+
+    #[test]
+    fn callbacks() {
+        let players = (1..4)
+            .map(|i| Player::new(format!("Player {}", i).as_str()))
+            .collect();
+        let mut tourn = Tournament::with_players(players).unwrap();
+        let events = Rc::new(RefCell::new(Vec::new()));
+        tourn.add_observer(Box::new(Recorder(Rc::clone(&events))));
+
+        let pairings = tourn.next_round().unwrap().unwrap();
+        assert!(tourn.end_match(pairings[0].0, 2, 1, 0).is_ok());
+        assert!(tourn.end_match(pairings[0].0, 3, 0, 0).is_err());
+
+        let dropped = tourn.players[0].uuid;
+        assert!(tourn.drop_player(dropped).unwrap().is_some());
+        assert!(tourn.drop_player(uuid::Uuid::new_v4()).unwrap().is_none());
+
+        let (uuid, _, _) = tourn.next_round().unwrap().unwrap()[0].clone();
+        tourn.end_match(uuid, 2, 0, 0).unwrap();
+        assert!(tourn.next_round().unwrap().is_none());
+        assert!(tourn.next_round().unwrap().is_none());
+
+        let events = events.borrow();
+        assert_eq!(events[0], "round 1 paired: 1");
+        assert!(events[1].starts_with("bye "));
+        assert_eq!(events[2], "result 2-1-0");
+        assert!(events[3].starts_with("drop "));
+        assert_eq!(events[4], "round 2 paired: 1");
+        assert_eq!(events.last().unwrap(), "complete");
+        assert_eq!(events.iter().filter(|e| *e == "complete").count(), 1);
+    }
+
+    #[test]
+    fn finish_early() {
+        use crate::history::TournamentState;
+
+        let players = (1..5)
+            .map(|i| Player::new(format!("Player {}", i).as_str()))
+            .collect();
+        let mut tourn = Tournament::with_players(players).unwrap();
+        let events = Rc::new(RefCell::new(Vec::new()));
+        tourn.add_observer(Box::new(Recorder(Rc::clone(&events))));
+        assert_eq!(tourn.state(), TournamentState::BetweenRounds);
+
+        let pairings = tourn.next_round().unwrap().unwrap();
+        tourn.end_match(pairings[0].0, 2, 1, 0).unwrap();
+        assert_eq!(tourn.state(), TournamentState::InRound(1));
+        assert!(tourn.finish().is_err());
+
+        tourn.end_match(pairings[1].0, 0, 2, 0).unwrap();
+        assert_eq!(tourn.state(), TournamentState::BetweenRounds);
+        tourn.finish().unwrap();
+        tourn.finish().unwrap();
+        assert_eq!(tourn.state(), TournamentState::Complete);
+        assert_eq!(tourn.rounds, 1);
+        assert!(tourn.standings_after(1).is_some());
+        assert!(tourn.next_round().unwrap().is_none());
+        assert!(matches!(
+            tourn.end_match(pairings[0].0, 2, 0, 0),
+            Err(crate::PairingResultError::Finished)
+        ));
+
+        let events = events.borrow();
+        assert_eq!(events.last().unwrap(), "complete");
+        assert_eq!(events.iter().filter(|e| *e == "complete").count(), 1);
+    }
+}
@@ -0,0 +1,262 @@
+//! Brute-force pairing oracle for small fields. It evaluates every possible pairing of a field and
+//! finds one with the fewest players paired against someone they must avoid, then the fewest
+//! rematches and, among those, the fewest players paired against their own club and the smallest
+//! match point differences between paired players. Besides checking the tournament's own
+//! pairings, it can be used to evaluate custom pairing strategies.
+
+use crate::Player;
+
+/// Largest field the oracle evaluates. The number of possible pairings grows factorially, so
+/// larger fields aren't feasible.
+pub const MAX_PLAYERS: usize = 10;
+
+/// Two players paired against each other, home first
+pub type PlayerPair<'a> = (&'a Player, &'a Player);
+
+/// How far a pairing is from an ideal one. Costs compare by pairings of players who must avoid
+/// each other first, by rematches second, by pairings within a club third, and by score difference
+/// last, so a lower cost is always the better pairing.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct PairingCost {
+    /// Number of pairings between players who must never be paired, see `Player::avoids()`
+    pub avoided: u32,
+    /// Number of pairings between players who already played each other
+    pub rematches: u32,
+    /// Number of pairings between players of the same club
+    pub same_club: u32,
+    /// Sum of the match point differences between paired players. A player who is left over
+    /// counts as if paired against an opponent without match points.
+    pub score_difference: u32,
+}
+
+/// Evaluates a pairing of the given players. Players who don't appear in any pair count as left
+/// over.
+pub fn cost(players: &[&Player], pairs: &[PlayerPair]) -> PairingCost {
+    let mut cost = PairingCost::default();
+
+    for (home, away) in pairs {
+        if home.avoids(away) {
+            cost.avoided += 1;
+        }
+        if home.opponents.contains(&away.uuid) {
+            cost.rematches += 1;
+        }
+        if home.same_club(away) {
+            cost.same_club += 1;
+        }
+
+        cost.score_difference += diff(home.match_points, away.match_points);
+    }
+
+    for p in players {
+        let paired = pairs
+            .iter()
+            .any(|(home, away)| home.uuid == p.uuid || away.uuid == p.uuid);
+        if !paired {
+            cost.score_difference += p.match_points;
+        }
+    }
+
+    cost
+}
+
+/// Finds an optimal pairing of the given players by trying all of them. If the number of players
+/// is odd, one player is left over. Returns `None` if there are more than `MAX_PLAYERS` players.
+///
+/// # Example
+///
+/// ```
+/// use swyss::{oracle, Player};
+///
+/// let players: Vec<_> = (1..5).map(|i| Player::new(&format!("Player {}", i))).collect();
+/// let players: Vec<&Player> = players.iter().collect();
+///
+/// let (pairs, cost) = oracle::optimal(&players).unwrap();
+/// assert_eq!(pairs.len(), 2);
+/// assert_eq!(cost, oracle::cost(&players, &pairs));
+/// assert_eq!(cost.rematches, 0);
+/// ```
+pub fn optimal<'a>(players: &[&'a Player]) -> Option<(Vec<PlayerPair<'a>>, PairingCost)> {
+    if players.len() > MAX_PLAYERS {
+        return None;
+    }
+
+    let n = players.len();
+    let points: Vec<u32> = players.iter().map(|p| p.match_points).collect();
+    let rematch: Vec<Vec<bool>> = players
+        .iter()
+        .map(|a| {
+            players
+                .iter()
+                .map(|b| a.opponents.contains(&b.uuid))
+                .collect()
+        })
+        .collect();
+    let same_club: Vec<Vec<bool>> = players
+        .iter()
+        .map(|a| players.iter().map(|b| a.same_club(b)).collect())
+        .collect();
+    let avoided: Vec<Vec<bool>> = players
+        .iter()
+        .map(|a| players.iter().map(|b| a.avoids(b)).collect())
+        .collect();
+
+    let mut search = Search {
+        points: &points,
+        avoided: &avoided,
+        rematch: &rematch,
+        same_club: &same_club,
+        used: vec![false; n],
+        current: Vec::with_capacity(n / 2),
+        best: None,
+    };
+    search.run(PairingCost::default(), n % 2 == 1);
+
+    search.best.map(|(pairs, cost)| {
+        let pairs = pairs
+            .iter()
+            .map(|&(a, b)| (players[a], players[b]))
+            .collect();
+        (pairs, cost)
+    })
+}
+
+fn diff(a: u32, b: u32) -> u32 {
+    a.max(b) - a.min(b)
+}
+
+/// State of the exhaustive search over all pairings, working on player indices
+struct Search<'a> {
+    points: &'a [u32],
+    avoided: &'a [Vec<bool>],
+    rematch: &'a [Vec<bool>],
+    same_club: &'a [Vec<bool>],
+    used: Vec<bool>,
+    current: Vec<(usize, usize)>,
+    best: Option<(Vec<(usize, usize)>, PairingCost)>,
+}
+
+impl<'a> Search<'a> {
+    /// Pairs the first unused player with every possible opponent in turn, or leaves them over if
+    /// `may_skip` is set, and recurses until everyone is used
+    fn run(&mut self, cost: PairingCost, may_skip: bool) {
+        if let Some((_, best)) = &self.best {
+            if cost >= *best {
+                return;
+            }
+        }
+
+        let first = match self.used.iter().position(|used| !used) {
+            Some(i) => i,
+            None => {
+                self.best = Some((self.current.clone(), cost));
+                return;
+            }
+        };
+
+        self.used[first] = true;
+
+        for other in first + 1..self.used.len() {
+            if self.used[other] {
+                continue;
+            }
+
+            let mut next = cost;
+            if self.avoided[first][other] {
+                next.avoided += 1;
+            }
+            if self.rematch[first][other] {
+                next.rematches += 1;
+            }
+            if self.same_club[first][other] {
+                next.same_club += 1;
+            }
+            next.score_difference += diff(self.points[first], self.points[other]);
+
+            self.used[other] = true;
+            self.current.push((first, other));
+            self.run(next, may_skip);
+            self.current.pop();
+            self.used[other] = false;
+        }
+
+        if may_skip {
+            let mut next = cost;
+            next.score_difference += self.points[first];
+            self.run(next, false);
+        }
+
+        self.used[first] = false;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn players(points: &[u32]) -> Vec<Player> {
+        points
+            .iter()
+            .enumerate()
+            .map(|(i, &mp)| {
+                let mut p = Player::new(&format!("Player {}", i + 1));
+                p.match_points = mp;
+                p
+            })
+            .collect()
+    }
+
+    #[test]
+    fn avoids_rematch() {
+        let mut players = players(&[6, 6, 3, 3]);
+        let (first, second) = (players[0].uuid, players[1].uuid);
+        players[0].opponents.push(second);
+        players[1].opponents.push(first);
+        let players: Vec<&Player> = players.iter().collect();
+
+        // Pairing within the score groups would be a rematch
+        let (pairs, cost) = optimal(&players).unwrap();
+        assert_eq!(cost.rematches, 0);
+        assert_eq!(cost.score_difference, 6);
+        assert!(pairs
+            .iter()
+            .all(|(a, b)| a.uuid != first || b.uuid != second));
+    }
+
+    #[test]
+    fn avoids_same_club() {
+        let mut players = players(&[3, 3, 3, 3]);
+        for p in &mut players[..2] {
+            p.club = Some(String::from("Rooks"));
+        }
+        let players: Vec<&Player> = players.iter().collect();
+
+        let (pairs, cost) = optimal(&players).unwrap();
+        assert_eq!((cost.same_club, cost.score_difference), (0, 0));
+        assert_eq!(cost, super::cost(&players, &pairs));
+    }
+
+    #[test]
+    fn odd_field() {
+        let players = players(&[3, 3, 0]);
+        let players: Vec<&Player> = players.iter().collect();
+
+        let (pairs, best) = optimal(&players).unwrap();
+        assert_eq!(pairs.len(), 1);
+        assert_eq!(best.score_difference, 0);
+        assert_eq!(pairs[0].0.uuid, players[0].uuid);
+        assert_eq!(pairs[0].1.uuid, players[1].uuid);
+
+        // A pairing that leaves a player with match points over is worse
+        let worse = vec![(players[1], players[2])];
+        assert_eq!(cost(&players, &worse).score_difference, 6);
+    }
+
+    #[test]
+    fn too_many_players() {
+        let fits = players(&[0; MAX_PLAYERS]);
+        let too_many = players(&[0; MAX_PLAYERS + 1]);
+        assert!(optimal(&fits.iter().collect::<Vec<_>>()).is_some());
+        assert!(optimal(&too_many.iter().collect::<Vec<_>>()).is_none());
+    }
+}
@@ -0,0 +1,264 @@
+//! Data for broadcast overlays: the top of the standings, the current round, and a featured match,
+//! in a schema that stays stable across versions of swyss so that overlay templates keep working.
+//! `OverlayFile` keeps an `overlay.json` up to date for streaming software to read.
+
+use crate::observer::TournamentObserver;
+use crate::persist::standings_rows;
+use crate::{Player, Tournament};
+use core::cell::RefCell;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Version of the overlay schema. Fields may be added without changing it; it only increases
+/// when a field is removed or changes its meaning.
+pub const OVERLAY_VERSION: u32 = 1;
+
+/// Number of players in the overlay's standings
+pub const OVERLAY_STANDINGS: usize = 8;
+
+/// A player as shown on the overlay
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct OverlayPlayer {
+    pub rank: u32,
+    /// The player's alias if they have one, and their name otherwise
+    pub name: String,
+    pub wins: u32,
+    pub losses: u32,
+    pub draws: u32,
+    pub match_points: u32,
+}
+
+/// The match the broadcast is showing. Games are 0 until the result is reported.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct FeaturedMatch {
+    pub table: u32,
+    pub home: OverlayPlayer,
+    pub away: OverlayPlayer,
+    pub home_games: u8,
+    pub away_games: u8,
+    pub drawn_games: u8,
+    pub finished: bool,
+}
+
+/// Everything an overlay shows, as returned by `Tournament::overlay()`
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct Overlay {
+    /// `OVERLAY_VERSION` at the time the overlay was written
+    pub version: u32,
+    pub event: String,
+    /// 0 before the first round is paired
+    pub round: u32,
+    pub rounds: u32,
+    pub finished: bool,
+    /// The top `OVERLAY_STANDINGS` players
+    pub standings: Vec<OverlayPlayer>,
+    /// `None` before the first round is paired
+    pub featured_match: Option<FeaturedMatch>,
+}
+
+impl Overlay {
+    /// Serializes the overlay to JSON
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("overlays are always serializable")
+    }
+}
+
+impl Tournament {
+    /// Collects the overlay data of the moment. The featured match is the pairing featured with
+    /// `feature_pairing()`, or by default the top table: the pairing of the best ranked player
+    /// who is paired in the current round.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use swyss::{Player, Tournament};
+    ///
+    /// let players = (1..11)
+    ///     .map(|i| Player::new(&format!("Player {}", i)))
+    ///     .collect();
+    /// let mut tourn = Tournament::with_players(players).unwrap();
+    /// tourn.next_round().unwrap();
+    ///
+    /// let overlay = tourn.overlay();
+    /// assert_eq!((overlay.round, overlay.rounds), (1, 4));
+    /// assert_eq!(overlay.standings.len(), 8);
+    /// assert!(!overlay.featured_match.unwrap().finished);
+    /// ```
+    pub fn overlay(&self) -> Overlay {
+        let players = self.sorted_players();
+        let standings: Vec<OverlayPlayer> = standings_rows(self, &players)
+            .into_iter()
+            .map(|row| OverlayPlayer {
+                rank: row.rank,
+                name: String::from(row.display_name()),
+                wins: row.matches_won,
+                losses: row.matches_played - row.matches_won - row.matches_drawn,
+                draws: row.matches_drawn,
+                match_points: row.match_points,
+            })
+            .collect();
+
+        let featured = self
+            .featured_pairing()
+            .and_then(|uuid| self.pairings.get(&uuid))
+            .or_else(|| {
+                players.iter().find_map(|p| {
+                    self.pairings
+                        .values()
+                        .find(|pair| pair.home == p.uuid || pair.away == p.uuid)
+                })
+            });
+        let rank_of = |player: uuid::Uuid| {
+            let i = players
+                .iter()
+                .position(|p| p.uuid == player)
+                .expect("paired players are in the tournament");
+            standings[i].clone()
+        };
+        let featured_match = featured.map(|pair| {
+            let result = pair.result.get();
+            FeaturedMatch {
+                table: self.table_number(pair.uuid).map_or(0, |(_, table)| table),
+                home: rank_of(pair.home),
+                away: rank_of(pair.away),
+                home_games: result.map_or(0, |r| r.home_score),
+                away_games: result.map_or(0, |r| r.away_score),
+                drawn_games: result.map_or(0, |r| r.drawn),
+                finished: result.is_some(),
+            }
+        });
+
+        Overlay {
+            version: OVERLAY_VERSION,
+            event: String::from(self.event.title()),
+            round: self.current_round.min(self.rounds),
+            rounds: self.rounds,
+            finished: self.is_finished(),
+            standings: standings.into_iter().take(OVERLAY_STANDINGS).collect(),
+            featured_match,
+        }
+    }
+}
+
+/// Observer that rewrites an overlay file whenever the tournament changes. The file is replaced
+/// in one step, so streaming software never reads it half-written. Failed writes don't interrupt
+/// the tournament; they are kept until collected with `take_errors()`.
+pub struct OverlayFile {
+    path: PathBuf,
+    errors: RefCell<Vec<io::Error>>,
+}
+
+impl OverlayFile {
+    pub fn new<P: AsRef<Path>>(path: P) -> OverlayFile {
+        OverlayFile {
+            path: path.as_ref().to_path_buf(),
+            errors: RefCell::new(Vec::new()),
+        }
+    }
+
+    /// Writes the overlay of the tournament right away
+    pub fn write(&self, tournament: &Tournament) -> io::Result<()> {
+        let json = tournament.overlay().to_json();
+        let mut tmp = self.path.clone().into_os_string();
+        tmp.push(".tmp");
+        fs::write(&tmp, json)?;
+        fs::rename(&tmp, &self.path)
+    }
+
+    /// Returns the errors of all failed writes since the last call
+    pub fn take_errors(&self) -> Vec<io::Error> {
+        self.errors.replace(Vec::new())
+    }
+
+    fn update(&self, tournament: &Tournament) {
+        if let Err(e) = self.write(tournament) {
+            self.errors.borrow_mut().push(e);
+        }
+    }
+}
+
+impl TournamentObserver for OverlayFile {
+    fn on_round_paired(&self, tournament: &Tournament, _: &[(uuid::Uuid, String, String)]) {
+        self.update(tournament);
+    }
+
+    fn on_result(&self, tournament: &Tournament, _: uuid::Uuid, _: u8, _: u8, _: u8) {
+        self.update(tournament);
+    }
+
+    fn on_drop(&self, tournament: &Tournament, _: &Player) {
+        self.update(tournament);
+    }
+
+    fn on_complete(&self, tournament: &Tournament) {
+        self.update(tournament);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::rc::Rc;
+
+    fn tournament(players: usize) -> Tournament {
+        let players = (1..=players)
+            .map(|i| Player::new(format!("Player {}", i).as_str()))
+            .collect();
+        Tournament::with_players(players).unwrap()
+    }
+
+    #[test]
+    fn top_table_is_featured() {
+        let mut tourn = tournament(8);
+        for (uuid, _, _) in tourn.next_round().unwrap().unwrap() {
+            tourn.end_match(uuid, 2, 0, 0).unwrap();
+        }
+        let pairings = tourn.next_round().unwrap().unwrap();
+
+        // The leader plays another winner at the top table
+        let overlay = tourn.overlay();
+        let featured = overlay.featured_match.unwrap();
+        assert_eq!(featured.home.match_points + featured.away.match_points, 6);
+        assert!(featured.home.rank.min(featured.away.rank) == 1);
+        assert!(!featured.finished);
+
+        // A featured pairing is shown with its result once it is in
+        let (uuid, home, _) = &pairings[3];
+        tourn.feature_pairing(*uuid).unwrap();
+        tourn.end_match(*uuid, 2, 1, 0).unwrap();
+        let featured = tourn.overlay().featured_match.unwrap();
+        assert_eq!(&featured.home.name, home);
+        assert_eq!((featured.home_games, featured.away_games), (2, 1));
+        assert!(featured.finished);
+        assert_eq!(featured.table, 1);
+    }
+
+    #[test]
+    fn overlay_file_is_rewritten() {
+        let path =
+            std::env::temp_dir().join(format!("swyss-overlay-{}.json", uuid::Uuid::new_v4()));
+        let overlay = Rc::new(OverlayFile::new(&path));
+        let mut tourn = tournament(4);
+        tourn.add_observer(Box::new(Rc::clone(&overlay)));
+
+        let (uuid, _, _) = tourn.next_round().unwrap().unwrap()[0];
+        let read =
+            || -> Overlay { serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap() };
+        assert_eq!(read().version, OVERLAY_VERSION);
+        assert_eq!(
+            read().standings.iter().map(|p| p.match_points).sum::<u32>(),
+            0
+        );
+
+        tourn.end_match(uuid, 2, 0, 0).unwrap();
+        assert_eq!(read().standings[0].match_points, 3);
+        assert!(overlay.take_errors().is_empty());
+
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,392 @@
+//! Printable event reports as PDF, e.g. to hand to players or to file with a sanctioning body.
+//! A report holds the event's details and settings, the standings with all tiebreakers, the prizes,
+//! the achievements, the pairings and results of every round, and the penalties issued. Requires
+//! the `pdf` feature.
+//!
+//! Reports use the fonts built into every PDF viewer, which only cover Windows-1252, so
+//! characters of other scripts in player names are left out.
+
+use crate::locale::Message;
+use crate::persist::standings_rows;
+use crate::{ByeScore, MatchFormat, PairingSystem, Tournament};
+use printpdf::{BuiltinFont, Mm, PdfDocument};
+
+/// Size of an A4 page in millimeters
+const PAGE_WIDTH: f64 = 210.0;
+const PAGE_HEIGHT: f64 = 297.0;
+const MARGIN: f64 = 20.0;
+
+/// Longest name printed in a table, so that columns stay aligned
+const NAME_WIDTH: usize = 24;
+
+/// How a line of a report is printed
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Style {
+    Title,
+    Heading,
+    /// Monospaced, so that table columns line up
+    Text,
+}
+
+impl Style {
+    /// Font size in points
+    fn size(self) -> f64 {
+        match self {
+            Style::Title => 18.0,
+            Style::Heading => 13.0,
+            Style::Text => 9.0,
+        }
+    }
+
+    /// Height of a line, including the space above it, in millimeters
+    fn height(self) -> f64 {
+        match self {
+            Style::Title => 10.0,
+            Style::Heading => 10.0,
+            Style::Text => 4.5,
+        }
+    }
+}
+
+/// A line of a report
+type Line = (Style, String);
+
+impl Tournament {
+    /// Renders a report of the event as a PDF document on A4 pages, titled with the event's name
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use swyss::event::EventInfo;
+    /// use swyss::{Player, Tournament};
+    ///
+    /// let players = (1..5)
+    ///     .map(|i| Player::new(&format!("Player {}", i)))
+    ///     .collect();
+    /// let mut tourn = Tournament::with_players(players).unwrap();
+    /// let event = EventInfo {
+    ///     name: Some(String::from("Friday Night")),
+    ///     ..EventInfo::default()
+    /// };
+    /// tourn.set_event(event).unwrap();
+    /// while let Some(pairings) = tourn.next_round().unwrap() {
+    ///     for (uuid, _, _) in pairings {
+    ///         tourn.end_match(uuid, 2, 1, 0).unwrap();
+    ///     }
+    /// }
+    ///
+    /// let pdf = tourn.report_pdf().unwrap();
+    /// assert!(pdf.starts_with(b"%PDF"));
+    /// ```
+    pub fn report_pdf(&self) -> Result<Vec<u8>, printpdf::Error> {
+        let pages = paginate(self.report_lines());
+
+        let (doc, page, layer) = PdfDocument::new(
+            self.event.title(),
+            Mm(PAGE_WIDTH),
+            Mm(PAGE_HEIGHT),
+            self.locale.format(Message::Page, &[&1]),
+        );
+        let regular = doc.add_builtin_font(BuiltinFont::Courier)?;
+        let bold = doc.add_builtin_font(BuiltinFont::HelveticaBold)?;
+
+        let mut layer = doc.get_page(page).get_layer(layer);
+        for (i, lines) in pages.iter().enumerate() {
+            if i > 0 {
+                let name = self.locale.format(Message::Page, &[&(i + 1)]);
+                let (page, index) = doc.add_page(Mm(PAGE_WIDTH), Mm(PAGE_HEIGHT), name);
+                layer = doc.get_page(page).get_layer(index);
+            }
+
+            for (style, text, y) in lines {
+                let font = match style {
+                    Style::Text => &regular,
+                    _ => &bold,
+                };
+                layer.use_text(text.as_str(), style.size(), Mm(MARGIN), Mm(*y), font);
+            }
+        }
+
+        doc.save_to_bytes()
+    }
+
+    /// The lines of the report, before they are laid out on pages
+    fn report_lines(&self) -> Vec<Line> {
+        let mut lines = vec![(Style::Title, String::from(self.event.title()))];
+        let text = |lines: &mut Vec<Line>, text: String| lines.push((Style::Text, text));
+        let heading = |lines: &mut Vec<Line>, message| {
+            lines.push((Style::Heading, String::from(self.locale.text(message))))
+        };
+        for detail in self.event.details() {
+            text(&mut lines, detail);
+        }
+
+        let MatchFormat::BestOf(games) = self.match_format;
+        let system = match self.pairing_system {
+            PairingSystem::Swiss => Message::Swiss,
+            PairingSystem::RoundRobin => Message::RoundRobin,
+            PairingSystem::Danish => Message::Danish,
+            PairingSystem::Monrad => Message::Monrad,
+        };
+        let bye = match self.bye_score {
+            ByeScore::Win => Message::Win,
+            ByeScore::Draw => Message::Draw,
+            ByeScore::Loss => Message::Loss,
+        };
+        let locale = self.locale;
+        text(
+            &mut lines,
+            locale.format(Message::Players, &[&self.players.len()]),
+        );
+        text(
+            &mut lines,
+            locale.format(
+                Message::RoundsOf,
+                &[&self.current_round.min(self.rounds), &self.rounds],
+            ),
+        );
+        text(
+            &mut lines,
+            locale.format(Message::PairingsFormat, &[&locale.text(system), &games]),
+        );
+        text(
+            &mut lines,
+            locale.format(Message::ByeCounts, &[&locale.text(bye)]),
+        );
+
+        heading(&mut lines, Message::Standings);
+        text(
+            &mut lines,
+            format!(
+                "{:>4}  {:<w$}  {:>3}  {:>8}  {:>5}  {:>5}  {:>5}",
+                locale.text(Message::Rank),
+                locale.text(Message::Name),
+                "MP",
+                locale.text(Message::Record),
+                "OMWP",
+                "GWP",
+                "OGWP",
+                w = NAME_WIDTH
+            ),
+        );
+        let players = self.sorted_players();
+        for row in standings_rows(self, &players) {
+            let record = format!(
+                "{}-{}-{}",
+                row.matches_won,
+                row.matches_played - row.matches_won - row.matches_drawn,
+                row.matches_drawn
+            );
+            text(
+                &mut lines,
+                format!(
+                    "{:>4}  {:<w$}  {:>3}  {:>8}  {:>5}  {:>5}  {:>5}",
+                    row.rank,
+                    truncate(&row.name),
+                    row.match_points,
+                    record,
+                    self.format_tiebreak(row.opponents_match_win_percentage),
+                    self.format_tiebreak(row.game_win_percentage),
+                    self.format_tiebreak(row.opponents_game_win_percentage),
+                    w = NAME_WIDTH
+                ),
+            );
+        }
+
+        if self.prizes.is_some() {
+            heading(&mut lines, Message::Prizes);
+            for line in self.prize_report().lines() {
+                text(&mut lines, String::from(line));
+            }
+        }
+
+        let achievements = self.achievements(None).to_string();
+        if !achievements.is_empty() {
+            heading(&mut lines, Message::Achievements);
+            for line in achievements.lines() {
+                text(&mut lines, String::from(line));
+            }
+        }
+
+        let name = |uuid: uuid::Uuid| {
+            let name = self.player(uuid).map(|p| p.name).unwrap_or_default();
+            truncate(&name)
+        };
+        for round in self.round_history() {
+            lines.push((
+                Style::Heading,
+                locale.format(Message::Round, &[&round.number]),
+            ));
+            for (i, pair) in round.pairings.iter().enumerate() {
+                let result = match pair.result {
+                    Some(r) => {
+                        let mut result = format!("{}-{}", r.home_score, r.away_score);
+                        if r.drawn > 0 {
+                            result.push_str(&format!("-{}", r.drawn));
+                        }
+                        if r.conceded.is_some() {
+                            result.push(' ');
+                            result.push_str(locale.text(Message::Conceded));
+                        } else if r.time_called {
+                            result.push(' ');
+                            result.push_str(locale.text(Message::TimeCalled));
+                        }
+                        result
+                    }
+                    None => String::from("-"),
+                };
+                text(
+                    &mut lines,
+                    format!(
+                        "{:>4}  {:<w$}  {:<w$}  {}",
+                        i + 1,
+                        name(pair.home),
+                        name(pair.away),
+                        result,
+                        w = NAME_WIDTH
+                    ),
+                );
+            }
+            if let Some(bye) = round.bye {
+                text(
+                    &mut lines,
+                    format!("      {}", locale.format(Message::Bye, &[&name(bye)])),
+                );
+            }
+            for bye in &round.requested_byes {
+                text(
+                    &mut lines,
+                    format!(
+                        "      {}",
+                        locale.format(Message::RequestedBye, &[&name(bye.player)])
+                    ),
+                );
+            }
+        }
+
+        if !self.penalties.is_empty() {
+            heading(&mut lines, Message::Penalties);
+            for line in self.penalty_report().lines() {
+                text(&mut lines, String::from(line));
+            }
+        }
+
+        lines
+    }
+}
+
+/// Shortens a name to fit its column
+fn truncate(name: &str) -> String {
+    if name.chars().count() <= NAME_WIDTH {
+        return String::from(name);
+    }
+
+    let mut short: String = name.chars().take(NAME_WIDTH - 1).collect();
+    short.push('~');
+    short
+}
+
+/// Lays the lines out on pages, giving each line its vertical position in millimeters from the
+/// bottom of the page. A heading that would end up at the bottom of a page starts the next page
+/// instead.
+fn paginate(lines: Vec<Line>) -> Vec<Vec<(Style, String, f64)>> {
+    let mut pages = vec![Vec::new()];
+    let mut y = PAGE_HEIGHT - MARGIN;
+
+    for (style, text) in lines {
+        let needed = match style {
+            Style::Heading => style.height() + 3.0 * Style::Text.height(),
+            _ => style.height(),
+        };
+        if y - needed < MARGIN {
+            pages.push(Vec::new());
+            y = PAGE_HEIGHT - MARGIN;
+        }
+
+        y -= style.height();
+        pages
+            .last_mut()
+            .expect("there is always a page")
+            .push((style, text, y));
+    }
+
+    pages
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::penalties::PenaltyKind;
+    use crate::{Player, PlayerSide};
+
+    fn tournament(n: usize) -> Tournament {
+        let players = (1..=n)
+            .map(|i| Player::new(format!("Player {}", i).as_str()))
+            .collect();
+        Tournament::with_players(players).unwrap()
+    }
+
+    #[test]
+    fn report() {
+        let mut tourn = tournament(5);
+        tourn.event.name = Some(String::from("Friday Night"));
+        tourn.event.organizer = Some(String::from("Jo"));
+        let pairings = tourn.next_round().unwrap().unwrap();
+        let (_, home, _) = &pairings[0];
+        let uuid = tourn.find_player(home).unwrap().uuid;
+        tourn
+            .penalize(uuid, PenaltyKind::Warning, "Slow play")
+            .unwrap();
+        tourn.end_match(pairings[0].0, 2, 1, 0).unwrap();
+        tourn.concede(pairings[1].0, PlayerSide::Away).unwrap();
+
+        let lines = tourn.report_lines();
+        assert_eq!(lines[0], (Style::Title, String::from("Friday Night")));
+        assert_eq!(lines[1], (Style::Text, String::from("Organizer: Jo")));
+        assert!(lines.contains(&(Style::Text, String::from("Rounds: 1 of 3"))));
+        let headings: Vec<&str> = lines
+            .iter()
+            .filter(|(style, _)| *style == Style::Heading)
+            .map(|(_, text)| text.as_str())
+            .collect();
+        assert_eq!(
+            headings,
+            ["Standings", "Achievements", "Round 1", "Penalties"]
+        );
+
+        let text: Vec<&str> = lines.iter().map(|(_, text)| text.as_str()).collect();
+        assert!(text[7].starts_with("Rank  Name"));
+        assert_eq!(text[7].len(), text[8].len());
+        assert!(text.iter().any(|line| line.ends_with("2-1")));
+        assert!(text.iter().any(|line| line.ends_with("2-0 (conceded)")));
+        assert!(text
+            .iter()
+            .any(|line| line.starts_with("      Bye: Player")));
+        assert!(text.iter().any(|line| line.contains("Slow play")));
+
+        assert!(tourn.report_pdf().unwrap().starts_with(b"%PDF"));
+    }
+
+    #[test]
+    fn long_reports_span_pages() {
+        let mut tourn = tournament(64);
+        while let Some(pairings) = tourn.next_round().unwrap() {
+            for (uuid, _, _) in pairings {
+                tourn.end_match(uuid, 2, 0, 0).unwrap();
+            }
+        }
+
+        let lines = tourn.report_lines();
+        let count = lines.len();
+        let pages = paginate(lines);
+        assert!(pages.len() > 1);
+        assert_eq!(pages.iter().map(Vec::len).sum::<usize>(), count);
+        for page in &pages {
+            assert!(page.iter().all(|(_, _, y)| *y >= MARGIN));
+            // Headings are followed by at least one line on the same page
+            assert_ne!(page.last().unwrap().0, Style::Heading);
+        }
+
+        assert_eq!(truncate(&"x".repeat(30)).chars().count(), NAME_WIDTH);
+    }
+}
@@ -0,0 +1,404 @@
+//! Penalties issued by judges. Every penalty is recorded with its reason and round. Game and match
+//! losses also change the result of the penalized player's match in the current round.
+
+use crate::reporting::{ReportState, ReportedResult};
+use crate::{LockError, PairingResultError, PlayerSide, Tournament};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Severity of a penalty
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub enum PenaltyKind {
+    /// Recorded only, without consequences for the match
+    Warning,
+    /// The opponent is awarded a game of the current match
+    GameLoss,
+    /// The current match is recorded as a 2-0 loss
+    MatchLoss,
+}
+
+impl fmt::Display for PenaltyKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PenaltyKind::Warning => write!(f, "Warning"),
+            PenaltyKind::GameLoss => write!(f, "Game loss"),
+            PenaltyKind::MatchLoss => write!(f, "Match loss"),
+        }
+    }
+}
+
+/// A penalty issued to a player
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct Penalty {
+    pub player: uuid::Uuid,
+    pub round: u32,
+    pub kind: PenaltyKind,
+    pub reason: String,
+}
+
+/// Reasons why a penalty can't be issued
+#[derive(Debug)]
+pub enum PenaltyError {
+    /// There is no such player
+    UnknownPlayer(uuid::Uuid),
+    /// Game and match losses need a match of the current round to apply to, but the player isn't
+    /// paired, e.g. because they have a bye
+    NotPaired(uuid::Uuid),
+    /// Recording the resulting match loss failed
+    Result(PairingResultError),
+    /// The tournament is complete and takes no more penalties
+    Finished,
+    /// The tournament was opened read-only and takes no penalties
+    ReadOnly,
+}
+
+impl std::error::Error for PenaltyError {}
+
+impl fmt::Display for PenaltyError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PenaltyError::UnknownPlayer(uuid) => write!(f, "Unknown player {}!", uuid),
+            PenaltyError::NotPaired(uuid) => {
+                write!(f, "Player {} has no match in the current round!", uuid)
+            }
+            PenaltyError::Result(e) => write!(f, "Could not record match loss: {}", e),
+            PenaltyError::Finished => write!(f, "The tournament is already finished!"),
+            PenaltyError::ReadOnly => write!(f, "The tournament was opened read-only!"),
+        }
+    }
+}
+
+impl From<PairingResultError> for PenaltyError {
+    fn from(e: PairingResultError) -> PenaltyError {
+        PenaltyError::Result(e)
+    }
+}
+
+impl From<LockError> for PenaltyError {
+    fn from(e: LockError) -> PenaltyError {
+        match e {
+            LockError::Finished => PenaltyError::Finished,
+            LockError::ReadOnly => PenaltyError::ReadOnly,
+        }
+    }
+}
+
+impl Tournament {
+    /// Issues a penalty to a player in the current round.
+    ///
+    /// A game loss awards the opponent a game that is added to the scores entered later with
+    /// `end_match()`, so only the games that were actually played are entered. If the result was
+    /// already entered, the game is added to it right away. A game loss that would decide the
+    /// match, e.g. the second one in best-of-three or any in best-of-one, turns into a match loss.
+    /// A match loss records the match as a win for the opponent right away, e.g. 2-0 in
+    /// best-of-three, so its result must not be entered anymore.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use swyss::penalties::PenaltyKind;
+    /// use swyss::{Player, Tournament};
+    ///
+    /// let players = (1..3)
+    ///     .map(|i| Player::new(&format!("Player {}", i)))
+    ///     .collect();
+    /// let mut tourn = Tournament::with_players(players).unwrap();
+    /// let pairings = tourn.next_round().unwrap().unwrap();
+    /// let offender = tourn.player_arena()[0].uuid;
+    ///
+    /// tourn
+    ///     .penalize(offender, PenaltyKind::GameLoss, "Deck problem")
+    ///     .unwrap();
+    /// // The game loss and one more game won by the opponent decide the match
+    /// let (uuid, home, _) = &pairings[0];
+    /// let (home_score, away_score) = if *home == tourn.player_arena()[0].name {
+    ///     (0, 1)
+    /// } else {
+    ///     (1, 0)
+    /// };
+    /// tourn.end_match(*uuid, home_score, away_score, 0).unwrap();
+    ///
+    /// assert_eq!(tourn.player_arena()[0].match_points, 0);
+    /// assert_eq!(tourn.penalties().len(), 1);
+    /// ```
+    pub fn penalize(
+        &mut self,
+        player: uuid::Uuid,
+        kind: PenaltyKind,
+        reason: &str,
+    ) -> Result<(), PenaltyError> {
+        self.check_unlocked()?;
+        if !self.players.iter().any(|p| p.uuid == player) {
+            return Err(PenaltyError::UnknownPlayer(player));
+        }
+
+        if kind != PenaltyKind::Warning {
+            self.apply_penalty(player, kind)?;
+        }
+
+        self.penalties.push(Penalty {
+            player,
+            round: self.current_round,
+            kind,
+            reason: String::from(reason),
+        });
+
+        Ok(())
+    }
+
+    /// Changes the result of the player's current match according to the penalty
+    fn apply_penalty(&mut self, player: uuid::Uuid, kind: PenaltyKind) -> Result<(), PenaltyError> {
+        let (uuid, side) = self
+            .pairings
+            .values()
+            .find_map(|pair| {
+                if pair.home == player {
+                    Some((pair.uuid, PlayerSide::Home))
+                } else if pair.away == player {
+                    Some((pair.uuid, PlayerSide::Away))
+                } else {
+                    None
+                }
+            })
+            .ok_or(PenaltyError::NotPaired(player))?;
+
+        let pair = self
+            .pairings
+            .get_mut(&uuid)
+            .expect("pairing was just found");
+        let wins = pair.format().wins_needed();
+        let completed = pair.result.get();
+        let before = pair.penalty_games;
+        let (home_games, away_games) = &mut pair.penalty_games;
+        let awarded = match side {
+            PlayerSide::Home => away_games,
+            PlayerSide::Away => home_games,
+        };
+        // Games the opponent has won so far, counting those of a result that was already entered
+        let opponent_games = match (completed, side) {
+            (Some(result), PlayerSide::Home) => result.away_score,
+            (Some(result), PlayerSide::Away) => result.home_score,
+            (None, _) => *awarded,
+        };
+
+        if kind == PenaltyKind::GameLoss && opponent_games + 1 < wins {
+            *awarded += 1;
+            let result = match completed {
+                Some(result) => result,
+                None => return Ok(()),
+            };
+
+            // A match that is already over gets the game added to its result. The scores passed
+            // on are those entered, without the penalty games that are added to them.
+            let amended = self.amend_match(
+                uuid,
+                result.home_score.saturating_sub(before.0),
+                result.away_score.saturating_sub(before.1),
+                result.drawn,
+            );
+            if let Err(e) = amended {
+                if let Some(pair) = self.pairings.get_mut(&uuid) {
+                    pair.penalty_games = before;
+                }
+                return Err(e.into());
+            }
+            return Ok(());
+        }
+
+        // A match loss, or a game loss that decides the match
+        pair.penalty_games = (0, 0);
+        let result = match side {
+            PlayerSide::Home => ReportedResult {
+                home_score: 0,
+                away_score: wins,
+                drawn: 0,
+            },
+            PlayerSide::Away => ReportedResult {
+                home_score: wins,
+                away_score: 0,
+                drawn: 0,
+            },
+        };
+        pair.report = Some(ReportState::Confirmed(result));
+
+        // A match that is already over is overturned
+        if pair.is_completed() {
+            self.amend_match(uuid, result.home_score, result.away_score, result.drawn)?;
+        } else {
+            self.end_match(uuid, result.home_score, result.away_score, result.drawn)?;
+        }
+
+        Ok(())
+    }
+
+    /// Returns all penalties issued to a player
+    pub fn penalties_of(&self, player: uuid::Uuid) -> Vec<&Penalty> {
+        self.penalties
+            .iter()
+            .filter(|penalty| penalty.player == player)
+            .collect()
+    }
+
+    /// Lists all penalties, one per line, for printing or exporting along with the standings
+    pub fn penalty_report(&self) -> String {
+        let mut report = String::new();
+
+        for penalty in &self.penalties {
+            let name = self
+                .players
+                .iter()
+                .find(|p| p.uuid == penalty.player)
+                .map(|p| p.name.clone())
+                .unwrap_or_else(|| penalty.player.to_string());
+
+            report.push_str(&format!(
+                "Round {}: {} - {} ({})\n",
+                penalty.round, name, penalty.kind, penalty.reason
+            ));
+        }
+
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{MatchFormat, Player};
+
+    fn tournament() -> Tournament {
+        let players = (1..4)
+            .map(|i| Player::new(format!("Player {}", i).as_str()))
+            .collect();
+        Tournament::with_players(players).unwrap()
+    }
+
+    /// Returns the pairing and the home and away player UUIDs of the only match of the round
+    fn only_match(tourn: &Tournament) -> (uuid::Uuid, uuid::Uuid, uuid::Uuid) {
+        let pair = tourn.pairings.values().next().unwrap();
+        (pair.uuid, pair.home, pair.away)
+    }
+
+    fn points(tourn: &Tournament, player: uuid::Uuid) -> (u32, u32) {
+        let p = tourn.players.iter().find(|p| p.uuid == player).unwrap();
+        (p.match_points, p.game_points)
+    }
+
+    #[test]
+    fn consequences() {
+        let mut tourn = tournament();
+        tourn.next_round().unwrap().unwrap();
+        let (uuid, home, away) = only_match(&tourn);
+
+        // A warning has no consequences
+        tourn
+            .penalize(home, PenaltyKind::Warning, "Slow play")
+            .unwrap();
+        assert_eq!(tourn.pairings[&uuid].penalty_games, (0, 0));
+
+        // A game loss counts as a game won by the opponent when the result is entered
+        tourn
+            .penalize(home, PenaltyKind::GameLoss, "Marked cards")
+            .unwrap();
+        tourn.end_match(uuid, 1, 1, 0).unwrap();
+        assert_eq!(points(&tourn, away), (3, 6));
+        assert_eq!(points(&tourn, home), (0, 3));
+
+        // A player with a bye has no match to lose
+        let bye = tourn
+            .players
+            .iter()
+            .map(|p| p.uuid)
+            .find(|&p| p != home && p != away)
+            .unwrap();
+        assert!(matches!(
+            tourn.penalize(bye, PenaltyKind::MatchLoss, "Unsporting conduct"),
+            Err(PenaltyError::NotPaired(_))
+        ));
+        assert!(matches!(
+            tourn.penalize(uuid::Uuid::new_v4(), PenaltyKind::Warning, ""),
+            Err(PenaltyError::UnknownPlayer(_))
+        ));
+        assert_eq!(tourn.penalties_of(home).len(), 2);
+        assert_eq!(tourn.penalties_of(bye).len(), 0);
+
+        // Two game losses make a match loss, which is recorded right away
+        tourn.next_round().unwrap().unwrap();
+        let (uuid, home, away) = only_match(&tourn);
+        let before = points(&tourn, away);
+        tourn
+            .penalize(away, PenaltyKind::GameLoss, "Tardiness")
+            .unwrap();
+        tourn
+            .penalize(away, PenaltyKind::GameLoss, "Tardiness")
+            .unwrap();
+        assert_eq!(points(&tourn, away), before);
+        assert_eq!(
+            tourn.report_state(uuid),
+            Some(ReportState::Confirmed(ReportedResult {
+                home_score: 2,
+                away_score: 0,
+                drawn: 0
+            }))
+        );
+        assert_eq!(tourn.pairings[&uuid].penalty_games, (0, 0));
+        assert!(points(&tourn, home).0 >= 3);
+
+        let report = tourn.penalty_report();
+        assert_eq!(report.lines().count(), 4);
+        assert!(report.contains("Round 2"));
+        assert!(report.contains("Game loss (Tardiness)"));
+    }
+
+    #[test]
+    fn best_of_one() {
+        let mut tourn = tournament();
+        tourn.match_format = MatchFormat::BestOf(1);
+        tourn.next_round().unwrap().unwrap();
+        let (uuid, home, _) = only_match(&tourn);
+
+        // A single game loss decides the match
+        tourn
+            .penalize(home, PenaltyKind::GameLoss, "Marked cards")
+            .unwrap();
+        assert_eq!(
+            tourn.report_state(uuid),
+            Some(ReportState::Confirmed(ReportedResult {
+                home_score: 0,
+                away_score: 1,
+                drawn: 0
+            }))
+        );
+    }
+
+    #[test]
+    fn after_the_result() {
+        let mut tourn = tournament();
+        tourn.next_round().unwrap().unwrap();
+        let (uuid, home, away) = only_match(&tourn);
+        tourn.end_match(uuid, 2, 0, 0).unwrap();
+        assert_eq!(points(&tourn, away), (0, 0));
+
+        // The game is added to the result, which still goes to the winner
+        tourn
+            .penalize(home, PenaltyKind::GameLoss, "Marked cards")
+            .unwrap();
+        let result = tourn.pairings[&uuid].result.get().unwrap();
+        assert_eq!((result.home_score, result.away_score), (2, 1));
+        assert_eq!(points(&tourn, home), (3, 6));
+        assert_eq!(points(&tourn, away), (0, 3));
+        assert!(tourn.check_invariants().is_ok());
+
+        // A second game loss decides the match for the opponent
+        tourn
+            .penalize(home, PenaltyKind::GameLoss, "Marked cards")
+            .unwrap();
+        let result = tourn.pairings[&uuid].result.get().unwrap();
+        assert_eq!((result.home_score, result.away_score), (0, 2));
+        assert_eq!(points(&tourn, home), (0, 0));
+        assert_eq!(points(&tourn, away), (3, 6));
+        assert!(tourn.check_invariants().is_ok());
+    }
+}
@@ -0,0 +1,847 @@
+//! Saving and loading tournaments. Players refer to their opponents by UUID, so they are stored
+//! as they are and every reference is checked on load.
+
+use crate::arena::PlayerArena;
+use crate::audit::AuditEntry;
+use crate::byes::{ByeWarning, RequestedBye};
+use crate::crosstable::Outcome;
+use crate::deadline::DeadlinePolicy;
+use crate::event::EventInfo;
+use crate::explain::Criterion;
+use crate::history::{MatchResult, Round};
+use crate::locale::Locale;
+use crate::penalties::Penalty;
+use crate::prizes::PayoutStructure;
+use crate::reporting::ReportState;
+use crate::{
+    criterion_value, Availability, AvailabilityConflict, ByeScore, ByeSelection, FinalTiebreak,
+    FirstPlayer, MatchFormat, Pairing, PairingSystem, PercentageFloor, Player, PlayerSide,
+    Tournament, MAX_TIEBREAK_DECIMALS,
+};
+use core::cell::Cell;
+use num_traits::ToPrimitive;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// Serializable form of a `Player`
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct PlayerData {
+    pub uuid: uuid::Uuid,
+    pub name: String,
+    #[serde(default)]
+    pub alias: Option<String>,
+    pub match_points: u32,
+    pub game_points: u32,
+    pub matches_played: u32,
+    pub matches_won: u32,
+    pub matches_drawn: u32,
+    pub games_played: u32,
+    #[serde(default)]
+    pub games_won: u32,
+    #[serde(default)]
+    pub cumulative_points: u32,
+    #[serde(default)]
+    pub outcomes: Vec<Outcome>,
+    pub opponents: Vec<uuid::Uuid>,
+    pub has_bye: bool,
+    /// Missing in files saved before byes were counted
+    #[serde(default)]
+    pub byes: Option<u32>,
+    pub availability: Availability,
+    #[serde(default)]
+    pub rating: Option<f64>,
+    #[serde(default)]
+    pub club: Option<String>,
+    #[serde(default)]
+    pub age_group: Option<String>,
+    #[serde(default)]
+    pub avoid: Vec<uuid::Uuid>,
+}
+
+/// Bye limit of files saved before it could be set
+fn default_max_byes() -> u32 {
+    1
+}
+
+/// Tiebreakers of files saved before they could be chosen
+fn default_tiebreakers() -> Vec<Criterion> {
+    Criterion::DEFAULT_TIEBREAKERS.to_vec()
+}
+
+/// Decimal places of files saved before they could be set
+fn default_tiebreak_decimals() -> u32 {
+    2
+}
+
+/// Featured table of files saved before it could be chosen
+fn default_featured_table() -> u32 {
+    1
+}
+
+/// Serializable form of a `Pairing`
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct PairingData {
+    pub uuid: uuid::Uuid,
+    pub home: uuid::Uuid,
+    pub away: uuid::Uuid,
+    #[serde(default)]
+    pub first: Option<PlayerSide>,
+    #[serde(default)]
+    pub tokens: Option<(String, String)>,
+    #[serde(default)]
+    pub report: Option<ReportState>,
+    #[serde(default)]
+    pub penalty_games: (u8, u8),
+    #[serde(default)]
+    pub result: Option<MatchResult>,
+    /// Seconds since the Unix epoch. Files without it count from the time they are loaded.
+    #[serde(default = "crate::audit::unix_time")]
+    pub created: u64,
+    #[serde(default)]
+    pub finished: Option<u64>,
+}
+
+/// Serializable form of a `Tournament`. Players are stored in their current order, so saving
+/// after `ranking()` preserves the final standings.
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct TournamentData {
+    #[serde(default)]
+    pub event: EventInfo,
+    pub rounds: u32,
+    pub current_round: u32,
+    pub players: Vec<PlayerData>,
+    /// Players who are no longer part of the tournament but still count as opponents
+    #[serde(default)]
+    pub former_players: Vec<PlayerData>,
+    pub pairings: Vec<PairingData>,
+    pub needs_bye: bool,
+    pub availability_conflicts: Vec<AvailabilityConflict>,
+    #[serde(default)]
+    pub first_player: FirstPlayer,
+    #[serde(default)]
+    pub match_format: MatchFormat,
+    #[serde(default)]
+    pub pairing_system: PairingSystem,
+    #[serde(default)]
+    pub bye_selection: ByeSelection,
+    #[serde(default)]
+    pub bye_score: ByeScore,
+    #[serde(default = "default_max_byes")]
+    pub max_byes: u32,
+    #[serde(default)]
+    pub bye_warnings: Vec<ByeWarning>,
+    #[serde(default)]
+    pub percentage_floor: PercentageFloor,
+    #[serde(default = "default_tiebreakers")]
+    pub tiebreakers: Vec<Criterion>,
+    #[serde(default)]
+    pub final_tiebreak: FinalTiebreak,
+    #[serde(default = "default_tiebreak_decimals")]
+    pub tiebreak_decimals: u32,
+    #[serde(default)]
+    pub tiebreak_seed: Option<u64>,
+    #[serde(default)]
+    pub house_player: Option<uuid::Uuid>,
+    #[serde(default)]
+    pub preferred_pairs: Vec<(uuid::Uuid, uuid::Uuid)>,
+    #[serde(default)]
+    pub requested_byes: Vec<RequestedBye>,
+    #[serde(default)]
+    pub round_length: Option<u32>,
+    #[serde(default)]
+    pub deadline: Option<DeadlinePolicy>,
+    #[serde(default = "default_featured_table")]
+    pub featured_table: u32,
+    #[serde(default)]
+    pub locale: Locale,
+    #[serde(default)]
+    pub prizes: Option<PayoutStructure>,
+    #[serde(default)]
+    pub audit_log: Vec<AuditEntry>,
+    #[serde(default)]
+    pub penalties: Vec<Penalty>,
+    /// Players who checked in, if the tournament is still open for registration
+    #[serde(default)]
+    pub checked_in: Option<Vec<uuid::Uuid>>,
+    /// All rounds paired so far. The results of the current round are kept with its pairings.
+    #[serde(default)]
+    pub history: Vec<Round>,
+    /// UUIDs of the players in the order of their player numbers. Older files without numbers
+    /// number the players in the order they are stored.
+    #[serde(default)]
+    pub player_numbers: Vec<uuid::Uuid>,
+}
+
+/// A single row of the tournament standings, as exported by `Tournament::standings_to_json()`
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct StandingsRow {
+    pub rank: u32,
+    pub uuid: uuid::Uuid,
+    pub name: String,
+    /// Handle the player competes under, if they have one
+    #[serde(default)]
+    pub alias: Option<String>,
+    pub match_points: u32,
+    pub matches_played: u32,
+    pub matches_won: u32,
+    pub matches_drawn: u32,
+    pub opponents_match_win_percentage: f64,
+    pub game_win_percentage: f64,
+    pub opponents_game_win_percentage: f64,
+    #[serde(default)]
+    pub opponents_opponents_match_win_percentage: f64,
+    /// The player's current streak, e.g. "W3" for three wins in a row. Empty before their first
+    /// match.
+    #[serde(default)]
+    pub streak: String,
+    /// The outcomes of the player's last five matches, oldest first, e.g. "WWLDW"
+    #[serde(default)]
+    pub form: String,
+    /// Penalties issued to the player
+    #[serde(default)]
+    pub penalties: Vec<Penalty>,
+    /// Rating the player was seeded by
+    #[serde(default)]
+    pub rating: Option<f64>,
+    #[serde(default)]
+    pub club: Option<String>,
+    /// The values of the tiebreakers the tournament applies, in order
+    #[serde(default)]
+    pub tiebreakers: Vec<TiebreakValue>,
+}
+
+/// The value of a tiebreaker for a row of the standings
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct TiebreakValue {
+    pub criterion: Criterion,
+    /// The exact value as a fraction in lowest terms, e.g. "5/9", or as an integer
+    pub rational: String,
+    pub value: f64,
+    /// The value rounded to the tournament's `tiebreak_decimals`, e.g. "0.556"
+    pub formatted: String,
+}
+
+impl StandingsRow {
+    /// Returns the player's alias if they have one, and their name otherwise
+    pub fn display_name(&self) -> &str {
+        self.alias.as_deref().unwrap_or(&self.name)
+    }
+}
+
+/// Loading a tournament can fail because the file can't be read, because it isn't a valid
+/// tournament file, because it references players that aren't part of the tournament, or because
+/// several players or pairings share a UUID, e.g. after the file was edited by hand
+#[derive(Debug)]
+pub enum LoadError {
+    Io(io::Error),
+    Parse(serde_json::Error),
+    UnknownPlayer(uuid::Uuid),
+    DuplicatePlayer(uuid::Uuid),
+    DuplicatePairing(uuid::Uuid),
+    /// Only finished tournaments can be loaded with `Tournament::load_readonly()`
+    Unfinished,
+}
+
+impl std::error::Error for LoadError {}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LoadError::Io(e) => write!(f, "Could not read tournament file: {}", e),
+            LoadError::Parse(e) => write!(f, "Could not parse tournament file: {}", e),
+            LoadError::UnknownPlayer(uuid) => write!(f, "Unknown player {}!", uuid),
+            LoadError::DuplicatePlayer(uuid) => write!(f, "Duplicate player {}!", uuid),
+            LoadError::DuplicatePairing(uuid) => write!(f, "Duplicate pairing {}!", uuid),
+            LoadError::Unfinished => write!(f, "The tournament isn't finished yet!"),
+        }
+    }
+}
+
+impl From<io::Error> for LoadError {
+    fn from(e: io::Error) -> LoadError {
+        LoadError::Io(e)
+    }
+}
+
+impl From<serde_json::Error> for LoadError {
+    fn from(e: serde_json::Error) -> LoadError {
+        LoadError::Parse(e)
+    }
+}
+
+impl From<&Player> for PlayerData {
+    fn from(p: &Player) -> PlayerData {
+        PlayerData {
+            uuid: p.uuid,
+            name: p.name.clone(),
+            alias: p.alias.clone(),
+            match_points: p.match_points,
+            game_points: p.game_points,
+            matches_played: p.matches_played,
+            matches_won: p.matches_won,
+            matches_drawn: p.matches_drawn,
+            games_played: p.games_played,
+            games_won: p.games_won,
+            cumulative_points: p.cumulative_points,
+            outcomes: p.outcomes.clone(),
+            opponents: p.opponents.clone(),
+            has_bye: p.has_bye,
+            byes: Some(p.byes),
+            availability: p.availability,
+            rating: p.rating,
+            club: p.club.clone(),
+            age_group: p.age_group.clone(),
+            avoid: p.avoid.clone(),
+        }
+    }
+}
+
+impl From<&Tournament> for TournamentData {
+    fn from(t: &Tournament) -> TournamentData {
+        // Pairings are kept in a map, so sort them to make the output deterministic
+        let mut pairings: Vec<PairingData> = t
+            .pairings
+            .values()
+            .map(|pair| PairingData {
+                uuid: pair.uuid,
+                home: pair.home,
+                away: pair.away,
+                first: pair.first,
+                tokens: pair.tokens.clone(),
+                report: pair.report,
+                penalty_games: pair.penalty_games,
+                result: pair.result.get(),
+                created: pair.created,
+                finished: pair.finished.get(),
+            })
+            .collect();
+        pairings.sort_by_key(|pair| pair.uuid);
+
+        TournamentData {
+            event: t.event.clone(),
+            rounds: t.rounds,
+            current_round: t.current_round,
+            players: t.players.iter().map(PlayerData::from).collect(),
+            former_players: t.players.former().iter().map(PlayerData::from).collect(),
+            pairings,
+            needs_bye: t.needs_bye,
+            availability_conflicts: t.availability_conflicts.clone(),
+            first_player: t.first_player,
+            match_format: t.match_format,
+            pairing_system: t.pairing_system,
+            bye_selection: t.bye_selection.clone(),
+            bye_score: t.bye_score,
+            max_byes: t.max_byes,
+            bye_warnings: t.bye_warnings.clone(),
+            percentage_floor: t.percentage_floor,
+            tiebreakers: t.tiebreakers.clone(),
+            final_tiebreak: t.final_tiebreak.clone(),
+            tiebreak_decimals: t.tiebreak_decimals,
+            tiebreak_seed: t.tiebreak_seed,
+            house_player: t.house_player,
+            preferred_pairs: t.preferred_pairs.clone(),
+            requested_byes: t.requested_byes.clone(),
+            round_length: t.round_length,
+            deadline: t.deadline,
+            featured_table: t.featured_table,
+            locale: t.locale,
+            prizes: t.prizes.clone(),
+            audit_log: t.audit_log.clone(),
+            penalties: t.penalties.clone(),
+            checked_in: t.checked_in.clone(),
+            history: t.history.clone(),
+            player_numbers: t.player_numbers.clone(),
+        }
+    }
+}
+
+impl TournamentData {
+    /// Rebuilds the tournament, restoring all references between players and pairings
+    pub fn into_tournament(self) -> Result<Tournament, LoadError> {
+        let mut uuids = HashSet::with_capacity(self.players.len() + self.former_players.len());
+        for data in self.players.iter().chain(&self.former_players) {
+            if !uuids.insert(data.uuid) {
+                return Err(LoadError::DuplicatePlayer(data.uuid));
+            }
+        }
+
+        let lookup = |uuid: &uuid::Uuid| match uuids.get(uuid) {
+            Some(&uuid) => Ok(uuid),
+            None => Err(LoadError::UnknownPlayer(*uuid)),
+        };
+
+        let restore = |data: &PlayerData| -> Result<Player, LoadError> {
+            let mut p = Player::new(&data.name);
+            p.uuid = data.uuid;
+            p.alias = data.alias.clone();
+            p.match_points = data.match_points;
+            p.game_points = data.game_points;
+            p.matches_played = data.matches_played;
+            p.matches_won = data.matches_won;
+            p.matches_drawn = data.matches_drawn;
+            p.games_played = data.games_played;
+            p.games_won = data.games_won;
+            p.cumulative_points = data.cumulative_points;
+            p.outcomes = data.outcomes.clone();
+            p.opponents = data
+                .opponents
+                .iter()
+                .map(lookup)
+                .collect::<Result<_, _>>()?;
+            p.has_bye = data.has_bye;
+            p.byes = data.byes.unwrap_or(u32::from(data.has_bye));
+            p.availability = data.availability;
+            p.rating = data.rating;
+            p.club = data.club.clone();
+            p.age_group = data.age_group.clone();
+            p.avoid = data.avoid.clone();
+            p.percentage_floor = self.percentage_floor;
+            Ok(p)
+        };
+
+        let mut players = self
+            .players
+            .iter()
+            .map(restore)
+            .collect::<Result<PlayerArena, _>>()?;
+        players.retire(
+            self.former_players
+                .iter()
+                .map(restore)
+                .collect::<Result<Vec<_>, _>>()?,
+        );
+
+        let mut pairings = HashMap::with_capacity(self.pairings.len());
+        for data in &self.pairings {
+            let pair = Pairing {
+                uuid: data.uuid,
+                home: lookup(&data.home)?,
+                away: lookup(&data.away)?,
+                first: data.first,
+                tokens: data.tokens.clone(),
+                report: data.report,
+                penalty_games: data.penalty_games,
+                result: Cell::new(data.result),
+                created: data.created,
+                finished: Cell::new(data.finished),
+                format: self.match_format,
+            };
+            if pairings.insert(data.uuid, pair).is_some() {
+                return Err(LoadError::DuplicatePairing(data.uuid));
+            }
+        }
+
+        let player_numbers = if self.player_numbers.is_empty() {
+            self.players.iter().map(|p| p.uuid).collect()
+        } else {
+            self.player_numbers
+        };
+
+        Ok(Tournament {
+            event: self.event,
+            rounds: self.rounds,
+            current_round: self.current_round,
+            players,
+            pairings,
+            needs_bye: self.needs_bye,
+            availability_conflicts: self.availability_conflicts,
+            first_player: self.first_player,
+            match_format: self.match_format,
+            pairing_system: self.pairing_system,
+            bye_selection: self.bye_selection,
+            bye_score: self.bye_score,
+            max_byes: self.max_byes,
+            bye_warnings: self.bye_warnings,
+            requested_byes: self.requested_byes,
+            percentage_floor: self.percentage_floor,
+            tiebreakers: self.tiebreakers,
+            final_tiebreak: self.final_tiebreak,
+            tiebreak_decimals: self.tiebreak_decimals.min(MAX_TIEBREAK_DECIMALS),
+            tiebreak_seed: self.tiebreak_seed,
+            house_player: self.house_player,
+            preferred_pairs: self.preferred_pairs,
+            round_length: self.round_length,
+            deadline: self.deadline,
+            featured_table: self.featured_table,
+            locale: self.locale,
+            prizes: self.prizes,
+            audit_log: self.audit_log,
+            penalties: self.penalties,
+            checked_in: self.checked_in,
+            history: self.history,
+            player_numbers,
+            rng: StdRng::from_entropy(),
+            pairing_stats: None,
+            observers: Vec::new(),
+            read_only: false,
+        })
+    }
+}
+
+/// Number of matches the form in the standings covers
+const FORM_MATCHES: usize = 5;
+
+/// Builds the standings rows of players that are already in ranking order, along with the
+/// penalties issued to them and their tiebreakers
+pub(crate) fn standings_rows(tourn: &Tournament, players: &[&Player]) -> Vec<StandingsRow> {
+    players
+        .iter()
+        .enumerate()
+        .map(|(i, p)| {
+            let tiebreakers = tourn
+                .tiebreakers
+                .iter()
+                .map(|&criterion| {
+                    let rational = criterion_value(p, &tourn.players, criterion);
+                    TiebreakValue {
+                        criterion,
+                        rational: rational.to_string(),
+                        value: rational.to_f64().expect("criteria are finite"),
+                        formatted: tourn.format_rational(&rational),
+                    }
+                })
+                .collect();
+            StandingsRow {
+                rank: i as u32 + 1,
+                uuid: p.uuid,
+                name: p.name.clone(),
+                alias: p.alias.clone(),
+                match_points: p.match_points,
+                matches_played: p.matches_played,
+                matches_won: p.matches_won,
+                matches_drawn: p.matches_drawn,
+                opponents_match_win_percentage: p.opponents_match_win_percentage(&tourn.players),
+                game_win_percentage: p.game_win_percentage(),
+                opponents_game_win_percentage: p.opponents_game_win_percentage(&tourn.players),
+                opponents_opponents_match_win_percentage: p
+                    .opponents_opponents_match_win_percentage(&tourn.players),
+                streak: p
+                    .current_streak()
+                    .map(|(outcome, length)| format!("{}{}", outcome.letter(), length))
+                    .unwrap_or_default(),
+                form: p
+                    .recent_form(FORM_MATCHES)
+                    .iter()
+                    .map(|o| o.letter())
+                    .collect(),
+                penalties: tourn
+                    .penalties
+                    .iter()
+                    .filter(|penalty| penalty.player == p.uuid)
+                    .cloned()
+                    .collect(),
+                rating: p.rating,
+                club: p.club.clone(),
+                tiebreakers,
+            }
+        })
+        .collect()
+}
+
+impl Tournament {
+    /// Serializes the tournament to JSON
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(&TournamentData::from(self))
+            .expect("tournament data is always serializable")
+    }
+
+    /// Restores a tournament from JSON created by `to_json()`
+    pub fn from_json(json: &str) -> Result<Tournament, LoadError> {
+        let data: TournamentData = serde_json::from_str(json)?;
+        data.into_tournament()
+    }
+
+    /// Ranks all players and serializes the standings, including their penalties, to JSON. The
+    /// standings are a plain array of rows; the event they belong to is part of `to_json()`.
+    pub fn standings_to_json(&mut self) -> String {
+        self.ranking();
+        let rows = standings_rows(self, &self.sorted_players());
+        serde_json::to_string_pretty(&rows).expect("standings are always serializable")
+    }
+
+    /// Saves the tournament to a file
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        fs::write(path, self.to_json())
+    }
+
+    /// Loads a tournament from a file created by `save()`
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Tournament, LoadError> {
+        let json = fs::read_to_string(path)?;
+        Tournament::from_json(&json)
+    }
+
+    /// Loads a past tournament from a file created by `save()` for querying only, e.g. to
+    /// regenerate its exports after the fact. Standings, cross-tables, and exports work as
+    /// usual, but every method that would change the tournament fails. Fails with
+    /// `LoadError::Unfinished` if the tournament isn't finished, since it would still be changed.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use swyss::Tournament;
+    ///
+    /// let mut tourn = Tournament::load_readonly("club-night.json").unwrap();
+    /// assert!(tourn.is_read_only());
+    /// assert!(tourn.next_round().is_err());
+    /// println!("{}", tourn.wall_chart());
+    /// ```
+    pub fn load_readonly<P: AsRef<Path>>(path: P) -> Result<Tournament, LoadError> {
+        let mut tournament = Tournament::load(path)?;
+        if !tournament.is_finished() {
+            return Err(LoadError::Unfinished);
+        }
+        tournament.read_only = true;
+        Ok(tournament)
+    }
+
+    /// Checks whether the tournament was loaded with `load_readonly()`
+    pub fn is_read_only(&self) -> bool {
+        self.read_only
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{Config, ConfigError};
+    use crate::constraints::ConstraintError;
+    use crate::history::RoundError;
+    use crate::penalties::{PenaltyError, PenaltyKind};
+    use crate::reporting::ReportError;
+    use crate::{LockError, PairingResultError};
+    use num_rational::BigRational;
+
+    #[test]
+    fn roundtrip() {
+        let mut players = Vec::with_capacity(4);
+
+        for i in 1..5 {
+            let p = Player::new(format!("Player {}", i).as_str());
+            players.push(p);
+        }
+
+        players[0].alias = Some(String::from("P1"));
+        players[1].rating = Some(1650.0);
+        players[1].club = Some(String::from("Rooks"));
+
+        let mut tourn = Tournament::with_players(players).unwrap();
+        let pairings = tourn.next_round().unwrap().unwrap();
+        assert!(tourn.end_match(pairings[0].0, 2, 1, 0).is_ok());
+
+        let json = tourn.to_json();
+        let mut loaded = Tournament::from_json(&json).unwrap();
+
+        assert_eq!(loaded.rounds, tourn.rounds);
+        assert_eq!(loaded.current_round, 1);
+        assert_eq!(loaded.players.len(), 4);
+        assert_eq!(loaded.pairings.len(), 2);
+
+        for (orig, p) in tourn.players.iter().zip(&loaded.players) {
+            assert_eq!(p.uuid, orig.uuid);
+            assert_eq!(p.name, orig.name);
+            assert_eq!(p.alias, orig.alias);
+            assert_eq!((p.rating, &p.club), (orig.rating, &orig.club));
+            assert_eq!(p.match_points, orig.match_points);
+            assert_eq!(p.game_points, orig.game_points);
+            assert_eq!(p.opponents.len(), 1);
+            assert_eq!(p.opponents[0], orig.opponents[0]);
+        }
+
+        // Loaded players must know the opponents they are paired against
+        let pair = loaded
+            .pairings
+            .values()
+            .find(|pair| !pair.is_completed())
+            .unwrap();
+        let home = loaded.players.get(pair.home).unwrap();
+        assert!(home.opponents.contains(&pair.away));
+        let pair = pair.uuid;
+        assert!(loaded.end_match(pair, 2, 0, 0).is_ok());
+
+        // Reported results stay reported
+        assert!(matches!(
+            loaded.end_match(pairings[0].0, 2, 0, 0),
+            Err(crate::PairingResultError::AlreadyReported(_))
+        ));
+    }
+
+    #[test]
+    fn unknown_opponent() {
+        let players = vec![Player::new("Player 1")];
+        let tourn = Tournament::with_players(players).unwrap();
+        let mut data = TournamentData::from(&tourn);
+        data.players[0].opponents.push(uuid::Uuid::new_v4());
+
+        match data.into_tournament() {
+            Err(LoadError::UnknownPlayer(_)) => {}
+            _ => panic!("Expected unknown player error"),
+        }
+    }
+
+    #[test]
+    fn duplicate_uuids() {
+        let players = vec![
+            Player::new("Player 1"),
+            Player::new("Player 2"),
+            Player::new("Player 3"),
+            Player::new("Player 4"),
+        ];
+        let mut tourn = Tournament::with_players(players).unwrap();
+        tourn.next_round().unwrap();
+        let json = tourn.to_json();
+
+        // A hand-edited file in which one player's UUID was copied over another's
+        let (first, second) = {
+            let data: TournamentData = serde_json::from_str(&json).unwrap();
+            (data.players[0].uuid, data.players[1].uuid)
+        };
+        let edited = json.replace(&second.to_string(), &first.to_string());
+        match Tournament::from_json(&edited) {
+            Err(LoadError::DuplicatePlayer(uuid)) => assert_eq!(uuid, first),
+            _ => panic!("Expected duplicate player error"),
+        }
+
+        // The same for pairings
+        let (first, second) = {
+            let data: TournamentData = serde_json::from_str(&json).unwrap();
+            (data.pairings[0].uuid, data.pairings[1].uuid)
+        };
+        let edited = json.replace(&second.to_string(), &first.to_string());
+        match Tournament::from_json(&edited) {
+            Err(LoadError::DuplicatePairing(uuid)) => assert_eq!(uuid, first),
+            _ => panic!("Expected duplicate pairing error"),
+        }
+    }
+
+    #[test]
+    fn standings() {
+        let players = vec![Player::new("Player 1"), Player::new("Player 2")];
+
+        let mut tourn = Tournament::with_players(players).unwrap();
+        let pairings = tourn.next_round().unwrap().unwrap();
+        let (uuid, home, _) = &pairings[0];
+        let winner = home.clone();
+        assert!(tourn.end_match(*uuid, 2, 0, 0).is_ok());
+
+        let json = tourn.standings_to_json();
+        let rows: Vec<StandingsRow> = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].rank, 1);
+        assert_eq!(rows[0].name, winner);
+        assert_eq!(rows[0].match_points, 3);
+        assert_eq!(rows[1].rank, 2);
+        assert_eq!(rows[1].matches_won, 0);
+
+        // The loser's only opponent won every game, so their tiebreakers are exact fractions
+        let loser = &rows[1].tiebreakers;
+        assert_eq!(loser.len(), tourn.tiebreakers.len());
+        assert_eq!(loser[0].criterion, Criterion::OpponentsMatchWinPercentage);
+        assert_eq!(loser[0].rational, "1");
+        assert_eq!(loser[1].criterion, Criterion::GameWinPercentage);
+        assert_eq!(loser[1].rational, "1/3");
+        assert_eq!(loser[1].formatted, "0.33");
+        tourn.tiebreak_decimals = 3;
+        let rows: Vec<StandingsRow> = serde_json::from_str(&tourn.standings_to_json()).unwrap();
+        assert_eq!(rows[1].tiebreakers[1].formatted, "0.333");
+
+        // Decimals are capped, and halves are rounded from the exact value
+        let mut data = TournamentData::from(&tourn);
+        data.tiebreak_decimals = 4_000_000_000;
+        let mut loaded = data.into_tournament().unwrap();
+        assert_eq!(loaded.tiebreak_decimals, MAX_TIEBREAK_DECIMALS);
+        let config = crate::config::Config {
+            tiebreak_decimals: Some(u32::MAX),
+            ..Default::default()
+        };
+        loaded.tiebreak_decimals = 2;
+        loaded.apply_config(&config).unwrap();
+        assert_eq!(loaded.tiebreak_decimals, MAX_TIEBREAK_DECIMALS);
+        loaded.tiebreak_decimals = 0;
+        let half = BigRational::new(1.into(), 2.into());
+        assert_eq!(loaded.format_rational(&half), "1");
+        assert_eq!(loaded.format_rational(&-half), "-1");
+        loaded.tiebreak_decimals = 1;
+        let quarter = BigRational::new(1.into(), 4.into());
+        assert_eq!(loaded.format_rational(&quarter), "0.3");
+        assert_eq!(
+            loaded.format_rational(&-(quarter / BigRational::from_integer(10.into()))),
+            "0.0"
+        );
+    }
+
+    #[test]
+    fn read_only() {
+        let players: Vec<_> = (1..5)
+            .map(|i| Player::new(format!("Player {}", i).as_str()))
+            .collect();
+        let uuids: Vec<_> = players.iter().map(|p| p.uuid).collect();
+        let mut tourn = Tournament::with_players(players).unwrap();
+        tourn.rounds = 1;
+        let pairings = tourn.next_round().unwrap().unwrap();
+        tourn.end_match(pairings[0].0, 2, 0, 0).unwrap();
+
+        // Tournaments that are still running can't be opened read-only
+        let path = std::env::temp_dir().join(format!("swyss-{}.json", uuid::Uuid::new_v4()));
+        tourn.save(&path).unwrap();
+        assert!(matches!(
+            Tournament::load_readonly(&path),
+            Err(LoadError::Unfinished)
+        ));
+
+        tourn.end_match(pairings[1].0, 2, 1, 0).unwrap();
+        tourn.finish().unwrap();
+        tourn.save(&path).unwrap();
+        let mut loaded = Tournament::load_readonly(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert!(loaded.is_read_only());
+        assert!(!Tournament::from_json(&tourn.to_json())
+            .unwrap()
+            .is_read_only());
+        assert_eq!(loaded.check_unlocked(), Err(LockError::ReadOnly));
+        assert!(matches!(
+            loaded.amend_match(pairings[0].0, 0, 2, 0),
+            Err(PairingResultError::ReadOnly)
+        ));
+        assert_eq!(loaded.next_round(), Err(RoundError::ReadOnly));
+        assert_eq!(loaded.finish(), Err(RoundError::ReadOnly));
+        assert!(matches!(
+            loaded.drop_player(uuids[0]),
+            Err(LockError::ReadOnly)
+        ));
+        assert!(matches!(
+            loaded.penalize(uuids[0], PenaltyKind::Warning, "Slow play"),
+            Err(PenaltyError::ReadOnly)
+        ));
+        assert_eq!(
+            loaded.avoid_pairing(uuids[0], uuids[1]),
+            Err(ConstraintError::ReadOnly)
+        );
+        let config = Config {
+            rounds: Some(3),
+            ..Config::default()
+        };
+        assert!(matches!(
+            loaded.apply_config(&config),
+            Err(ConfigError::ReadOnly)
+        ));
+        assert_eq!(loaded.report_tokens(), Err(ReportError::ReadOnly));
+
+        assert_eq!(loaded.rounds, 1);
+        assert_eq!(loaded.players.len(), 4);
+        assert!(loaded.penalties.is_empty());
+        assert_eq!(loaded.ranking()[0].match_points, 3);
+    }
+}
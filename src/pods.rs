@@ -0,0 +1,311 @@
+//! Pod events, where the field is split into small groups that each play their own Swiss or round
+//! robin. The best players of every pod then meet in a playoff. Each pod and the playoff are run
+//! as their own `Tournament`, so pods are ranked just like any other tournament.
+
+use crate::arena::PlayerArena;
+use crate::explain::Criterion;
+use crate::history::RoundError;
+use crate::stage::fresh_player;
+use crate::{sort_by_tiebreakers, PairingResultError, PairingSystem, Pairings, Player, Tournament};
+
+/// Configuration of a pod event
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PodConfig {
+    /// Largest number of players in a pod
+    pub size: usize,
+    /// How players are paired within their pod
+    pub pairing_system: PairingSystem,
+    /// Number of rounds played in each pod, or `None` to derive it from the pod's size
+    pub rounds: Option<u32>,
+    /// Number of top-ranked players of each pod who advance to the playoff
+    pub advance: usize,
+    /// Number of Swiss rounds of the playoff, or `None` to derive it from the number of players
+    pub playoff_rounds: Option<u32>,
+}
+
+impl PodConfig {
+    /// Returns a configuration for round robin pods of the given size, of which only the winner
+    /// advances
+    pub fn new(size: usize) -> PodConfig {
+        PodConfig {
+            size,
+            pairing_system: PairingSystem::RoundRobin,
+            rounds: None,
+            advance: 1,
+            playoff_rounds: None,
+        }
+    }
+}
+
+/// Runs the pods side by side, followed by the playoff
+pub struct PodEvent {
+    config: PodConfig,
+    pods: Vec<Tournament>,
+    playoff: Option<Tournament>,
+    /// Players who didn't make the playoff, with everyone else from the pods as their former
+    /// opponents
+    eliminated: PlayerArena,
+}
+
+impl PodEvent {
+    /// Splits the players into as few pods as the configured size allows. Players are dealt out
+    /// one pod at a time in the given order, so listing them by seed spreads the top seeds over
+    /// all pods.
+    pub fn new(players: Vec<Player>, config: PodConfig) -> PodEvent {
+        assert!(config.size >= 2, "A pod needs at least two players");
+
+        let num_pods = (players.len() as f64 / config.size as f64).ceil() as usize;
+        let mut members = vec![Vec::new(); num_pods];
+        for (i, p) in players.into_iter().enumerate() {
+            members[i % num_pods].push(p);
+        }
+
+        let pods = members
+            .into_iter()
+            .map(|players| {
+                let mut pod = Tournament::from_arena(players.into());
+                pod.pairing_system = config.pairing_system;
+                pod.rounds = match config.rounds {
+                    Some(rounds) => rounds,
+                    None => config.pairing_system.rounds(pod.players.len()),
+                };
+                pod
+            })
+            .collect();
+
+        PodEvent {
+            config,
+            pods,
+            playoff: None,
+            eliminated: PlayerArena::new(),
+        }
+    }
+
+    /// The tournaments of the pods
+    pub fn pods(&mut self) -> &mut [Tournament] {
+        &mut self.pods
+    }
+
+    /// The tournament of the playoff, once all pods are finished
+    pub fn playoff(&mut self) -> Option<&mut Tournament> {
+        self.playoff.as_mut()
+    }
+
+    /// Advances the event by one round. Pods that still have rounds left are paired together, and
+    /// once all pods are finished, the playoff is set up and paired. Returns `None` once the
+    /// playoff is complete, and an error if results of any pod's current round are outstanding.
+    pub fn next_round(&mut self) -> Result<Option<Pairings>, RoundError> {
+        if let Some(playoff) = &mut self.playoff {
+            return playoff.next_round();
+        }
+
+        // Check all pods before pairing any, so that no pod gets ahead of the others
+        let outstanding: Vec<_> = self
+            .pods
+            .iter()
+            .flat_map(|pod| pod.round_status().outstanding)
+            .collect();
+        if !outstanding.is_empty() {
+            return Err(RoundError::ResultsOutstanding(outstanding));
+        }
+
+        let mut pairings = Vec::new();
+        for pod in self
+            .pods
+            .iter_mut()
+            .filter(|pod| pod.current_round <= pod.rounds)
+        {
+            if let Some(pod_pairings) = pod.next_round()? {
+                pairings.extend(pod_pairings);
+            }
+        }
+
+        if !pairings.is_empty() {
+            return Ok(Some(pairings));
+        }
+
+        self.start_playoff();
+        self.next_round()
+    }
+
+    /// Ranks every pod and moves its best players into a fresh playoff tournament. The playoff is
+    /// seeded by pod rank, so pod winners come before runners-up.
+    fn start_playoff(&mut self) {
+        let mut qualified = vec![Vec::new(); self.config.advance];
+        let mut eliminated = Vec::new();
+        let mut others = Vec::new();
+
+        for pod in &mut self.pods {
+            let ranked = pod.ranking().len();
+            let cut = self.config.advance.min(ranked);
+
+            // The house player follows the ranked players
+            for (rank, p) in pod.players.iter().enumerate() {
+                if rank < cut {
+                    qualified[rank].push(fresh_player(p));
+                }
+                if (cut..ranked).contains(&rank) {
+                    eliminated.push(p.clone());
+                } else {
+                    others.push(p.clone());
+                }
+            }
+        }
+        self.eliminated = eliminated.into_iter().collect();
+        self.eliminated.retire(others);
+
+        let mut playoff = Tournament::from_arena(qualified.concat().into());
+        if let Some(rounds) = self.config.playoff_rounds {
+            playoff.rounds = rounds;
+        }
+        if let Some(pod) = self.pods.first() {
+            playoff.first_player = pod.first_player;
+            playoff.match_format = pod.match_format;
+        }
+
+        self.playoff = Some(playoff);
+    }
+
+    /// Records the result of a pairing of any pod or the playoff
+    pub fn end_match(
+        &mut self,
+        uuid: uuid::Uuid,
+        home_score: u8,
+        away_score: u8,
+        drawn: u8,
+    ) -> Result<(), PairingResultError> {
+        self.playoff
+            .iter_mut()
+            .chain(&mut self.pods)
+            .find(|t| t.pairings.contains_key(&uuid))
+            .ok_or(PairingResultError::NotFound(uuid))?
+            .end_match(uuid, home_score, away_score, drawn)
+    }
+
+    /// Combined standings of the event. Once the playoff has started, its players are ranked above
+    /// everyone else; the remaining players are ranked by their pod results across all pods, using
+    /// the default tiebreakers.
+    pub fn standings(&mut self) -> Vec<Player> {
+        match &mut self.playoff {
+            Some(playoff) => {
+                let mut standings: Vec<Player> = playoff.ranking().into_iter().cloned().collect();
+                let mut eliminated: Vec<&Player> = self.eliminated.iter().collect();
+                sort_by_tiebreakers(
+                    &mut eliminated,
+                    &self.eliminated,
+                    &Criterion::DEFAULT_TIEBREAKERS,
+                );
+                standings.extend(eliminated.into_iter().cloned());
+                standings
+            }
+            None => {
+                let players: PlayerArena = self
+                    .pods
+                    .iter()
+                    .flat_map(|pod| pod.players.iter().cloned())
+                    .collect();
+                let mut standings: Vec<&Player> = players.iter().collect();
+                sort_by_tiebreakers(&mut standings, &players, &Criterion::DEFAULT_TIEBREAKERS);
+                standings.into_iter().cloned().collect()
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use regex::Regex;
+
+    /// Reports a round, with the player with the higher number always winning 2-1
+    fn report(event: &mut PodEvent, pairings: &Pairings) {
+        let re = Regex::new(r"Player (\d+)").unwrap();
+        let number = |name: &str| -> u32 { re.captures(name).unwrap()[1].parse().unwrap() };
+
+        for (uuid, home, away) in pairings {
+            let (home_score, away_score) = if number(home) > number(away) {
+                (2, 1)
+            } else {
+                (1, 2)
+            };
+
+            assert!(event.end_match(*uuid, home_score, away_score, 0).is_ok());
+        }
+    }
+
+    /// Plays all remaining rounds of the event
+    fn play(event: &mut PodEvent) {
+        while let Some(pairings) = event.next_round().unwrap() {
+            report(event, &pairings);
+        }
+    }
+
+    fn players(n: u32) -> Vec<Player> {
+        (1..=n)
+            .map(|i| Player::new(format!("Player {}", i).as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn round_robin_pods_and_playoff() {
+        let config = PodConfig {
+            advance: 2,
+            ..PodConfig::new(4)
+        };
+        let mut event = PodEvent::new(players(8), config);
+        assert_eq!(event.pods().len(), 2);
+
+        // Both pods are paired in the same round
+        let pairings = event.next_round().unwrap().unwrap();
+        assert_eq!(pairings.len(), 4);
+        assert!(event.next_round().is_err());
+        report(&mut event, &pairings);
+
+        play(&mut event);
+
+        // Everyone met their whole pod, and nobody from the other pod
+        for pod in event.pods() {
+            assert_eq!(pod.rounds, 3);
+            for p in &pod.players {
+                assert_eq!(p.opponents.len(), 3);
+            }
+        }
+
+        let playoff = event.playoff().unwrap();
+        assert_eq!(playoff.players.len(), 4);
+        assert_eq!(playoff.rounds, 2);
+
+        let standings = event.standings();
+        assert_eq!(standings.len(), 8);
+        assert_eq!(standings[0].name, "Player 8");
+
+        // The playoff starts from scratch
+        assert_eq!(standings[0].matches_played, 2);
+        for p in &standings[4..] {
+            let number = p.name[7..].parse::<u32>().unwrap();
+            assert!(number <= 4);
+            assert_eq!(p.matches_played, 3);
+        }
+    }
+
+    #[test]
+    fn uneven_swiss_pods() {
+        let config = PodConfig {
+            pairing_system: PairingSystem::Swiss,
+            rounds: Some(2),
+            playoff_rounds: Some(1),
+            ..PodConfig::new(3)
+        };
+        let mut event = PodEvent::new(players(7), config);
+        assert_eq!(event.pods().len(), 3);
+        assert!(event.end_match(uuid::Uuid::new_v4(), 2, 0, 0).is_err());
+
+        play(&mut event);
+
+        // The three pod winners meet in the playoff, and one of them gets a bye
+        let playoff = event.playoff().unwrap();
+        assert_eq!(playoff.players.len(), 3);
+        assert_eq!(playoff.players.iter().filter(|p| p.has_bye).count(), 1);
+        assert_eq!(event.standings().len(), 7);
+    }
+}
@@ -0,0 +1,156 @@
+//! Presets that set up a tournament for a common kind of event in one go: the match format, the
+//! tiebreakers and their percentage floor, who goes first, and how byes are granted and scored.
+//! Settings can still be changed afterwards, e.g. by command line flags or a configuration file.
+
+use crate::explain::Criterion;
+use crate::{
+    ByeScore, ByeSelection, FinalTiebreak, FirstPlayer, LockError, MatchFormat, PercentageFloor,
+    Tournament,
+};
+use std::str::FromStr;
+
+/// A bundle of settings for a kind of event
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Preset {
+    /// Competitive Magic: best-of-three, the usual tiebreakers with percentages floored at 1/3,
+    /// and a random roll for who plays first
+    MtgCompetitive,
+    /// A chess club evening: single games, half-point byes, no percentage floor, and the
+    /// cumulative score and wins as tiebreakers
+    ChessClub,
+    /// Image mode, where one picture is picked over the other: single games without a floor or
+    /// first player, and a random draw between images tied on everything
+    ImageBracket,
+    /// A relaxed event: best-of-three with simple tiebreakers, matches won and then game
+    /// differential, and a bye for the lowest scoring player
+    Casual,
+}
+
+impl Preset {
+    /// All presets
+    pub const ALL: [Preset; 4] = [
+        Preset::MtgCompetitive,
+        Preset::ChessClub,
+        Preset::ImageBracket,
+        Preset::Casual,
+    ];
+}
+
+impl FromStr for Preset {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Preset, String> {
+        match s {
+            "mtg-competitive" => Ok(Preset::MtgCompetitive),
+            "chess-club" => Ok(Preset::ChessClub),
+            "image-bracket" => Ok(Preset::ImageBracket),
+            "casual" => Ok(Preset::Casual),
+            _ => Err(format!("Unknown preset: {}", s)),
+        }
+    }
+}
+
+impl Tournament {
+    /// Applies the settings of a preset. Meant to be called on a new tournament, before any
+    /// other settings are made, since it overwrites the match format, the tiebreakers, the
+    /// percentage floor, the final tiebreak, who goes first, and the bye settings.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use swyss::presets::Preset;
+    /// use swyss::{ByeScore, MatchFormat, Player, Tournament};
+    ///
+    /// let players = vec![
+    ///     Player::new("Alice"),
+    ///     Player::new("Bob"),
+    /// ];
+    /// let mut tourn = Tournament::with_players(players).unwrap();
+    /// tourn.apply_preset(Preset::ChessClub).unwrap();
+    ///
+    /// assert_eq!(tourn.match_format(), MatchFormat::BestOf(1));
+    /// assert_eq!(tourn.bye_score(), ByeScore::Draw);
+    /// ```
+    pub fn apply_preset(&mut self, preset: Preset) -> Result<(), LockError> {
+        self.check_unlocked()?;
+        let (format, floor, tiebreakers, first_player, bye_score) = match preset {
+            Preset::MtgCompetitive => (
+                MatchFormat::BestOf(3),
+                PercentageFloor::default(),
+                Criterion::DEFAULT_TIEBREAKERS.to_vec(),
+                FirstPlayer::Random,
+                ByeScore::Win,
+            ),
+            Preset::ChessClub => (
+                MatchFormat::BestOf(1),
+                PercentageFloor::None,
+                vec![
+                    Criterion::OpponentsMatchWinPercentage,
+                    Criterion::CumulativeScore,
+                    Criterion::Wins,
+                ],
+                FirstPlayer::Random,
+                ByeScore::Draw,
+            ),
+            Preset::ImageBracket => (
+                MatchFormat::BestOf(1),
+                PercentageFloor::None,
+                vec![
+                    Criterion::OpponentsMatchWinPercentage,
+                    Criterion::OpponentsOpponentsMatchWinPercentage,
+                ],
+                FirstPlayer::Undecided,
+                ByeScore::Win,
+            ),
+            Preset::Casual => (
+                MatchFormat::BestOf(3),
+                PercentageFloor::default(),
+                vec![Criterion::Wins, Criterion::GameDifferential],
+                FirstPlayer::Undecided,
+                ByeScore::Win,
+            ),
+        };
+        debug_event!(?preset, "applying preset");
+
+        self.match_format = format;
+        self.apply_percentage_floor(floor);
+        self.tiebreakers = tiebreakers;
+        self.final_tiebreak = FinalTiebreak::Random;
+        self.first_player = first_player;
+        self.bye_selection = ByeSelection::LowestScore;
+        self.bye_score = bye_score;
+        self.max_byes = 1;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Player;
+
+    #[test]
+    fn presets_play_through() {
+        for preset in &Preset::ALL {
+            let players = (1..6)
+                .map(|i| Player::new(format!("Player {}", i).as_str()))
+                .collect();
+            let mut tourn = Tournament::with_players(players).unwrap();
+            tourn.apply_preset(*preset).unwrap();
+            let wins = tourn.match_format.wins_needed();
+
+            while let Some(pairings) = tourn.next_round().unwrap() {
+                for (uuid, _, _) in pairings {
+                    tourn.end_match(uuid, wins, 0, 0).unwrap();
+                }
+            }
+            assert_eq!(tourn.standings().len(), 5);
+            assert!(tourn.check_invariants().is_ok());
+        }
+
+        let names = ["mtg-competitive", "chess-club", "image-bracket", "casual"];
+        let parsed: Vec<Preset> = names.iter().map(|name| name.parse().unwrap()).collect();
+        assert_eq!(parsed, Preset::ALL);
+        assert!("swiss".parse::<Preset>().is_err());
+    }
+}
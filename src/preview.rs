@@ -0,0 +1,285 @@
+//! Pairing a round without committing it, so that organizers can check the pairings before
+//! publishing them. A preview can be re-rolled by previewing again, which draws a new seed, and
+//! adjusted by hand before it is published.
+
+use crate::history::RoundError;
+use crate::oracle::PlayerPair;
+use crate::persist::TournamentData;
+use crate::{Pairings, Player, Tournament};
+use rand::{thread_rng, Rng};
+use std::collections::HashSet;
+
+/// The player who gets the bye, if any, and the pairings of a round
+pub(crate) type PlannedRound<'a> = (Option<&'a Player>, Vec<PlayerPair<'a>>);
+
+/// Pairings of the next round as computed by `Tournament::preview_round()`
+#[derive(Clone, Debug, PartialEq)]
+pub struct RoundPreview {
+    /// Number of the round the pairings are for
+    pub round: u32,
+    /// The seed the pairings were derived from. `Tournament::next_round_with_seed()` pairs the
+    /// round exactly like this as long as the tournament doesn't change in the meantime.
+    pub seed: u64,
+    /// Home and away player of every pairing
+    pub pairings: Vec<(uuid::Uuid, uuid::Uuid)>,
+    /// The player who gets the bye, if any
+    pub bye: Option<uuid::Uuid>,
+}
+
+impl Tournament {
+    /// Pairs the next round like `next_round()` would, without changing the tournament: no
+    /// opponents are recorded, no bye is granted, and the current round stays the same. Each call
+    /// draws a new seed, so calling it again re-rolls the pairings.
+    ///
+    /// Returns `None` if all rounds have been played, and an error if results of the current
+    /// round are still outstanding.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use swyss::{Player, Tournament};
+    ///
+    /// let players = (1..6)
+    ///     .map(|i| Player::new(&format!("Player {}", i)))
+    ///     .collect();
+    /// let mut tourn = Tournament::with_players(players).unwrap();
+    ///
+    /// let mut preview = tourn.preview_round().unwrap().unwrap();
+    /// assert_eq!(preview.pairings.len(), 2);
+    /// assert_eq!(tourn.current_round(), 0);
+    ///
+    /// // Swap the away players of the two tables before publishing
+    /// let away = preview.pairings[0].1;
+    /// preview.pairings[0].1 = preview.pairings[1].1;
+    /// preview.pairings[1].1 = away;
+    /// tourn.publish_round(&preview).unwrap();
+    /// assert_eq!(tourn.current_round(), 1);
+    /// assert_eq!(tourn.round(1).unwrap().bye, preview.bye);
+    /// ```
+    pub fn preview_round(&self) -> Result<Option<RoundPreview>, RoundError> {
+        self.preview_round_with_seed(thread_rng().gen())
+    }
+
+    /// Previews the next round like `preview_round()`, but derives all random decisions from the
+    /// given seed
+    pub fn preview_round_with_seed(&self, seed: u64) -> Result<Option<RoundPreview>, RoundError> {
+        let mut scratch = self.scratch_copy()?;
+        let pairings = match scratch.next_round_with_seed(seed)? {
+            Some(pairings) => pairings,
+            None => return Ok(None),
+        };
+
+        Ok(Some(RoundPreview {
+            round: scratch.current_round,
+            seed,
+            pairings: pairings
+                .iter()
+                .map(|(uuid, _, _)| scratch.pairings[uuid].players())
+                .collect(),
+            bye: scratch
+                .round(scratch.current_round)
+                .and_then(|round| round.bye),
+        }))
+    }
+
+    /// Pairs the next round exactly as given by a preview, which may have been adjusted by hand.
+    /// The preview must pair every player who is available in the round once, either in a pairing
+    /// or with the bye. Only if nobody is eligible for the bye may a single player be left out,
+    /// like `next_round()` does. The bye is granted to the player given, even if they already had
+    /// one.
+    ///
+    /// Fails without changing the tournament if the preview is of another round or doesn't pair
+    /// the players as required.
+    pub fn publish_round(
+        &mut self,
+        preview: &RoundPreview,
+    ) -> Result<Option<Pairings>, RoundError> {
+        let next = self.current_round + 1;
+        if preview.round != next {
+            return Err(RoundError::StalePreview {
+                previewed: preview.round,
+                next,
+            });
+        }
+
+        // Pair a copy first, so that an invalid preview leaves the tournament as it is
+        self.scratch_copy()?
+            .pair_next_round(preview.seed, Some(preview))?;
+        self.pair_next_round(preview.seed, Some(preview))
+    }
+
+    /// Returns an independent copy of the tournament, without observers. Fails if the tournament
+    /// couldn't be loaded again after saving it, e.g. because two players share a UUID.
+    fn scratch_copy(&self) -> Result<Tournament, RoundError> {
+        TournamentData::from(self)
+            .into_tournament()
+            .map_err(|e| RoundError::Inconsistent(e.to_string()))
+    }
+
+    /// Takes the players paired by the preview out of the active players, and returns them with
+    /// the player who gets the bye of the preview. Fails if the preview pairs anyone who isn't
+    /// active or pairs anyone twice, or leaves out more players than necessary.
+    pub(crate) fn planned_pairs<'a>(
+        &mut self,
+        preview: &RoundPreview,
+        active: &mut Vec<&'a Player>,
+    ) -> Result<PlannedRound<'a>, RoundError> {
+        let mut seen = HashSet::new();
+        let planned = preview
+            .pairings
+            .iter()
+            .flat_map(|&(home, away)| vec![home, away])
+            .chain(preview.bye);
+        for uuid in planned {
+            let is_active = active.iter().any(|p| p.uuid == uuid);
+            if !is_active || !seen.insert(uuid) {
+                return Err(RoundError::InvalidPreview(uuid));
+            }
+        }
+
+        let left_out: Vec<_> = active
+            .iter()
+            .map(|p| p.uuid)
+            .filter(|uuid| !seen.contains(uuid))
+            .collect();
+        let may_leave_out =
+            preview.bye.is_none() && active.iter().all(|p| p.byes_granted() >= self.max_byes);
+        match left_out.as_slice() {
+            [] => (),
+            [_] if may_leave_out => (),
+            [uuid, ..] => return Err(RoundError::InvalidPreview(*uuid)),
+        }
+
+        let find = |uuid: uuid::Uuid| {
+            let p = active.iter().find(|p| p.uuid == uuid);
+            *p.expect("every planned player is active")
+        };
+        let pairs = preview
+            .pairings
+            .iter()
+            .map(|&(home, away)| (find(home), find(away)))
+            .collect();
+
+        let bye = preview.bye.map(find);
+        if let Some(bye) = bye {
+            active.retain(|p| p.uuid != bye.uuid);
+        }
+
+        Ok((bye, pairs))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn players(n: usize) -> Vec<Player> {
+        (1..=n)
+            .map(|i| Player::new(format!("Player {}", i).as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn preview_changes_nothing() {
+        let mut tourn = Tournament::with_players(players(7)).unwrap();
+        for (uuid, _, _) in tourn.next_round().unwrap().unwrap() {
+            tourn.end_match(uuid, 2, 1, 0).unwrap();
+        }
+        let before = TournamentData::from(&tourn);
+
+        let preview = tourn.preview_round_with_seed(7).unwrap().unwrap();
+        assert_eq!(preview.round, 2);
+        assert_eq!(preview.pairings.len(), 3);
+        assert!(preview.bye.is_some());
+        assert_eq!(
+            tourn.preview_round_with_seed(7).unwrap(),
+            Some(preview.clone())
+        );
+
+        let after = TournamentData::from(&tourn);
+        assert_eq!(
+            serde_json::to_string(&before).unwrap(),
+            serde_json::to_string(&after).unwrap()
+        );
+
+        // Publishing pairs the players as previewed
+        tourn.publish_round(&preview).unwrap();
+        let round = tourn.round(2).unwrap();
+        let mut published: Vec<_> = round.pairings.iter().map(|p| (p.home, p.away)).collect();
+        let mut previewed = preview.pairings.clone();
+        published.sort();
+        previewed.sort();
+        assert_eq!(published, previewed);
+        assert_eq!(round.bye, preview.bye);
+
+        assert_eq!(
+            tourn.publish_round(&preview),
+            Err(RoundError::StalePreview {
+                previewed: 2,
+                next: 3
+            })
+        );
+    }
+
+    #[test]
+    fn invalid_previews_are_rejected() {
+        let mut tourn = Tournament::with_players(players(4)).unwrap();
+        let preview = tourn.preview_round().unwrap().unwrap();
+        let (home, away) = preview.pairings[0];
+
+        let mut twice = preview.clone();
+        twice.pairings[1].0 = home;
+        assert_eq!(
+            tourn.publish_round(&twice),
+            Err(RoundError::InvalidPreview(home))
+        );
+
+        let mut missing = preview.clone();
+        missing.pairings.remove(1);
+        assert!(matches!(
+            tourn.publish_round(&missing),
+            Err(RoundError::InvalidPreview(_))
+        ));
+
+        let mut stranger = preview.clone();
+        let unknown = uuid::Uuid::new_v4();
+        stranger.pairings[0].1 = unknown;
+        assert_eq!(
+            tourn.publish_round(&stranger),
+            Err(RoundError::InvalidPreview(unknown))
+        );
+
+        // Nothing was paired by the failed attempts
+        assert_eq!(tourn.current_round, 0);
+        assert!(tourn.pairings.is_empty());
+        assert!(tourn.players.iter().all(|p| p.opponents.is_empty()));
+
+        tourn.publish_round(&preview).unwrap();
+        assert!(tourn.rematches(1).is_empty());
+        let opponents = &tourn.players.get(home).unwrap().opponents;
+        assert_eq!(opponents[0], away);
+    }
+
+    #[test]
+    fn inconsistent_tournament_is_not_previewed() {
+        let mut tourn = Tournament::with_players(players(3)).unwrap();
+        let copy = tourn.players[0].clone();
+        tourn.players.push(copy);
+
+        assert!(matches!(
+            tourn.preview_round(),
+            Err(RoundError::Inconsistent(_))
+        ));
+        let preview = RoundPreview {
+            round: 1,
+            seed: 1,
+            pairings: Vec::new(),
+            bye: None,
+        };
+        assert!(matches!(
+            tourn.publish_round(&preview),
+            Err(RoundError::Inconsistent(_))
+        ));
+        assert_eq!(tourn.current_round, 0);
+    }
+}
@@ -0,0 +1,208 @@
+//! Prize payouts from the final standings. A payout structure gives the prize of every place,
+//! either as shares of a prize pool or as a fixed prize per place such as booster packs. Players
+//! tied on match points and all tiebreakers split the prizes of the places they share evenly, so
+//! the final tiebreak never decides who gets paid more.
+
+use crate::{compare_standing, Tournament};
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::fmt;
+
+/// The prize of every place, starting with first place. Places beyond the listed ones get
+/// nothing.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub enum PayoutStructure {
+    /// Shares of a prize pool in percent, e.g. 50/30/20
+    Split { pool: f64, percentages: Vec<f64> },
+    /// A fixed prize per place, e.g. a number of booster packs
+    Places(Vec<f64>),
+}
+
+impl PayoutStructure {
+    /// Returns the prize of every paid place, starting with first place
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use swyss::prizes::PayoutStructure;
+    ///
+    /// let split = PayoutStructure::Split {
+    ///     pool: 200.0,
+    ///     percentages: vec![50.0, 30.0, 20.0],
+    /// };
+    /// assert_eq!(split.prizes(), [100.0, 60.0, 40.0]);
+    /// ```
+    pub fn prizes(&self) -> Vec<f64> {
+        match self {
+            PayoutStructure::Split { pool, percentages } => {
+                percentages.iter().map(|p| pool * p / 100.0).collect()
+            }
+            PayoutStructure::Places(prizes) => prizes.clone(),
+        }
+    }
+}
+
+/// A player's prize, as returned by `Tournament::payouts()`
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct Payout {
+    pub rank: u32,
+    pub uuid: uuid::Uuid,
+    pub name: String,
+    pub amount: f64,
+    /// Number of players who split the prizes of their places evenly, 1 if the player wasn't tied
+    pub split: u32,
+}
+
+impl fmt::Display for Payout {
+    /// Formats the payout like "2. Bob: 50 (split 2 ways)", printing whole amounts without
+    /// decimals
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.amount.fract() == 0.0 {
+            write!(f, "{}. {}: {}", self.rank, self.name, self.amount)?;
+        } else {
+            write!(f, "{}. {}: {:.2}", self.rank, self.name, self.amount)?;
+        }
+        if self.split > 1 {
+            write!(f, " (split {} ways)", self.split)?;
+        }
+        Ok(())
+    }
+}
+
+impl Tournament {
+    /// Pays out the prizes of the given structure over the current standings. Players who are
+    /// tied on match points and all tiebreakers split the prizes of their places evenly. Only
+    /// players who receive a prize are returned, in ranking order.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use swyss::prizes::PayoutStructure;
+    /// use swyss::{Player, Tournament};
+    ///
+    /// let players = (1..5)
+    ///     .map(|i| Player::new(&format!("Player {}", i)))
+    ///     .collect();
+    /// let mut tourn = Tournament::with_players(players).unwrap();
+    /// for (uuid, _, _) in tourn.next_round().unwrap().unwrap() {
+    ///     tourn.end_match(uuid, 2, 0, 0).unwrap();
+    /// }
+    ///
+    /// // Both winners are tied on everything, so they split first and second place
+    /// let payouts = tourn.payouts(&PayoutStructure::Places(vec![6.0, 4.0, 2.0]));
+    /// assert_eq!(payouts.len(), 4);
+    /// assert_eq!(payouts[0].amount, 5.0);
+    /// assert_eq!(payouts[1].amount, 5.0);
+    /// assert_eq!(payouts[2].amount, 1.0);
+    /// assert_eq!(payouts[2].split, 2);
+    /// ```
+    pub fn payouts(&self, structure: &PayoutStructure) -> Vec<Payout> {
+        let prizes = structure.prizes();
+        let players = self.sorted_players();
+        let mut payouts = Vec::new();
+
+        let mut start = 0;
+        while start < players.len() && start < prizes.len() {
+            let first = players[start];
+            let end = start
+                + players[start..]
+                    .iter()
+                    .take_while(|p| {
+                        compare_standing(first, p, &self.players, &self.tiebreakers)
+                            == Ordering::Equal
+                    })
+                    .count();
+
+            let split = (end - start) as u32;
+            let total: f64 = prizes.iter().skip(start).take(end - start).sum();
+            for (i, p) in players.iter().enumerate().take(end).skip(start) {
+                payouts.push(Payout {
+                    rank: i as u32 + 1,
+                    uuid: p.uuid,
+                    name: p.name.clone(),
+                    amount: total / f64::from(split),
+                    split,
+                });
+            }
+
+            start = end;
+        }
+
+        payouts
+    }
+
+    /// Lists the payouts of the tournament's `prizes`, one per line, for printing or exporting
+    /// along with the standings. Empty if no prizes are set.
+    pub fn prize_report(&self) -> String {
+        let mut report = String::new();
+
+        if let Some(prizes) = &self.prizes {
+            for payout in self.payouts(prizes) {
+                report.push_str(&format!("{}\n", payout));
+            }
+        }
+
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Player;
+
+    fn tournament(players: usize) -> Tournament {
+        let players = (1..=players)
+            .map(|i| Player::new(format!("Player {}", i).as_str()))
+            .collect();
+        Tournament::with_players(players).unwrap()
+    }
+
+    #[test]
+    fn split_pool() {
+        let mut tourn = tournament(8);
+        for _ in 0..3 {
+            for (uuid, _, _) in tourn.next_round().unwrap().unwrap() {
+                tourn.end_match(uuid, 2, 0, 0).unwrap();
+            }
+        }
+
+        // The only 3-0 player takes first place alone
+        let structure = PayoutStructure::Split {
+            pool: 100.0,
+            percentages: vec![50.0, 30.0, 20.0],
+        };
+        let payouts = tourn.payouts(&structure);
+        let standings = tourn.standings();
+        assert_eq!(payouts[0].uuid, standings[0].uuid);
+        assert_eq!(payouts[0].amount, 50.0);
+        assert_eq!(payouts[0].split, 1);
+
+        // Nothing is lost or created by splitting
+        let paid: f64 = payouts.iter().map(|p| p.amount).sum();
+        assert!((paid - 100.0).abs() < 1e-9);
+        for payout in &payouts {
+            assert_eq!(payout.rank, standings[payout.rank as usize - 1].rank);
+        }
+    }
+
+    #[test]
+    fn report() {
+        let mut tourn = tournament(2);
+        assert_eq!(tourn.prize_report(), "");
+
+        let (uuid, _, _) = tourn.next_round().unwrap().unwrap()[0];
+        tourn.end_match(uuid, 2, 1, 0).unwrap();
+        tourn.prizes = Some(PayoutStructure::Places(vec![3.0, 1.5]));
+        let standings = tourn.standings();
+        assert_eq!(
+            tourn.prize_report(),
+            format!(
+                "1. {}: 3\n2. {}: 1.50\n",
+                standings[0].name, standings[1].name
+            )
+        );
+    }
+}
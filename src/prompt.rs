@@ -0,0 +1,170 @@
+//! The interactive prompt of the CLI, with line editing, a history of earlier inputs to go back to
+//! with the arrow keys, and tab completion of commands and the names of the players at the table.
+//! Standard input that isn't a terminal, like a piped file, is read line by line as before.
+
+use rustyline::completion::{Completer, Pair};
+use rustyline::error::ReadlineError;
+use rustyline::highlight::Highlighter;
+use rustyline::hint::Hinter;
+use rustyline::history::DefaultHistory;
+use rustyline::validate::Validator;
+use rustyline::{Context, Editor, Helper};
+use std::io;
+
+/// Commands of the score prompt, completed like names
+pub const COMMANDS: [&str; 4] = ["bye", "c", "id", "s"];
+
+/// Completes the word before the cursor from a list of commands and names
+#[derive(Default)]
+pub struct Completions {
+    words: Vec<String>,
+}
+
+impl Completions {
+    /// Returns the start of the completed text in `line` and the words it completes to. Names may
+    /// contain spaces, so the longest text before the cursor that starts a word wins, ignoring
+    /// case.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use swyss::prompt::Completions;
+    ///
+    /// let mut completions = Completions::default();
+    /// completions.set_words(&["Anna Maria", "Anton"]);
+    ///
+    /// assert_eq!(completions.complete("c an", 4), (2, vec!["Anna Maria", "Anton"]));
+    /// assert_eq!(completions.complete("c anna m", 8), (2, vec!["Anna Maria"]));
+    /// ```
+    pub fn complete(&self, line: &str, pos: usize) -> (usize, Vec<&str>) {
+        let before = &line[..pos];
+        let starts = std::iter::once(0).chain(
+            before
+                .char_indices()
+                .filter(|&(_, c)| c == ' ')
+                .map(|(i, _)| i + 1),
+        );
+
+        for start in starts {
+            let typed = before[start..].to_lowercase();
+            if typed.is_empty() {
+                continue;
+            }
+            let matches: Vec<&str> = self
+                .words
+                .iter()
+                .filter(|word| word.to_lowercase().starts_with(&typed))
+                .map(String::as_str)
+                .collect();
+            if !matches.is_empty() {
+                return (start, matches);
+            }
+        }
+
+        (pos, Vec::new())
+    }
+
+    /// Replaces the words that are completed
+    pub fn set_words<S: AsRef<str>>(&mut self, words: &[S]) {
+        self.words = words.iter().map(|w| String::from(w.as_ref())).collect();
+    }
+}
+
+impl Completer for Completions {
+    type Candidate = Pair;
+
+    fn complete(
+        &self,
+        line: &str,
+        pos: usize,
+        _: &Context<'_>,
+    ) -> rustyline::Result<(usize, Vec<Pair>)> {
+        let (start, words) = Completions::complete(self, line, pos);
+        let pairs = words
+            .into_iter()
+            .map(|word| Pair {
+                display: String::from(word),
+                replacement: String::from(word),
+            })
+            .collect();
+        Ok((start, pairs))
+    }
+}
+
+impl Hinter for Completions {
+    type Hint = String;
+}
+
+impl Highlighter for Completions {}
+
+impl Validator for Completions {}
+
+impl Helper for Completions {}
+
+/// A prompt that reads one line of input at a time
+pub struct Prompt {
+    editor: Editor<Completions, DefaultHistory>,
+}
+
+impl Prompt {
+    pub fn new() -> io::Result<Prompt> {
+        let mut editor = Editor::new().map_err(into_io_error)?;
+        editor.set_helper(Some(Completions::default()));
+        Ok(Prompt { editor })
+    }
+
+    /// Completes the given names in addition to the commands from now on, e.g. the names of the
+    /// players of the pairing whose result is entered
+    pub fn complete_names<S: AsRef<str>>(&mut self, names: &[S]) {
+        let mut words: Vec<&str> = names.iter().map(AsRef::as_ref).collect();
+        words.extend(COMMANDS.iter());
+        if let Some(completions) = self.editor.helper_mut() {
+            completions.set_words(&words);
+        }
+    }
+
+    /// Reads a line, adding it to the history unless it is empty. Returns `None` if the input
+    /// ended or was interrupted with Ctrl-C.
+    pub fn read(&mut self, prompt: &str) -> io::Result<Option<String>> {
+        match self.editor.readline(prompt) {
+            Ok(line) => {
+                if !line.trim().is_empty() {
+                    self.editor
+                        .add_history_entry(line.as_str())
+                        .map_err(into_io_error)?;
+                }
+                Ok(Some(line))
+            }
+            Err(ReadlineError::Eof) | Err(ReadlineError::Interrupted) => Ok(None),
+            Err(e) => Err(into_io_error(e)),
+        }
+    }
+}
+
+fn into_io_error(e: ReadlineError) -> io::Error {
+    match e {
+        ReadlineError::Io(e) => e,
+        e => io::Error::other(e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn complete_names_and_commands() {
+        let mut completions = Completions::default();
+        completions.set_words(&["Bob", "Bernadette", "bye", "c"]);
+
+        assert_eq!(
+            completions.complete("B", 1),
+            (0, vec!["Bob", "Bernadette", "bye"])
+        );
+        assert_eq!(completions.complete("bye be", 6), (4, vec!["Bernadette"]));
+        assert_eq!(completions.complete("c bo", 4), (2, vec!["Bob"]));
+        assert_eq!(completions.complete("c bo and more", 4), (2, vec!["Bob"]));
+        assert_eq!(completions.complete("2-1", 3), (3, vec![]));
+        assert_eq!(completions.complete("", 0), (0, vec![]));
+    }
+}
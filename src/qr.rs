@@ -0,0 +1,101 @@
+//! QR codes linking to the spectator page, so that organizers can post the code at the venue
+//! and players only have to point their phone at it. Requires the `qr` feature.
+
+use image::Luma;
+use qrcode::render::unicode::Dense1x2;
+use qrcode::types::QrError;
+use qrcode::QrCode;
+use std::fmt;
+use std::path::Path;
+
+/// Pixels per module of the QR code in exported images, which makes a code for a typical URL
+/// about 300 pixels wide
+const MODULE_SIZE: u32 = 8;
+
+/// Creating a QR code fails if the text is too long to fit into one, or if the image can't be
+/// written
+#[derive(Debug)]
+pub enum QrCodeError {
+    Encode(QrError),
+    Image(image::ImageError),
+}
+
+impl std::error::Error for QrCodeError {}
+
+impl fmt::Display for QrCodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            QrCodeError::Encode(e) => write!(f, "Could not create QR code: {}", e),
+            QrCodeError::Image(e) => write!(f, "Could not write QR code: {}", e),
+        }
+    }
+}
+
+impl From<QrError> for QrCodeError {
+    fn from(e: QrError) -> Self {
+        QrCodeError::Encode(e)
+    }
+}
+
+impl From<image::ImageError> for QrCodeError {
+    fn from(e: image::ImageError) -> Self {
+        QrCodeError::Image(e)
+    }
+}
+
+/// Renders a QR code of the text for printing to a terminal. Every line of text holds two rows
+/// of the code, drawn in light blocks so that the code stays readable on the usual dark terminal
+/// background.
+///
+/// # Example
+///
+/// ```
+/// let code = swyss::qr::terminal_qr_code("http://192.168.1.10:8080/").unwrap();
+/// assert!(code.lines().count() > 10);
+/// ```
+pub fn terminal_qr_code(text: &str) -> Result<String, QrCodeError> {
+    let code = QrCode::new(text)?;
+    Ok(code
+        .render::<Dense1x2>()
+        .dark_color(Dense1x2::Light)
+        .light_color(Dense1x2::Dark)
+        .build())
+}
+
+/// Writes a QR code of the text to an image file, e.g. a PNG for printing. The format is chosen
+/// by the file extension.
+pub fn save_qr_code(text: &str, path: &Path) -> Result<(), QrCodeError> {
+    let code = QrCode::new(text)?;
+    let image = code
+        .render::<Luma<u8>>()
+        .module_dimensions(MODULE_SIZE, MODULE_SIZE)
+        .build();
+    image.save(path)?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn qr_codes() {
+        let url = "http://192.168.1.10:8080/";
+        let code = terminal_qr_code(url).unwrap();
+        let widths: Vec<usize> = code.lines().map(|line| line.chars().count()).collect();
+        assert!(widths.iter().all(|&w| w == widths[0]));
+
+        let path = std::env::temp_dir().join(format!("swyss-qr-{}.png", uuid::Uuid::new_v4()));
+        save_qr_code(url, &path).unwrap();
+        let image = image::open(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(image.width(), image.height());
+        assert_eq!(image.width() % MODULE_SIZE, 0);
+
+        assert!(matches!(
+            terminal_qr_code(&"x".repeat(8000)),
+            Err(QrCodeError::Encode(_))
+        ));
+    }
+}
@@ -0,0 +1,377 @@
+//! Player ratings that are carried across tournaments. Two rating systems are available: classic
+//! Elo and Glicko-2, which additionally tracks how reliable each rating is. Players are identified
+//! by their name, since every tournament assigns its own UUIDs.
+
+use crate::persist::LoadError;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::f64::consts::PI;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// Conversion factor between the Glicko and the Glicko-2 scale
+const GLICKO2_SCALE: f64 = 173.7178;
+
+/// Convergence tolerance of the Glicko-2 volatility iteration
+const GLICKO2_EPSILON: f64 = 0.000_001;
+
+/// The rating system used to update ratings
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub enum RatingSystem {
+    /// Elo with the given K-factor, i.e. the maximum change of a rating from a single match
+    Elo { k_factor: f64 },
+    /// Glicko-2 with the given system constant τ, which limits how much the volatility may change
+    /// over time. Reasonable values lie between 0.3 and 1.2.
+    Glicko2 { tau: f64 },
+}
+
+impl Default for RatingSystem {
+    fn default() -> RatingSystem {
+        RatingSystem::Elo { k_factor: 32.0 }
+    }
+}
+
+/// A player's rating. Elo only uses `rating`; Glicko-2 also keeps track of the rating deviation
+/// (how uncertain the rating is) and the volatility (how erratic the player's results are).
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct Rating {
+    pub rating: f64,
+    pub deviation: f64,
+    pub volatility: f64,
+}
+
+impl Default for Rating {
+    /// The rating of a new, unrated player
+    fn default() -> Rating {
+        Rating {
+            rating: 1500.0,
+            deviation: 350.0,
+            volatility: 0.06,
+        }
+    }
+}
+
+impl Rating {
+    /// Expected score against the given opponent according to the Elo formula, between 0 and 1
+    pub fn expected_score(&self, opponent: &Rating) -> f64 {
+        1.0 / (1.0 + 10f64.powf((opponent.rating - self.rating) / 400.0))
+    }
+}
+
+/// The result of a single match between two players
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct MatchResult {
+    pub home: String,
+    pub away: String,
+    /// Score of the home player: 1 for a win, 0.5 for a draw, 0 for a loss
+    pub home_score: f64,
+}
+
+impl MatchResult {
+    pub fn new(home: &str, away: &str, home_score: f64) -> MatchResult {
+        MatchResult {
+            home: String::from(home),
+            away: String::from(away),
+            home_score,
+        }
+    }
+}
+
+/// Ratings of all players, updated with the configured rating system
+///
+/// # Example
+///
+/// ```
+/// use swyss::ratings::{MatchResult, Ratings, RatingSystem};
+/// let mut ratings = Ratings::new(RatingSystem::Elo { k_factor: 32.0 });
+/// ratings.rate_period(&[MatchResult::new("Alice", "Bob", 1.0)]);
+/// assert_eq!(ratings.get("Alice").rating, 1516.0);
+/// assert_eq!(ratings.get("Bob").rating, 1484.0);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct Ratings {
+    pub system: RatingSystem,
+    pub ratings: HashMap<String, Rating>,
+}
+
+impl Ratings {
+    /// Returns a new set of ratings without any rated players
+    pub fn new(system: RatingSystem) -> Ratings {
+        Ratings {
+            system,
+            ratings: HashMap::new(),
+        }
+    }
+
+    /// Returns the rating of a player, or the default rating if the player hasn't been rated yet
+    pub fn get(&self, name: &str) -> Rating {
+        self.ratings.get(name).copied().unwrap_or_default()
+    }
+
+    /// Updates all ratings with the results of a rating period, e.g. a round or a whole
+    /// tournament. All results of the period are rated against the ratings from before the
+    /// period, so the order of the results doesn't matter. With Glicko-2, the deviation of rated
+    /// players who didn't play during the period increases.
+    pub fn rate_period(&mut self, results: &[MatchResult]) {
+        let mut games: HashMap<&str, Vec<(Rating, f64)>> = HashMap::new();
+
+        for result in results {
+            let home = self.get(&result.home);
+            let away = self.get(&result.away);
+
+            games
+                .entry(&result.home)
+                .or_default()
+                .push((away, result.home_score));
+            games
+                .entry(&result.away)
+                .or_default()
+                .push((home, 1.0 - result.home_score));
+        }
+
+        let mut updated: HashMap<String, Rating> = games
+            .iter()
+            .map(|(name, games)| {
+                let rating = self.get(name);
+                let new = match self.system {
+                    RatingSystem::Elo { k_factor } => elo(rating, games, k_factor),
+                    RatingSystem::Glicko2 { tau } => glicko2(rating, games, tau),
+                };
+                (String::from(*name), new)
+            })
+            .collect();
+
+        if let RatingSystem::Glicko2 { tau } = self.system {
+            for (name, rating) in &self.ratings {
+                if !updated.contains_key(name) {
+                    updated.insert(name.clone(), glicko2(*rating, &[], tau));
+                }
+            }
+        }
+
+        self.ratings.extend(updated);
+    }
+
+    /// Returns all rated players ordered by rating
+    pub fn leaderboard(&self) -> Vec<(String, Rating)> {
+        let mut leaderboard: Vec<(String, Rating)> = self
+            .ratings
+            .iter()
+            .map(|(name, rating)| (name.clone(), *rating))
+            .collect();
+
+        leaderboard.sort_by(|a, b| {
+            b.1.rating
+                .partial_cmp(&a.1.rating)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then(a.0.cmp(&b.0))
+        });
+
+        leaderboard
+    }
+
+    /// Serializes the ratings to JSON
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("ratings are always serializable")
+    }
+
+    /// Restores ratings from JSON created by `to_json()`
+    pub fn from_json(json: &str) -> Result<Ratings, LoadError> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    /// Saves the ratings to a file
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        fs::write(path, self.to_json())
+    }
+
+    /// Loads ratings from a file created by `save()`
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Ratings, LoadError> {
+        let json = fs::read_to_string(path)?;
+        Ratings::from_json(&json)
+    }
+}
+
+/// Elo update for all games of a rating period
+fn elo(rating: Rating, games: &[(Rating, f64)], k_factor: f64) -> Rating {
+    let change: f64 = games
+        .iter()
+        .map(|(opponent, score)| k_factor * (score - rating.expected_score(opponent)))
+        .sum();
+
+    Rating {
+        rating: rating.rating + change,
+        ..rating
+    }
+}
+
+/// Glicko-2 update for all games of a rating period, following Glickman's "Example of the
+/// Glicko-2 system"
+fn glicko2(rating: Rating, games: &[(Rating, f64)], tau: f64) -> Rating {
+    let mu = (rating.rating - 1500.0) / GLICKO2_SCALE;
+    let phi = rating.deviation / GLICKO2_SCALE;
+    let sigma = rating.volatility;
+
+    // A player who didn't play only becomes less certain
+    if games.is_empty() {
+        return Rating {
+            deviation: (phi * phi + sigma * sigma).sqrt() * GLICKO2_SCALE,
+            ..rating
+        };
+    }
+
+    let g = |phi: f64| 1.0 / (1.0 + 3.0 * phi * phi / (PI * PI)).sqrt();
+
+    let mut v_inv = 0.0;
+    let mut improvement = 0.0;
+
+    for (opponent, score) in games {
+        let mu_j = (opponent.rating - 1500.0) / GLICKO2_SCALE;
+        let g_j = g(opponent.deviation / GLICKO2_SCALE);
+        let expected = 1.0 / (1.0 + (-g_j * (mu - mu_j)).exp());
+
+        v_inv += g_j * g_j * expected * (1.0 - expected);
+        improvement += g_j * (score - expected);
+    }
+
+    let v = 1.0 / v_inv;
+    let delta = v * improvement;
+
+    // Find the new volatility with the Illinois algorithm
+    let a = (sigma * sigma).ln();
+    let f = |x: f64| {
+        let ex = x.exp();
+        let denom = phi * phi + v + ex;
+        ex * (delta * delta - phi * phi - v - ex) / (2.0 * denom * denom) - (x - a) / (tau * tau)
+    };
+
+    let mut lower = a;
+    let mut upper = if delta * delta > phi * phi + v {
+        (delta * delta - phi * phi - v).ln()
+    } else {
+        let mut k = 1.0;
+        while f(a - k * tau) < 0.0 {
+            k += 1.0;
+        }
+        a - k * tau
+    };
+
+    let mut f_lower = f(lower);
+    let mut f_upper = f(upper);
+
+    while (upper - lower).abs() > GLICKO2_EPSILON {
+        let c = lower + (lower - upper) * f_lower / (f_upper - f_lower);
+        let f_c = f(c);
+
+        if f_c * f_upper <= 0.0 {
+            lower = upper;
+            f_lower = f_upper;
+        } else {
+            f_lower /= 2.0;
+        }
+
+        upper = c;
+        f_upper = f_c;
+    }
+
+    let new_sigma = (lower / 2.0).exp();
+
+    let phi_star = (phi * phi + new_sigma * new_sigma).sqrt();
+    let new_phi = 1.0 / (1.0 / (phi_star * phi_star) + 1.0 / v).sqrt();
+    let new_mu = mu + new_phi * new_phi * improvement;
+
+    Rating {
+        rating: new_mu * GLICKO2_SCALE + 1500.0,
+        deviation: new_phi * GLICKO2_SCALE,
+        volatility: new_sigma,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rating(rating: f64, deviation: f64) -> Rating {
+        Rating {
+            rating,
+            deviation,
+            volatility: 0.06,
+        }
+    }
+
+    #[test]
+    fn elo_draw_between_unequal_players() {
+        let mut ratings = Ratings::new(RatingSystem::Elo { k_factor: 20.0 });
+        ratings
+            .ratings
+            .insert(String::from("Alice"), rating(1600.0, 0.0));
+
+        ratings.rate_period(&[MatchResult::new("Alice", "Bob", 0.5)]);
+
+        // The favourite loses what the underdog gains
+        let alice = ratings.get("Alice").rating;
+        let bob = ratings.get("Bob").rating;
+        assert!(alice < 1600.0);
+        assert!((alice + bob - 3100.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn glicko2_example() {
+        // The worked example from Glickman's description of the Glicko-2 system
+        let mut ratings = Ratings::new(RatingSystem::Glicko2 { tau: 0.5 });
+        ratings
+            .ratings
+            .insert(String::from("A"), rating(1500.0, 200.0));
+        ratings
+            .ratings
+            .insert(String::from("B"), rating(1400.0, 30.0));
+        ratings
+            .ratings
+            .insert(String::from("C"), rating(1550.0, 100.0));
+        ratings
+            .ratings
+            .insert(String::from("D"), rating(1700.0, 300.0));
+
+        let a = ratings.get("A");
+        let games = [
+            (ratings.get("B"), 1.0),
+            (ratings.get("C"), 0.0),
+            (ratings.get("D"), 0.0),
+        ];
+        let new = glicko2(a, &games, 0.5);
+
+        assert!((new.rating - 1464.06).abs() < 0.01);
+        assert!((new.deviation - 151.52).abs() < 0.01);
+        assert!((new.volatility - 0.05999).abs() < 0.00001);
+    }
+
+    #[test]
+    fn glicko2_inactive_players() {
+        let mut ratings = Ratings::new(RatingSystem::Glicko2 { tau: 0.5 });
+        ratings.rate_period(&[MatchResult::new("Alice", "Bob", 1.0)]);
+
+        let alice = ratings.get("Alice");
+        let bob = ratings.get("Bob");
+        assert!(alice.rating > 1500.0);
+        assert!(bob.rating < 1500.0);
+        assert!(alice.deviation < 350.0);
+
+        // Bob sits out the next period, so his rating becomes less certain
+        ratings.rate_period(&[MatchResult::new("Alice", "Carol", 0.5)]);
+        assert_eq!(ratings.get("Bob").rating, bob.rating);
+        assert!(ratings.get("Bob").deviation > bob.deviation);
+
+        let leaderboard = ratings.leaderboard();
+        assert_eq!(leaderboard.len(), 3);
+        assert_eq!(leaderboard[0].0, "Alice");
+        assert_eq!(leaderboard[2].0, "Bob");
+
+        let loaded = Ratings::from_json(&ratings.to_json()).unwrap();
+        assert_eq!(loaded, ratings);
+    }
+}
@@ -0,0 +1,218 @@
+//! Why a round was paired the way it was, recorded with the round so that a player who asks why
+//! they were paired up, paired down, paired against an old opponent, or given the bye can be
+//! answered from the record instead of from memory. `Round::rationale()` returns it.
+
+use crate::{ByeSelection, PairingSystem, Tournament};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+/// The reasoning behind the pairings of a round
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct PairingRationale {
+    /// The players available for pairing, grouped by their match points going into the round,
+    /// highest first
+    pub score_groups: Vec<ScoreGroup>,
+    /// Players who were paired against an opponent with a different score
+    pub floats: Vec<Float>,
+    /// Pairings of players who already met, because no pairing without them was found or the
+    /// round was published from a preview that paired them
+    pub rematches: Vec<uuid::Uuid>,
+    /// Who got the bye and why
+    pub bye: Option<ByeRationale>,
+}
+
+/// Players with the same match points
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct ScoreGroup {
+    pub match_points: u32,
+    pub players: Vec<uuid::Uuid>,
+}
+
+/// A player paired outside their score group
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct Float {
+    pub pairing: uuid::Uuid,
+    pub player: uuid::Uuid,
+    pub direction: FloatDirection,
+    /// The match points of the player going into the round
+    pub match_points: u32,
+    /// The match points of their opponent going into the round
+    pub opponent_match_points: u32,
+}
+
+/// Whether a player was paired against a stronger or a weaker opponent
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub enum FloatDirection {
+    /// Paired against an opponent with more match points
+    Up,
+    /// Paired against an opponent with fewer match points
+    Down,
+}
+
+/// The player who got the bye of a round
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct ByeRationale {
+    pub player: uuid::Uuid,
+    /// The match points of the player going into the round, before the bye
+    pub match_points: u32,
+    pub reason: ByeReason,
+}
+
+/// Why a player got the bye. Only players below `Tournament::max_byes()` are eligible.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub enum ByeReason {
+    /// They had the fewest match points of the eligible players
+    LowestScore,
+    /// They were ranked lowest of the eligible players
+    LowestTiebreakers,
+    /// They volunteered for the bye
+    Volunteered,
+    /// The round robin schedule has them sit out
+    Scheduled,
+    /// The round was published from a preview that gave them the bye
+    Planned,
+}
+
+impl Tournament {
+    /// Explains the round that was just paired, given the match points every available player
+    /// had going into it
+    pub(crate) fn pairing_rationale(
+        &self,
+        points: &[(uuid::Uuid, u32)],
+        planned: bool,
+    ) -> PairingRationale {
+        let points_of: HashMap<uuid::Uuid, u32> = points.iter().copied().collect();
+        let mut groups: BTreeMap<u32, Vec<uuid::Uuid>> = BTreeMap::new();
+        for &(player, match_points) in points {
+            groups.entry(match_points).or_default().push(player);
+        }
+        let score_groups = groups
+            .into_iter()
+            .rev()
+            .map(|(match_points, players)| ScoreGroup {
+                match_points,
+                players,
+            })
+            .collect();
+
+        let round = self.round(self.current_round);
+        let pairings = round
+            .as_ref()
+            .map(|round| round.pairings.as_slice())
+            .unwrap_or_default();
+        let mut floats = Vec::new();
+        for pair in pairings {
+            let (home, away) = (points_of[&pair.home], points_of[&pair.away]);
+            if home == away {
+                continue;
+            }
+            for &(player, own, other) in &[(pair.home, home, away), (pair.away, away, home)] {
+                floats.push(Float {
+                    pairing: pair.uuid,
+                    player,
+                    direction: if own < other {
+                        FloatDirection::Up
+                    } else {
+                        FloatDirection::Down
+                    },
+                    match_points: own,
+                    opponent_match_points: other,
+                });
+            }
+        }
+
+        let bye = round.as_ref().and_then(|round| round.bye).map(|player| {
+            let reason = if planned {
+                ByeReason::Planned
+            } else if self.pairing_system == PairingSystem::RoundRobin {
+                ByeReason::Scheduled
+            } else {
+                match &self.bye_selection {
+                    ByeSelection::LowestScore => ByeReason::LowestScore,
+                    ByeSelection::LowestTiebreakers => ByeReason::LowestTiebreakers,
+                    ByeSelection::Volunteers(volunteers) if volunteers.contains(&player) => {
+                        ByeReason::Volunteered
+                    }
+                    ByeSelection::Volunteers(_) => ByeReason::LowestScore,
+                }
+            };
+            ByeRationale {
+                player,
+                match_points: points_of.get(&player).copied().unwrap_or_default(),
+                reason,
+            }
+        });
+
+        PairingRationale {
+            score_groups,
+            floats,
+            rematches: self.rematches(self.current_round),
+            bye,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Player;
+
+    #[test]
+    fn floats_and_bye() {
+        let players: Vec<_> = (1..6)
+            .map(|i| Player::new(format!("Player {}", i).as_str()))
+            .collect();
+        let uuids: Vec<uuid::Uuid> = players.iter().map(|p| p.uuid).collect();
+        let mut tourn = Tournament::with_players(players).unwrap();
+        tourn.bye_selection = ByeSelection::Volunteers(vec![uuids[4]]);
+
+        tourn.next_round_with_seed(1).unwrap().unwrap();
+        let first = tourn.round(1).unwrap();
+        let rationale = first.rationale().unwrap();
+        assert_eq!(rationale.score_groups.len(), 1);
+        assert_eq!(rationale.score_groups[0].players.len(), 5);
+        assert!(rationale.floats.is_empty());
+        assert!(rationale.rematches.is_empty());
+        assert_eq!(
+            rationale.bye,
+            Some(ByeRationale {
+                player: uuids[4],
+                match_points: 0,
+                reason: ByeReason::Volunteered,
+            })
+        );
+
+        for pair in &first.pairings {
+            tourn.end_match(pair.uuid, 2, 0, 0).unwrap();
+        }
+        tourn.next_round_with_seed(1).unwrap().unwrap();
+
+        // Two winners and the bye have 3 points, two losers 0, so one winner meets a loser
+        let rationale = tourn.round(2).unwrap().rationale().cloned().unwrap();
+        let points: Vec<u32> = rationale
+            .score_groups
+            .iter()
+            .map(|group| group.match_points)
+            .collect();
+        assert_eq!(points, vec![3, 0]);
+        assert_eq!(rationale.floats.len(), 2);
+        let down = rationale
+            .floats
+            .iter()
+            .find(|float| float.direction == FloatDirection::Down)
+            .unwrap();
+        assert_eq!((down.match_points, down.opponent_match_points), (3, 0));
+        assert_eq!(rationale.bye.unwrap().reason, ByeReason::LowestScore);
+        assert_eq!(rationale.bye.unwrap().match_points, 0);
+
+        // The rationale is saved with the round
+        let loaded = Tournament::from_json(&tourn.to_json()).unwrap();
+        assert_eq!(loaded.round(2).unwrap().rationale(), Some(&rationale));
+    }
+}
@@ -0,0 +1,183 @@
+//! Check-in before the first round. A tournament opened for registration only pairs the players
+//! who checked in, and computes the number of rounds from them, so that no-shows don't distort the
+//! event.
+
+use crate::{LockError, Player, PlayerError, Tournament};
+use std::fmt;
+
+/// Reasons why a player can't be checked in or the tournament can't be started
+#[derive(Debug, PartialEq)]
+pub enum RegistrationError {
+    /// There is no such player
+    UnknownPlayer(uuid::Uuid),
+    /// The tournament isn't open for registration, e.g. because it has already started
+    Closed,
+    /// The tournament is complete, so its players are final
+    Finished,
+    /// The tournament was opened read-only, so no players can be checked in or added
+    ReadOnly,
+}
+
+impl std::error::Error for RegistrationError {}
+
+impl fmt::Display for RegistrationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RegistrationError::UnknownPlayer(uuid) => write!(f, "Unknown player {}!", uuid),
+            RegistrationError::Closed => write!(f, "Registration is closed!"),
+            RegistrationError::Finished => write!(f, "The tournament is already finished!"),
+            RegistrationError::ReadOnly => write!(f, "The tournament was opened read-only!"),
+        }
+    }
+}
+
+impl From<LockError> for RegistrationError {
+    fn from(e: LockError) -> RegistrationError {
+        match e {
+            LockError::Finished => RegistrationError::Finished,
+            LockError::ReadOnly => RegistrationError::ReadOnly,
+        }
+    }
+}
+
+impl Tournament {
+    /// Creates a tournament that is open for registration. Players have to be checked in with
+    /// `check_in()` before the tournament is started with `start()`; the first call to
+    /// `next_round()` starts it implicitly. Fails if two of the players have the same UUID.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use swyss::{Player, Tournament};
+    ///
+    /// let players: Vec<_> = (1..9)
+    ///     .map(|i| Player::new(&format!("Player {}", i)))
+    ///     .collect();
+    /// let mut tourn = Tournament::with_registration(players.clone()).unwrap();
+    /// for p in &players[..4] {
+    ///     tourn.check_in(p.uuid).unwrap();
+    /// }
+    ///
+    /// let no_shows = tourn.start().unwrap();
+    /// assert_eq!(no_shows.len(), 4);
+    /// assert_eq!(tourn.player_arena().len(), 4);
+    /// assert_eq!(tourn.rounds(), 2);
+    /// ```
+    pub fn with_registration(players: Vec<Player>) -> Result<Tournament, PlayerError> {
+        let mut tourn = Tournament::with_players(players)?;
+        tourn.checked_in = Some(Vec::new());
+        Ok(tourn)
+    }
+
+    /// Checks whether the tournament is open for registration
+    pub fn is_registering(&self) -> bool {
+        self.checked_in.is_some()
+    }
+
+    /// Checks in a registered player, so that they are paired once the tournament starts.
+    /// Checking in a player twice has no further effect.
+    pub fn check_in(&mut self, player: uuid::Uuid) -> Result<(), RegistrationError> {
+        self.check_unlocked()?;
+        if !self.is_registering() {
+            return Err(RegistrationError::Closed);
+        }
+        if !self.players.iter().any(|p| p.uuid == player) {
+            return Err(RegistrationError::UnknownPlayer(player));
+        }
+
+        let checked_in = self.checked_in.as_mut().expect("registration is open");
+        if !checked_in.contains(&player) {
+            checked_in.push(player);
+        }
+
+        Ok(())
+    }
+
+    /// Withdraws the check-in of a player, e.g. one who was checked in by mistake
+    pub fn undo_check_in(&mut self, player: uuid::Uuid) -> Result<(), RegistrationError> {
+        self.check_unlocked()?;
+        let checked_in = self.checked_in.as_mut().ok_or(RegistrationError::Closed)?;
+        let i = checked_in
+            .iter()
+            .position(|&uuid| uuid == player)
+            .ok_or(RegistrationError::UnknownPlayer(player))?;
+        checked_in.remove(i);
+
+        Ok(())
+    }
+
+    /// Checks whether a player has checked in. Once the tournament has started, every remaining
+    /// player counts as checked in.
+    pub fn is_checked_in(&self, player: uuid::Uuid) -> bool {
+        match &self.checked_in {
+            Some(checked_in) => checked_in.contains(&player),
+            None => self.players.iter().any(|p| p.uuid == player),
+        }
+    }
+
+    /// Closes registration: removes all players who didn't check in and computes the number of
+    /// rounds for the remaining field, replacing any number of rounds set before. Returns the
+    /// removed players.
+    pub fn start(&mut self) -> Result<Vec<Player>, RegistrationError> {
+        self.check_unlocked()?;
+        let checked_in = self.checked_in.take().ok_or(RegistrationError::Closed)?;
+
+        let no_shows = self
+            .players
+            .iter()
+            .filter(|p| !checked_in.contains(&p.uuid))
+            .cloned()
+            .collect();
+
+        self.players.retain(|p| checked_in.contains(&p.uuid));
+        self.player_numbers.retain(|uuid| checked_in.contains(uuid));
+        self.rounds = self.pairing_system.rounds(self.players.len());
+        self.needs_bye = self.players.len() % 2 == 1;
+
+        Ok(no_shows)
+    }
+}
+
+/// The number of rounds needed to find a single winner among the given number of players
+pub(crate) fn default_rounds(num_players: usize) -> u32 {
+    (num_players as f64).log2().ceil() as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn no_shows_are_not_paired() {
+        let players: Vec<_> = (1..8)
+            .map(|i| Player::new(format!("Player {}", i).as_str()))
+            .collect();
+        let mut tourn = Tournament::with_registration(players.clone()).unwrap();
+        assert_eq!(tourn.rounds, 3);
+
+        let id = |i: usize| players[i].uuid;
+        for i in 0..4 {
+            tourn.check_in(id(i)).unwrap();
+        }
+        tourn.check_in(id(0)).unwrap();
+        tourn.undo_check_in(id(3)).unwrap();
+        let stranger = uuid::Uuid::new_v4();
+        assert_eq!(
+            tourn.check_in(stranger),
+            Err(RegistrationError::UnknownPlayer(stranger))
+        );
+        assert!(tourn.is_checked_in(id(0)));
+        assert!(!tourn.is_checked_in(id(3)));
+
+        // Starting happens with the first round
+        let pairings = tourn.next_round().unwrap().unwrap();
+        assert!(!tourn.is_registering());
+        assert_eq!(tourn.players.len(), 3);
+        assert_eq!(tourn.rounds, 2);
+        assert!(tourn.needs_bye);
+        assert_eq!(pairings.len(), 1);
+
+        assert_eq!(tourn.check_in(id(3)), Err(RegistrationError::Closed));
+        assert_eq!(tourn.start().err(), Some(RegistrationError::Closed));
+    }
+}
@@ -0,0 +1,469 @@
+//! Entering results from more than one terminal at once. `RemoteServer` accepts connections from
+//! other terminals over TCP, and `RemoteClient` is the terminal side. The tournament itself stays
+//! with the server: requests of all terminals are queued and answered one after the other by
+//! whoever owns the tournament, so results are recorded in the order they arrive and a second
+//! result for the same table is rejected like any result that was already reported.
+//!
+//! Only terminals that know the server's token are let in. The server makes up a new token every
+//! time it starts, which the organizer passes on to whoever enters results, so that nobody else on
+//! the venue's network can enter results.
+//!
+//! The protocol is plain text, one request per line:
+//!
+//! ```text
+//! HELLO k7mq2xwe    must come first, with the server's token; answered by "OK", or by "ERR"
+//!                   followed by the reason, after which the server closes the connection
+//! TABLES            the tables of the current round, one per line, ended by an empty line:
+//!                   table, home, away, and result ("-" if there is none yet), separated by tabs
+//! REPORT 3 2-1      records a result as entered at the score prompt, e.g. "2-1", "id", or "c 2";
+//!                   answered by "OK" or "ERR" followed by the reason
+//! ```
+
+use crate::entry::{ResultInput, ScoreError};
+use crate::{PlayerSide, Tournament};
+use rand::seq::SliceRandom;
+use rand::thread_rng;
+use std::io;
+use std::io::{BufRead, BufReader, Write};
+use std::net::{IpAddr, Ipv4Addr, SocketAddr, TcpListener, TcpStream};
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+
+/// Port the server listens on and clients connect to unless another one is given
+pub const DEFAULT_PORT: u16 = 7878;
+
+/// Address the server listens on unless another one is given. Only terminals on the same machine
+/// can connect to it; other machines need the server to listen on e.g. `0.0.0.0`.
+pub const DEFAULT_BIND: IpAddr = IpAddr::V4(Ipv4Addr::LOCALHOST);
+
+/// How long a terminal has to send its token after connecting, so that connections that never
+/// say anything don't keep a thread waiting forever
+const HELLO_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Characters tokens are made of, leaving out those that are easily mistaken for one another
+const TOKEN_CHARS: &[u8] = b"abcdefghjkmnpqrstuvwxyz23456789";
+
+/// Number of characters of a token
+const TOKEN_LENGTH: usize = 8;
+
+/// A request of a terminal
+#[derive(Clone, Debug, PartialEq)]
+pub enum Request {
+    Tables,
+    /// A result for the table with the given number, or the pairing with the given UUID
+    Report {
+        table: String,
+        result: String,
+    },
+}
+
+impl Request {
+    /// Parses a line of the protocol
+    pub fn parse(line: &str) -> Option<Request> {
+        let line = line.trim();
+        let (command, rest) = match line.find(' ') {
+            Some(i) => (&line[..i], line[i + 1..].trim_start()),
+            None => (line, ""),
+        };
+
+        match command {
+            "TABLES" if rest.is_empty() => Some(Request::Tables),
+            "REPORT" => {
+                let i = rest.find(' ')?;
+                Some(Request::Report {
+                    table: String::from(&rest[..i]),
+                    result: String::from(rest[i + 1..].trim()),
+                })
+            }
+            _ => None,
+        }
+    }
+}
+
+/// A table of the current round as a terminal sees it
+#[derive(Clone, Debug, PartialEq)]
+pub struct RemoteTable {
+    pub table: u32,
+    pub home: String,
+    pub away: String,
+    /// The result as "home-away-drawn", if it was entered
+    pub result: Option<String>,
+}
+
+/// What came of a request, for the server to show
+#[derive(Clone, Debug, PartialEq)]
+pub enum Handled {
+    Tables,
+    /// The result of the pairing was recorded
+    Reported(uuid::Uuid),
+    /// The request was answered with this error
+    Rejected(String),
+}
+
+struct Queued {
+    line: String,
+    reply: mpsc::Sender<String>,
+}
+
+/// Accepts terminals and queues their requests until the owner of the tournament answers them
+pub struct RemoteServer {
+    requests: mpsc::Receiver<Queued>,
+    addr: SocketAddr,
+    token: String,
+}
+
+impl RemoteServer {
+    /// Starts accepting terminals on the given address and port, or on a free port if it is 0.
+    /// Terminals must send the token returned by `token()` before anything else.
+    pub fn start(bind: IpAddr, port: u16) -> io::Result<RemoteServer> {
+        let listener = TcpListener::bind((bind, port))?;
+        let addr = listener.local_addr()?;
+        let (sender, requests) = mpsc::channel();
+        let mut rng = thread_rng();
+        let token: String = (0..TOKEN_LENGTH)
+            .map(|_| char::from(*TOKEN_CHARS.choose(&mut rng).expect("there are characters")))
+            .collect();
+
+        let expected = token.clone();
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let sender = sender.clone();
+                let expected = expected.clone();
+                // A terminal that goes away only ends its own connection
+                thread::spawn(move || {
+                    let _ = serve_terminal(stream, sender, &expected);
+                });
+            }
+        });
+
+        Ok(RemoteServer {
+            requests,
+            addr,
+            token,
+        })
+    }
+
+    /// Returns the address the server listens on
+    pub fn local_addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// Returns the token terminals need to connect
+    pub fn token(&self) -> &str {
+        &self.token
+    }
+
+    /// Waits for the next request of any terminal and answers it. Fails if the server stopped
+    /// accepting terminals, e.g. because listening failed.
+    pub fn handle_next(&self, tournament: &mut Tournament) -> io::Result<Handled> {
+        let queued = self.requests.recv().map_err(|_| {
+            io::Error::new(
+                io::ErrorKind::BrokenPipe,
+                "The server stopped accepting terminals",
+            )
+        })?;
+        Ok(answer(tournament, queued))
+    }
+
+    /// Answers the requests that are waiting, if any, without waiting for more
+    pub fn handle_pending(&self, tournament: &mut Tournament) -> Vec<Handled> {
+        self.requests
+            .try_iter()
+            .map(|queued| answer(tournament, queued))
+            .collect()
+    }
+}
+
+/// Checks the token of one terminal, then reads its requests and writes the answers back
+fn serve_terminal(stream: TcpStream, sender: mpsc::Sender<Queued>, token: &str) -> io::Result<()> {
+    stream.set_read_timeout(Some(HELLO_TIMEOUT))?;
+    let mut writer = stream.try_clone()?;
+    let mut reader = BufReader::new(stream);
+    let mut hello = String::new();
+    reader.read_line(&mut hello)?;
+    if hello.trim().strip_prefix("HELLO ").map(str::trim) != Some(token) {
+        return writer.write_all(b"ERR Wrong token\n");
+    }
+    // Once let in, a terminal may take as long as it likes between requests
+    reader.get_ref().set_read_timeout(None)?;
+    writer.write_all(b"OK\n")?;
+
+    for line in reader.lines() {
+        let (reply, answer) = mpsc::channel();
+        let queued = Queued { line: line?, reply };
+        if sender.send(queued).is_err() {
+            break;
+        }
+        match answer.recv() {
+            Ok(answer) => writer.write_all(answer.as_bytes())?,
+            Err(_) => break,
+        }
+    }
+
+    Ok(())
+}
+
+fn answer(tournament: &mut Tournament, queued: Queued) -> Handled {
+    let (reply, handled) = match Request::parse(&queued.line) {
+        Some(Request::Tables) => (tables(tournament), Handled::Tables),
+        Some(Request::Report { table, result }) => {
+            match tournament.remote_report(&table, &result) {
+                Ok(uuid) => (String::from("OK\n"), Handled::Reported(uuid)),
+                Err(e) => (format!("ERR {}\n", e), Handled::Rejected(e)),
+            }
+        }
+        None => {
+            let e = format!("Unknown request: {}", queued.line.trim());
+            (format!("ERR {}\n", e), Handled::Rejected(e))
+        }
+    };
+
+    // The terminal may have gone away in the meantime, which only matters to it
+    let _ = queued.reply.send(reply);
+    handled
+}
+
+/// The answer to `TABLES`
+fn tables(tournament: &Tournament) -> String {
+    let mut out = String::new();
+    let name = |uuid| tournament.player(uuid).map(|p| p.name).unwrap_or_default();
+    if !tournament.is_finished() {
+        let round = tournament.round(tournament.current_round);
+        let pairings = round.map(|round| round.pairings).unwrap_or_default();
+        for (i, pair) in pairings.iter().enumerate() {
+            let result = pair.result.map_or(String::from("-"), |result| {
+                format!(
+                    "{}-{}-{}",
+                    result.home_score, result.away_score, result.drawn
+                )
+            });
+            out.push_str(&format!(
+                "{}\t{}\t{}\t{}\n",
+                i + 1,
+                name(pair.home),
+                name(pair.away),
+                result
+            ));
+        }
+    }
+    out.push('\n');
+    out
+}
+
+impl Tournament {
+    /// Records a result sent by a terminal, given the table and the result as entered at the
+    /// score prompt. Returns the pairing, or why the result was rejected in the tournament's
+    /// locale.
+    fn remote_report(&mut self, table: &str, result: &str) -> Result<uuid::Uuid, String> {
+        let locale = self.locale;
+        let pairings = self
+            .round(self.current_round)
+            .map(|round| round.pairings)
+            .unwrap_or_default();
+        let pair = self
+            .resolve_pairing(table)
+            .and_then(|uuid| pairings.iter().find(|pair| pair.uuid == uuid))
+            .ok_or_else(|| locale.format(crate::locale::Message::NoSuchTable, &[&table]))?;
+
+        let name = |uuid| self.player(uuid).map(|p| p.name).unwrap_or_default();
+        let input = ResultInput::parse_with_names(result, &name(pair.home), &name(pair.away))
+            .map_err(|e| e.text(locale))?;
+        let recorded = match input {
+            ResultInput::Concede(side) => self.concede(pair.uuid, side).map(|_| ()),
+            ResultInput::Bye(side) => self.concede(pair.uuid, other_side(side)).map(|_| ()),
+            input => {
+                let (home, away, drawn) = input
+                    .scores(self.match_format)
+                    .ok_or_else(|| ScoreError::Unreadable(String::from(result)).text(locale))?;
+                crate::entry::validate(self.match_format, home, away, drawn)
+                    .map_err(|e| e.text(locale))?;
+                self.end_match(pair.uuid, home, away, drawn)
+            }
+        };
+
+        recorded
+            .map(|_| pair.uuid)
+            .map_err(|e| String::from(locale.text(e.message())))
+    }
+}
+
+fn other_side(side: PlayerSide) -> PlayerSide {
+    match side {
+        PlayerSide::Home => PlayerSide::Away,
+        PlayerSide::Away => PlayerSide::Home,
+    }
+}
+
+/// A terminal connected to a `RemoteServer`
+pub struct RemoteClient {
+    reader: BufReader<TcpStream>,
+    writer: TcpStream,
+}
+
+impl RemoteClient {
+    /// Connects to a server, given as "host" or "host:port", with the token the server printed
+    /// when it started. Fails with `io::ErrorKind::PermissionDenied` if the server rejects the
+    /// token.
+    pub fn connect(host: &str, token: &str) -> io::Result<RemoteClient> {
+        let stream = if host.contains(':') {
+            TcpStream::connect(host)?
+        } else {
+            TcpStream::connect((host, DEFAULT_PORT))?
+        };
+        let writer = stream.try_clone()?;
+        let mut client = RemoteClient {
+            reader: BufReader::new(stream),
+            writer,
+        };
+
+        writeln!(client.writer, "HELLO {}", token.trim())?;
+        let line = client.read_line()?;
+        if line == "OK" {
+            Ok(client)
+        } else if let Some(reason) = line.strip_prefix("ERR ") {
+            Err(io::Error::new(io::ErrorKind::PermissionDenied, reason))
+        } else {
+            Err(bad_answer(&line))
+        }
+    }
+
+    /// Fetches the tables of the current round
+    pub fn tables(&mut self) -> io::Result<Vec<RemoteTable>> {
+        self.writer.write_all(b"TABLES\n")?;
+        let mut tables = Vec::new();
+        loop {
+            let line = self.read_line()?;
+            if line.is_empty() {
+                return Ok(tables);
+            }
+
+            let fields: Vec<&str> = line.split('\t').collect();
+            let table = match fields.as_slice() {
+                [table, home, away, result] => RemoteTable {
+                    table: table.parse().map_err(|_| bad_answer(&line))?,
+                    home: String::from(*home),
+                    away: String::from(*away),
+                    result: Some(String::from(*result)).filter(|result| result != "-"),
+                },
+                _ => return Err(bad_answer(&line)),
+            };
+            tables.push(table);
+        }
+    }
+
+    /// Sends a result for a table. The outer error is a broken connection; the inner one is the
+    /// reason the server rejected the result.
+    pub fn report(&mut self, table: &str, result: &str) -> io::Result<Result<(), String>> {
+        writeln!(self.writer, "REPORT {} {}", table.trim(), result.trim())?;
+        let line = self.read_line()?;
+        if line == "OK" {
+            Ok(Ok(()))
+        } else if let Some(reason) = line.strip_prefix("ERR ") {
+            Ok(Err(String::from(reason)))
+        } else {
+            Err(bad_answer(&line))
+        }
+    }
+
+    /// Reads a line of the answer without its line break, failing if the server went away
+    fn read_line(&mut self) -> io::Result<String> {
+        let mut line = String::new();
+        if self.reader.read_line(&mut line)? == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "The server closed the connection",
+            ));
+        }
+        Ok(String::from(line.trim_end_matches(&['\r', '\n'][..])))
+    }
+}
+
+fn bad_answer(line: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("Unexpected answer from the server: {}", line),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Player;
+
+    #[test]
+    fn two_terminals() {
+        let players = (1..5)
+            .map(|i| Player::new(format!("Player {}", i).as_str()))
+            .collect();
+        let mut tourn = Tournament::with_players(players).unwrap();
+        tourn.next_round().unwrap().unwrap();
+        let server = RemoteServer::start(DEFAULT_BIND, 0).unwrap();
+        let host = format!("127.0.0.1:{}", server.local_addr().port());
+        let token = String::from(server.token());
+
+        // Terminals without the token are turned away before they can send anything
+        let error = RemoteClient::connect(&host, "wrong").err().unwrap();
+        assert_eq!(error.kind(), io::ErrorKind::PermissionDenied);
+        assert_eq!(error.to_string(), "Wrong token");
+
+        // Terminals run on threads of their own, the tournament stays on this one
+        let first = {
+            let (host, token) = (host.clone(), token.clone());
+            thread::spawn(move || {
+                let mut client = RemoteClient::connect(&host, &token).unwrap();
+                let tables = client.tables().unwrap();
+                let reported = client.report("1", "2-1").unwrap();
+                (tables, reported)
+            })
+        };
+        assert_eq!(server.handle_next(&mut tourn).unwrap(), Handled::Tables);
+        assert!(matches!(
+            server.handle_next(&mut tourn).unwrap(),
+            Handled::Reported(_)
+        ));
+        let (tables, reported) = first.join().unwrap();
+        assert_eq!(tables.len(), 2);
+        assert_eq!(tables[0].table, 1);
+        assert_eq!(tables[0].result, None);
+        assert_eq!(reported, Ok(()));
+
+        let second = thread::spawn(move || {
+            let mut client = RemoteClient::connect(&host, &token).unwrap();
+            let again = client.report("1", "0-2").unwrap();
+            let invalid = client.report("2", "3-0").unwrap();
+            let unknown = client.report("9", "2-0").unwrap();
+            let tables = client.tables().unwrap();
+            (again, invalid, unknown, tables)
+        });
+        for _ in 0..4 {
+            server.handle_next(&mut tourn).unwrap();
+        }
+        let (again, invalid, unknown, tables) = second.join().unwrap();
+        assert_eq!(again, Err(String::from("Result already reported!")));
+        assert_eq!(
+            invalid,
+            Err(String::from("3 games won, but the match ends after 2 wins"))
+        );
+        assert_eq!(
+            unknown,
+            Err(String::from("There is no table 9 in the current round!"))
+        );
+        assert_eq!(tables[0].result.as_deref(), Some("2-1-0"));
+        assert!(server.handle_pending(&mut tourn).is_empty());
+    }
+
+    #[test]
+    fn parse_requests() {
+        assert_eq!(Request::parse("TABLES\r\n"), Some(Request::Tables));
+        assert_eq!(
+            Request::parse("REPORT 3 c Alice"),
+            Some(Request::Report {
+                table: String::from("3"),
+                result: String::from("c Alice")
+            })
+        );
+        assert_eq!(Request::parse("REPORT 3"), None);
+        assert_eq!(Request::parse("PAIR"), None);
+    }
+}
@@ -0,0 +1,455 @@
+//! Result reporting by the players themselves. Each player of a pairing in the current round can
+//! be given a secret token. One player reports the result with their token and the opponent
+//! confirms or disputes it with theirs. A result both players agree on is recorded right away;
+//! results that only one player reported, or that are disputed, wait for the organizer. This way,
+//! result entry doesn't depend on a single operator. A server mode can accept submissions by
+//! calling the token-based methods.
+
+use crate::persist::TournamentData;
+use crate::{check_scores, LockError, PairingResultError, Player, PlayerSide, Tournament};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Result of a match as reported by one of its players
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct ReportedResult {
+    pub home_score: u8,
+    pub away_score: u8,
+    pub drawn: u8,
+}
+
+/// Where a reported result stands. A report starts out as `Reported` and becomes `Confirmed`
+/// when the opponent agrees or the organizer accepts it, or `Disputed` when the opponent
+/// disagrees.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub enum ReportState {
+    /// Reported by the player on `side`, waiting for the opponent
+    Reported {
+        side: PlayerSide,
+        result: ReportedResult,
+    },
+    /// Both players agreed on the result, or the organizer accepted it. The result has been
+    /// recorded.
+    Confirmed(ReportedResult),
+    /// The opponent of the player on `side` disagreed with the reported result, so the organizer
+    /// has to decide
+    Disputed {
+        side: PlayerSide,
+        result: ReportedResult,
+    },
+}
+
+/// Reasons why a report can't be submitted, confirmed or disputed
+#[derive(Debug, PartialEq)]
+pub enum ReportError {
+    /// No player of the current round has this token
+    UnknownToken,
+    /// There is no such pairing in the current round
+    NotFound(uuid::Uuid),
+    /// The pairing has no open report
+    NoReport(uuid::Uuid),
+    /// The result of the pairing has already been confirmed and recorded
+    AlreadyConfirmed(uuid::Uuid),
+    /// Players can't confirm or dispute their own report
+    OwnReport(uuid::Uuid),
+    /// The reported scores are invalid
+    OutOfRange(u8),
+    /// The tournament is complete and takes no more results
+    Finished,
+    /// The pairing is of an earlier round, e.g. reported from a page that wasn't reloaded
+    RoundClosed(uuid::Uuid),
+    /// The tournament was opened read-only and takes no reports
+    ReadOnly,
+}
+
+impl std::error::Error for ReportError {}
+
+impl fmt::Display for ReportError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ReportError::UnknownToken => write!(f, "Unknown token!"),
+            ReportError::NotFound(_) => write!(f, "Pairing not found!"),
+            ReportError::NoReport(_) => write!(f, "No result was reported for this pairing!"),
+            ReportError::AlreadyConfirmed(_) => {
+                write!(f, "The result of this pairing was already confirmed!")
+            }
+            ReportError::OwnReport(_) => {
+                write!(f, "The result has to be confirmed by the opponent!")
+            }
+            ReportError::OutOfRange(_) => write!(f, "Score(s) out of range!"),
+            ReportError::Finished => write!(f, "The tournament is already finished!"),
+            ReportError::RoundClosed(_) => {
+                write!(f, "The round of this pairing is already over!")
+            }
+            ReportError::ReadOnly => write!(f, "The tournament was opened read-only!"),
+        }
+    }
+}
+
+impl From<LockError> for ReportError {
+    fn from(e: LockError) -> ReportError {
+        match e {
+            LockError::Finished => ReportError::Finished,
+            LockError::ReadOnly => ReportError::ReadOnly,
+        }
+    }
+}
+
+impl From<PairingResultError> for ReportError {
+    fn from(e: PairingResultError) -> ReportError {
+        match e {
+            PairingResultError::NotFound(uuid) => ReportError::NotFound(uuid),
+            PairingResultError::OutOfRange(value) => ReportError::OutOfRange(value),
+            PairingResultError::AlreadyReported(uuid) => ReportError::AlreadyConfirmed(uuid),
+            PairingResultError::NotReported(uuid) => ReportError::NoReport(uuid),
+            PairingResultError::Finished => ReportError::Finished,
+            PairingResultError::RoundClosed(uuid) => ReportError::RoundClosed(uuid),
+            PairingResultError::ReadOnly => ReportError::ReadOnly,
+        }
+    }
+}
+
+/// Creates a random token of 32 hex digits. The tournament's own random number generator is
+/// seeded with the published round seed, so tokens are drawn from the thread-local generator
+/// instead to keep them unpredictable.
+fn new_token() -> String {
+    let bytes: [u8; 16] = rand::thread_rng().gen();
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn opposite(side: PlayerSide) -> PlayerSide {
+    match side {
+        PlayerSide::Home => PlayerSide::Away,
+        PlayerSide::Away => PlayerSide::Home,
+    }
+}
+
+impl Tournament {
+    /// Returns the tokens of the home and away player of every pairing of the current round,
+    /// creating tokens for pairings that don't have them yet. Tokens are only valid during their
+    /// round.
+    pub fn report_tokens(&mut self) -> Result<Vec<(uuid::Uuid, String, String)>, ReportError> {
+        self.check_unlocked()?;
+        Ok(self
+            .pairings
+            .values_mut()
+            .map(|pair| {
+                let (home, away) = pair
+                    .tokens
+                    .get_or_insert_with(|| (new_token(), new_token()));
+                (pair.uuid, home.clone(), away.clone())
+            })
+            .collect())
+    }
+
+    /// Finds the pairing and side of the player a token belongs to
+    fn find_token(&self, token: &str) -> Result<(uuid::Uuid, PlayerSide), ReportError> {
+        self.pairings
+            .values()
+            .find_map(|pair| match &pair.tokens {
+                Some((home, _)) if home == token => Some((pair.uuid, PlayerSide::Home)),
+                Some((_, away)) if away == token => Some((pair.uuid, PlayerSide::Away)),
+                _ => None,
+            })
+            .ok_or(ReportError::UnknownToken)
+    }
+
+    /// Moves a pairing to a new report state, recording the result if it was confirmed
+    fn set_report(&mut self, uuid: uuid::Uuid, state: ReportState) -> Result<(), ReportError> {
+        self.check_unlocked()?;
+        if let ReportState::Confirmed(result) = state {
+            self.end_match(uuid, result.home_score, result.away_score, result.drawn)?;
+        }
+
+        if let Some(pair) = self.pairings.get_mut(&uuid) {
+            pair.report = Some(state);
+        }
+
+        Ok(())
+    }
+
+    /// Returns the report state of a pairing, or `None` if nothing was reported yet
+    pub fn report_state(&self, pairing: uuid::Uuid) -> Option<ReportState> {
+        self.pairings.get(&pairing).and_then(|pair| pair.report)
+    }
+
+    /// Submits a result on behalf of the player the token belongs to. If the opponent already
+    /// reported the same result, it counts as confirmed and is recorded; if they reported a
+    /// different one, the result is disputed. Otherwise, the report replaces any earlier report
+    /// and waits for the opponent. Returns the UUID of the pairing.
+    pub fn submit_report(
+        &mut self,
+        token: &str,
+        home_score: u8,
+        away_score: u8,
+        drawn: u8,
+    ) -> Result<uuid::Uuid, ReportError> {
+        let (uuid, side) = self.find_token(token)?;
+
+        check_scores(self.match_format, home_score, away_score, drawn)
+            .map_err(|e| ReportError::OutOfRange(e.outside_value))?;
+
+        let result = ReportedResult {
+            home_score,
+            away_score,
+            drawn,
+        };
+
+        let state = match self.report_state(uuid) {
+            Some(ReportState::Confirmed(_)) => return Err(ReportError::AlreadyConfirmed(uuid)),
+            Some(ReportState::Reported {
+                side: reporter,
+                result: reported,
+            }) if reporter != side => {
+                if reported == result {
+                    ReportState::Confirmed(result)
+                } else {
+                    ReportState::Disputed {
+                        side: reporter,
+                        result: reported,
+                    }
+                }
+            }
+            _ => ReportState::Reported { side, result },
+        };
+
+        self.set_report(uuid, state)?;
+        Ok(uuid)
+    }
+
+    /// Confirms the result the opponent of the token's player reported, which records it
+    pub fn confirm_result(&mut self, token: &str) -> Result<uuid::Uuid, ReportError> {
+        let (uuid, side) = self.find_token(token)?;
+
+        match self.report_state(uuid) {
+            Some(ReportState::Reported {
+                side: reporter,
+                result,
+            }) if reporter == opposite(side) => {
+                self.set_report(uuid, ReportState::Confirmed(result))?;
+                Ok(uuid)
+            }
+            Some(ReportState::Reported { .. }) => Err(ReportError::OwnReport(uuid)),
+            Some(ReportState::Confirmed(_)) => Err(ReportError::AlreadyConfirmed(uuid)),
+            _ => Err(ReportError::NoReport(uuid)),
+        }
+    }
+
+    /// Disputes the result the opponent of the token's player reported, leaving the decision to
+    /// the organizer
+    pub fn dispute_result(&mut self, token: &str) -> Result<uuid::Uuid, ReportError> {
+        let (uuid, side) = self.find_token(token)?;
+
+        match self.report_state(uuid) {
+            Some(ReportState::Reported {
+                side: reporter,
+                result,
+            }) if reporter == opposite(side) => {
+                self.set_report(
+                    uuid,
+                    ReportState::Disputed {
+                        side: reporter,
+                        result,
+                    },
+                )?;
+                Ok(uuid)
+            }
+            Some(ReportState::Reported { .. }) => Err(ReportError::OwnReport(uuid)),
+            Some(ReportState::Confirmed(_)) => Err(ReportError::AlreadyConfirmed(uuid)),
+            _ => Err(ReportError::NoReport(uuid)),
+        }
+    }
+
+    /// Returns all reports of the current round that wait for the opponent or the organizer
+    pub fn pending_reports(&self) -> Vec<(uuid::Uuid, ReportState)> {
+        self.pairings
+            .values()
+            .filter_map(|pair| match pair.report {
+                Some(ReportState::Confirmed(_)) | None => None,
+                Some(state) => Some((pair.uuid, state)),
+            })
+            .collect()
+    }
+
+    /// Accepts the reported result of a pairing as the organizer, whether or not the opponent
+    /// agreed, and records it like `end_match()`
+    pub fn confirm_report(&mut self, pairing: uuid::Uuid) -> Result<(), ReportError> {
+        match self.report_state(pairing) {
+            Some(ReportState::Reported { result, .. })
+            | Some(ReportState::Disputed { result, .. }) => {
+                self.set_report(pairing, ReportState::Confirmed(result))
+            }
+            Some(ReportState::Confirmed(_)) => Err(ReportError::AlreadyConfirmed(pairing)),
+            None if self.pairings.contains_key(&pairing) => Err(ReportError::NoReport(pairing)),
+            None => Err(ReportError::NotFound(pairing)),
+        }
+    }
+
+    /// Discards the reported result of a pairing as the organizer, e.g. because it is disputed
+    /// and the players have to report again
+    pub fn reject_report(&mut self, pairing: uuid::Uuid) -> Result<(), ReportError> {
+        self.check_unlocked()?;
+        let pair = self
+            .pairings
+            .get_mut(&pairing)
+            .ok_or(ReportError::NotFound(pairing))?;
+
+        match pair.report {
+            Some(ReportState::Confirmed(_)) => Err(ReportError::AlreadyConfirmed(pairing)),
+            Some(_) => {
+                pair.report = None;
+                Ok(())
+            }
+            None => Err(ReportError::NoReport(pairing)),
+        }
+    }
+
+    /// Ranks all players as if every result that was reported but not yet confirmed or disputed
+    /// counted. The tournament itself is not changed; the returned players are copies that
+    /// include the provisional results.
+    pub fn provisional_ranking(&self) -> Vec<Player> {
+        let mut provisional = TournamentData::from(self)
+            .into_tournament()
+            .expect("a tournament's own data always restores");
+
+        for (uuid, state) in provisional.pending_reports() {
+            if let ReportState::Reported { result, .. } = state {
+                provisional
+                    .end_match(uuid, result.home_score, result.away_score, result.drawn)
+                    .expect("reported results were checked on submission");
+            }
+        }
+
+        provisional.ranking().into_iter().cloned().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tournament() -> Tournament {
+        let players = (1..5)
+            .map(|i| Player::new(format!("Player {}", i).as_str()))
+            .collect();
+        let mut tourn = Tournament::with_players(players).unwrap();
+        assert!(tourn.next_round().unwrap().is_some());
+        tourn
+    }
+
+    fn played(tourn: &Tournament) -> usize {
+        tourn
+            .players
+            .iter()
+            .filter(|p| p.matches_played == 1)
+            .count()
+    }
+
+    #[test]
+    fn both_players_agree() {
+        let mut tourn = tournament();
+        let tokens = tourn.report_tokens().unwrap();
+        assert_eq!(tokens.len(), 2);
+        assert_ne!(tokens[0].1, tokens[0].2);
+        assert_eq!(tourn.report_tokens().unwrap(), tokens);
+
+        let (uuid, home, away) = &tokens[0];
+        assert_eq!(
+            tourn.submit_report("guess", 2, 0, 0),
+            Err(ReportError::UnknownToken)
+        );
+        assert_eq!(
+            tourn.submit_report(home, 3, 0, 0),
+            Err(ReportError::OutOfRange(3))
+        );
+        assert_eq!(tourn.submit_report(home, 2, 1, 0), Ok(*uuid));
+
+        // Nothing counts until the opponent confirms
+        assert_eq!(played(&tourn), 0);
+        assert_eq!(
+            tourn.confirm_result(home),
+            Err(ReportError::OwnReport(*uuid))
+        );
+        assert_eq!(tourn.pending_reports().len(), 1);
+
+        assert_eq!(tourn.confirm_result(away), Ok(*uuid));
+        assert_eq!(played(&tourn), 2);
+        assert!(tourn.pending_reports().is_empty());
+        assert_eq!(
+            tourn.submit_report(away, 2, 0, 0),
+            Err(ReportError::AlreadyConfirmed(*uuid))
+        );
+
+        // Reporting the same result from both sides also confirms it
+        let (uuid, home, away) = &tokens[1];
+        assert_eq!(tourn.submit_report(away, 0, 2, 0), Ok(*uuid));
+        assert_eq!(tourn.submit_report(home, 0, 2, 0), Ok(*uuid));
+        assert_eq!(
+            tourn.report_state(*uuid),
+            Some(ReportState::Confirmed(ReportedResult {
+                home_score: 0,
+                away_score: 2,
+                drawn: 0
+            }))
+        );
+        assert_eq!(played(&tourn), 4);
+    }
+
+    #[test]
+    fn dispute_and_resolve() {
+        let mut tourn = tournament();
+        let (uuid, home, away) = tourn.report_tokens().unwrap().remove(0);
+
+        assert_eq!(tourn.submit_report(&home, 2, 0, 0), Ok(uuid));
+        assert_eq!(tourn.dispute_result(&away), Ok(uuid));
+        assert!(matches!(
+            tourn.report_state(uuid),
+            Some(ReportState::Disputed {
+                side: PlayerSide::Home,
+                ..
+            })
+        ));
+
+        // The organizer can throw the report out, or accept it despite the dispute
+        assert_eq!(tourn.reject_report(uuid), Ok(()));
+        assert_eq!(tourn.report_state(uuid), None);
+        assert_eq!(tourn.reject_report(uuid), Err(ReportError::NoReport(uuid)));
+
+        assert_eq!(tourn.submit_report(&home, 2, 1, 0), Ok(uuid));
+        assert_eq!(tourn.submit_report(&away, 1, 2, 0), Ok(uuid));
+        assert_eq!(tourn.confirm_report(uuid), Ok(()));
+        assert_eq!(played(&tourn), 2);
+        assert_eq!(
+            tourn.confirm_report(uuid),
+            Err(ReportError::AlreadyConfirmed(uuid))
+        );
+
+        // Tokens don't carry over into the next round
+        for uuid in tourn.round_status().outstanding {
+            tourn.end_match(uuid, 2, 0, 0).unwrap();
+        }
+        assert!(tourn.next_round().unwrap().is_some());
+        assert_eq!(
+            tourn.submit_report(&home, 2, 0, 0),
+            Err(ReportError::UnknownToken)
+        );
+    }
+
+    #[test]
+    fn provisional() {
+        let mut tourn = tournament();
+        let (uuid, home, _) = tourn.report_tokens().unwrap().remove(0);
+        assert_eq!(tourn.submit_report(&home, 2, 0, 0), Ok(uuid));
+
+        let winner = tourn.pairings[&uuid].home;
+        let ranking = tourn.provisional_ranking();
+        assert_eq!(ranking[0].uuid, winner);
+        assert_eq!(ranking[0].match_points, 3);
+
+        // The tournament itself only has confirmed results
+        assert_eq!(played(&tourn), 0);
+    }
+}
@@ -0,0 +1,193 @@
+//! Player lists with ratings, clubs, and aliases, read from CSV files with one player per line:
+//!
+//! ```text
+//! name,rating,club,alias
+//! Alice,1850,Rooks
+//! "Doe, John",1720,,JD
+//! Bob
+//! ```
+//!
+//! The header line is optional, and so are the rating, the club, and the alias. Names that contain
+//! commas or quotes are quoted, with quotes inside doubled. Empty lines and lines starting with `#`
+//! are skipped. Players are returned in seeding order: by rating, highest first, with unrated
+//! players last in the order they are listed.
+
+use crate::Player;
+use std::collections::HashSet;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// Reading a player list fails if the file can't be read or a line isn't a valid player
+#[derive(Debug)]
+pub enum RosterError {
+    Io(io::Error),
+    /// The line, counted from 1, and what is wrong with it
+    Line(usize, String),
+}
+
+impl std::error::Error for RosterError {}
+
+impl fmt::Display for RosterError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RosterError::Io(e) => write!(f, "Could not read player list: {}", e),
+            RosterError::Line(line, message) => write!(f, "Line {}: {}", line, message),
+        }
+    }
+}
+
+impl From<io::Error> for RosterError {
+    fn from(e: io::Error) -> RosterError {
+        RosterError::Io(e)
+    }
+}
+
+/// Reads a player list from a CSV file
+pub fn load<P: AsRef<Path>>(path: P) -> Result<Vec<Player>, RosterError> {
+    parse(&fs::read_to_string(path)?)
+}
+
+/// Parses a player list in CSV form
+///
+/// # Example
+///
+/// ```
+/// use swyss::roster;
+///
+/// let players = roster::parse("name,rating,club\nBob\nAlice,1850,Rooks\n").unwrap();
+/// assert_eq!(players[0].name, "Alice");
+/// assert_eq!(players[0].rating, Some(1850.0));
+/// assert_eq!(players[0].club.as_deref(), Some("Rooks"));
+/// assert_eq!(players[1].rating, None);
+///
+/// let e = roster::parse("Alice,strong\n").err().unwrap();
+/// assert_eq!(e.to_string(), "Line 1: The rating \"strong\" is not a number");
+/// ```
+pub fn parse(text: &str) -> Result<Vec<Player>, RosterError> {
+    let mut players = Vec::new();
+    let mut names = HashSet::new();
+    let mut first = true;
+
+    for (i, line) in text.lines().enumerate() {
+        let error = |message: String| RosterError::Line(i + 1, message);
+        if line.trim().is_empty() || line.trim_start().starts_with('#') {
+            continue;
+        }
+
+        let fields = fields(line).map_err(error)?;
+        let header = first && fields[0].eq_ignore_ascii_case("name");
+        first = false;
+        if header {
+            continue;
+        }
+
+        if fields.len() > 4 {
+            return Err(error(format!(
+                "Expected name,rating,club,alias but found {} fields",
+                fields.len()
+            )));
+        }
+        let name = &fields[0];
+        if name.is_empty() {
+            return Err(error(String::from("The name is missing")));
+        }
+        if !names.insert(name.clone()) {
+            return Err(error(format!("{} is listed twice", name)));
+        }
+
+        let mut player = Player::new(name);
+        player.rating = match fields.get(1).map(String::as_str) {
+            None | Some("") => None,
+            Some(rating) => match rating.parse::<f64>() {
+                Ok(r) if r.is_finite() => Some(r),
+                _ => return Err(error(format!("The rating \"{}\" is not a number", rating))),
+            },
+        };
+        player.club = fields.get(2).filter(|club| !club.is_empty()).cloned();
+        player.alias = fields.get(3).filter(|alias| !alias.is_empty()).cloned();
+        players.push(player);
+    }
+
+    // The sort is stable, so unrated players keep the order they are listed in
+    players.sort_by(|a, b| match (a.rating, b.rating) {
+        (Some(a), Some(b)) => b.total_cmp(&a),
+        (a, b) => b.is_some().cmp(&a.is_some()),
+    });
+
+    Ok(players)
+}
+
+/// Splits a line into its fields, trimmed and unquoted
+pub(crate) fn fields(line: &str) -> Result<Vec<String>, String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                chars.next();
+                field.push('"');
+            }
+            '"' if quoted => quoted = false,
+            '"' if field.trim().is_empty() => {
+                field.clear();
+                quoted = true;
+            }
+            ',' if !quoted => fields.push(std::mem::take(&mut field)),
+            c => field.push(c),
+        }
+    }
+
+    if quoted {
+        return Err(String::from("A quote is never closed"));
+    }
+    fields.push(field);
+
+    Ok(fields.iter().map(|f| String::from(f.trim())).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn seeding_order_and_errors() {
+        let players = parse(
+            "# Club night\n\
+             Carol,,Knights\n\
+             \"Doe, John\",1720,,JD\n\
+             \n\
+             Alice, 1850 , Rooks\n\
+             \"Bob \"\"The Rook\"\"\"\n",
+        )
+        .unwrap();
+        let names: Vec<&str> = players.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, ["Alice", "Doe, John", "Carol", "Bob \"The Rook\""]);
+        assert_eq!(players[2].club.as_deref(), Some("Knights"));
+        assert_eq!(players[1].club, None);
+        assert_eq!(players[1].display_name(), "JD");
+
+        let line = |text: &str| match parse(text) {
+            Err(RosterError::Line(line, message)) => (line, message),
+            other => panic!("{:?}", other.map(|p| p.len())),
+        };
+        assert_eq!(line("Alice\n\nAlice\n").0, 3);
+        assert_eq!(
+            line("Alice,1,Rooks,Al,extra\n"),
+            (
+                1,
+                String::from("Expected name,rating,club,alias but found 5 fields")
+            )
+        );
+        assert_eq!(
+            line("name\n,1500\n"),
+            (2, String::from("The name is missing"))
+        );
+        assert_eq!(line("\"Alice,1500\n").1, "A quote is never closed");
+        assert_eq!(line("Alice,NaN\n").1, "The rating \"NaN\" is not a number");
+    }
+}
@@ -0,0 +1,126 @@
+//! Round robin tournaments, where every player meets every other player exactly once. The schedule
+//! is fixed in advance using the circle method, so match points don't affect the pairings.
+
+use crate::oracle::PlayerPair;
+use crate::{AvailabilityConflict, PairingSystem, Player, PlayerError, Tournament};
+
+impl Tournament {
+    /// Creates a new round robin tournament with enough rounds for every player to meet every
+    /// other player. Fails if two of the players have the same UUID.
+    pub fn round_robin(players: Vec<Player>) -> Result<Tournament, PlayerError> {
+        let mut tourn = Tournament::with_players(players)?;
+        tourn.pairing_system = PairingSystem::RoundRobin;
+        tourn.rounds = tourn.pairing_system.rounds(tourn.players.len());
+        Ok(tourn)
+    }
+
+    /// Pairs the current round according to the round robin schedule of all players in the
+    /// tournament. With an uneven number of players, the player scheduled against nobody gets a
+    /// bye and is removed from `active`. Players scheduled against someone who sits the round out
+    /// can't be paired and are recorded as availability conflicts.
+    ///
+    /// The schedule is derived from the players still in the tournament, so a drop changes the
+    /// pairings of later rounds and may lead to rematches.
+    pub(crate) fn pair_round_robin<'a>(
+        &mut self,
+        active: &mut Vec<&'a Player>,
+        resting: &[&'a Player],
+    ) -> (Option<&'a Player>, Vec<PlayerPair<'a>>) {
+        let mut slots: Vec<Option<&Player>> =
+            active.iter().chain(resting).copied().map(Some).collect();
+        slots.sort_by_key(|p| p.as_ref().map(|p| p.uuid));
+        if slots.len() % 2 == 1 {
+            slots.push(None);
+        }
+
+        let mut pairs = Vec::with_capacity(slots.len() / 2);
+        let mut bye = None;
+
+        if slots.len() < 2 {
+            return (bye, pairs);
+        }
+
+        // Circle method: the first slot stays put while all others rotate by one each round
+        let turn = (self.current_round as usize - 1) % (slots.len() - 1);
+        slots[1..].rotate_right(turn);
+
+        let is_active = |p: &Player| active.iter().any(|a| a.uuid == p.uuid);
+        let last = slots.len() - 1;
+
+        for i in 0..slots.len() / 2 {
+            // Alternate the fixed player's side so they aren't always the home player
+            let (home, away) = if i == 0 && turn % 2 == 1 {
+                (&slots[last], &slots[0])
+            } else {
+                (&slots[i], &slots[last - i])
+            };
+
+            match (home, away) {
+                (Some(home), Some(away)) if is_active(home) && is_active(away) => {
+                    pairs.push((*home, *away));
+                }
+                (Some(p), None) | (None, Some(p)) if is_active(p) => {
+                    bye = Some(*p);
+                }
+                (Some(home), Some(away)) => {
+                    for p in &[home, away] {
+                        if is_active(p) {
+                            let uuid = p.uuid;
+                            self.availability_conflicts
+                                .push(AvailabilityConflict::Unpaired(uuid, self.current_round));
+                        }
+                    }
+                }
+                _ => (),
+            }
+        }
+
+        if let Some(bye) = bye {
+            active.retain(|p| p.uuid != bye.uuid);
+        }
+
+        (bye, pairs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn play(n: u32) -> Tournament {
+        let players: Vec<_> = (1..=n)
+            .map(|i| Player::new(format!("Player {}", i).as_str()))
+            .collect();
+        let mut tourn = Tournament::round_robin(players).unwrap();
+
+        while let Some(pairings) = tourn.next_round().unwrap() {
+            for (uuid, _, _) in pairings {
+                tourn.end_match(uuid, 2, 1, 0).unwrap();
+            }
+        }
+
+        tourn
+    }
+
+    #[test]
+    fn everyone_meets_everyone() {
+        for &n in &[2, 5, 6] {
+            let tourn = play(n);
+            let games = if n % 2 == 1 { n } else { n - 1 };
+            assert_eq!(tourn.rounds, games);
+
+            for p in &tourn.players {
+                assert_eq!(p.opponents.len(), n as usize - 1);
+                assert_eq!(p.has_bye, n % 2 == 1);
+
+                for other in &tourn.players {
+                    if other.uuid != p.uuid {
+                        assert!(p.opponents.contains(&other.uuid));
+                    }
+                }
+            }
+
+            assert!(tourn.availability_conflicts.is_empty());
+        }
+    }
+}
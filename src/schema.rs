@@ -0,0 +1,108 @@
+//! JSON Schema for the serialized formats of the crate, so that third-party tools can validate
+//! swyss data and generate clients for it. Only available with the `schemars` feature.
+
+use crate::audit::AuditEntry;
+use crate::journal::JournalEntry;
+use crate::overlay::Overlay;
+use crate::persist::{StandingsRow, TournamentData};
+use crate::ratings::Ratings;
+use crate::season::Season;
+use crate::sections::EventReport;
+use crate::webhook::WebhookPayload;
+use schemars::schema::RootSchema;
+use schemars::schema_for;
+
+/// Schema of tournament files as written by `Tournament::save()`
+pub fn tournament() -> RootSchema {
+    schema_for!(TournamentData)
+}
+
+/// Schema of the standings as exported by `Tournament::standings_to_json()`
+pub fn standings() -> RootSchema {
+    schema_for!(Vec<StandingsRow>)
+}
+
+/// Schema of season files as written by `Season::save()`
+pub fn season() -> RootSchema {
+    schema_for!(Season)
+}
+
+/// Schema of the report of a sectioned event as exported by `SectionEvent::report_json()`
+pub fn event_report() -> RootSchema {
+    schema_for!(EventReport)
+}
+
+/// Schema of rating files as written by `Ratings::save()`
+pub fn ratings() -> RootSchema {
+    schema_for!(Ratings)
+}
+
+/// Schema of the audit log kept in `Tournament::audit_log`
+pub fn audit_log() -> RootSchema {
+    schema_for!(Vec<AuditEntry>)
+}
+
+/// Schema of a single line of a result journal as written by `Journal::append()`
+pub fn journal_entry() -> RootSchema {
+    schema_for!(JournalEntry)
+}
+
+/// Schema of the bodies of webhook requests
+pub fn webhook_payload() -> RootSchema {
+    schema_for!(WebhookPayload)
+}
+
+/// Schema of the overlay file as written by `OverlayFile`
+pub fn overlay() -> RootSchema {
+    schema_for!(Overlay)
+}
+
+/// Renders a schema as pretty-printed JSON
+pub fn to_json(schema: &RootSchema) -> String {
+    serde_json::to_string_pretty(schema).expect("schemas are always serializable")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tournament_schema() {
+        let json: serde_json::Value = serde_json::from_str(&to_json(&tournament())).unwrap();
+        assert_eq!(json["title"], "TournamentData");
+
+        let properties = &json["properties"];
+        assert!(properties["players"].is_object());
+        assert!(properties["pairings"].is_object());
+        assert!(json["definitions"]["PlayerData"].is_object());
+    }
+
+    #[test]
+    fn standings_schema() {
+        let json: serde_json::Value = serde_json::from_str(&to_json(&standings())).unwrap();
+        assert_eq!(json["type"], "array");
+        assert!(json["definitions"]["StandingsRow"]["properties"]["match_points"].is_object());
+    }
+
+    #[test]
+    fn season_schema() {
+        let json: serde_json::Value = serde_json::from_str(&to_json(&season())).unwrap();
+        assert_eq!(json["title"], "Season");
+        assert!(json["definitions"]["SeasonEntry"].is_object());
+    }
+
+    #[test]
+    fn audit_log_schema() {
+        let json: serde_json::Value = serde_json::from_str(&to_json(&audit_log())).unwrap();
+        assert_eq!(json["type"], "array");
+        assert!(json["definitions"]["AuditEvent"].is_object());
+    }
+
+    #[test]
+    fn overlay_schema() {
+        let json: serde_json::Value = serde_json::from_str(&to_json(&overlay())).unwrap();
+        assert_eq!(json["title"], "Overlay");
+        assert!(json["properties"]["version"].is_object());
+        assert!(json["definitions"]["FeaturedMatch"].is_object());
+    }
+}
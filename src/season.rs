@@ -0,0 +1,200 @@
+//! League seasons that accumulate points over several tournaments, e.g. weekly store events.
+//! Players are identified across tournaments by their name, since every tournament assigns its
+//! own UUIDs.
+
+use crate::persist::LoadError;
+use crate::Tournament;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// A player's accumulated season results
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct SeasonEntry {
+    pub name: String,
+    pub points: u32,
+    pub events: u32,
+    pub best_placement: u32,
+    pub match_points: u32,
+}
+
+/// Accumulates season points from the final standings of several tournaments
+///
+/// # Example
+///
+/// ```
+/// use swyss::season::Season;
+/// let season = Season::new(vec![10, 6, 4], 1);
+/// assert_eq!(season.points_for(1), 10);
+/// assert_eq!(season.points_for(3), 4);
+/// assert_eq!(season.points_for(8), 1);
+/// ```
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct Season {
+    /// Season points awarded for each placement, starting with first place
+    pub placement_points: Vec<u32>,
+    /// Season points awarded to every player placing beyond the end of `placement_points`
+    pub participation_points: u32,
+    /// Number of tournaments added to the season so far
+    pub events: u32,
+    pub entries: Vec<SeasonEntry>,
+}
+
+impl Season {
+    /// Returns a new season without any results
+    ///
+    /// # Arguments
+    ///
+    /// * `placement_points` - Season points for each placement, starting with first place
+    /// * `participation_points` - Season points for all other players who took part
+    pub fn new(placement_points: Vec<u32>, participation_points: u32) -> Season {
+        Season {
+            placement_points,
+            participation_points,
+            events: 0,
+            entries: Vec::new(),
+        }
+    }
+
+    /// Season points awarded for the given placement, starting at 1
+    pub fn points_for(&self, placement: u32) -> u32 {
+        placement
+            .checked_sub(1)
+            .and_then(|i| self.placement_points.get(i as usize))
+            .copied()
+            .unwrap_or(self.participation_points)
+    }
+
+    /// Adds the final standings of a tournament to the season. This should only be called once
+    /// the tournament is finished, and only once per tournament.
+    pub fn add_tournament(&mut self, tournament: &mut Tournament) {
+        for (i, p) in tournament.ranking().iter().enumerate() {
+            let placement = i as u32 + 1;
+            let points = self.points_for(placement);
+
+            match self.entries.iter_mut().find(|e| e.name == p.name) {
+                Some(entry) => {
+                    entry.points += points;
+                    entry.events += 1;
+                    entry.best_placement = entry.best_placement.min(placement);
+                    entry.match_points += p.match_points;
+                }
+                None => self.entries.push(SeasonEntry {
+                    name: p.name.clone(),
+                    points,
+                    events: 1,
+                    best_placement: placement,
+                    match_points: p.match_points,
+                }),
+            }
+        }
+
+        self.events += 1;
+    }
+
+    /// Season standings, ordered by season points. Ties are broken by best placement and then by
+    /// total match points.
+    pub fn standings(&self) -> Vec<SeasonEntry> {
+        let mut standings = self.entries.clone();
+        standings.sort_by(|a, b| {
+            b.points
+                .cmp(&a.points)
+                .then(a.best_placement.cmp(&b.best_placement))
+                .then(b.match_points.cmp(&a.match_points))
+                .then(a.name.cmp(&b.name))
+        });
+        standings
+    }
+
+    /// Serializes the season to JSON
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("season data is always serializable")
+    }
+
+    /// Restores a season from JSON created by `to_json()`
+    pub fn from_json(json: &str) -> Result<Season, LoadError> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    /// Saves the season to a file
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        fs::write(path, self.to_json())
+    }
+
+    /// Loads a season from a file created by `save()`
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Season, LoadError> {
+        let json = fs::read_to_string(path)?;
+        Season::from_json(&json)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Player;
+
+    /// Plays a two-player tournament in which `winner` beats `loser` 2-0
+    fn play(winner: &str, loser: &str) -> Tournament {
+        let players = vec![Player::new(winner), Player::new(loser)];
+
+        let mut tourn = Tournament::with_players(players).unwrap();
+        while let Some(pairings) = tourn.next_round().unwrap() {
+            for (uuid, home, _) in &pairings {
+                if home == winner {
+                    assert!(tourn.end_match(*uuid, 2, 0, 0).is_ok());
+                } else {
+                    assert!(tourn.end_match(*uuid, 0, 2, 0).is_ok());
+                }
+            }
+        }
+
+        tourn
+    }
+
+    #[test]
+    fn league() {
+        let mut season = Season::new(vec![5, 3], 1);
+
+        season.add_tournament(&mut play("Alice", "Bob"));
+        season.add_tournament(&mut play("Bob", "Carol"));
+        season.add_tournament(&mut play("Alice", "Carol"));
+
+        assert_eq!(season.events, 3);
+
+        let standings = season.standings();
+        assert_eq!(standings.len(), 3);
+
+        assert_eq!(standings[0].name, "Alice");
+        assert_eq!(standings[0].points, 10);
+        assert_eq!(standings[0].events, 2);
+        assert_eq!(standings[0].best_placement, 1);
+        assert_eq!(standings[0].match_points, 6);
+
+        assert_eq!(standings[1].name, "Bob");
+        assert_eq!(standings[1].points, 8);
+
+        assert_eq!(standings[2].name, "Carol");
+        assert_eq!(standings[2].points, 6);
+        assert_eq!(standings[2].best_placement, 2);
+    }
+
+    #[test]
+    fn participation_points() {
+        let season = Season::new(vec![3], 1);
+        assert_eq!(season.points_for(0), 1);
+        assert_eq!(season.points_for(1), 3);
+        assert_eq!(season.points_for(2), 1);
+    }
+
+    #[test]
+    fn roundtrip() {
+        let mut season = Season::new(vec![5, 3], 1);
+        season.add_tournament(&mut play("Alice", "Bob"));
+
+        let loaded = Season::from_json(&season.to_json()).unwrap();
+        assert_eq!(loaded, season);
+    }
+}
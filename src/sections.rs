@@ -0,0 +1,387 @@
+//! Sections, as scholastic and open chess events split their field into: players are sorted into
+//! sections by rating band or age group, and every section runs its own Swiss with its own
+//! standings and prizes. Unlike flights, sections are never ranked together, but the event is
+//! still reported as a whole, with the standings of every section in one export.
+
+use crate::event::EventInfo;
+use crate::history::RoundError;
+use crate::persist::StandingsRow;
+use crate::{PairingResultError, PairingSystem, Pairings, Player, Tournament};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// How players are sorted into sections
+#[derive(Clone, Debug, PartialEq)]
+pub enum SectionRule {
+    /// Sections by rating band, given by their lowest ratings, highest first: `[1800.0, 1400.0]`
+    /// makes the sections "1800+", "1400-1799", and "U1400". Unrated players play in the lowest
+    /// section.
+    ByRating(Vec<f64>),
+    /// One section per age group, e.g. `["U8", "U10", "U12"]`, in the order given. Every player
+    /// needs one of the listed age groups.
+    ByAgeGroup(Vec<String>),
+}
+
+impl SectionRule {
+    /// Returns the names of the sections the rule makes, in order
+    pub fn section_names(&self) -> Vec<String> {
+        match self {
+            SectionRule::ByRating(bands) => {
+                let mut names = Vec::with_capacity(bands.len() + 1);
+                for (i, &low) in bands.iter().enumerate() {
+                    match i.checked_sub(1).map(|above| bands[above]) {
+                        Some(high) => names.push(format!("{}-{}", low, high - 1.0)),
+                        None => names.push(format!("{}+", low)),
+                    }
+                }
+                match bands.last() {
+                    Some(lowest) => names.push(format!("U{}", lowest)),
+                    None => names.push(String::from("Open")),
+                }
+                names
+            }
+            SectionRule::ByAgeGroup(groups) => groups.clone(),
+        }
+    }
+
+    /// Returns the index of the section a player belongs in
+    fn section_of(&self, player: &Player) -> Result<usize, SectionError> {
+        match self {
+            SectionRule::ByRating(bands) => Ok(match player.rating {
+                Some(rating) => bands
+                    .iter()
+                    .position(|&low| rating >= low)
+                    .unwrap_or(bands.len()),
+                None => bands.len(),
+            }),
+            SectionRule::ByAgeGroup(groups) => {
+                let group = player
+                    .age_group
+                    .as_ref()
+                    .ok_or(SectionError::NoAgeGroup(player.uuid))?;
+                groups
+                    .iter()
+                    .position(|g| g == group)
+                    .ok_or_else(|| SectionError::UnknownAgeGroup(player.uuid, group.clone()))
+            }
+        }
+    }
+}
+
+/// Reasons why players can't be sorted into sections
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SectionError {
+    /// Rating bands must be listed highest first, without any listed twice
+    UnorderedBands,
+    /// The player has no age group
+    NoAgeGroup(uuid::Uuid),
+    /// The player's age group isn't one of the sections
+    UnknownAgeGroup(uuid::Uuid, String),
+}
+
+impl std::error::Error for SectionError {}
+
+impl fmt::Display for SectionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SectionError::UnorderedBands => {
+                write!(f, "Rating bands must be listed highest first")
+            }
+            SectionError::NoAgeGroup(uuid) => write!(f, "Player {} has no age group", uuid),
+            SectionError::UnknownAgeGroup(uuid, group) => write!(
+                f,
+                "Player {} is in age group {}, which has no section",
+                uuid, group
+            ),
+        }
+    }
+}
+
+/// A section of the event and the tournament it plays
+pub struct Section {
+    pub name: String,
+    pub tournament: Tournament,
+}
+
+/// Runs the sections of an event side by side
+pub struct SectionEvent {
+    /// Details of the event as a whole, as opposed to those of the sections' tournaments
+    pub event: EventInfo,
+    sections: Vec<Section>,
+}
+
+/// The standings of all sections of an event, as exported by `SectionEvent::report_json()`
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct EventReport {
+    pub event: EventInfo,
+    pub sections: Vec<SectionReport>,
+}
+
+/// The standings of a single section
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct SectionReport {
+    pub name: String,
+    /// Number of rounds the section plays
+    pub rounds: u32,
+    /// Number of rounds paired so far
+    pub rounds_paired: u32,
+    /// Whether the section has played all of its rounds
+    pub finished: bool,
+    pub standings: Vec<StandingsRow>,
+}
+
+impl SectionEvent {
+    /// Sorts the players into sections by the rule. Sections nobody belongs in are left out, and
+    /// every section plays the given number of rounds, or as many as its size calls for.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use swyss::sections::{SectionEvent, SectionRule};
+    /// use swyss::Player;
+    ///
+    /// let players = [1900.0, 1500.0, 1750.0, 1200.0]
+    ///     .iter()
+    ///     .enumerate()
+    ///     .map(|(i, &rating)| {
+    ///         let mut player = Player::new(&format!("Player {}", i + 1));
+    ///         player.rating = Some(rating);
+    ///         player
+    ///     })
+    ///     .collect();
+    /// let rule = SectionRule::ByRating(vec![1600.0]);
+    /// let mut event = SectionEvent::new(players, &rule, None).unwrap();
+    ///
+    /// while let Some(pairings) = event.next_round().unwrap() {
+    ///     for (uuid, _, _) in pairings {
+    ///         event.end_match(uuid, 2, 1, 0).unwrap();
+    ///     }
+    /// }
+    ///
+    /// let report = event.report();
+    /// assert_eq!(report.sections[0].name, "1600+");
+    /// assert_eq!(report.sections[1].name, "U1600");
+    /// assert_eq!(report.sections[1].standings.len(), 2);
+    /// ```
+    pub fn new(
+        players: Vec<Player>,
+        rule: &SectionRule,
+        rounds: Option<u32>,
+    ) -> Result<SectionEvent, SectionError> {
+        if let SectionRule::ByRating(bands) = rule {
+            if bands.windows(2).any(|pair| pair[0] <= pair[1]) {
+                return Err(SectionError::UnorderedBands);
+            }
+        }
+
+        let names = rule.section_names();
+        let mut members = vec![Vec::new(); names.len()];
+        for p in players {
+            let section = rule.section_of(&p)?;
+            members[section].push(p);
+        }
+
+        let sections = names
+            .into_iter()
+            .zip(members)
+            .filter(|(_, players)| !players.is_empty())
+            .map(|(name, players)| {
+                let size = players.len();
+                let mut tournament = Tournament::from_arena(players.into());
+                tournament.rounds = rounds.unwrap_or_else(|| PairingSystem::Swiss.rounds(size));
+                Section { name, tournament }
+            })
+            .collect();
+        debug_event!(?rule, "sorted players into sections");
+
+        Ok(SectionEvent {
+            event: EventInfo::default(),
+            sections,
+        })
+    }
+
+    /// The sections of the event, in the order of the rule
+    pub fn sections(&mut self) -> &mut [Section] {
+        &mut self.sections
+    }
+
+    /// Returns the name of the section a player plays in
+    pub fn section_of(&self, player: uuid::Uuid) -> Option<&str> {
+        self.sections
+            .iter()
+            .find(|section| section.tournament.players.iter().any(|p| p.uuid == player))
+            .map(|section| section.name.as_str())
+    }
+
+    /// Pairs the next round of every section that has rounds left. Returns `None` once all
+    /// sections are finished, and an error if results of any section's current round are
+    /// outstanding.
+    pub fn next_round(&mut self) -> Result<Option<Pairings>, RoundError> {
+        // Check all sections before pairing any, so that no section gets ahead of the others
+        let outstanding: Vec<_> = self
+            .sections
+            .iter()
+            .flat_map(|section| section.tournament.round_status().outstanding)
+            .collect();
+        if !outstanding.is_empty() {
+            return Err(RoundError::ResultsOutstanding(outstanding));
+        }
+
+        let mut pairings = Vec::new();
+        for section in self
+            .sections
+            .iter_mut()
+            .filter(|section| !section.tournament.is_finished())
+        {
+            if let Some(section_pairings) = section.tournament.next_round()? {
+                pairings.extend(section_pairings);
+            }
+        }
+
+        Ok(Some(pairings).filter(|p| !p.is_empty()))
+    }
+
+    /// Records the result of a pairing of any section
+    pub fn end_match(
+        &mut self,
+        uuid: uuid::Uuid,
+        home_score: u8,
+        away_score: u8,
+        drawn: u8,
+    ) -> Result<(), PairingResultError> {
+        self.sections
+            .iter_mut()
+            .map(|section| &mut section.tournament)
+            .find(|t| t.pairings.contains_key(&uuid))
+            .ok_or(PairingResultError::NotFound(uuid))?
+            .end_match(uuid, home_score, away_score, drawn)
+    }
+
+    /// Returns the standings of every section as of now, in the order of the sections
+    pub fn report(&self) -> EventReport {
+        let sections = self
+            .sections
+            .iter()
+            .map(|section| SectionReport {
+                name: section.name.clone(),
+                rounds: section.tournament.rounds,
+                rounds_paired: section
+                    .tournament
+                    .current_round
+                    .min(section.tournament.rounds),
+                finished: section.tournament.is_finished(),
+                standings: section.tournament.standings(),
+            })
+            .collect();
+
+        EventReport {
+            event: self.event.clone(),
+            sections,
+        }
+    }
+
+    /// Serializes the standings of every section to JSON, as a single export of the whole event
+    pub fn report_json(&self) -> String {
+        serde_json::to_string_pretty(&self.report()).expect("reports are always serializable")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sections_by_rating_and_age() {
+        let rule = SectionRule::ByRating(vec![2000.0, 1600.0, 1200.0]);
+        assert_eq!(
+            rule.section_names(),
+            ["2000+", "1600-1999", "1200-1599", "U1200"]
+        );
+
+        // Nobody is rated 2000 or above, so that section is left out
+        let ratings = [Some(1650.0), None, Some(1999.0), Some(1250.0), Some(800.0)];
+        let players: Vec<_> = ratings
+            .iter()
+            .enumerate()
+            .map(|(i, &rating)| {
+                let mut player = Player::new(format!("Player {}", i + 1).as_str());
+                player.rating = rating;
+                player
+            })
+            .collect();
+        let uuids: Vec<uuid::Uuid> = players.iter().map(|p| p.uuid).collect();
+        let mut event = SectionEvent::new(players, &rule, Some(2)).unwrap();
+        event.event.name = Some(String::from("Scholastic Open"));
+        let names: Vec<_> = event.sections().iter().map(|s| s.name.clone()).collect();
+        assert_eq!(names, ["1600-1999", "1200-1599", "U1200"]);
+        assert_eq!(event.section_of(uuids[1]), Some("U1200"));
+        assert_eq!(event.section_of(uuids[2]), Some("1600-1999"));
+
+        let first = event.next_round().unwrap().unwrap();
+        assert!(matches!(
+            event.next_round(),
+            Err(RoundError::ResultsOutstanding(_))
+        ));
+        for (uuid, _, _) in first {
+            event.end_match(uuid, 2, 0, 0).unwrap();
+        }
+        let second = event.next_round().unwrap().unwrap();
+        for (uuid, _, _) in second {
+            event.end_match(uuid, 1, 1, 1).unwrap();
+        }
+        assert!(event.next_round().unwrap().is_none());
+
+        let report: EventReport = serde_json::from_str(&event.report_json()).unwrap();
+        assert_eq!(report.event.name.as_deref(), Some("Scholastic Open"));
+        let sizes: Vec<_> = report.sections.iter().map(|s| s.standings.len()).collect();
+        assert_eq!(sizes, [2, 1, 2]);
+        assert!(report
+            .sections
+            .iter()
+            .all(|s| s.rounds_paired == 2 && s.finished));
+
+        // Age groups come from the players, and the section of every player must be listed
+        let players: Vec<_> = ["U10", "U12", "U10"]
+            .iter()
+            .map(|group| {
+                let mut player = Player::new(group);
+                player.age_group = Some(String::from(*group));
+                player
+            })
+            .collect();
+        let mut u8_player = Player::new("Youngest");
+        u8_player.age_group = Some(String::from("U8"));
+        let uuid = u8_player.uuid;
+        let groups = vec![String::from("U10"), String::from("U12")];
+        let mut all = players.clone();
+        all.push(u8_player);
+        assert_eq!(
+            SectionEvent::new(all, &SectionRule::ByAgeGroup(groups.clone()), None).err(),
+            Some(SectionError::UnknownAgeGroup(uuid, String::from("U8")))
+        );
+        let mut event = SectionEvent::new(players, &SectionRule::ByAgeGroup(groups), None).unwrap();
+        let sizes: Vec<_> = event
+            .sections()
+            .iter()
+            .map(|s| s.tournament.players.len())
+            .collect();
+        assert_eq!(sizes, [2, 1]);
+
+        // The age group is saved with the player
+        let section = &event.sections()[1].tournament;
+        let loaded = Tournament::from_json(&section.to_json()).unwrap();
+        assert_eq!(loaded.players[0].age_group.as_deref(), Some("U12"));
+
+        assert_eq!(
+            SectionEvent::new(
+                Vec::new(),
+                &SectionRule::ByRating(vec![1200.0, 1600.0]),
+                None
+            )
+            .err(),
+            Some(SectionError::UnorderedBands)
+        );
+    }
+}
@@ -0,0 +1,311 @@
+//! Read access to the state and settings of a tournament. The fields of `Tournament` are private,
+//! so that a finished tournament or one loaded with `Tournament::load_readonly()` can't be
+//! changed behind the back of `Tournament::check_unlocked()`: settings are changed through the
+//! setters here, which fail with a `LockError` once the tournament is locked.
+//!
+//! ```compile_fail
+//! use swyss::Tournament;
+//!
+//! let mut tourn = Tournament::load_readonly("club-night.json").unwrap();
+//! tourn.rounds = 10;
+//! ```
+
+use crate::arena::PlayerArena;
+use crate::audit::AuditEntry;
+use crate::deadline::DeadlinePolicy;
+use crate::event::EventInfo;
+use crate::explain::Criterion;
+use crate::locale::Locale;
+use crate::penalties::Penalty;
+use crate::prizes::PayoutStructure;
+use crate::{
+    AvailabilityConflict, ByeScore, ByeSelection, FinalTiebreak, FirstPlayer, LockError,
+    MatchFormat, Pairing, PairingSystem, Tournament,
+};
+use std::collections::HashMap;
+
+impl Tournament {
+    /// Name, date and other details of the event, which are carried into exports and reports
+    pub fn event(&self) -> &EventInfo {
+        &self.event
+    }
+
+    /// Changes the details of the event
+    pub fn set_event(&mut self, event: EventInfo) -> Result<(), LockError> {
+        self.check_unlocked()?;
+        self.event = event;
+        Ok(())
+    }
+
+    /// Number of rounds the tournament is played over
+    pub fn rounds(&self) -> u32 {
+        self.rounds
+    }
+
+    /// Changes the number of rounds. `apply_config()` also checks that rounds already played
+    /// aren't cut off.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use swyss::{LockError, Player, Tournament};
+    ///
+    /// let players = (1..5)
+    ///     .map(|i| Player::new(&format!("Player {}", i)))
+    ///     .collect();
+    /// let mut tourn = Tournament::with_players(players).unwrap();
+    /// tourn.set_rounds(1).unwrap();
+    /// for (uuid, _, _) in tourn.next_round().unwrap().unwrap() {
+    ///     tourn.end_match(uuid, 2, 0, 0).unwrap();
+    /// }
+    /// tourn.finish().unwrap();
+    ///
+    /// assert_eq!(tourn.set_rounds(3), Err(LockError::Finished));
+    /// assert_eq!(tourn.rounds(), 1);
+    /// ```
+    pub fn set_rounds(&mut self, rounds: u32) -> Result<(), LockError> {
+        self.check_unlocked()?;
+        self.rounds = rounds;
+        Ok(())
+    }
+
+    /// Number of the round being played, 0 before the first round is paired
+    pub fn current_round(&self) -> u32 {
+        self.current_round
+    }
+
+    /// The players, including those who dropped, in the order of the last ranking. See
+    /// `players()` for snapshots of their records that can be kept.
+    pub fn player_arena(&self) -> &PlayerArena {
+        &self.players
+    }
+
+    /// The pairings of the current round by their UUID
+    pub fn pairings(&self) -> &HashMap<uuid::Uuid, Pairing> {
+        &self.pairings
+    }
+
+    /// Checks whether the number of players is odd, so that a round needs a bye
+    pub fn needs_bye(&self) -> bool {
+        self.needs_bye
+    }
+
+    /// Availability constraints that couldn't be honored when rounds were paired
+    pub fn availability_conflicts(&self) -> &[AvailabilityConflict] {
+        &self.availability_conflicts
+    }
+
+    /// Who goes first in a match
+    pub fn first_player(&self) -> FirstPlayer {
+        self.first_player
+    }
+
+    /// Changes who goes first in the matches of rounds paired from now on
+    pub fn set_first_player(&mut self, first_player: FirstPlayer) -> Result<(), LockError> {
+        self.check_unlocked()?;
+        self.first_player = first_player;
+        Ok(())
+    }
+
+    /// How many games each match consists of
+    pub fn match_format(&self) -> MatchFormat {
+        self.match_format
+    }
+
+    /// Changes how many games each match consists of. Change it before the first round only.
+    pub fn set_match_format(&mut self, format: MatchFormat) -> Result<(), LockError> {
+        self.check_unlocked()?;
+        self.match_format = format;
+        Ok(())
+    }
+
+    /// How players are paired
+    pub fn pairing_system(&self) -> PairingSystem {
+        self.pairing_system
+    }
+
+    /// Changes how players are paired. Change it before the first round only.
+    pub fn set_pairing_system(&mut self, system: PairingSystem) -> Result<(), LockError> {
+        self.check_unlocked()?;
+        self.pairing_system = system;
+        Ok(())
+    }
+
+    /// Who receives the bye
+    pub fn bye_selection(&self) -> &ByeSelection {
+        &self.bye_selection
+    }
+
+    /// Changes who receives the bye in rounds paired from now on
+    pub fn set_bye_selection(&mut self, selection: ByeSelection) -> Result<(), LockError> {
+        self.check_unlocked()?;
+        self.bye_selection = selection;
+        Ok(())
+    }
+
+    /// What the bye is worth
+    pub fn bye_score(&self) -> ByeScore {
+        self.bye_score
+    }
+
+    /// Changes what byes granted from now on are worth. Byes already granted keep their score.
+    pub fn set_bye_score(&mut self, score: ByeScore) -> Result<(), LockError> {
+        self.check_unlocked()?;
+        self.bye_score = score;
+        Ok(())
+    }
+
+    /// How many byes a player may be granted for an uneven number of players
+    pub fn max_byes(&self) -> u32 {
+        self.max_byes
+    }
+
+    /// Changes how many byes a player may be granted for an uneven number of players
+    pub fn set_max_byes(&mut self, max_byes: u32) -> Result<(), LockError> {
+        self.check_unlocked()?;
+        self.max_byes = max_byes;
+        Ok(())
+    }
+
+    /// Criteria that break ties between players with the same match points, in the order they
+    /// are applied
+    pub fn tiebreakers(&self) -> &[Criterion] {
+        &self.tiebreakers
+    }
+
+    /// Changes the criteria that break ties between players with the same match points
+    pub fn set_tiebreakers(&mut self, tiebreakers: Vec<Criterion>) -> Result<(), LockError> {
+        self.check_unlocked()?;
+        self.tiebreakers = tiebreakers;
+        Ok(())
+    }
+
+    /// Decides the order of players tied on all tiebreakers
+    pub fn final_tiebreak(&self) -> &FinalTiebreak {
+        &self.final_tiebreak
+    }
+
+    /// Changes how the order of players tied on all tiebreakers is decided
+    pub fn set_final_tiebreak(&mut self, tiebreak: FinalTiebreak) -> Result<(), LockError> {
+        self.check_unlocked()?;
+        self.final_tiebreak = tiebreak;
+        Ok(())
+    }
+
+    /// Decimal places tiebreaker values are shown with
+    pub fn tiebreak_decimals(&self) -> u32 {
+        self.tiebreak_decimals
+    }
+
+    /// Changes the decimal places tiebreaker values are shown with. Larger settings are capped to
+    /// `MAX_TIEBREAK_DECIMALS`.
+    pub fn set_tiebreak_decimals(&mut self, decimals: u32) -> Result<(), LockError> {
+        self.check_unlocked()?;
+        self.tiebreak_decimals = decimals;
+        Ok(())
+    }
+
+    /// Length of a round in minutes, if the organizer set one
+    pub fn round_length(&self) -> Option<u32> {
+        self.round_length
+    }
+
+    /// Changes the length of a round in minutes, or removes it with `None`
+    pub fn set_round_length(&mut self, minutes: Option<u32>) -> Result<(), LockError> {
+        self.check_unlocked()?;
+        self.round_length = minutes;
+        Ok(())
+    }
+
+    /// What happens to results that aren't reported in time, if the organizer set a deadline
+    pub fn deadline(&self) -> Option<DeadlinePolicy> {
+        self.deadline
+    }
+
+    /// Changes what happens to results that aren't reported in time, or removes the deadline
+    /// with `None`
+    pub fn set_deadline(&mut self, deadline: Option<DeadlinePolicy>) -> Result<(), LockError> {
+        self.check_unlocked()?;
+        self.deadline = deadline;
+        Ok(())
+    }
+
+    /// Table number the featured pairing of each round is moved to, starting at 1
+    pub fn featured_table(&self) -> u32 {
+        self.featured_table
+    }
+
+    /// Changes the table number the featured pairing of rounds paired from now on is moved to
+    pub fn set_featured_table(&mut self, table: u32) -> Result<(), LockError> {
+        self.check_unlocked()?;
+        self.featured_table = table;
+        Ok(())
+    }
+
+    /// Language of the texts shown to players and organizers
+    pub fn locale(&self) -> Locale {
+        self.locale
+    }
+
+    /// Changes the language of the texts shown to players and organizers
+    pub fn set_locale(&mut self, locale: Locale) -> Result<(), LockError> {
+        self.check_unlocked()?;
+        self.locale = locale;
+        Ok(())
+    }
+
+    /// Prizes paid out over the final standings, if the organizer set them
+    pub fn prizes(&self) -> Option<&PayoutStructure> {
+        self.prizes.as_ref()
+    }
+
+    /// Changes the prizes paid out over the final standings, or removes them with `None`
+    pub fn set_prizes(&mut self, prizes: Option<PayoutStructure>) -> Result<(), LockError> {
+        self.check_unlocked()?;
+        self.prizes = prizes;
+        Ok(())
+    }
+
+    /// Everything that happened to the tournament, in order
+    pub fn audit_log(&self) -> &[AuditEntry] {
+        &self.audit_log
+    }
+
+    /// Penalties given to players, in order. See `penalize()`.
+    pub fn penalties(&self) -> &[Penalty] {
+        &self.penalties
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Player;
+
+    #[test]
+    fn read_only_settings_are_kept() {
+        let players = (1..5)
+            .map(|i| Player::new(&format!("Player {}", i)))
+            .collect();
+        let mut tourn = Tournament::with_players(players).unwrap();
+        tourn.set_rounds(1).unwrap();
+        tourn.set_max_byes(2).unwrap();
+        for (uuid, _, _) in tourn.next_round().unwrap().unwrap() {
+            tourn.end_match(uuid, 2, 0, 0).unwrap();
+        }
+        tourn.finish().unwrap();
+        assert_eq!(tourn.set_rounds(3), Err(LockError::Finished));
+
+        let path = std::env::temp_dir().join(format!("swyss-{}.json", uuid::Uuid::new_v4()));
+        tourn.save(&path).unwrap();
+        let mut tourn = Tournament::load_readonly(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(tourn.set_rounds(10), Err(LockError::ReadOnly));
+        assert_eq!(tourn.set_max_byes(0), Err(LockError::ReadOnly));
+        assert_eq!(tourn.set_tiebreakers(Vec::new()), Err(LockError::ReadOnly));
+        assert_eq!(tourn.set_locale(Locale::German), Err(LockError::ReadOnly));
+        assert_eq!((tourn.rounds(), tourn.max_byes()), (1, 2));
+        assert_eq!(tourn.tiebreakers(), Criterion::DEFAULT_TIEBREAKERS);
+    }
+}
@@ -0,0 +1,302 @@
+//! Projections of the final standings. The remaining rounds of a tournament are played out many
+//! times with random results, which answers questions like "can I still make the top 8?".
+//!
+//! The same random results drive tournaments of synthetic players, for demos and for load-testing
+//! changes to pairing and ranking with realistic fields.
+
+use crate::persist::TournamentData;
+use crate::ratings::{Rating, RatingSystem, Ratings};
+use crate::{Player, Tournament};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::collections::HashMap;
+
+/// A player's projected chance of finishing in the top N
+#[derive(Clone, Debug, PartialEq)]
+pub struct Projection {
+    pub uuid: uuid::Uuid,
+    pub name: String,
+    /// Share of simulations in which the player finished in the top N, between 0 and 1
+    pub probability: f64,
+}
+
+/// Simulates the remaining rounds of a tournament `iterations` times and returns each player's
+/// probability of finishing in the top `n`, ordered from most to least likely. The tournament
+/// itself is not modified.
+///
+/// Results of the current round should be entered before simulating, since only the rounds that
+/// haven't been paired yet are simulated. Without ratings, every match is a coin flip; with
+/// ratings, the winner is drawn according to the players' expected scores.
+///
+/// # Example
+///
+/// ```
+/// use swyss::{simulate, Player, Tournament};
+///
+/// let players = (1..9)
+///     .map(|i| Player::new(&format!("Player {}", i)))
+///     .collect();
+/// let tourn = Tournament::with_players(players).unwrap();
+///
+/// let projections = simulate::top_n(&tourn, 1, 100, None);
+/// assert_eq!(projections.len(), 8);
+/// let total: f64 = projections.iter().map(|p| p.probability).sum();
+/// assert!((total - 1.0).abs() < 1e-9);
+/// ```
+pub fn top_n(
+    tournament: &Tournament,
+    n: usize,
+    iterations: u32,
+    ratings: Option<&Ratings>,
+) -> Vec<Projection> {
+    let mut rng = rand::thread_rng();
+    let mut counts: HashMap<uuid::Uuid, u32> = HashMap::new();
+
+    for _ in 0..iterations {
+        let mut sim = TournamentData::from(tournament)
+            .into_tournament()
+            .expect("a tournament's own data always restores");
+
+        // Results that are still outstanding in the current round are simulated as well
+        play_out(&mut sim, ratings, &mut rng);
+
+        for p in sim.ranking().iter().take(n) {
+            *counts.entry(p.uuid).or_insert(0) += 1;
+        }
+    }
+
+    let mut projections: Vec<Projection> = tournament
+        .players
+        .iter()
+        .map(|p| {
+            let count = counts.get(&p.uuid).copied().unwrap_or(0);
+            Projection {
+                uuid: p.uuid,
+                name: p.name.clone(),
+                probability: if iterations == 0 {
+                    0.0
+                } else {
+                    count as f64 / iterations as f64
+                },
+            }
+        })
+        .collect();
+
+    projections.sort_by(|a, b| {
+        b.probability
+            .partial_cmp(&a.probability)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then(a.name.cmp(&b.name))
+    });
+
+    projections
+}
+
+/// Enters random results for all outstanding pairings of the current round. Without ratings,
+/// every match is a coin flip; with ratings, the winner is drawn according to the players'
+/// expected scores. The loser wins a random number of games short of the match.
+pub fn play_round<R: Rng>(tournament: &mut Tournament, ratings: Option<&Ratings>, rng: &mut R) {
+    // Pairing UUIDs are always random, so results are drawn in the order of the home players
+    let mut outstanding = tournament.round_status().outstanding;
+    outstanding.sort_by_key(|uuid| tournament.pairings[uuid].home);
+
+    for uuid in outstanding {
+        let p = {
+            let pair = &tournament.pairings[&uuid];
+            let name = |uuid| {
+                tournament
+                    .players
+                    .opponent(uuid)
+                    .map_or("", |p| p.name.as_str())
+            };
+            let (home, away) = (name(pair.home), name(pair.away));
+            match ratings {
+                Some(r) => r.get(home).expected_score(&r.get(away)),
+                None => Rating::default().expected_score(&Rating::default()),
+            }
+        };
+
+        let wins = tournament.match_format.wins_needed();
+        let loser_games = rng.gen_range(0, wins);
+        let (home_score, away_score) = if rng.gen_bool(p) {
+            (wins, loser_games)
+        } else {
+            (loser_games, wins)
+        };
+
+        tournament
+            .end_match(uuid, home_score, away_score, 0)
+            .expect("simulated results are always valid");
+    }
+}
+
+/// Plays the tournament to its end with random results as by `play_round()`, starting with the
+/// outstanding pairings of the current round. Every round is paired with a seed drawn from `rng`,
+/// so a seeded `rng` plays out the same tournament every time.
+pub fn play_out<R: Rng>(tournament: &mut Tournament, ratings: Option<&Ratings>, rng: &mut R) {
+    loop {
+        play_round(tournament, ratings, rng);
+
+        match tournament.next_round_with_seed(rng.gen()) {
+            Ok(Some(_)) => {}
+            Ok(None) => break,
+            Err(e) => unreachable!("all results were simulated: {}", e),
+        }
+    }
+}
+
+/// Creates `n` synthetic players named "Player 1" to "Player n", along with ratings that stand
+/// for their skill. Ratings are spread evenly at random within `spread` points of the default
+/// rating of 1500, so a spread of 0 makes every match a coin flip.
+///
+/// # Example
+///
+/// ```
+/// use swyss::simulate;
+///
+/// let (players, ratings) = simulate::synthetic_players(64, 200.0, &mut rand::thread_rng());
+/// assert_eq!(players.len(), 64);
+/// let rating = ratings.get(&players[0].name).rating;
+/// assert!((1300.0..=1700.0).contains(&rating));
+/// ```
+pub fn synthetic_players<R: Rng>(n: u32, spread: f64, rng: &mut R) -> (Vec<Player>, Ratings) {
+    let mut ratings = Ratings::new(RatingSystem::default());
+    let players = (1..=n)
+        .map(|i| {
+            let name = format!("Player {}", i);
+            let offset = if spread > 0.0 {
+                rng.gen_range(-spread, spread)
+            } else {
+                0.0
+            };
+            ratings.ratings.insert(
+                name.clone(),
+                Rating {
+                    rating: Rating::default().rating + offset,
+                    ..Rating::default()
+                },
+            );
+            // UUIDs come from `rng` as well, so that a seeded `rng` creates the same players
+            let mut player = Player::new(&name);
+            player.uuid = uuid::Uuid::from_u128(rng.gen());
+            player
+        })
+        .collect();
+
+    (players, ratings)
+}
+
+/// Plays a whole tournament of `n` synthetic players of the given skill spread, as created by
+/// `synthetic_players()`, and returns it along with the players' ratings. The same seed always
+/// produces the same pairings and results.
+///
+/// # Example
+///
+/// ```
+/// use swyss::simulate;
+///
+/// let (tourn, _) = simulate::demo(16, 200.0, 42);
+/// assert_eq!(tourn.round_history().len(), 4);
+/// assert!(tourn.round_status().is_complete());
+/// ```
+pub fn demo(n: u32, spread: f64, seed: u64) -> (Tournament, Ratings) {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let (players, ratings) = synthetic_players(n, spread, &mut rng);
+    let mut tournament = Tournament::from_arena(players.into());
+
+    tournament
+        .next_round_with_seed(rng.gen())
+        .expect("a new tournament has no outstanding results");
+    play_out(&mut tournament, Some(&ratings), &mut rng);
+
+    (tournament, ratings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ratings::RatingSystem;
+    use crate::Player;
+
+    fn players(n: u32) -> Vec<Player> {
+        (1..=n)
+            .map(|i| Player::new(format!("Player {}", i).as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn finished_tournament() {
+        let mut tourn = Tournament::with_players(players(2)).unwrap();
+        let pairings = tourn.next_round().unwrap().unwrap();
+        let (uuid, home, _) = &pairings[0];
+        let winner = home.clone();
+        assert!(tourn.end_match(*uuid, 2, 0, 0).is_ok());
+        assert!(tourn.next_round().unwrap().is_none());
+
+        let projections = top_n(&tourn, 1, 50, None);
+        assert_eq!(projections[0].name, winner);
+        assert_eq!(projections[0].probability, 1.0);
+        assert_eq!(projections[1].probability, 0.0);
+    }
+
+    #[test]
+    fn final_round() {
+        let mut tourn = Tournament::with_players(players(4)).unwrap();
+        let pairings = tourn.next_round().unwrap().unwrap();
+        let mut winners = Vec::new();
+        for (uuid, home, _) in &pairings {
+            winners.push(home.clone());
+            assert!(tourn.end_match(*uuid, 2, 0, 0).is_ok());
+        }
+
+        // Only the two undefeated players can still win, and the stronger one is the favourite
+        let mut ratings = Ratings::new(RatingSystem::default());
+        ratings.ratings.insert(
+            winners[0].clone(),
+            Rating {
+                rating: 2000.0,
+                ..Rating::default()
+            },
+        );
+
+        let projections = top_n(&tourn, 1, 500, Some(&ratings));
+        assert_eq!(projections[0].name, winners[0]);
+        assert!(projections[0].probability > 0.9);
+        assert_eq!(projections[1].name, winners[1]);
+        assert_eq!(projections[2].probability, 0.0);
+        assert_eq!(projections[3].probability, 0.0);
+
+        // The tournament itself is left untouched
+        assert_eq!(tourn.current_round, 1);
+        assert!(tourn.players.iter().all(|p| p.matches_played == 1));
+    }
+
+    #[test]
+    fn demo_is_reproducible() {
+        // Pairings are compared by their players, as pairing UUIDs differ between runs
+        let results = |tourn: &Tournament| {
+            let mut results: Vec<_> = tourn
+                .round_history()
+                .iter()
+                .flat_map(|round| {
+                    round
+                        .pairings
+                        .iter()
+                        .map(move |pair| (round.number, pair.home, pair.away, pair.result))
+                })
+                .collect();
+            results.sort_by_key(|&(round, home, _, _)| (round, home));
+            results
+        };
+
+        let (a, ratings) = demo(32, 300.0, 7);
+        let (b, _) = demo(32, 300.0, 7);
+        assert_eq!(a.round_history().len(), 5);
+        assert_eq!(results(&a), results(&b));
+        assert!(results(&a).iter().all(|(_, _, _, result)| result.is_some()));
+
+        // Skill shows: the winner is rated above the average player
+        let winner = a.standings()[0].name.clone();
+        assert!(ratings.get(&winner).rating > 1500.0);
+    }
+}
@@ -0,0 +1,308 @@
+//! A read-only web page for spectators, so that players can check the pairings and standings on
+//! their phones instead of crowding around a printout. `SpectatorServer` serves the page over
+//! plain HTTP from a background thread and, registered as an observer, keeps it up to date as
+//! the tournament progresses.
+
+use crate::archive::escape_html;
+use crate::locale::Message;
+use crate::observer::TournamentObserver;
+use crate::persist::standings_rows;
+use crate::{Player, Tournament};
+use std::io;
+use std::io::{BufRead, BufReader, Write};
+use std::net::{IpAddr, Ipv4Addr, SocketAddr, TcpListener, TcpStream, UdpSocket};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+/// How often the page reloads itself, in seconds
+pub const REFRESH_SECONDS: u32 = 30;
+
+impl Tournament {
+    /// Renders the pairings of the current round and the standings as a standalone HTML page
+    /// that reloads itself every `refresh` seconds
+    pub fn spectator_html(&self, refresh: u32) -> String {
+        let mut out = format!(
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n\
+             <meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n\
+             <meta http-equiv=\"refresh\" content=\"{}\">\n\
+             <title>{title}</title>\n</head>\n<body>\n<h1>{title}</h1>\n",
+            refresh,
+            title = escape_html(self.event.title())
+        );
+        let details = self.event.details();
+        if !details.is_empty() {
+            out.push_str(&format!("<p>{}</p>\n", escape_html(&details.join(" | "))));
+        }
+
+        if let Some(round) = self.round(self.current_round) {
+            let name = |uuid: uuid::Uuid| {
+                let name = self
+                    .player(uuid)
+                    .map(|p| String::from(p.display_name()))
+                    .unwrap_or_default();
+                match self.player_number(uuid) {
+                    Some(number) => format!("{} (#{})", escape_html(&name), number),
+                    None => escape_html(&name),
+                }
+            };
+
+            let text = |message| self.locale.text(message);
+            out.push_str(&format!(
+                "<h2>{}</h2>\n<table>\n",
+                self.locale.format(
+                    Message::Round,
+                    &[&format!("{}/{}", round.number, self.rounds)]
+                )
+            ));
+            out.push_str(&format!(
+                "<tr><th>{}</th><th>{}</th><th>{}</th><th>{}</th></tr>\n",
+                text(Message::TableColumn),
+                text(Message::Home),
+                text(Message::Result),
+                text(Message::Away)
+            ));
+            for (i, pair) in round.pairings.iter().enumerate() {
+                let result = match pair.result {
+                    Some(r) if r.drawn > 0 => {
+                        format!("{}-{}-{}", r.home_score, r.away_score, r.drawn)
+                    }
+                    Some(r) => format!("{}-{}", r.home_score, r.away_score),
+                    None => String::new(),
+                };
+                let table = if round.featured == Some(pair.uuid) {
+                    format!("{} {}", i + 1, text(Message::Featured))
+                } else {
+                    (i + 1).to_string()
+                };
+                out.push_str(&format!(
+                    "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+                    table,
+                    name(pair.home),
+                    result,
+                    name(pair.away)
+                ));
+            }
+            out.push_str("</table>\n");
+
+            if let Some(bye) = round.bye {
+                out.push_str(&format!(
+                    "<p>{}</p>\n",
+                    self.locale.format(Message::Bye, &[&name(bye)])
+                ));
+            }
+        }
+
+        let players = self.sorted_players();
+        let text = |message| self.locale.text(message);
+        out.push_str(&format!("<h2>{}</h2>\n<table>\n", text(Message::Standings)));
+        out.push_str(&format!(
+            "<tr><th>{}</th><th>{}</th><th>{}</th><th>MP</th><th>OMWP</th><th>{}</th></tr>\n",
+            text(Message::Rank),
+            text(Message::Name),
+            text(Message::Record),
+            text(Message::Form)
+        ));
+        for row in standings_rows(self, &players) {
+            out.push_str(&format!(
+                "<tr><td>{}</td><td>{}</td><td>{}-{}-{}</td><td>{}</td><td>{}</td>\
+                 <td>{}</td></tr>\n",
+                row.rank,
+                escape_html(row.display_name()),
+                row.matches_won,
+                row.matches_played - row.matches_won - row.matches_drawn,
+                row.matches_drawn,
+                row.match_points,
+                self.format_tiebreak(row.opponents_match_win_percentage),
+                row.form
+            ));
+        }
+        out.push_str("</table>\n</body>\n</html>\n");
+
+        out
+    }
+}
+
+/// Serves the spectator page of a tournament over HTTP. The page is rendered whenever the
+/// tournament notifies the server as its observer, and requests are answered from a background
+/// thread that runs until the program exits.
+///
+/// # Example
+///
+/// ```no_run
+/// use swyss::spectator::SpectatorServer;
+/// use swyss::{Player, Tournament};
+///
+/// let players = (1..5)
+///     .map(|i| Player::new(&format!("Player {}", i)))
+///     .collect();
+/// let mut tourn = Tournament::with_players(players).unwrap();
+/// let server = SpectatorServer::start(8080, &tourn).unwrap();
+/// println!("Pairings and standings at port {}", server.local_addr().port());
+/// tourn.add_observer(Box::new(server));
+/// ```
+pub struct SpectatorServer {
+    page: Arc<Mutex<String>>,
+    addr: SocketAddr,
+}
+
+impl SpectatorServer {
+    /// Starts serving the page of the tournament on the given port of all network interfaces,
+    /// or on a free port if it is 0
+    pub fn start(port: u16, tournament: &Tournament) -> io::Result<SpectatorServer> {
+        let listener = TcpListener::bind(("0.0.0.0", port))?;
+        let addr = listener.local_addr()?;
+        let page = Arc::new(Mutex::new(tournament.spectator_html(REFRESH_SECONDS)));
+
+        let served = Arc::clone(&page);
+        thread::spawn(move || {
+            // A client that goes away mid-request only affects that request
+            for stream in listener.incoming().flatten() {
+                let _ = respond(stream, &served);
+            }
+        });
+
+        Ok(SpectatorServer { page, addr })
+    }
+
+    /// Returns the address the server listens on
+    pub fn local_addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// Returns the URL of the page as seen from the local network, e.g. for a QR code posted at
+    /// the venue. Falls back to `localhost` if the machine's address can't be determined.
+    pub fn url(&self) -> String {
+        let host = match local_ip() {
+            Some(ip) => ip.to_string(),
+            None => String::from("localhost"),
+        };
+        format!("http://{}:{}/", host, self.addr.port())
+    }
+
+    /// Renders the page again from the current state of the tournament
+    pub fn update(&self, tournament: &Tournament) {
+        let html = tournament.spectator_html(REFRESH_SECONDS);
+        *self.page.lock().expect("the server thread never panics") = html;
+    }
+}
+
+/// Finds the address of the network interface that other machines reach this one by. Connecting
+/// a UDP socket only picks the route; no packet is sent.
+fn local_ip() -> Option<IpAddr> {
+    let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0)).ok()?;
+    socket.connect((Ipv4Addr::new(192, 0, 2, 1), 80)).ok()?;
+    let ip = socket.local_addr().ok()?.ip();
+    if ip.is_unspecified() {
+        None
+    } else {
+        Some(ip)
+    }
+}
+
+/// Answers a single request: the page for `GET /`, and an error for anything else
+fn respond(stream: TcpStream, page: &Mutex<String>) -> io::Result<()> {
+    let mut reader = BufReader::new(stream);
+    let mut request = String::new();
+    reader.read_line(&mut request)?;
+
+    // Skip the headers, which don't matter for a single read-only page
+    let mut header = String::new();
+    while reader.read_line(&mut header)? > 2 {
+        header.clear();
+    }
+
+    let (status, body) = match request.split_whitespace().collect::<Vec<_>>().as_slice() {
+        ["GET", "/", ..] => (
+            "200 OK",
+            page.lock().expect("the server thread never panics").clone(),
+        ),
+        ["GET", ..] => ("404 Not Found", String::from("Not found\n")),
+        _ => (
+            "405 Method Not Allowed",
+            String::from("Method not allowed\n"),
+        ),
+    };
+
+    let content_type = if status == "200 OK" {
+        "text/html; charset=utf-8"
+    } else {
+        "text/plain; charset=utf-8"
+    };
+
+    let mut stream = reader.into_inner();
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nCache-Control: no-store\r\n\
+         Connection: close\r\n\r\n{}",
+        status,
+        content_type,
+        body.len(),
+        body
+    )?;
+    stream.flush()
+}
+
+impl TournamentObserver for SpectatorServer {
+    fn on_round_paired(&self, tournament: &Tournament, _: &[(uuid::Uuid, String, String)]) {
+        self.update(tournament);
+    }
+
+    fn on_result(&self, tournament: &Tournament, _: uuid::Uuid, _: u8, _: u8, _: u8) {
+        self.update(tournament);
+    }
+
+    fn on_drop(&self, tournament: &Tournament, _: &Player) {
+        self.update(tournament);
+    }
+
+    fn on_complete(&self, tournament: &Tournament) {
+        self.update(tournament);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+
+    fn get(addr: SocketAddr, path: &str) -> String {
+        let mut stream = TcpStream::connect(("127.0.0.1", addr.port())).unwrap();
+        write!(stream, "GET {} HTTP/1.1\r\nHost: localhost\r\n\r\n", path).unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        response
+    }
+
+    #[test]
+    fn serves_current_pairings() {
+        let players = ["Alice", "<Bob>", "Carol"]
+            .iter()
+            .map(|name| Player::new(name))
+            .collect();
+        let mut tourn = Tournament::with_players(players).unwrap();
+        let server = SpectatorServer::start(0, &tourn).unwrap();
+        let addr = server.local_addr();
+        assert!(server.url().starts_with("http://"));
+        assert!(server.url().ends_with(&format!(":{}/", addr.port())));
+        tourn.add_observer(Box::new(server));
+
+        let page = get(addr, "/");
+        assert!(page.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(page.contains("<meta http-equiv=\"refresh\" content=\"30\">"));
+        assert!(page.contains("&lt;Bob&gt;"));
+        assert!(page.contains("<title>Tournament</title>"));
+        assert!(!page.contains("<h2>Round"));
+
+        let (uuid, _, _) = tourn.next_round().unwrap().unwrap()[0];
+        assert!(get(addr, "/").contains("<h2>Round 1/2</h2>"));
+        assert!(get(addr, "/").contains("<p>Bye: "));
+
+        tourn.end_match(uuid, 2, 1, 0).unwrap();
+        assert!(get(addr, "/").contains("<td>1</td><td>"));
+        assert!(get(addr, "/").contains("</td><td>2-1</td><td>"));
+        tourn.feature_pairing(uuid).unwrap();
+        assert!(tourn.spectator_html(30).contains("<td>1 (featured)</td>"));
+
+        assert!(get(addr, "/favicon.ico").starts_with("HTTP/1.1 404 Not Found\r\n"));
+    }
+}
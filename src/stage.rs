@@ -0,0 +1,412 @@
+//! Multi-stage events, e.g. a day 1 of Swiss rounds followed by a cut to the players with enough
+//! match points and a day 2 of further Swiss rounds. Each stage is run as its own `Tournament`,
+//! with its own match format, bye settings, and percentage floor if the stage sets them.
+
+use crate::arena::PlayerArena;
+use crate::history::RoundError;
+use crate::{
+    sort_by_tiebreakers, ByeScore, ByeSelection, MatchFormat, Pairings, PercentageFloor, Player,
+    Tournament,
+};
+use std::fmt;
+use uuid::Uuid;
+
+/// Decides which players advance at the end of a stage
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Cut {
+    /// Players with at least this many match points advance
+    MinPoints(u32),
+    /// This many top-ranked players advance
+    Top(usize),
+}
+
+/// Configuration of a single stage. Settings left at `None` are carried over from the previous
+/// stage, or are the defaults of a new tournament for the first stage. Who goes first and the
+/// tiebreakers are always carried over.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Stage {
+    /// Number of Swiss rounds to play, or `None` to derive it from the number of players
+    pub rounds: Option<u32>,
+    /// Cut applied when the stage ends, or `None` if everyone advances
+    pub cut: Option<Cut>,
+    /// Whether players enter the stage with their points, tiebreakers, and opponents from the
+    /// previous stage. Ignored for the first stage.
+    pub carry_standings: bool,
+    /// Match format of the stage, e.g. best-of-one for a Swiss day and best-of-three for the cut
+    pub match_format: Option<MatchFormat>,
+    /// Who receives the bye in the stage
+    pub bye_selection: Option<ByeSelection>,
+    /// What the bye is worth in the stage
+    pub bye_score: Option<ByeScore>,
+    /// How many byes a player may be granted in the stage
+    pub max_byes: Option<u32>,
+    /// Lowest value the players' win percentages count as in the stage
+    pub percentage_floor: Option<PercentageFloor>,
+}
+
+impl Stage {
+    /// Returns a new stage that plays the given number of rounds, carries standings, and doesn't
+    /// cut
+    pub fn new(rounds: Option<u32>) -> Stage {
+        Stage {
+            rounds,
+            cut: None,
+            carry_standings: true,
+            match_format: None,
+            bye_selection: None,
+            bye_score: None,
+            max_byes: None,
+            percentage_floor: None,
+        }
+    }
+}
+
+/// Reasons why a staged event can't be set up
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StageError {
+    /// The event has no stages
+    NoStages,
+}
+
+impl std::error::Error for StageError {}
+
+impl fmt::Display for StageError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            StageError::NoStages => write!(f, "An event needs at least one stage"),
+        }
+    }
+}
+
+/// Runs an event consisting of several consecutive stages
+pub struct StagedEvent {
+    stages: Vec<Stage>,
+    current_stage: usize,
+    tournament: Tournament,
+    /// Players cut after each stage, in their final order within that stage, with everyone else
+    /// in the stage as their former opponents
+    eliminated: Vec<PlayerArena>,
+}
+
+impl StagedEvent {
+    /// Creates a new event and sets up its first stage with all players
+    ///
+    /// # Arguments
+    ///
+    /// * `players` - All players registered for the event
+    /// * `stages` - Configuration of the stages, in the order they are played. Fails with
+    ///   `StageError::NoStages` if it is empty.
+    pub fn new(players: Vec<Player>, stages: Vec<Stage>) -> Result<StagedEvent, StageError> {
+        let first = stages.first().ok_or(StageError::NoStages)?;
+        let tournament = StagedEvent::stage_tournament(players.into(), first, None);
+
+        Ok(StagedEvent {
+            stages,
+            current_stage: 0,
+            tournament,
+            eliminated: Vec::new(),
+        })
+    }
+
+    /// Sets up the tournament of a stage with the settings of the previous stage, if there is
+    /// one, and those the stage changes
+    fn stage_tournament(
+        players: PlayerArena,
+        stage: &Stage,
+        previous: Option<&Tournament>,
+    ) -> Tournament {
+        let mut tournament = Tournament::from_arena(players);
+        if let Some(rounds) = stage.rounds {
+            tournament.rounds = rounds;
+        }
+
+        if let Some(previous) = previous {
+            tournament.first_player = previous.first_player;
+            tournament.tiebreakers = previous.tiebreakers.clone();
+            tournament.match_format = previous.match_format;
+            tournament.bye_selection = previous.bye_selection.clone();
+            tournament.bye_score = previous.bye_score;
+            tournament.max_byes = previous.max_byes;
+            tournament.apply_percentage_floor(previous.percentage_floor);
+        }
+
+        if let Some(format) = stage.match_format {
+            tournament.match_format = format;
+        }
+        if let Some(selection) = &stage.bye_selection {
+            tournament.bye_selection = selection.clone();
+        }
+        if let Some(score) = stage.bye_score {
+            tournament.bye_score = score;
+        }
+        if let Some(max_byes) = stage.max_byes {
+            tournament.max_byes = max_byes;
+        }
+        if let Some(floor) = stage.percentage_floor {
+            tournament.apply_percentage_floor(floor);
+        }
+
+        tournament
+    }
+
+    /// Index of the stage currently being played, starting at 0
+    pub fn current_stage(&self) -> usize {
+        self.current_stage
+    }
+
+    /// The tournament of the current stage
+    pub fn tournament(&mut self) -> &mut Tournament {
+        &mut self.tournament
+    }
+
+    /// Advances the event by one round. Once all rounds of a stage have been played, the stage's
+    /// cut is applied and the first round of the next stage is paired. Returns `None` once the
+    /// final stage is complete, and an error if results of the current round are outstanding.
+    pub fn next_round(&mut self) -> Result<Option<Pairings>, RoundError> {
+        loop {
+            if let Some(pairings) = self.tournament.next_round()? {
+                return Ok(Some(pairings));
+            }
+
+            if self.current_stage + 1 >= self.stages.len() {
+                return Ok(None);
+            }
+
+            self.advance_stage();
+        }
+    }
+
+    /// Applies the cut of the current stage and sets up the next one
+    fn advance_stage(&mut self) {
+        let mut standings: Vec<Uuid> = self.tournament.ranking().iter().map(|p| p.uuid).collect();
+
+        let advancing = match self.stages[self.current_stage].cut {
+            Some(Cut::MinPoints(points)) => standings
+                .iter()
+                .filter_map(|&uuid| self.tournament.players.get(uuid))
+                .take_while(|p| p.match_points >= points)
+                .count(),
+            Some(Cut::Top(n)) => n.min(standings.len()),
+            None => standings.len(),
+        };
+
+        let eliminated = standings.split_off(advancing);
+        self.eliminated
+            .push(split_players(&self.tournament.players, &eliminated));
+
+        self.current_stage += 1;
+        let stage = &self.stages[self.current_stage];
+
+        let players = if stage.carry_standings {
+            split_players(&self.tournament.players, &standings)
+        } else {
+            standings
+                .iter()
+                .filter_map(|&uuid| self.tournament.players.get(uuid))
+                .map(fresh_player)
+                .collect()
+        };
+
+        self.tournament = StagedEvent::stage_tournament(players, stage, Some(&self.tournament));
+    }
+
+    /// Combined standings over all stages. Players who reached a later stage are always ranked
+    /// above those who were cut earlier; within a stage, the tiebreakers of the current stage
+    /// apply.
+    pub fn standings(&mut self) -> Vec<Player> {
+        let mut standings: Vec<Player> = self.tournament.ranking().into_iter().cloned().collect();
+
+        for (k, eliminated) in self.eliminated.iter().enumerate().rev() {
+            // Opponents count as they were when the stage they last played in ended
+            let opponents = (k..self.eliminated.len())
+                .find(|&j| !self.stages[j + 1].carry_standings)
+                .map_or(&self.tournament.players, |j| &self.eliminated[j]);
+            let mut players: Vec<&Player> = eliminated.iter().collect();
+            sort_by_tiebreakers(&mut players, opponents, &self.tournament.tiebreakers);
+            standings.extend(players.into_iter().cloned());
+        }
+
+        standings
+    }
+}
+
+/// Copies the given players, in order, into a new arena. Everyone else who played in the
+/// tournament is kept as a former player, so that they still count as opponents.
+fn split_players(players: &PlayerArena, members: &[Uuid]) -> PlayerArena {
+    let mut split: PlayerArena = members
+        .iter()
+        .filter_map(|&uuid| players.get(uuid))
+        .cloned()
+        .collect();
+    let others: Vec<Player> = players
+        .iter()
+        .chain(players.former())
+        .filter(|p| !split.contains(p.uuid))
+        .cloned()
+        .collect();
+    split.retire(others);
+    split
+}
+
+/// Returns a copy of a player without any results, for a stage that starts from scratch. Details
+/// of the player other than their results, such as their alias, rating, club, age group, and the
+/// players they must never be paired against, are kept.
+pub(crate) fn fresh_player(player: &Player) -> Player {
+    let mut fresh = Player::new(&player.name);
+    fresh.uuid = player.uuid;
+    fresh.alias = player.alias.clone();
+    fresh.availability = player.availability;
+    fresh.rating = player.rating;
+    fresh.club = player.club.clone();
+    fresh.age_group = player.age_group.clone();
+    fresh.avoid = player.avoid.clone();
+    fresh
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use regex::Regex;
+
+    /// Plays all rounds of the event, with the player with the higher number always winning 2-1
+    fn play(event: &mut StagedEvent) {
+        let re = Regex::new(r"Player (\d+)").unwrap();
+        let number = |name: &str| -> u32 { re.captures(name).unwrap()[1].parse().unwrap() };
+
+        while let Some(pairings) = event.next_round().unwrap() {
+            for (uuid, home, away) in &pairings {
+                let (home_score, away_score) = if number(home) > number(away) {
+                    (2, 1)
+                } else {
+                    (1, 2)
+                };
+
+                assert!(event
+                    .tournament()
+                    .end_match(*uuid, home_score, away_score, 0)
+                    .is_ok());
+            }
+        }
+    }
+
+    fn players(n: u32) -> Vec<Player> {
+        (1..=n)
+            .map(|i| Player::new(format!("Player {}", i).as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn cut_by_points_and_carry() {
+        let day1 = Stage {
+            rounds: Some(1),
+            cut: Some(Cut::MinPoints(3)),
+            carry_standings: true,
+            ..Stage::new(Some(1))
+        };
+        let day2 = Stage::new(Some(1));
+
+        let mut event = StagedEvent::new(players(8), vec![day1, day2]).unwrap();
+        play(&mut event);
+
+        assert_eq!(event.current_stage(), 1);
+
+        // Only the winners of the first round make day 2
+        assert_eq!(event.tournament().players.len(), 4);
+
+        let standings = event.standings();
+        assert_eq!(standings.len(), 8);
+
+        // Day 2 players keep their day 1 results and are ranked above everyone who was cut
+        for p in &standings[..4] {
+            assert_eq!(p.matches_played, 2);
+            assert_eq!(p.opponents.len(), 2);
+            assert!(p.match_points >= 3);
+        }
+
+        for p in &standings[4..] {
+            assert_eq!(p.matches_played, 1);
+            assert_eq!(p.match_points, 0);
+        }
+
+        assert_eq!(standings[0].match_points, 6);
+    }
+
+    #[test]
+    fn cut_top_and_reset() {
+        let swiss = Stage {
+            cut: Some(Cut::Top(2)),
+            max_byes: Some(2),
+            ..Stage::new(None)
+        };
+        let fin = Stage {
+            carry_standings: false,
+            percentage_floor: Some(PercentageFloor::None),
+            ..Stage::new(Some(1))
+        };
+
+        let mut players = players(4);
+        players[3].alias = Some(String::from("P4"));
+        players[3].rating = Some(1800.0);
+        players[3].club = Some(String::from("Rooks"));
+        players[3].age_group = Some(String::from("U18"));
+        let mut event = StagedEvent::new(players, vec![swiss, fin]).unwrap();
+        play(&mut event);
+
+        let standings = event.standings();
+        assert_eq!(standings.len(), 4);
+
+        // The final starts from scratch, so the finalists only have the one match on record
+        let winner = &standings[0];
+        assert_eq!(winner.name, "Player 4");
+        assert_eq!(winner.matches_played, 1);
+        assert_eq!(winner.opponents.len(), 1);
+        assert_eq!(winner.display_name(), "P4");
+        assert_eq!(winner.rating, Some(1800.0));
+        assert_eq!(winner.club.as_deref(), Some("Rooks"));
+        assert_eq!(winner.age_group.as_deref(), Some("U18"));
+
+        let runner_up = &standings[1];
+        assert_eq!(runner_up.matches_played, 1);
+        assert_eq!(runner_up.match_points, 0);
+
+        // Players cut after the Swiss keep their Swiss results
+        assert_eq!(standings[3].match_points, 0);
+        assert!(standings[2].matches_played > 1);
+
+        // The final changes the percentage floor and keeps the bye limit of the Swiss
+        assert_eq!(event.tournament().percentage_floor(), PercentageFloor::None);
+        assert_eq!(event.tournament().max_byes, 2);
+
+        assert_eq!(
+            StagedEvent::new(Vec::new(), Vec::new()).err(),
+            Some(StageError::NoStages)
+        );
+    }
+
+    #[test]
+    fn reset_keeps_avoided_pairs() {
+        let players = players(4);
+        let (a, b) = (players[0].uuid, players[1].uuid);
+        let stages = vec![
+            Stage::new(Some(1)),
+            Stage {
+                carry_standings: false,
+                ..Stage::new(Some(3))
+            },
+        ];
+        let mut event = StagedEvent::new(players, stages).unwrap();
+        event.tournament().avoid_pairing(a, b).unwrap();
+        play(&mut event);
+
+        // Four players can't play three rounds without a rematch, which is preferred over
+        // pairing players who must be kept apart
+        assert_eq!(event.current_stage(), 1);
+        let tourn = event.tournament();
+        for round in 1..=3 {
+            let pairings = tourn.round(round).unwrap().pairings;
+            assert!(pairings
+                .iter()
+                .all(|pair| (pair.home, pair.away) != (a, b) && (pair.home, pair.away) != (b, a)));
+        }
+    }
+}
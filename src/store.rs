@@ -0,0 +1,53 @@
+//! Recording tournaments while they are running. A `Store` is told about every change as it
+//! happens, so that a tournament can be recovered after a crash and queried after the event.
+//! Enable the `storage-sqlite` feature for a store backed by an SQLite database.
+
+#[cfg(feature = "storage-sqlite")]
+mod sqlite;
+
+#[cfg(feature = "storage-sqlite")]
+pub use sqlite::{SqliteStore, StoreError};
+
+use crate::Tournament;
+
+/// The result of a single match, as recorded by a store
+#[derive(Clone, Debug, PartialEq)]
+pub struct RecordedResult {
+    pub round: u32,
+    pub pairing: uuid::Uuid,
+    pub home: uuid::Uuid,
+    pub away: uuid::Uuid,
+    pub home_score: u8,
+    pub away_score: u8,
+    pub drawn: u8,
+}
+
+/// Storage that records a tournament as it happens. Every method that records something also
+/// records the current state of the whole tournament, so `recover()` always returns the
+/// tournament as of the last successful call.
+pub trait Store {
+    type Error: std::error::Error;
+
+    /// Records the players, e.g. after players were added or dropped
+    fn record_players(&mut self, tournament: &Tournament) -> Result<(), Self::Error>;
+
+    /// Records a round and its pairings. Call this after `Tournament::next_round()`.
+    fn record_round(&mut self, tournament: &Tournament) -> Result<(), Self::Error>;
+
+    /// Records the result of a match. Call this after `Tournament::end_match()` succeeded, with
+    /// the same arguments.
+    fn record_result(
+        &mut self,
+        tournament: &Tournament,
+        pairing: uuid::Uuid,
+        home_score: u8,
+        away_score: u8,
+        drawn: u8,
+    ) -> Result<(), Self::Error>;
+
+    /// Restores the tournament as it was last recorded, or `None` if nothing was recorded yet
+    fn recover(&self) -> Result<Option<Tournament>, Self::Error>;
+
+    /// Returns all recorded results in the order they were entered
+    fn results(&self) -> Result<Vec<RecordedResult>, Self::Error>;
+}
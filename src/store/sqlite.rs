@@ -0,0 +1,337 @@
+//! `Store` backed by an SQLite database. Besides a snapshot of the whole tournament used for
+//! recovery, players, rounds, pairings and results are kept in their own tables, so they can be
+//! queried with plain SQL after the event:
+//!
+//! ```sql
+//! players  (uuid, name, match_points, game_points, matches_played, matches_won, matches_drawn,
+//!           games_played, has_bye, first_round, last_round)
+//! rounds   (round, seed)
+//! pairings (uuid, round, home, away)
+//! results  (id, pairing, home_score, away_score, drawn)
+//! ```
+
+use super::{RecordedResult, Store};
+use crate::persist::LoadError;
+use crate::Tournament;
+use rusqlite::types::Type;
+use rusqlite::{params, Connection, OptionalExtension, Transaction};
+use std::fmt;
+use std::path::Path;
+
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS tournament (
+        id INTEGER PRIMARY KEY CHECK (id = 1),
+        rounds INTEGER NOT NULL,
+        current_round INTEGER NOT NULL,
+        state TEXT NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS players (
+        uuid TEXT PRIMARY KEY,
+        name TEXT NOT NULL,
+        match_points INTEGER NOT NULL,
+        game_points INTEGER NOT NULL,
+        matches_played INTEGER NOT NULL,
+        matches_won INTEGER NOT NULL,
+        matches_drawn INTEGER NOT NULL,
+        games_played INTEGER NOT NULL,
+        has_bye INTEGER NOT NULL,
+        first_round INTEGER NOT NULL,
+        last_round INTEGER
+    );
+    CREATE TABLE IF NOT EXISTS rounds (
+        round INTEGER PRIMARY KEY,
+        seed INTEGER
+    );
+    CREATE TABLE IF NOT EXISTS pairings (
+        uuid TEXT PRIMARY KEY,
+        round INTEGER NOT NULL,
+        home TEXT NOT NULL,
+        away TEXT NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS results (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        pairing TEXT NOT NULL REFERENCES pairings (uuid),
+        home_score INTEGER NOT NULL,
+        away_score INTEGER NOT NULL,
+        drawn INTEGER NOT NULL
+    );
+";
+
+/// Errors of the SQLite store, either from the database itself or from restoring the recorded
+/// tournament
+#[derive(Debug)]
+pub enum StoreError {
+    Sqlite(rusqlite::Error),
+    Load(LoadError),
+}
+
+impl std::error::Error for StoreError {}
+
+impl fmt::Display for StoreError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            StoreError::Sqlite(e) => write!(f, "Database error: {}", e),
+            StoreError::Load(e) => write!(f, "Could not restore tournament: {}", e),
+        }
+    }
+}
+
+impl From<rusqlite::Error> for StoreError {
+    fn from(e: rusqlite::Error) -> StoreError {
+        StoreError::Sqlite(e)
+    }
+}
+
+impl From<LoadError> for StoreError {
+    fn from(e: LoadError) -> StoreError {
+        StoreError::Load(e)
+    }
+}
+
+/// Records a tournament in an SQLite database. Every recording happens in a single transaction,
+/// so the database never holds a partially recorded change.
+///
+/// # Example
+///
+/// ```
+/// use swyss::store::{SqliteStore, Store};
+/// use swyss::{Player, Tournament};
+///
+/// let players = (1..5)
+///     .map(|i| Player::new(&format!("Player {}", i)))
+///     .collect();
+/// let mut tourn = Tournament::with_players(players).unwrap();
+/// let mut store = SqliteStore::open_in_memory().unwrap();
+///
+/// let pairings = tourn.next_round().unwrap().unwrap();
+/// store.record_round(&tourn).unwrap();
+///
+/// let (uuid, _, _) = pairings[0];
+/// tourn.end_match(uuid, 2, 1, 0).unwrap();
+/// store.record_result(&tourn, uuid, 2, 1, 0).unwrap();
+///
+/// let recovered = store.recover().unwrap().unwrap();
+/// assert_eq!(recovered.to_json(), tourn.to_json());
+/// assert_eq!(store.results().unwrap().len(), 1);
+/// ```
+pub struct SqliteStore {
+    conn: Connection,
+}
+
+impl SqliteStore {
+    /// Opens the database at `path`, creating it if it doesn't exist yet
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<SqliteStore, StoreError> {
+        SqliteStore::new(Connection::open(path)?)
+    }
+
+    /// Opens a database that only lives in memory, e.g. for testing
+    pub fn open_in_memory() -> Result<SqliteStore, StoreError> {
+        SqliteStore::new(Connection::open_in_memory()?)
+    }
+
+    fn new(conn: Connection) -> Result<SqliteStore, StoreError> {
+        conn.execute_batch(SCHEMA)?;
+        Ok(SqliteStore { conn })
+    }
+
+    /// The underlying connection, for queries beyond what `Store` offers
+    pub fn connection(&self) -> &Connection {
+        &self.conn
+    }
+}
+
+/// Replaces the snapshot of the tournament and all players
+fn write_state(tx: &Transaction, tournament: &Tournament) -> Result<(), rusqlite::Error> {
+    tx.execute(
+        "INSERT OR REPLACE INTO tournament (id, rounds, current_round, state)
+         VALUES (1, ?1, ?2, ?3)",
+        params![
+            tournament.rounds,
+            tournament.current_round,
+            tournament.to_json()
+        ],
+    )?;
+
+    tx.execute("DELETE FROM players", [])?;
+    for p in &tournament.players {
+        tx.execute(
+            "INSERT INTO players VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+            params![
+                p.uuid.to_string(),
+                p.name,
+                p.match_points,
+                p.game_points,
+                p.matches_played,
+                p.matches_won,
+                p.matches_drawn,
+                p.games_played,
+                p.has_bye,
+                p.availability.first_round,
+                p.availability.last_round
+            ],
+        )?;
+    }
+
+    Ok(())
+}
+
+fn uuid_column(row: &rusqlite::Row, i: usize) -> Result<uuid::Uuid, rusqlite::Error> {
+    let text: String = row.get(i)?;
+    uuid::Uuid::parse_str(&text)
+        .map_err(|e| rusqlite::Error::FromSqlConversionFailure(i, Type::Text, Box::new(e)))
+}
+
+impl Store for SqliteStore {
+    type Error = StoreError;
+
+    fn record_players(&mut self, tournament: &Tournament) -> Result<(), StoreError> {
+        let tx = self.conn.transaction()?;
+        write_state(&tx, tournament)?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn record_round(&mut self, tournament: &Tournament) -> Result<(), StoreError> {
+        let round = tournament.current_round;
+        let seed = tournament
+            .round_seeds()
+            .into_iter()
+            .find(|&(r, _)| r == round)
+            .map(|(_, seed)| seed as i64);
+
+        let tx = self.conn.transaction()?;
+        write_state(&tx, tournament)?;
+        tx.execute(
+            "INSERT OR REPLACE INTO rounds (round, seed) VALUES (?1, ?2)",
+            params![round, seed],
+        )?;
+        for pair in tournament.pairings.values() {
+            tx.execute(
+                "INSERT OR REPLACE INTO pairings (uuid, round, home, away) VALUES (?1, ?2, ?3, ?4)",
+                params![
+                    pair.uuid.to_string(),
+                    round,
+                    pair.home.to_string(),
+                    pair.away.to_string()
+                ],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn record_result(
+        &mut self,
+        tournament: &Tournament,
+        pairing: uuid::Uuid,
+        home_score: u8,
+        away_score: u8,
+        drawn: u8,
+    ) -> Result<(), StoreError> {
+        let tx = self.conn.transaction()?;
+        write_state(&tx, tournament)?;
+        tx.execute(
+            "INSERT INTO results (pairing, home_score, away_score, drawn) VALUES (?1, ?2, ?3, ?4)",
+            params![pairing.to_string(), home_score, away_score, drawn],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn recover(&self) -> Result<Option<Tournament>, StoreError> {
+        let state: Option<String> = self
+            .conn
+            .query_row("SELECT state FROM tournament WHERE id = 1", [], |row| {
+                row.get(0)
+            })
+            .optional()?;
+
+        match state {
+            Some(json) => Ok(Some(Tournament::from_json(&json)?)),
+            None => Ok(None),
+        }
+    }
+
+    fn results(&self) -> Result<Vec<RecordedResult>, StoreError> {
+        let mut stmt = self.conn.prepare(
+            "SELECT p.round, r.pairing, p.home, p.away, r.home_score, r.away_score, r.drawn
+             FROM results r JOIN pairings p ON p.uuid = r.pairing
+             ORDER BY r.id",
+        )?;
+
+        let results = stmt
+            .query_map([], |row| {
+                Ok(RecordedResult {
+                    round: row.get(0)?,
+                    pairing: uuid_column(row, 1)?,
+                    home: uuid_column(row, 2)?,
+                    away: uuid_column(row, 3)?,
+                    home_score: row.get(4)?,
+                    away_score: row.get(5)?,
+                    drawn: row.get(6)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(results)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Player;
+
+    fn players(n: u32) -> Vec<Player> {
+        (1..=n)
+            .map(|i| Player::new(format!("Player {}", i).as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn recover_after_crash() {
+        let path = std::env::temp_dir().join(format!("swyss-{}.sqlite", uuid::Uuid::new_v4()));
+        let mut tourn = Tournament::with_players(players(5)).unwrap();
+
+        {
+            let mut store = SqliteStore::open(&path).unwrap();
+            assert!(store.recover().unwrap().is_none());
+            store.record_players(&tourn).unwrap();
+
+            let pairings = tourn.next_round_with_seed(7).unwrap().unwrap();
+            store.record_round(&tourn).unwrap();
+            for (uuid, _, _) in &pairings {
+                tourn.end_match(*uuid, 2, 0, 1).unwrap();
+                store.record_result(&tourn, *uuid, 2, 0, 1).unwrap();
+            }
+        }
+
+        // Reopening the database picks up where the tournament left off
+        let store = SqliteStore::open(&path).unwrap();
+        let recovered = store.recover().unwrap().unwrap();
+        assert_eq!(recovered.to_json(), tourn.to_json());
+
+        let results = store.results().unwrap();
+        assert_eq!(results.len(), 2);
+        assert!(results.iter().all(|r| r.round == 1 && r.home_score == 2));
+
+        let seed: i64 = store
+            .connection()
+            .query_row("SELECT seed FROM rounds WHERE round = 1", [], |row| {
+                row.get(0)
+            })
+            .unwrap();
+        assert_eq!(seed, 7);
+
+        let byes: u32 = store
+            .connection()
+            .query_row("SELECT COUNT(*) FROM players WHERE has_bye", [], |row| {
+                row.get(0)
+            })
+            .unwrap();
+        assert_eq!(byes, 1);
+
+        drop(store);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
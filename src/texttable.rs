@@ -0,0 +1,187 @@
+//! Plain text tables for the terminal and text exports. Columns are as wide as their widest cell
+//! as it is displayed, so names with accents, emoji, or CJK characters line up like any other,
+//! and overlong names are cut short with an ellipsis instead of pushing the other columns out.
+
+use std::fmt;
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+/// Widest a column of names gets before names are truncated
+pub const MAX_NAME_WIDTH: usize = 24;
+
+/// Space between two columns
+const GAP: &str = "  ";
+
+/// Where the cells of a column are placed within it
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Align {
+    Left,
+    Right,
+}
+
+struct Column {
+    header: String,
+    align: Align,
+    max_width: Option<usize>,
+}
+
+/// A table of text with a header row, rendered with `to_string()`
+///
+/// # Example
+///
+/// ```
+/// use swyss::texttable::{Align, TextTable};
+///
+/// let mut table = TextTable::new(&[("Name", Align::Left), ("MP", Align::Right)]);
+/// table.push(vec![String::from("Zoë"), String::from("9")]);
+/// table.push(vec![String::from("李小龍"), String::from("12")]);
+///
+/// assert_eq!(table.to_string(), "Name    MP\nZoë      9\n李小龍  12\n");
+/// ```
+pub struct TextTable {
+    columns: Vec<Column>,
+    rows: Vec<Vec<String>>,
+}
+
+impl TextTable {
+    /// Creates an empty table with the given column headers and alignments
+    pub fn new(columns: &[(&str, Align)]) -> TextTable {
+        TextTable {
+            columns: columns
+                .iter()
+                .map(|&(header, align)| Column {
+                    header: String::from(header),
+                    align,
+                    max_width: None,
+                })
+                .collect(),
+            rows: Vec::new(),
+        }
+    }
+
+    /// Truncates the cells of a column, counted from 0, that are wider than `width`
+    pub fn set_max_width(&mut self, column: usize, width: usize) {
+        self.columns[column].max_width = Some(width);
+    }
+
+    /// Adds a row. Missing cells are left empty and surplus cells are ignored.
+    pub fn push(&mut self, row: Vec<String>) {
+        self.rows.push(row);
+    }
+
+    /// Returns the cells of a row as they are shown, truncated where necessary
+    fn cells<'a>(&'a self, row: &'a [String]) -> impl Iterator<Item = String> + 'a {
+        self.columns.iter().enumerate().map(move |(i, column)| {
+            let cell = row.get(i).map_or("", String::as_str);
+            match column.max_width {
+                Some(width) => truncate(cell, width),
+                None => String::from(cell),
+            }
+        })
+    }
+}
+
+impl fmt::Display for TextTable {
+    /// Renders the header and every row on a line of its own, without trailing spaces
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let header: Vec<String> = self.columns.iter().map(|c| c.header.clone()).collect();
+        let rows: Vec<Vec<String>> = std::iter::once(header)
+            .chain(self.rows.iter().map(|row| self.cells(row).collect()))
+            .collect();
+
+        let mut widths = vec![0; self.columns.len()];
+        for row in &rows {
+            for (width, cell) in widths.iter_mut().zip(row) {
+                *width = (*width).max(cell.width());
+            }
+        }
+
+        for row in &rows {
+            let cells: Vec<String> = row
+                .iter()
+                .zip(&self.columns)
+                .zip(&widths)
+                .map(|((cell, column), &width)| pad(cell, width, column.align))
+                .collect();
+            writeln!(f, "{}", cells.join(GAP).trim_end())?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns the width of a text in a terminal, where wide characters such as CJK take two columns
+pub fn display_width(text: &str) -> usize {
+    text.width()
+}
+
+/// Shortens a text to at most `width` columns, ending it with an ellipsis if anything was cut
+pub fn truncate(text: &str, width: usize) -> String {
+    if text.width() <= width {
+        return String::from(text);
+    }
+
+    let mut short = String::new();
+    let mut used = 0;
+    for c in text.chars() {
+        let w = c.width().unwrap_or(0);
+        if used + w + 1 > width {
+            break;
+        }
+        short.push(c);
+        used += w;
+    }
+    if width > 0 {
+        short.push('…');
+    }
+    short
+}
+
+/// Fills a text up to `width` columns with spaces
+pub fn pad(text: &str, width: usize, align: Align) -> String {
+    let fill = " ".repeat(width.saturating_sub(text.width()));
+    match align {
+        Align::Left => format!("{}{}", text, fill),
+        Align::Right => format!("{}{}", fill, text),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wide_and_long_names() {
+        assert_eq!(display_width("Zoë"), 3);
+        assert_eq!(display_width("李小龍"), 6);
+        assert_eq!(truncate("Bartholomew", 6), "Barth…");
+        assert_eq!(truncate("李小龍", 4), "李…");
+        assert_eq!(truncate("李小龍", 6), "李小龍");
+        assert_eq!(truncate("Bob", 0), "");
+
+        let mut table = TextTable::new(&[
+            ("#", Align::Right),
+            ("Name", Align::Left),
+            ("MP", Align::Right),
+        ]);
+        table.set_max_width(1, 8);
+        table.push(vec![
+            String::from("1"),
+            String::from("李小龍"),
+            String::from("9"),
+        ]);
+        table.push(vec![
+            String::from("10"),
+            String::from("Maximilian Mustermann"),
+            String::from("12"),
+        ]);
+        table.push(vec![String::from("11")]);
+
+        let out = table.to_string();
+        let lines: Vec<&str> = out.lines().collect();
+        assert_eq!(lines[0], " #  Name      MP");
+        assert_eq!(lines[1], " 1  李小龍     9");
+        assert_eq!(lines[2], "10  Maximil…  12");
+        assert_eq!(lines[3], "11");
+        assert!(lines[..3].iter().all(|line| display_width(line) == 16));
+    }
+}
@@ -0,0 +1,173 @@
+//! A live ticker of the tournament: one human-readable line per event, such as "R3: Alice 2-1
+//! Bob", written as it happens. Writing the ticker to a file lets streaming software like OBS
+//! show it as a text source.
+
+use crate::observer::TournamentObserver;
+use crate::{Player, Tournament};
+use core::cell::RefCell;
+use std::io::{self, Write};
+
+/// Observer that writes a line for every pairing, result, bye, drop, and the end of the
+/// tournament, naming players by their alias if they have one. Each line is flushed right away,
+/// so that readers of a file see it. Failed writes don't interrupt the tournament; they are kept
+/// until collected with `take_errors()`.
+///
+/// # Example
+///
+/// ```
+/// use std::rc::Rc;
+/// use swyss::ticker::Ticker;
+/// use swyss::{Player, Tournament};
+///
+/// let players = vec![
+///     Player::new("Alice"),
+///     Player::new("Bob"),
+/// ];
+/// let mut tourn = Tournament::with_players(players).unwrap();
+/// let ticker = Rc::new(Ticker::new(Vec::new()));
+/// tourn.add_observer(Box::new(Rc::clone(&ticker)));
+///
+/// let (uuid, home, away) = tourn.next_round().unwrap().unwrap().remove(0);
+/// tourn.end_match(uuid, 2, 1, 0).unwrap();
+///
+/// let lines = String::from_utf8(ticker.writer().clone()).unwrap();
+/// assert!(lines.ends_with(&format!("R1: {} 2-1 {}\n", home, away)));
+/// ```
+pub struct Ticker<W: Write> {
+    writer: RefCell<W>,
+    errors: RefCell<Vec<io::Error>>,
+}
+
+impl<W: Write> Ticker<W> {
+    pub fn new(writer: W) -> Ticker<W> {
+        Ticker {
+            writer: RefCell::new(writer),
+            errors: RefCell::new(Vec::new()),
+        }
+    }
+
+    /// Returns the writer the ticker writes to, e.g. to read back what was written to a buffer
+    pub fn writer(&self) -> std::cell::Ref<'_, W> {
+        self.writer.borrow()
+    }
+
+    /// Returns the errors of all failed writes since the last call
+    pub fn take_errors(&self) -> Vec<io::Error> {
+        self.errors.replace(Vec::new())
+    }
+
+    fn line(&self, line: &str) {
+        let mut writer = self.writer.borrow_mut();
+        let written = writeln!(writer, "{}", line).and_then(|_| writer.flush());
+        if let Err(e) = written {
+            self.errors.borrow_mut().push(e);
+        }
+    }
+}
+
+impl<W: Write> TournamentObserver for Ticker<W> {
+    fn on_round_paired(&self, tournament: &Tournament, pairings: &[(uuid::Uuid, String, String)]) {
+        for (uuid, _, _) in pairings {
+            if let Some((home, away)) = display_names(tournament, *uuid) {
+                self.line(&format!(
+                    "R{}: {} vs. {}",
+                    tournament.current_round, home, away
+                ));
+            }
+        }
+    }
+
+    fn on_result(
+        &self,
+        tournament: &Tournament,
+        pairing: uuid::Uuid,
+        home_score: u8,
+        away_score: u8,
+        drawn: u8,
+    ) {
+        let (home, away) = match display_names(tournament, pairing) {
+            Some(names) => names,
+            None => return,
+        };
+        let score = if drawn > 0 {
+            format!("{}-{}-{}", home_score, away_score, drawn)
+        } else {
+            format!("{}-{}", home_score, away_score)
+        };
+        self.line(&format!(
+            "R{}: {} {} {}",
+            tournament.current_round, home, score, away
+        ));
+    }
+
+    fn on_bye(&self, tournament: &Tournament, player: &Player) {
+        self.line(&format!(
+            "R{}: {} has a bye",
+            tournament.current_round,
+            player.display_name()
+        ));
+    }
+
+    fn on_drop(&self, _tournament: &Tournament, player: &Player) {
+        self.line(&format!("{} dropped", player.display_name()));
+    }
+
+    fn on_complete(&self, tournament: &Tournament) {
+        if let Some(winner) = tournament.sorted_players().first() {
+            self.line(&format!(
+                "Final: {} wins the tournament",
+                winner.display_name()
+            ));
+        }
+    }
+}
+
+/// Names the players of a pairing are shown with
+fn display_names(tournament: &Tournament, pairing: uuid::Uuid) -> Option<(&str, &str)> {
+    let pair = tournament.pairings.get(&pairing)?;
+    let home = tournament.players.opponent(pair.home)?;
+    let away = tournament.players.opponent(pair.away)?;
+    Some((home.display_name(), away.display_name()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::rc::Rc;
+
+    #[test]
+    fn whole_tournament() {
+        let players = ["Alice", "Bob", "Carol"]
+            .iter()
+            .map(|name| Player::new(name))
+            .collect();
+        let mut tourn = Tournament::with_players(players).unwrap();
+        tourn.players[0].alias = Some(String::from("Ace"));
+        let ticker = Rc::new(Ticker::new(Vec::new()));
+        tourn.add_observer(Box::new(Rc::clone(&ticker)));
+
+        while let Some(pairings) = tourn.next_round().unwrap() {
+            for (uuid, _, _) in pairings {
+                tourn.end_match(uuid, 1, 1, 1).unwrap();
+            }
+        }
+
+        let lines = String::from_utf8(ticker.writer().clone()).unwrap();
+        let lines: Vec<&str> = lines.lines().collect();
+        // Every round has a pairing, a bye, and a result
+        assert_eq!(lines.len(), 3 * tourn.rounds as usize + 1);
+        assert!(lines[0].starts_with("R1: ") && lines[0].contains(" vs. "));
+        assert!(lines[1].starts_with("R1: ") && lines[1].ends_with(" has a bye"));
+        assert!(lines[2].starts_with("R1: ") && lines[2].contains(" 1-1-1 "));
+        assert!(lines.last().unwrap().starts_with("Final: "));
+        // Players are named by their alias
+        let name = tourn
+            .players()
+            .find(|p| p.alias.is_some())
+            .map(|p| p.name)
+            .unwrap();
+        assert!(lines.iter().any(|line| line.contains("Ace")));
+        assert!(!lines.iter().any(|line| line.contains(&name)));
+        assert!(ticker.take_errors().is_empty());
+    }
+}
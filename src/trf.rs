@@ -0,0 +1,366 @@
+//! Importing events from FIDE's Tournament Report File (TRF), so that an event started in other
+//! pairing software can be continued in swyss. The player lines (`001`) give each player's name,
+//! rating, and the opponent, color, and result of every round played; the event's name (`012`),
+//! city (`022`), and start date (`042`) are carried over as well, and the planned number of rounds
+//! (`XXR`) if it is given.
+//!
+//! Games are imported as best-of-one matches with the white player as the home player. Forfeits
+//! are imported as concessions, and the pairing-allocated bye (`U`) as the bye of the round. Other
+//! byes and absences are imported as requested byes worth a win (`F`, `+`), a draw (`H`), or
+//! nothing (`Z`, `-`, or no entry). Only the last round may have games without a result, which
+//! are left outstanding to be reported in swyss.
+
+use crate::preview::RoundPreview;
+use crate::{registration, ByeScore, MatchFormat, Player, PlayerSide, Tournament};
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// Importing a TRF file fails if it can't be read, a line is malformed, or a round can't be
+/// replayed as recorded
+#[derive(Debug)]
+pub enum TrfError {
+    Io(io::Error),
+    /// The line, counted from 1, and what is wrong with it
+    Line(usize, String),
+    /// The round and what is wrong with it
+    Round(u32, String),
+}
+
+impl std::error::Error for TrfError {}
+
+impl fmt::Display for TrfError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TrfError::Io(e) => write!(f, "Could not read TRF file: {}", e),
+            TrfError::Line(line, message) => write!(f, "Line {}: {}", line, message),
+            TrfError::Round(round, message) => write!(f, "Round {}: {}", round, message),
+        }
+    }
+}
+
+impl From<io::Error> for TrfError {
+    fn from(e: io::Error) -> TrfError {
+        TrfError::Io(e)
+    }
+}
+
+/// One round of a player line: the starting rank of the opponent, or 0 for none, the color, and
+/// the result, each as written
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Entry {
+    opponent: usize,
+    color: char,
+    result: char,
+}
+
+/// A player line
+struct TrfPlayer {
+    rank: usize,
+    player: Player,
+    rounds: Vec<Option<Entry>>,
+}
+
+impl Tournament {
+    /// Reads a tournament from a TRF file, with every round played so far replayed as recorded
+    pub fn from_trf<P: AsRef<Path>>(path: P) -> Result<Tournament, TrfError> {
+        parse(&fs::read_to_string(path)?)
+    }
+}
+
+/// Parses a tournament in TRF form, replaying every round played so far
+pub fn parse(text: &str) -> Result<Tournament, TrfError> {
+    let mut players: Vec<TrfPlayer> = Vec::new();
+    let mut event = crate::event::EventInfo::default();
+    let mut rounds = None;
+
+    for (i, line) in text.lines().enumerate() {
+        let error = |message: String| TrfError::Line(i + 1, message);
+        let value = || Some(String::from(field(line, 5, line.chars().count())));
+        match line.get(..3) {
+            Some("001") => {
+                let player = player_line(line).map_err(error)?;
+                if players.iter().any(|p| p.rank == player.rank) {
+                    let message = format!("The starting rank {} is used twice", player.rank);
+                    return Err(error(message));
+                }
+                players.push(player);
+            }
+            Some("012") => event.name = value(),
+            Some("022") => event.location = value(),
+            Some("042") => event.date = value(),
+            Some("XXR") => match field(line, 5, line.chars().count()).parse::<u32>() {
+                Ok(number) => rounds = Some(number),
+                Err(_) => return Err(error(String::from("The number of rounds is not a number"))),
+            },
+            _ => {}
+        }
+    }
+
+    players.sort_by_key(|p| p.rank);
+    let played = players.iter().map(|p| p.rounds.len()).max().unwrap_or(0) as u32;
+
+    let mut tourn = Tournament::from_arena(players.iter().map(|p| p.player.clone()).collect());
+    tourn.match_format = MatchFormat::BestOf(1);
+    tourn.bye_score = ByeScore::Win;
+    tourn.event = event;
+    tourn.rounds = rounds
+        .unwrap_or_else(|| registration::default_rounds(players.len()))
+        .max(played);
+
+    for round in 1..=played {
+        replay(&mut tourn, &players, round, round == played)?;
+    }
+
+    Ok(tourn)
+}
+
+/// Pairs a round as recorded and enters its results. Only the last round may lack results.
+fn replay(
+    tourn: &mut Tournament,
+    players: &[TrfPlayer],
+    round: u32,
+    last: bool,
+) -> Result<(), TrfError> {
+    let error = |message: String| TrfError::Round(round, message);
+    let entry_of = |p: &TrfPlayer| p.rounds.get(round as usize - 1).copied().flatten();
+    let by_rank = |rank: usize| players.iter().find(|p| p.rank == rank);
+    let uuid = |p: &TrfPlayer| p.player.uuid;
+
+    let mut preview = RoundPreview {
+        round,
+        seed: 0,
+        pairings: Vec::new(),
+        bye: None,
+    };
+    let mut games = Vec::new();
+    for p in players {
+        let entry = match entry_of(p) {
+            Some(entry) if entry.opponent > 0 => entry,
+            unpaired => {
+                let score = match unpaired.map(|e| e.result) {
+                    Some('U') if preview.bye.is_none() => {
+                        preview.bye = Some(uuid(p));
+                        continue;
+                    }
+                    Some('U') => {
+                        return Err(error(String::from("More than one player got the bye")))
+                    }
+                    Some('F') | Some('+') => ByeScore::Win,
+                    Some('H') => ByeScore::Draw,
+                    _ => ByeScore::Loss,
+                };
+                tourn
+                    .request_bye(uuid(p), round, score)
+                    .map_err(|e| error(e.to_string()))?;
+                continue;
+            }
+        };
+
+        let opponent = by_rank(entry.opponent).ok_or_else(|| {
+            error(format!(
+                "Player {} has no opponent {}",
+                p.rank, entry.opponent
+            ))
+        })?;
+        let mirrored = entry_of(opponent).is_some_and(|e| {
+            e.opponent == p.rank && matches!((entry.color, e.color), ('w', 'b') | ('b', 'w'))
+        });
+        if !mirrored {
+            return Err(error(format!(
+                "Players {} and {} don't list each other with opposite colors",
+                p.rank, opponent.rank
+            )));
+        }
+        if entry.color == 'w' {
+            preview.pairings.push((uuid(p), uuid(opponent)));
+            games.push((uuid(p), uuid(opponent), entry.result));
+        }
+    }
+
+    tourn
+        .publish_round(&preview)
+        .map_err(|e| error(e.to_string()))?;
+    let pairings = tourn.round(round).map(|r| r.pairings).unwrap_or_default();
+    for (home, away, result) in games {
+        let pair = pairings
+            .iter()
+            .find(|pair| pair.home == home && pair.away == away)
+            .expect("every game was paired as recorded");
+        let reported = match result {
+            '1' | 'W' => tourn.end_match(pair.uuid, 1, 0, 0),
+            '0' | 'L' => tourn.end_match(pair.uuid, 0, 1, 0),
+            '=' | 'D' => tourn.end_match(pair.uuid, 0, 0, 1),
+            '+' => tourn.concede(pair.uuid, PlayerSide::Away).map(|_| ()),
+            '-' => tourn.concede(pair.uuid, PlayerSide::Home).map(|_| ()),
+            ' ' | '*' if last => Ok(()),
+            ' ' | '*' => return Err(error(String::from("A game has no result"))),
+            other => return Err(error(format!("Unknown result \"{}\"", other))),
+        };
+        reported.map_err(|e| error(e.to_string()))?;
+    }
+
+    Ok(())
+}
+
+/// Parses a player line
+fn player_line(line: &str) -> Result<TrfPlayer, String> {
+    let len = line.chars().count();
+    let rank = field(line, 5, 8);
+    let rank = rank
+        .parse::<usize>()
+        .map_err(|_| format!("The starting rank \"{}\" is not a number", rank))?;
+    let name = field(line, 15, 47);
+    if name.is_empty() {
+        return Err(String::from("The name is missing"));
+    }
+    let mut player = Player::new(name);
+    player.rating = match field(line, 49, 52) {
+        "" | "0" => None,
+        rating => match rating.parse::<u32>() {
+            Ok(r) => Some(f64::from(r)),
+            Err(_) => return Err(format!("The rating \"{}\" is not a number", rating)),
+        },
+    };
+
+    let mut rounds = Vec::new();
+    let mut start = 92;
+    while start <= len {
+        let opponent = field(line, start, start + 3);
+        let color = field(line, start + 5, start + 5)
+            .chars()
+            .next()
+            .unwrap_or('-');
+        let result = field(line, start + 7, start + 7)
+            .chars()
+            .next()
+            .unwrap_or(' ');
+        rounds.push(if opponent.is_empty() && color == '-' && result == ' ' {
+            None
+        } else {
+            let opponent = match opponent {
+                "" => 0,
+                opponent => opponent
+                    .parse::<usize>()
+                    .map_err(|_| format!("The opponent \"{}\" is not a number", opponent))?,
+            };
+            Some(Entry {
+                opponent,
+                color,
+                result,
+            })
+        });
+        start += 10;
+    }
+    // Rounds without an entry at the end haven't been played yet
+    while rounds.last() == Some(&None) {
+        rounds.pop();
+    }
+
+    Ok(TrfPlayer {
+        rank,
+        player,
+        rounds,
+    })
+}
+
+/// The columns from `first` to `last` of a line, counted from 1, trimmed
+fn field(line: &str, first: usize, last: usize) -> &str {
+    let offset = |column: usize| {
+        line.char_indices()
+            .nth(column)
+            .map_or(line.len(), |(i, _)| i)
+    };
+    let start = offset(first - 1);
+    line[start..offset(last).max(start)].trim()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A player line with the given rounds of opponent, color, and result
+    fn line(rank: usize, name: &str, rating: u32, rounds: &[(usize, char, char)]) -> String {
+        let mut line = format!(
+            "001 {:>4} m{:>3} {:<33} {:>4} {:>3} {:>11} {:>10} {:>4} {:>4}  ",
+            rank, "", name, rating, "GER", 0, "", "0.0", rank
+        );
+        for (opponent, color, result) in rounds {
+            line.push_str(&format!("{:>4} {} {}  ", opponent, color, result));
+        }
+        line
+    }
+
+    #[test]
+    fn continue_after_import() {
+        let text = [
+            String::from("012 Club Championship"),
+            String::from("042 2024/05/18"),
+            String::from("XXR 3"),
+            line(1, "Müller, Anna", 2000, &[(4, 'w', '1'), (3, 'b', '=')]),
+            line(2, "Bauer, Ben", 1900, &[(0, '-', 'U'), (4, 'w', ' ')]),
+            line(3, "Schmidt, Carl", 1800, &[(5, 'w', '+'), (1, 'w', '=')]),
+            line(4, "Weber, Dora", 1700, &[(1, 'b', '0'), (2, 'b', ' ')]),
+            line(5, "Fischer, Emil", 1600, &[(3, 'b', '-'), (0, '-', 'H')]),
+        ]
+        .join("\n");
+
+        let mut tourn = parse(&text).unwrap();
+        assert_eq!(tourn.event.name.as_deref(), Some("Club Championship"));
+        assert_eq!((tourn.current_round, tourn.rounds), (2, 3));
+        let points = |name: &str| {
+            tourn
+                .players()
+                .find(|p| p.name == name)
+                .unwrap()
+                .match_points
+        };
+        // Anna won and drew, Ben got the bye, Carl won by forfeit and drew, Dora lost, and Emil
+        // forfeited and took a half-point bye
+        assert_eq!(points("Müller, Anna"), 4);
+        assert_eq!(points("Bauer, Ben"), 3);
+        assert_eq!(points("Schmidt, Carl"), 4);
+        assert_eq!(points("Weber, Dora"), 0);
+        assert_eq!(points("Fischer, Emil"), 1);
+        let first = tourn.round(1).unwrap();
+        let conceded: Vec<_> = first
+            .pairings
+            .iter()
+            .filter_map(|pair| pair.result.unwrap().conceded)
+            .collect();
+        assert_eq!(conceded, [PlayerSide::Away]);
+
+        // The game of round 2 without a result is reported in swyss
+        let status = tourn.round_status();
+        assert_eq!(status.outstanding.len(), 1);
+        tourn.end_match(status.outstanding[0], 1, 0, 0).unwrap();
+        assert_eq!(tourn.next_round().unwrap().unwrap().len(), 2);
+
+        let error = |text: &str| parse(text).err().unwrap().to_string();
+        assert_eq!(
+            error(
+                &[
+                    line(1, "A", 0, &[(2, 'w', '1')]),
+                    line(2, "B", 0, &[(1, 'w', '0')])
+                ]
+                .join("\n")
+            ),
+            "Round 1: Players 1 and 2 don't list each other with opposite colors"
+        );
+        assert_eq!(
+            error(
+                &[
+                    line(1, "A", 0, &[(2, 'w', ' '), (2, 'w', '1')]),
+                    line(2, "B", 0, &[(1, 'b', ' '), (1, 'b', '0')])
+                ]
+                .join("\n")
+            ),
+            "Round 1: A game has no result"
+        );
+        assert_eq!(
+            error("001 abcd"),
+            "Line 1: The starting rank \"abcd\" is not a number"
+        );
+    }
+}
@@ -0,0 +1,107 @@
+//! Swiss variants for short events, which pair strictly by rank instead of by score groups: the
+//! Danish system pairs first against second, third against fourth, and so on, allowing rematches,
+//! while the Monrad system pairs each player from the top with the next ranked player they haven't
+//! met yet. Both rank the players by match points and tiebreakers, and by rating or at random in
+//! the first round.
+
+use crate::arena::PlayerArena;
+use crate::oracle::PlayerPair;
+use crate::{sort_by_rating, sort_by_tiebreakers, Player, Tournament};
+use rand::seq::SliceRandom;
+
+impl Tournament {
+    /// Pairs the active players by rank, avoiding rematches if `avoid_rematches` is set and
+    /// pairing neighbours in the ranking regardless of previous rounds otherwise. Players who must
+    /// avoid each other are only paired if nobody else is left. Opponents are looked up in
+    /// `players` for the tiebreakers.
+    pub(crate) fn pair_by_rank<'a>(
+        &mut self,
+        active: &[&'a Player],
+        players: &PlayerArena,
+        avoid_rematches: bool,
+    ) -> Vec<PlayerPair<'a>> {
+        let (mut pairs, mut queue) = self.take_preferred_pairs(active);
+        queue.shuffle(&mut self.rng);
+        sort_by_rating(&mut queue);
+        // The sort is stable, so players tied on everything stay in rating order
+        sort_by_tiebreakers(&mut queue, players, &self.tiebreakers);
+        debug_event!(players = queue.len(), avoid_rematches, "pairing by rank");
+
+        while queue.len() >= 2 {
+            let home = queue.remove(0);
+            let allowed = |away: &&Player| !home.avoids(away);
+            let i = if avoid_rematches {
+                queue
+                    .iter()
+                    .position(|away| allowed(away) && !home.opponents.contains(&away.uuid))
+                    .or_else(|| queue.iter().position(allowed))
+            } else {
+                queue.iter().position(allowed)
+            };
+            let i = i.unwrap_or(0);
+            let away = queue.remove(i);
+            pairs.push((home, away));
+        }
+
+        pairs
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{PairingSystem, Player, Tournament};
+    use std::collections::{HashMap, HashSet};
+
+    /// Plays three rounds in which the higher rated player always wins and returns the pairs of
+    /// the third round by rating
+    fn third_round(system: PairingSystem) -> HashSet<(u32, u32)> {
+        let players: Vec<_> = (1..5)
+            .map(|i| {
+                let mut player = Player::new(format!("Player {}", i).as_str());
+                player.rating = Some(f64::from(2100 - 100 * i));
+                player
+            })
+            .collect();
+        let ratings: HashMap<uuid::Uuid, u32> = players
+            .iter()
+            .map(|p| (p.uuid, p.rating.unwrap() as u32))
+            .collect();
+        let rating = |uuid: uuid::Uuid| ratings[&uuid];
+        let mut tourn = Tournament::with_players(players).unwrap();
+        tourn.pairing_system = system;
+        tourn.rounds = 3;
+
+        for round in 1..4 {
+            tourn.next_round_with_seed(round).unwrap().unwrap();
+            let pairings = tourn.round(round as u32).unwrap().pairings;
+            if round == 3 {
+                return pairings
+                    .iter()
+                    .map(|pair| {
+                        let (home, away) = (rating(pair.home), rating(pair.away));
+                        (home.max(away), home.min(away))
+                    })
+                    .collect();
+            }
+            for pair in pairings {
+                if rating(pair.home) > rating(pair.away) {
+                    tourn.end_match(pair.uuid, 2, 0, 0).unwrap();
+                } else {
+                    tourn.end_match(pair.uuid, 0, 2, 0).unwrap();
+                }
+            }
+        }
+        unreachable!()
+    }
+
+    #[test]
+    fn danish_and_monrad() {
+        // After 2000-1900, 1800-1700, then 2000-1800, 1900-1700, the ranking is 2000, 1900, 1800,
+        // 1700, the players with 3 points being tied on everything but their rating
+        let danish: HashSet<_> = vec![(2000, 1900), (1800, 1700)].into_iter().collect();
+        assert_eq!(third_round(PairingSystem::Danish), danish);
+        let monrad: HashSet<_> = vec![(2000, 1700), (1900, 1800)].into_iter().collect();
+        assert_eq!(third_round(PairingSystem::Monrad), monrad);
+        assert_eq!("monrad".parse(), Ok(PairingSystem::Monrad));
+    }
+}
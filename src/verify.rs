@@ -0,0 +1,392 @@
+//! Consistency checks of a tournament's state, meant for tests: the rounds played so far are
+//! checked against each other and against the players' records. Call
+//! `Tournament::check_invariants()` after driving a tournament through any sequence of operations.
+
+use crate::history::Round;
+use crate::{ByeScore, MatchFormat, Player, Tournament};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+/// Largest number of players in a round for which rematches are checked. Finding the fewest
+/// possible rematches takes time exponential in the number of players, so rematches in larger
+/// rounds are not checked.
+pub const MAX_REMATCH_CHECK: usize = 16;
+
+/// A broken invariant, as found by `Tournament::check_invariants()`
+#[derive(Clone, Debug, PartialEq)]
+pub enum Violation {
+    /// The player was paired more than once in the round, or paired while having a bye
+    PairedTwice { round: u32, player: uuid::Uuid },
+    /// The round has more rematches than the fewest possible for its players
+    AvoidableRematch {
+        round: u32,
+        rematches: u32,
+        fewest: u32,
+    },
+    /// The player received a bye in each of these rounds, more than `Tournament::max_byes`
+    MultipleByes {
+        player: uuid::Uuid,
+        rounds: Vec<u32>,
+    },
+    /// One of the player's counters doesn't match the results recorded for them
+    RecordMismatch {
+        player: uuid::Uuid,
+        counter: &'static str,
+        recorded: u32,
+        expected: u32,
+    },
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Violation::PairedTwice { round, player } => {
+                write!(f, "Player {} paired twice in round {}", player, round)
+            }
+            Violation::AvoidableRematch {
+                round,
+                rematches,
+                fewest,
+            } => write!(
+                f,
+                "Round {} has {} rematch(es) where {} would have been possible",
+                round, rematches, fewest
+            ),
+            Violation::MultipleByes { player, rounds } => {
+                write!(f, "Player {} received byes in rounds {:?}", player, rounds)
+            }
+            Violation::RecordMismatch {
+                player,
+                counter,
+                recorded,
+                expected,
+            } => write!(
+                f,
+                "Player {} has {} {} but their results add up to {}",
+                player, recorded, counter, expected
+            ),
+        }
+    }
+}
+
+/// The counters of a player's record that can be derived from their results
+#[derive(Default)]
+struct Record {
+    match_points: u32,
+    matches_played: u32,
+    game_points: u32,
+    games_played: u32,
+}
+
+impl Record {
+    fn add_match(&mut self, won: u8, lost: u8, drawn: u8) {
+        self.matches_played += 1;
+        self.match_points += match won.cmp(&lost) {
+            std::cmp::Ordering::Greater => 3,
+            std::cmp::Ordering::Equal => 1,
+            std::cmp::Ordering::Less => 0,
+        };
+        self.games_played += u32::from(won) + u32::from(lost) + u32::from(drawn);
+        self.game_points += 3 * u32::from(won) + u32::from(drawn);
+    }
+
+    fn add_double_loss(&mut self) {
+        self.matches_played += 1;
+    }
+
+    fn add_bye(&mut self, score: ByeScore, format: MatchFormat) {
+        let (won, drawn) = score.games(format);
+        self.matches_played += 1;
+        self.match_points += match score {
+            ByeScore::Win => 3,
+            ByeScore::Draw => 1,
+            ByeScore::Loss => 0,
+        };
+        self.games_played += u32::from(won) + u32::from(drawn);
+        self.game_points += 3 * u32::from(won) + u32::from(drawn);
+    }
+
+    fn mismatches(&self, p: &Player) -> Vec<Violation> {
+        let counters = [
+            ("match points", p.match_points, self.match_points),
+            ("matches played", p.matches_played, self.matches_played),
+            ("game points", p.game_points, self.game_points),
+            ("games played", p.games_played, self.games_played),
+        ];
+
+        counters
+            .iter()
+            .filter(|(_, recorded, expected)| recorded != expected)
+            .map(|&(counter, recorded, expected)| Violation::RecordMismatch {
+                player: p.uuid,
+                counter,
+                recorded,
+                expected,
+            })
+            .collect()
+    }
+}
+
+/// Fewest rematches any pairing of the players could have, given who already played whom. The
+/// players are the bits of `remaining`; `met[i]` has bit j set if players i and j already met.
+fn fewest_rematches(remaining: u32, met: &[u32], memo: &mut HashMap<u32, u32>) -> u32 {
+    if remaining.count_ones() < 2 {
+        return 0;
+    }
+    if let Some(&fewest) = memo.get(&remaining) {
+        return fewest;
+    }
+
+    // The lowest player has to be paired with someone, so trying all their opponents covers
+    // every pairing
+    let first = remaining.trailing_zeros() as usize;
+    let rest = remaining & !(1 << first);
+    let fewest = (0..met.len())
+        .filter(|j| rest & (1 << j) != 0)
+        .map(|j| ((met[first] >> j) & 1) + fewest_rematches(rest & !(1 << j), met, memo))
+        .min()
+        .unwrap_or(0);
+
+    memo.insert(remaining, fewest);
+    fewest
+}
+
+/// Checks that no player was paired twice in the round, and that it doesn't have more rematches
+/// than necessary
+fn check_round(round: &Round, met: &HashSet<(uuid::Uuid, uuid::Uuid)>) -> Vec<Violation> {
+    let mut violations = Vec::new();
+    let mut seen: HashSet<uuid::Uuid> = round.bye.iter().copied().collect();
+    seen.extend(round.requested_byes.iter().map(|bye| bye.player));
+
+    for pair in &round.pairings {
+        for &player in &[pair.home, pair.away] {
+            if !seen.insert(player) {
+                violations.push(Violation::PairedTwice {
+                    round: round.number,
+                    player,
+                });
+            }
+        }
+    }
+
+    let players: Vec<_> = round
+        .pairings
+        .iter()
+        .flat_map(|pair| vec![pair.home, pair.away])
+        .collect();
+    if !violations.is_empty() || players.len() > MAX_REMATCH_CHECK {
+        return violations;
+    }
+
+    let rematches = round
+        .pairings
+        .iter()
+        .filter(|pair| met.contains(&(pair.home, pair.away)))
+        .count() as u32;
+
+    if rematches > 0 {
+        let met: Vec<u32> = players
+            .iter()
+            .map(|a| {
+                players
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, b)| met.contains(&(*a, **b)))
+                    .fold(0, |bits, (j, _)| bits | 1 << j)
+            })
+            .collect();
+        let all = (1u32 << players.len()) - 1;
+        let fewest = fewest_rematches(all, &met, &mut HashMap::new());
+
+        if rematches > fewest {
+            violations.push(Violation::AvoidableRematch {
+                round: round.number,
+                rematches,
+                fewest,
+            });
+        }
+    }
+
+    violations
+}
+
+impl Tournament {
+    /// Checks the rounds played so far and the players' records for consistency:
+    ///
+    /// * No player is paired twice in a round
+    /// * No round has more rematches than necessary, for rounds of up to `MAX_REMATCH_CHECK`
+    ///   players
+    /// * No player receives more byes than `max_byes`
+    /// * Every player's match and game points and the number of matches and games played add up
+    ///   to their recorded results and byes
+    ///
+    /// The last check assumes that players entered the tournament without results, so it doesn't
+    /// apply to stages that carry standings over. Returns all violations found.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use swyss::{Player, Tournament};
+    ///
+    /// let players = (1..=6)
+    ///     .map(|i| Player::new(&format!("Player {}", i)))
+    ///     .collect();
+    /// let mut tourn = Tournament::with_players(players).unwrap();
+    ///
+    /// while let Some(pairings) = tourn.next_round().unwrap() {
+    ///     for (uuid, _, _) in pairings {
+    ///         tourn.end_match(uuid, 2, 1, 0).unwrap();
+    ///     }
+    ///     assert_eq!(tourn.check_invariants(), Ok(()));
+    /// }
+    /// ```
+    pub fn check_invariants(&self) -> Result<(), Vec<Violation>> {
+        let mut violations = Vec::new();
+        let mut met = HashSet::new();
+        let mut byes: HashMap<uuid::Uuid, Vec<u32>> = HashMap::new();
+        let mut records: HashMap<uuid::Uuid, Record> = HashMap::new();
+
+        for round in self.round_history() {
+            violations.extend(check_round(&round, &met));
+
+            for pair in &round.pairings {
+                met.insert((pair.home, pair.away));
+                met.insert((pair.away, pair.home));
+
+                match pair.result {
+                    Some(result) if result.double_loss => {
+                        records.entry(pair.home).or_default().add_double_loss();
+                        records.entry(pair.away).or_default().add_double_loss();
+                    }
+                    Some(result) => {
+                        records.entry(pair.home).or_default().add_match(
+                            result.home_score,
+                            result.away_score,
+                            result.drawn,
+                        );
+                        records.entry(pair.away).or_default().add_match(
+                            result.away_score,
+                            result.home_score,
+                            result.drawn,
+                        );
+                    }
+                    None => {}
+                }
+            }
+
+            if let Some(bye) = round.bye {
+                byes.entry(bye).or_default().push(round.number);
+                records
+                    .entry(bye)
+                    .or_default()
+                    .add_bye(round.bye_score, self.match_format);
+            }
+
+            for bye in &round.requested_byes {
+                records
+                    .entry(bye.player)
+                    .or_default()
+                    .add_bye(bye.score, self.match_format);
+            }
+        }
+
+        for (player, rounds) in byes {
+            if rounds.len() > self.max_byes as usize {
+                violations.push(Violation::MultipleByes { player, rounds });
+            }
+        }
+
+        for p in &self.players {
+            let record = records.remove(&p.uuid).unwrap_or_default();
+            violations.extend(record.mismatches(p));
+        }
+
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            Err(violations)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::collection;
+    use proptest::prelude::{any, prop_assert_eq, proptest};
+
+    fn players(n: usize) -> Vec<Player> {
+        (1..=n)
+            .map(|i| Player::new(format!("Player {}", i).as_str()))
+            .collect()
+    }
+
+    proptest! {
+        #[test]
+        fn random_tournaments_are_consistent(
+            n in 2..14usize,
+            results in collection::vec(any::<u8>(), 1..100),
+            drops in collection::vec(any::<u8>(), 0..4),
+        ) {
+            let mut tourn = Tournament::with_players(players(n)).unwrap();
+            tourn.rounds = tourn.rounds.max(3);
+            let mut results = results.iter().cycle();
+
+            while let Some(pairings) = tourn.next_round().unwrap() {
+                for (uuid, _, _) in pairings {
+                    // Any valid best-of-three result: 2-0, 2-1, 1-1, 1-0-1 and so on
+                    let score = results.next().unwrap();
+                    let (home, away, drawn) = match score % 6 {
+                        0 => (2, 0, 0),
+                        1 => (2, 1, 0),
+                        2 => (0, 2, 0),
+                        3 => (1, 2, 0),
+                        4 => (1, 1, 0),
+                        _ => (1, 0, 1),
+                    };
+                    tourn.end_match(uuid, home, away, drawn).unwrap();
+                }
+
+                let drop = drops.get(tourn.current_round as usize);
+                if let (Some(drop), true) = (drop, tourn.players.len() > 2) {
+                    let uuid = tourn.players[*drop as usize % tourn.players.len()].uuid;
+                    tourn.drop_player(uuid).unwrap();
+                }
+
+                prop_assert_eq!(tourn.check_invariants(), Ok(()));
+            }
+        }
+    }
+
+    #[test]
+    fn broken_record_is_found() {
+        let mut tourn = Tournament::with_players(players(3)).unwrap();
+        let (uuid, _, _) = tourn.next_round().unwrap().unwrap()[0];
+        tourn.end_match(uuid, 2, 0, 0).unwrap();
+        assert_eq!(tourn.check_invariants(), Ok(()));
+
+        let bye = tourn.round(1).unwrap().bye.unwrap();
+        tourn.players.get_mut(bye).unwrap().match_points += 1;
+
+        assert_eq!(
+            tourn.check_invariants(),
+            Err(vec![Violation::RecordMismatch {
+                player: bye,
+                counter: "match points",
+                recorded: 4,
+                expected: 3,
+            }])
+        );
+    }
+
+    #[test]
+    fn fewest_rematches_of_four() {
+        // Players 0 and 1 met, and so did 2 and 3: pairing 0-2 and 1-3 avoids both rematches
+        let met = [0b0010, 0b0001, 0b1000, 0b0100];
+        assert_eq!(fewest_rematches(0b1111, &met, &mut HashMap::new()), 0);
+
+        // Everyone met everyone
+        let met = [0b1110, 0b1101, 0b1011, 0b0111];
+        assert_eq!(fewest_rematches(0b1111, &met, &mut HashMap::new()), 2);
+    }
+}
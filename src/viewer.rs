@@ -0,0 +1,385 @@
+//! Image viewers for image tournaments, where the two images of a pairing are shown side by side
+//! while the result is entered. Which program is used depends on the platform and can be chosen
+//! in the configuration file. With the `integrated-viewer` feature, swyss can also show both
+//! images in a window of its own and take votes with the keys 1 and 2.
+
+use crate::PlayerSide;
+use serde::{Deserialize, Serialize};
+use std::io;
+use std::path::Path;
+use std::process::{Child, Command, Stdio};
+use std::str::FromStr;
+
+/// The available viewer backends
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub enum ViewerKind {
+    /// `feh`, with both images placed next to each other on a 1920x1080 screen
+    Feh,
+    /// The desktop's default viewer on Linux and BSD, through `xdg-open`
+    XdgOpen,
+    /// Preview on macOS
+    Preview,
+    /// The default viewer on Windows
+    Windows,
+    /// Both images in one window of swyss itself, voting with the keys 1 and 2
+    #[cfg(feature = "integrated-viewer")]
+    Integrated,
+}
+
+impl Default for ViewerKind {
+    fn default() -> ViewerKind {
+        if cfg!(target_os = "macos") {
+            ViewerKind::Preview
+        } else if cfg!(windows) {
+            ViewerKind::Windows
+        } else {
+            ViewerKind::Feh
+        }
+    }
+}
+
+impl FromStr for ViewerKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<ViewerKind, String> {
+        match s.to_lowercase().as_str() {
+            "feh" => Ok(ViewerKind::Feh),
+            "xdg-open" => Ok(ViewerKind::XdgOpen),
+            "preview" => Ok(ViewerKind::Preview),
+            "windows" => Ok(ViewerKind::Windows),
+            #[cfg(feature = "integrated-viewer")]
+            "integrated" => Ok(ViewerKind::Integrated),
+            _ => Err(format!("Unknown image viewer: {}", s)),
+        }
+    }
+}
+
+/// Shows images of a pairing. The home player's image is shown on the left and the away
+/// player's image on the right, where the viewer supports placing windows.
+pub trait ImageViewer {
+    /// Opens an image for one side of the pairing
+    fn show(&mut self, image: &Path, side: PlayerSide) -> io::Result<()>;
+
+    /// Closes the images opened so far, if the viewer supports it, and waits for the processes
+    /// it started to exit
+    fn close_all(&mut self) -> io::Result<()>;
+
+    /// Lets the user pick the better of the two images shown right in the viewer. Returns `None`
+    /// if the viewer doesn't support voting or the user left without voting, in which case the
+    /// result has to be entered some other way.
+    fn vote(&mut self) -> io::Result<Option<PlayerSide>> {
+        Ok(None)
+    }
+}
+
+/// Returns the viewer for the given backend
+pub fn viewer(kind: ViewerKind) -> Box<dyn ImageViewer> {
+    match kind {
+        ViewerKind::Feh => Box::new(Feh::new()),
+        ViewerKind::XdgOpen => Box::new(Opener::new("xdg-open", &[])),
+        ViewerKind::Preview => Box::new(Opener::new("open", &["-a", "Preview"])),
+        ViewerKind::Windows => Box::new(Opener::new("cmd", &["/C", "start", ""])),
+        #[cfg(feature = "integrated-viewer")]
+        ViewerKind::Integrated => Box::new(integrated::SideBySide::new(1920, 1080)),
+    }
+}
+
+/// Starts a viewer process without waiting for it
+fn spawn(command: &mut Command) -> io::Result<Child> {
+    command.stdout(Stdio::null()).stderr(Stdio::null()).spawn()
+}
+
+/// Waits for all processes to exit, after killing them if `kill` is set. Every process is
+/// waited for even if some fail; the first error is returned.
+fn reap(children: &mut Vec<Child>, kill: bool) -> io::Result<()> {
+    let mut result = Ok(());
+
+    for mut child in children.drain(..) {
+        // Killing fails if the process has already exited, e.g. because the user closed its
+        // window, which is fine as long as it can be waited for
+        if kill {
+            let _ = child.kill();
+        }
+        if let Err(e) = child.wait() {
+            if result.is_ok() {
+                result = Err(e);
+            }
+        }
+    }
+
+    result
+}
+
+/// Shows images with `feh`, one window per side. Only the windows opened by this viewer are
+/// closed, so other `feh` windows the user has open are left alone.
+pub struct Feh {
+    children: Vec<Child>,
+}
+
+impl Feh {
+    pub fn new() -> Feh {
+        Feh {
+            children: Vec::new(),
+        }
+    }
+}
+
+impl Default for Feh {
+    fn default() -> Feh {
+        Feh::new()
+    }
+}
+
+impl Drop for Feh {
+    fn drop(&mut self) {
+        let _ = reap(&mut self.children, true);
+    }
+}
+
+impl ImageViewer for Feh {
+    fn show(&mut self, image: &Path, side: PlayerSide) -> io::Result<()> {
+        let (geometry, title) = match side {
+            PlayerSide::Home => ("960x1080+0+0", "1"),
+            PlayerSide::Away => ("960x1080+1920+0", "2"),
+        };
+
+        let child = spawn(
+            Command::new("feh")
+                .arg("-g")
+                .arg(geometry)
+                .arg(image)
+                .arg("--scale-down")
+                .arg("--title")
+                .arg(title),
+        )?;
+        self.children.push(child);
+
+        Ok(())
+    }
+
+    fn close_all(&mut self) -> io::Result<()> {
+        reap(&mut self.children, true)
+    }
+}
+
+/// Hands images to a program that opens them in the desktop's viewer. Those windows belong to
+/// the desktop, so they can't be closed again and have to be closed by the user. The program
+/// itself usually exits right away and is only waited for.
+pub struct Opener {
+    program: String,
+    args: Vec<String>,
+    children: Vec<Child>,
+}
+
+impl Opener {
+    /// Creates a viewer that runs `program` with `args` followed by the path of the image
+    pub fn new(program: &str, args: &[&str]) -> Opener {
+        Opener {
+            program: String::from(program),
+            args: args.iter().map(|&arg| String::from(arg)).collect(),
+            children: Vec::new(),
+        }
+    }
+}
+
+impl ImageViewer for Opener {
+    fn show(&mut self, image: &Path, _side: PlayerSide) -> io::Result<()> {
+        let child = spawn(Command::new(&self.program).args(&self.args).arg(image))?;
+        self.children.push(child);
+
+        Ok(())
+    }
+
+    fn close_all(&mut self) -> io::Result<()> {
+        reap(&mut self.children, false)
+    }
+}
+
+#[cfg(feature = "integrated-viewer")]
+pub mod integrated {
+    //! A window that shows both images of a pairing next to each other
+
+    use super::ImageViewer;
+    use crate::PlayerSide;
+    use image::imageops::FilterType;
+    use minifb::{Key, KeyRepeat, Window, WindowOptions};
+    use std::io;
+    use std::path::{Path, PathBuf};
+    use std::time::Duration;
+
+    fn other_error<E: std::fmt::Display>(e: E) -> io::Error {
+        io::Error::other(e.to_string())
+    }
+
+    /// Shows the home image on the left half and the away image on the right half of a window.
+    /// Pressing 1 or 2 votes for the left or right image; Escape or closing the window leaves
+    /// without a vote.
+    pub struct SideBySide {
+        width: usize,
+        height: usize,
+        home: Option<PathBuf>,
+        away: Option<PathBuf>,
+    }
+
+    impl SideBySide {
+        /// Creates a viewer whose window has the given size in pixels
+        pub fn new(width: usize, height: usize) -> SideBySide {
+            SideBySide {
+                width,
+                height,
+                home: None,
+                away: None,
+            }
+        }
+
+        /// Renders both images, scaled to fit and centered in their half of the window
+        pub(crate) fn render(&self) -> io::Result<Vec<u32>> {
+            let mut buffer = vec![0; self.width * self.height];
+            let half = self.width / 2;
+
+            for (path, left) in &[(&self.home, 0), (&self.away, half)] {
+                let path = match path {
+                    Some(path) => path,
+                    None => continue,
+                };
+
+                let image = image::open(path)
+                    .map_err(other_error)?
+                    .resize(half as u32, self.height as u32, FilterType::Triangle)
+                    .to_rgb8();
+                let x0 = left + (half - image.width() as usize) / 2;
+                let y0 = (self.height - image.height() as usize) / 2;
+
+                for (x, y, pixel) in image.enumerate_pixels() {
+                    let [r, g, b] = pixel.0;
+                    buffer[(y0 + y as usize) * self.width + x0 + x as usize] =
+                        (r as u32) << 16 | (g as u32) << 8 | b as u32;
+                }
+            }
+
+            Ok(buffer)
+        }
+    }
+
+    impl ImageViewer for SideBySide {
+        fn show(&mut self, image: &Path, side: PlayerSide) -> io::Result<()> {
+            match side {
+                PlayerSide::Home => self.home = Some(image.to_path_buf()),
+                PlayerSide::Away => self.away = Some(image.to_path_buf()),
+            }
+            Ok(())
+        }
+
+        fn close_all(&mut self) -> io::Result<()> {
+            self.home = None;
+            self.away = None;
+            Ok(())
+        }
+
+        fn vote(&mut self) -> io::Result<Option<PlayerSide>> {
+            let buffer = self.render()?;
+            let mut window = Window::new(
+                "swyss: press 1 or 2",
+                self.width,
+                self.height,
+                WindowOptions::default(),
+            )
+            .map_err(other_error)?;
+            window.limit_update_rate(Some(Duration::from_millis(30)));
+
+            while window.is_open() {
+                for key in window.get_keys_pressed(KeyRepeat::No) {
+                    match key {
+                        Key::Key1 | Key::NumPad1 => return Ok(Some(PlayerSide::Home)),
+                        Key::Key2 | Key::NumPad2 => return Ok(Some(PlayerSide::Away)),
+                        Key::Escape => return Ok(None),
+                        _ => {}
+                    }
+                }
+
+                window
+                    .update_with_buffer(&buffer, self.width, self.height)
+                    .map_err(other_error)?;
+            }
+
+            Ok(None)
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use image::{Rgb, RgbImage};
+
+        #[test]
+        fn render_side_by_side() {
+            let dir = std::env::temp_dir();
+            let red = dir.join(format!("swyss-{}.png", uuid::Uuid::new_v4()));
+            let blue = dir.join(format!("swyss-{}.png", uuid::Uuid::new_v4()));
+            RgbImage::from_pixel(4, 2, Rgb([255, 0, 0]))
+                .save(&red)
+                .unwrap();
+            RgbImage::from_pixel(2, 4, Rgb([0, 0, 255]))
+                .save(&blue)
+                .unwrap();
+
+            let mut viewer = SideBySide::new(16, 8);
+            viewer.show(&red, PlayerSide::Home).unwrap();
+            viewer.show(&blue, PlayerSide::Away).unwrap();
+            let buffer = viewer.render().unwrap();
+
+            // The wide image fills the width of the left half, the tall one the height of the
+            // right half
+            assert_eq!(buffer[4 * 16], 0xff0000);
+            assert_eq!(buffer[4 * 16 + 7], 0xff0000);
+            assert_eq!(buffer[0], 0);
+            assert_eq!(buffer[10], 0x0000ff);
+            assert_eq!(buffer[8], 0);
+
+            viewer.close_all().unwrap();
+            assert!(viewer.render().unwrap().iter().all(|&p| p == 0));
+
+            std::fs::remove_file(&red).unwrap();
+            std::fs::remove_file(&blue).unwrap();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn kinds() {
+        assert_eq!("feh".parse(), Ok(ViewerKind::Feh));
+        assert_eq!("XDG-Open".parse(), Ok(ViewerKind::XdgOpen));
+        assert!("minifb".parse::<ViewerKind>().is_err());
+
+        // A viewer that doesn't exist fails to open images instead of panicking
+        let mut missing = Opener::new("swyss-no-such-viewer", &[]);
+        assert!(missing.show(Path::new("a.png"), PlayerSide::Home).is_err());
+        assert!(missing.close_all().is_ok());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn only_own_processes_are_closed() {
+        // Stands in for a viewer window the user opened themselves
+        let mut other = Command::new("sleep").arg("30").spawn().unwrap();
+
+        let mut viewer = Opener::new("sleep", &[]);
+        viewer.show(Path::new("0"), PlayerSide::Home).unwrap();
+        viewer.show(Path::new("0"), PlayerSide::Away).unwrap();
+        assert!(viewer.close_all().is_ok());
+        assert!(viewer.children.is_empty());
+
+        let mut children = vec![spawn(Command::new("sleep").arg("30")).unwrap()];
+        assert!(reap(&mut children, true).is_ok());
+        assert!(children.is_empty());
+
+        assert!(other.try_wait().unwrap().is_none());
+        other.kill().unwrap();
+        other.wait().unwrap();
+    }
+}
@@ -0,0 +1,236 @@
+//! Webhook notifications, so that chat bots and websites can announce pairings and final
+//! standings as soon as they are known. The payloads are always available; the `Webhook`
+//! observer that POSTs them to a URL requires the `webhooks` feature.
+
+use crate::event::EventInfo;
+use crate::persist::{standings_rows, StandingsRow};
+use crate::Tournament;
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "webhooks")]
+use crate::observer::TournamentObserver;
+#[cfg(feature = "webhooks")]
+use core::cell::RefCell;
+
+/// A pairing as announced by a webhook
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct AnnouncedPairing {
+    pub uuid: uuid::Uuid,
+    pub home: String,
+    pub away: String,
+}
+
+/// Body of a webhook request. The `event` field tells the kinds of payload apart.
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum WebhookPayload {
+    /// A round was paired
+    RoundPaired {
+        /// The tournament's details, named so as not to clash with the tag
+        #[serde(default)]
+        event_info: EventInfo,
+        round: u32,
+        rounds: u32,
+        pairings: Vec<AnnouncedPairing>,
+    },
+    /// The tournament is over
+    Complete {
+        #[serde(default)]
+        event_info: EventInfo,
+        standings: Vec<StandingsRow>,
+    },
+}
+
+impl WebhookPayload {
+    /// Announces the pairings of the current round, as returned by `next_round()`
+    pub fn round_paired(
+        tournament: &Tournament,
+        pairings: &[(uuid::Uuid, String, String)],
+    ) -> WebhookPayload {
+        WebhookPayload::RoundPaired {
+            event_info: tournament.event.clone(),
+            round: tournament.current_round,
+            rounds: tournament.rounds,
+            pairings: pairings
+                .iter()
+                .map(|(uuid, home, away)| AnnouncedPairing {
+                    uuid: *uuid,
+                    home: home.clone(),
+                    away: away.clone(),
+                })
+                .collect(),
+        }
+    }
+
+    /// Announces the final standings. Unlike `Tournament::ranking()`, this doesn't reorder the
+    /// tournament's players or make the random draw between players tied on everything.
+    pub fn complete(tournament: &Tournament) -> WebhookPayload {
+        let players = tournament.sorted_players();
+
+        WebhookPayload::Complete {
+            event_info: tournament.event.clone(),
+            standings: standings_rows(tournament, &players),
+        }
+    }
+
+    /// Serializes the payload to JSON
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("webhook payloads are always serializable")
+    }
+}
+
+/// Observer that POSTs every round's pairings and the final standings as JSON to a URL. Failed
+/// requests don't interrupt the tournament; they are kept until collected with `take_errors()`.
+///
+/// # Example
+///
+/// ```no_run
+/// use swyss::webhook::Webhook;
+/// use swyss::{Player, Tournament};
+///
+/// let players = (1..5)
+///     .map(|i| Player::new(&format!("Player {}", i)))
+///     .collect();
+/// let mut tourn = Tournament::with_players(players).unwrap();
+/// tourn.add_observer(Box::new(Webhook::new("https://example.com/swyss")));
+/// tourn.next_round().unwrap();
+/// ```
+#[cfg(feature = "webhooks")]
+pub struct Webhook {
+    url: String,
+    errors: RefCell<Vec<ureq::Error>>,
+}
+
+#[cfg(feature = "webhooks")]
+impl Webhook {
+    pub fn new(url: &str) -> Webhook {
+        Webhook {
+            url: String::from(url),
+            errors: RefCell::new(Vec::new()),
+        }
+    }
+
+    /// Sends a payload right away
+    pub fn post(&self, payload: &WebhookPayload) -> Result<(), Box<ureq::Error>> {
+        ureq::post(&self.url)
+            .set("Content-Type", "application/json")
+            .send_string(&payload.to_json())?;
+        Ok(())
+    }
+
+    /// Returns the errors of all failed requests since the last call
+    pub fn take_errors(&self) -> Vec<ureq::Error> {
+        self.errors.replace(Vec::new())
+    }
+
+    fn publish(&self, payload: &WebhookPayload) {
+        if let Err(e) = self.post(payload) {
+            self.errors.borrow_mut().push(*e);
+        }
+    }
+}
+
+#[cfg(feature = "webhooks")]
+impl TournamentObserver for Webhook {
+    fn on_round_paired(&self, tournament: &Tournament, pairings: &[(uuid::Uuid, String, String)]) {
+        self.publish(&WebhookPayload::round_paired(tournament, pairings));
+    }
+
+    fn on_complete(&self, tournament: &Tournament) {
+        self.publish(&WebhookPayload::complete(tournament));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Player;
+
+    fn tournament() -> Tournament {
+        let players = (1..5)
+            .map(|i| Player::new(format!("Player {}", i).as_str()))
+            .collect();
+        Tournament::with_players(players).unwrap()
+    }
+
+    #[test]
+    fn payloads() {
+        let mut tourn = tournament();
+        let pairings = tourn.next_round().unwrap().unwrap();
+
+        let json: serde_json::Value =
+            serde_json::from_str(&WebhookPayload::round_paired(&tourn, &pairings).to_json())
+                .unwrap();
+        assert_eq!(json["event"], "round_paired");
+        assert_eq!(json["round"], 1);
+        assert_eq!(json["event_info"]["name"], serde_json::Value::Null);
+        assert_eq!(json["pairings"][0]["home"], pairings[0].1.as_str());
+
+        let (uuid, winner, _) = &pairings[0];
+        assert!(tourn.end_match(*uuid, 2, 0, 0).is_ok());
+
+        tourn.event.name = Some(String::from("Friday Night"));
+        let json: serde_json::Value =
+            serde_json::from_str(&WebhookPayload::complete(&tourn).to_json()).unwrap();
+        assert_eq!(json["event_info"]["name"], "Friday Night");
+        assert_eq!(json["event"], "complete");
+        assert_eq!(json["standings"][0]["name"], winner.as_str());
+        assert_eq!(json["standings"].as_array().unwrap().len(), 4);
+    }
+
+    #[cfg(feature = "webhooks")]
+    #[test]
+    fn posts_to_url() {
+        use std::io::{BufRead, BufReader, Read, Write};
+        use std::net::TcpListener;
+        use std::thread;
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+
+        // Accepts one request per round and once for the final standings, and returns the bodies
+        let server = thread::spawn(move || {
+            let mut bodies = Vec::new();
+            for stream in listener.incoming().take(3) {
+                let mut reader = BufReader::new(stream.unwrap());
+                let mut length = 0;
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    let lower = line.to_lowercase();
+                    if let Some(value) = lower.strip_prefix("content-length:") {
+                        length = value.trim().parse().unwrap();
+                    }
+                    if line.trim().is_empty() {
+                        break;
+                    }
+                }
+
+                let mut body = vec![0; length];
+                reader.read_exact(&mut body).unwrap();
+                bodies.push(String::from_utf8(body).unwrap());
+
+                reader
+                    .get_mut()
+                    .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 0\r\n\r\n")
+                    .unwrap();
+            }
+            bodies
+        });
+
+        let mut tourn = tournament();
+        tourn.add_observer(Box::new(Webhook::new(&url)));
+        while let Some(pairings) = tourn.next_round().unwrap() {
+            for (uuid, _, _) in pairings {
+                tourn.end_match(uuid, 2, 0, 0).unwrap();
+            }
+        }
+
+        let bodies = server.join().unwrap();
+        assert!(bodies[0].contains("\"event\":\"round_paired\""));
+        assert!(bodies[1].contains("\"round\":2"));
+        assert!(bodies[2].contains("\"event\":\"complete\""));
+    }
+}